@@ -4,17 +4,20 @@
 use netbench::Result;
 use structopt::StructOpt;
 
+mod profile;
 mod report;
 mod report_tree;
 
 #[derive(StructOpt)]
 enum Args {
+    Profile(profile::Profile),
     Report(report::Report),
     ReportTree(report_tree::ReportTree),
 }
 
 fn main() -> Result<()> {
     match Args::from_args() {
+        Args::Profile(profile) => profile.run(),
         Args::Report(report) => report.run(),
         Args::ReportTree(report) => report.run(),
     }
@@ -0,0 +1,283 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Result;
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    io::BufRead,
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+
+/// Frames belonging to the allocator rather than the code requesting the allocation
+static ALLOCATOR_FRAMES: &[&str] = &[
+    "netbench_driver::alloc",
+    "<netbench_driver::alloc",
+    "__rust_",
+    "__rdl_",
+    "alloc::",
+    "<alloc::",
+    "core::alloc",
+    "std::alloc",
+    "mi_",
+    "_mi_",
+    "malloc",
+];
+
+#[derive(Debug, StructOpt)]
+pub struct Profile {
+    /// The prefixes passed to `netbench-collector --profile`
+    inputs: Vec<PathBuf>,
+
+    /// The number of entries to show in each table
+    #[structopt(long, short, default_value = "20")]
+    count: usize,
+}
+
+impl Profile {
+    pub fn run(&self) -> Result<()> {
+        for input in &self.inputs {
+            println!("== {} ==", input.display());
+            self.cpu(input)?;
+            self.alloc(input)?;
+            println!();
+        }
+
+        Ok(())
+    }
+
+    fn cpu(&self, prefix: &Path) -> Result<()> {
+        let stacks = read_folded(prefix, "cpu.folded")?;
+        let CpuSamples {
+            total,
+            exclusive,
+            inclusive,
+        } = CpuSamples::new(&stacks);
+
+        println!();
+        println!("cpu samples: {}", total);
+
+        if total == 0 {
+            return Ok(());
+        }
+
+        let percent = |value: u64| value as f64 * 100.0 / total as f64;
+
+        println!();
+        println!("{:>8} {:>8}  function", "self %", "total %");
+        for (frame, samples) in top(&exclusive, self.count) {
+            println!(
+                "{:>8.2} {:>8.2}  {}",
+                percent(samples),
+                percent(inclusive[frame]),
+                frame
+            );
+        }
+
+        Ok(())
+    }
+
+    fn alloc(&self, prefix: &Path) -> Result<()> {
+        let bytes = read_folded(prefix, "alloc_bytes.folded")?;
+        let counts = read_folded(prefix, "alloc_count.folded")?;
+
+        let mut total_bytes = 0u64;
+        let mut total_count = 0u64;
+        let mut sites: HashMap<&str, (u64, u64)> = HashMap::new();
+
+        for (frames, value) in &bytes {
+            total_bytes += value;
+            sites.entry(site(frames)).or_default().0 += value;
+        }
+
+        for (frames, value) in &counts {
+            total_count += value;
+            sites.entry(site(frames)).or_default().1 += value;
+        }
+
+        println!();
+        println!("allocations: {} ({} bytes)", total_count, total_bytes);
+
+        if sites.is_empty() {
+            return Ok(());
+        }
+
+        let by_bytes: HashMap<&str, u64> = sites
+            .iter()
+            .map(|(site, (bytes, _count))| (*site, *bytes))
+            .collect();
+
+        println!();
+        println!("{:>12} {:>10} {:>10}  site", "bytes", "count", "avg");
+        for (site, bytes) in top(&by_bytes, self.count) {
+            let count = sites[site].1;
+            let avg = bytes.checked_div(count).unwrap_or(0);
+            println!("{:>12} {:>10} {:>10}  {}", bytes, count, avg, site);
+        }
+
+        Ok(())
+    }
+}
+
+/// The CPU samples of each function
+#[derive(Debug, Default)]
+struct CpuSamples<'a> {
+    total: u64,
+    /// The samples in which the function was the leaf frame
+    exclusive: HashMap<&'a str, u64>,
+    /// The samples in which the function was anywhere in the stack
+    inclusive: HashMap<&'a str, u64>,
+}
+
+impl<'a> CpuSamples<'a> {
+    fn new(stacks: &'a [(Vec<String>, u64)]) -> Self {
+        let mut summary = Self::default();
+
+        for (frames, samples) in stacks {
+            summary.total += samples;
+
+            if let Some(leaf) = frames.last() {
+                *summary.exclusive.entry(leaf.as_str()).or_insert(0) += samples;
+            }
+
+            // recursive functions should only be counted once per stack
+            let unique: HashSet<_> = frames.iter().map(String::as_str).collect();
+            for frame in unique {
+                *summary.inclusive.entry(frame).or_insert(0) += samples;
+            }
+        }
+
+        summary
+    }
+}
+
+/// Reads a file in the folded stack format (`root;...;leaf value`)
+fn read_folded(prefix: &Path, extension: &str) -> Result<Vec<(Vec<String>, u64)>> {
+    let mut path = OsString::from(prefix);
+    path.push(".");
+    path.push(extension);
+
+    let file = std::fs::File::open(&path)
+        .map_err(|err| format!("could not open {}: {}", Path::new(&path).display(), err))?;
+    let file = std::io::BufReader::new(file);
+
+    let mut stacks = vec![];
+    for line in file.lines() {
+        let line = line?;
+        if let Some((frames, value)) = line.rsplit_once(' ') {
+            let frames = frames.split(';').map(String::from).collect();
+            let value = value.parse()?;
+            stacks.push((frames, value));
+        }
+    }
+
+    Ok(stacks)
+}
+
+/// Returns the first frame, starting from the leaf, which isn't part of the allocator
+fn site(frames: &[String]) -> &str {
+    frames
+        .iter()
+        .rev()
+        .find(|frame| {
+            !ALLOCATOR_FRAMES
+                .iter()
+                .any(|prefix| frame.starts_with(prefix))
+        })
+        .or_else(|| frames.last())
+        .map_or("[unknown]", String::as_str)
+}
+
+/// Returns the `count` largest entries
+fn top<'a>(values: &HashMap<&'a str, u64>, count: usize) -> Vec<(&'a str, u64)> {
+    let mut values: Vec<_> = values.iter().map(|(k, v)| (*k, *v)).collect();
+    values.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then(a_name.cmp(b_name)));
+    values.truncate(count);
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stacks(folded: &[(&str, u64)]) -> Vec<(Vec<String>, u64)> {
+        folded
+            .iter()
+            .map(|(frames, value)| (frames.split(';').map(String::from).collect(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn cpu_samples_test() {
+        let stacks = stacks(&[
+            ("main;run;parse", 3),
+            ("main;run;encode", 2),
+            ("main;run", 1),
+            // recursive frames are only counted once in the total
+            ("main;walk;walk;walk", 4),
+            ("0x7f1d2c029d90", 5),
+        ]);
+        let samples = CpuSamples::new(&stacks);
+
+        assert_eq!(samples.total, 15);
+
+        let exclusive: HashMap<_, _> = [
+            ("parse", 3),
+            ("encode", 2),
+            ("run", 1),
+            ("walk", 4),
+            ("0x7f1d2c029d90", 5),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(samples.exclusive, exclusive);
+
+        let inclusive: HashMap<_, _> = [
+            ("main", 10),
+            ("run", 6),
+            ("parse", 3),
+            ("encode", 2),
+            ("walk", 4),
+            ("0x7f1d2c029d90", 5),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(samples.inclusive, inclusive);
+
+        assert_eq!(
+            top(&samples.exclusive, 2),
+            vec![("0x7f1d2c029d90", 5), ("walk", 4)]
+        );
+        assert_eq!(
+            top(&samples.inclusive, 10),
+            vec![
+                ("main", 10),
+                ("run", 6),
+                ("0x7f1d2c029d90", 5),
+                ("walk", 4),
+                ("parse", 3),
+                ("encode", 2),
+            ]
+        );
+
+        // ties are sorted by name
+        let ties: HashMap<_, _> = [("b", 1), ("c", 1), ("a", 1)].into_iter().collect();
+        assert_eq!(top(&ties, 2), vec![("a", 1), ("b", 1)]);
+    }
+
+    #[test]
+    fn site_test() {
+        let frames = |stack: &str| stack.split(';').map(String::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            site(&frames(
+                "main;bytes::Bytes::copy_from_slice;alloc::raw_vec::RawVec::allocate;__rust_alloc;malloc"
+            )),
+            "bytes::Bytes::copy_from_slice"
+        );
+        // the leaf is used if all of the frames are part of the allocator
+        assert_eq!(site(&frames("alloc::vec::Vec::new;malloc")), "malloc");
+        assert_eq!(site(&[]), "[unknown]");
+    }
+}
@@ -37,26 +37,10 @@ impl Report {
         let mut names = vec![];
         let mut scenario_names = BTreeSet::new();
 
-        // expose an option to select the view
-        signals.push(json!({
-            "name": "ui$view",
-            "value": Stat::NAMES[0],
-            "bind": {
-                "input": "select",
-                "name": "View",
-                "options": Stat::NAMES,
-            },
-        }));
-
-        // translate the view name into an index
-        signals.push(json!({
-            "name": "sig$view",
-            "value": "0",
-            "update": format!("indexof({:?},ui$view)", Stat::NAMES),
-        }));
-
         let mut stream_ids = HashMap::new();
         let mut pids = vec![];
+        let mut latency_groups = BTreeSet::new();
+        let mut latency_rows = vec![];
 
         for (pid, input) in self.inputs.iter().enumerate() {
             let pid = pid as u64;
@@ -108,6 +92,7 @@ impl Report {
                     accept,
                     send,
                     receive,
+                    latency,
                 } = event?;
 
                 let x = time.as_millis() as u64;
@@ -208,10 +193,66 @@ impl Report {
                     );
                 }
 
+                for (group, l) in latency {
+                    for (percentile, value) in [l.p50, l.p99, l.p999].iter().enumerate() {
+                        // convert from nanoseconds to microseconds
+                        let y = *value as f64 / 1000.0;
+                        latency_rows.push((
+                            group.clone(),
+                            percentile,
+                            Row {
+                                x,
+                                y,
+                                pid,
+                                stat: 0,
+                                stream_id: None,
+                            },
+                        ));
+                    }
+                    latency_groups.insert(group);
+                }
+
                 prev_x = x;
             }
         }
 
+        // each latency group gets a view per percentile after the fixed stats
+        let mut views: Vec<String> = Stat::NAMES.iter().map(|name| name.to_string()).collect();
+        let latency_groups: HashMap<_, _> = latency_groups
+            .into_iter()
+            .enumerate()
+            .map(|(idx, group)| {
+                for percentile in LATENCY_PERCENTILES.iter() {
+                    views.push(format!("{} {} latency (us)", group, percentile));
+                }
+                (group, idx)
+            })
+            .collect();
+
+        for (group, percentile, mut row) in latency_rows {
+            let idx = latency_groups[&group] * LATENCY_PERCENTILES.len() + percentile;
+            row.stat = (Stat::NAMES.len() + idx) as _;
+            stats_table.push(row);
+        }
+
+        // expose an option to select the view
+        signals.push(json!({
+            "name": "ui$view",
+            "value": views[0],
+            "bind": {
+                "input": "select",
+                "name": "View",
+                "options": views,
+            },
+        }));
+
+        // translate the view name into an index
+        signals.push(json!({
+            "name": "sig$view",
+            "value": "0",
+            "update": format!("indexof({:?},ui$view)", views),
+        }));
+
         stats_table.sort_by(|a, b| {
             a.x.cmp(&b.x)
                 .then(a.pid.cmp(&b.pid))
@@ -311,6 +352,8 @@ impl Report {
     }
 }
 
+static LATENCY_PERCENTILES: [&str; 3] = ["p50", "p99", "p999"];
+
 macro_rules! stat {
     (enum Stat { $($name:ident = $desc:expr),* $(,)? }) => {
         #[repr(u64)]
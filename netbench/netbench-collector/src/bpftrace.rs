@@ -1,14 +1,20 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{procinfo::Proc, Result};
-use netbench::stats::{Initialize, Print, Stat, Stats, StreamId};
+use crate::{procinfo::Proc, profile::Profile, Result};
+use hdrhistogram::Histogram;
+use netbench::{
+    scenario::Scenario,
+    stats::{Initialize, Latency, Print, Stat, Stats, StreamId},
+};
 use serde_json::json;
 use std::{
     collections::HashMap,
     io,
     io::BufRead,
+    path::Path,
     process::{Command, Stdio},
+    sync::Arc,
     time::Duration,
 };
 
@@ -22,11 +28,17 @@ struct Report {
     current: Stats,
     send: HashMap<StreamId, Stat>,
     receive: HashMap<StreamId, Stat>,
+    /// The latency samples of each profile group since the start of the run
+    latency: HashMap<u64, Histogram<u64>>,
+    /// The time spent parked at each checkpoint since the start of the run
+    checkpoints: HashMap<u64, Histogram<u64>>,
+    traces: Arc<Vec<String>>,
     proc: Option<Proc>,
+    profile: Option<Profile>,
 }
 
 impl Report {
-    fn new(interval: Duration) -> Self {
+    fn new(interval: Duration, traces: Arc<Vec<String>>, profile: Option<Profile>) -> Self {
         Self {
             interval,
             count: 0,
@@ -34,32 +46,42 @@ impl Report {
             current: Default::default(),
             send: Default::default(),
             receive: Default::default(),
+            latency: Default::default(),
+            checkpoints: Default::default(),
+            traces,
             proc: None,
+            profile,
         }
     }
 
-    fn push(&mut self, line: &str) {
+    fn push(&mut self, line: &str) -> Result<()> {
         let line = line.trim();
 
         if line.is_empty() {
-            return;
+            return Ok(());
+        }
+
+        if let Some(profile) = self.profile.as_mut() {
+            if profile.push(line)? {
+                return Ok(());
+            }
         }
 
         if line == "===" {
             self.dump();
-            return;
+            return Ok(());
         }
 
         if let Some(count) = line.strip_prefix("@: ") {
-            self.count = count.parse().unwrap();
-            return;
+            self.count = count.parse()?;
+            return Ok(());
         }
 
         macro_rules! stat {
             ($prefix:literal, $name:ident) => {
                 if let Some(value) = line.strip_prefix(concat!("@", $prefix, ": ")) {
-                    self.current.$name = BpfParse::parse(value).unwrap();
-                    return;
+                    self.current.$name = BpfParse::parse(value)?;
+                    return Ok(());
                 }
             };
         }
@@ -79,14 +101,14 @@ impl Report {
             ($prefix:literal, $on_value:expr) => {
                 if let Some(line) = line.strip_prefix(concat!("@", $prefix, "[")) {
                     let mut on_value = $on_value;
-                    let (id, line) = line.split_once("]: ").unwrap();
-                    let (conn, id) = id.split_once(", ").unwrap();
-                    let connection_id = conn.parse().unwrap();
-                    let id = id.parse().unwrap();
+                    let (id, line) = line.split_once("]: ").ok_or("missing value")?;
+                    let (conn, id) = id.split_once(", ").ok_or("missing stream id")?;
+                    let connection_id = conn.parse()?;
+                    let id = id.parse()?;
                     let id = StreamId { connection_id, id };
-                    let value = BpfParse::parse(line).unwrap();
+                    let value = BpfParse::parse(line)?;
                     on_value(id, value);
-                    return;
+                    return Ok(());
                 }
             };
         }
@@ -100,16 +122,38 @@ impl Report {
         stream_stat!("s", send);
         stream_stat!("r", receive);
 
+        macro_rules! latency {
+            ($prefix:literal, $name:ident) => {
+                if let Some(line) = line.strip_prefix(concat!("@", $prefix, "[")) {
+                    let (id, value) = line.split_once("]: ").ok_or("missing value")?;
+                    let id = id.parse()?;
+                    let value = value.parse()?;
+                    // `saturating_record` would clamp to the initial range instead of resizing
+                    self.$name
+                        .entry(id)
+                        .or_insert_with(|| Histogram::new(3).unwrap())
+                        .record(value)
+                        .map_err(|_| "latency value out of range")?;
+                    return Ok(());
+                }
+            };
+        }
+
+        latency!("p", latency);
+        latency!("k", checkpoints);
+
         if let Some(pid) = line.strip_prefix("cpid=") {
-            let pid = pid.parse().unwrap();
+            let pid = pid.parse()?;
             self.proc = Some(Proc::new(pid));
 
             // dump the initial numbers
             self.dump();
-            return;
+            return Ok(());
         }
 
         eprintln!("> {}", line);
+
+        Ok(())
     }
 
     fn dump(&mut self) {
@@ -159,8 +203,45 @@ impl Report {
             deallocs: current.deallocs,
             send: core::mem::take(&mut self.send),
             receive: core::mem::take(&mut self.receive),
+            latency: self.latency(),
         }
     }
+
+    /// Returns the latency distribution of each group since the start of the run
+    ///
+    /// Profile groups are named after their trace and the time parked at each checkpoint is
+    /// reported as `checkpoint <id>`. The histograms are never reset so the final entry reports
+    /// the latency for the whole run.
+    fn latency(&self) -> HashMap<String, Latency> {
+        let traces = &self.traces;
+        let profiles = self.latency.iter().map(|(id, hist)| {
+            let name = traces
+                .get(*id as usize)
+                .cloned()
+                .unwrap_or_else(|| id.to_string());
+            (name, hist)
+        });
+        let checkpoints = self
+            .checkpoints
+            .iter()
+            .map(|(id, hist)| (format!("checkpoint {}", id), hist));
+
+        profiles
+            .chain(checkpoints)
+            .map(|(name, hist)| {
+                let latency = Latency {
+                    count: hist.len(),
+                    min: hist.min(),
+                    p50: hist.value_at_quantile(0.5),
+                    p90: hist.value_at_quantile(0.9),
+                    p99: hist.value_at_quantile(0.99),
+                    p999: hist.value_at_quantile(0.999),
+                    max: hist.max(),
+                };
+                (name, latency)
+            })
+            .collect()
+    }
 }
 
 trait BpfParse: Sized {
@@ -210,6 +291,11 @@ pub fn try_run(args: &crate::Args) -> Result<Option<()>> {
     let interval = args.interval;
     let scenario = &args.scenario;
 
+    // the trace names are used to label the latency groups
+    let traces = Scenario::open(Path::new(scenario))
+        .map(|scenario| scenario.traces)
+        .unwrap_or_default();
+
     let program = {
         let template = handlebars::Handlebars::new();
         template.render_template(
@@ -218,7 +304,9 @@ pub fn try_run(args: &crate::Args) -> Result<Option<()>> {
                 "bin": &driver,
                 "interval_ms": interval.as_millis() as u64,
                 "libc": libc_location(driver)?.unwrap_or_else(|| driver.to_string()),
-                "hardware": detect_hardware_events()?
+                "hardware": detect_hardware_events()?,
+                "profile": args.profile.is_some(),
+                "profile_hz": args.profile_frequency,
             }),
         )?
     };
@@ -242,23 +330,30 @@ pub fn try_run(args: &crate::Args) -> Result<Option<()>> {
     }
     .print()?;
 
+    let profile = args.profile.as_deref().map(Profile::new);
+
     let output = proc.stdout.take().unwrap();
     let handle = std::thread::spawn(move || {
         let output = io::BufReader::new(output);
-        let mut report = Report::new(interval);
+        let mut report = Report::new(interval, traces, profile);
         for line in output.lines() {
             if let Ok(line) = line {
-                report.push(&line);
+                if let Err(err) = report.push(&line) {
+                    eprintln!("invalid bpftrace output {:?}: {}", line, err);
+                }
             } else {
                 break;
             }
         }
         report.dump();
+        report.profile
     });
 
     proc.wait()?;
 
-    let _ = handle.join();
+    if let Ok(Some(profile)) = handle.join() {
+        profile.write()?;
+    }
 
     Ok(Some(()))
 }
@@ -281,8 +376,8 @@ fn libc_location(cmd: &str) -> Result<Option<String>> {
     for line in out.lines() {
         let line = line.trim();
         if line.starts_with("libc") {
-            let (_, path) = line.split_once("=>").unwrap();
-            let (path, _) = path.split_once('(').unwrap();
+            let (_, path) = line.split_once("=>").ok_or("invalid ldd output")?;
+            let (path, _) = path.split_once('(').ok_or("invalid ldd output")?;
             let path = path.trim();
             return Ok(Some(path.to_string()));
         }
@@ -301,3 +396,115 @@ fn detect_hardware_events() -> Result<bool> {
 
     Ok(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        let traces = Arc::new(vec!["request".to_string(), "response".to_string()]);
+        Report::new(Duration::from_secs(1), traces, None)
+    }
+
+    /// Asserts the value is within the precision of the histogram
+    fn assert_close(actual: u64, expected: u64) {
+        let diff = actual.abs_diff(expected);
+        assert!(
+            diff <= expected / 1000,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn profile_latency_test() {
+        let mut report = report();
+
+        for value in 1..=1000 {
+            report.push(&format!("@p[0]: {}", value * 1000)).unwrap();
+        }
+        report.push("@p[1]: 42").unwrap();
+        // ids without a trace name fall back to the id
+        report.push("@p[5]: 7").unwrap();
+
+        let latency = report.latency();
+        assert_eq!(latency.len(), 3);
+
+        let request = latency["request"];
+        assert_eq!(request.count, 1000);
+        assert_close(request.min, 1_000);
+        assert_close(request.p50, 500_000);
+        assert_close(request.p90, 900_000);
+        assert_close(request.p99, 990_000);
+        assert_close(request.p999, 999_000);
+        assert_close(request.max, 1_000_000);
+
+        let response = latency["response"];
+        assert_eq!(response.count, 1);
+        assert_eq!(response.min, 42);
+        assert_eq!(response.max, 42);
+
+        assert_eq!(latency["5"].count, 1);
+    }
+
+    #[test]
+    fn checkpoint_latency_test() {
+        let mut report = report();
+
+        report.push("@k[0]: 100").unwrap();
+        report.push("@k[0]: 300").unwrap();
+        report.push("@k[2]: 50").unwrap();
+
+        let latency = report.latency();
+        assert_eq!(latency.len(), 2);
+
+        let checkpoint = latency["checkpoint 0"];
+        assert_eq!(checkpoint.count, 2);
+        assert_eq!(checkpoint.min, 100);
+        assert_eq!(checkpoint.p50, 100);
+        assert_eq!(checkpoint.max, 300);
+
+        assert_eq!(latency["checkpoint 2"].count, 1);
+    }
+
+    #[test]
+    fn latency_is_kept_across_intervals_test() {
+        let mut report = report();
+
+        report.push("@p[0]: 10").unwrap();
+        let first = report.entry();
+        report.push("@p[0]: 20").unwrap();
+        let second = report.entry();
+
+        assert_eq!(first.latency["request"].count, 1);
+        assert_eq!(second.latency["request"].count, 2);
+        assert_eq!(second.latency["request"].max, 20);
+    }
+
+    #[test]
+    fn invalid_latency_test() {
+        let mut report = report();
+
+        assert!(report.push("@p[0]: fast").is_err());
+        assert!(report.push("@p[zero]: 10").is_err());
+        assert!(report.push("@p[0] 10").is_err());
+        assert!(report.push("@k[0]: -1").is_err());
+        assert!(report.latency().is_empty());
+    }
+
+    #[test]
+    fn stream_stat_test() {
+        let mut report = report();
+
+        report.push("@s[1, 2]: count 3, average 4, total 12").unwrap();
+        let id = StreamId {
+            connection_id: 1,
+            id: 2,
+        };
+        assert_eq!(report.send[&id], Stat { count: 3, total: 12 });
+
+        assert!(report.push("@r[1, 2]: count 3").is_err());
+        assert!(report.push("@r[1]: count 3, average 4, total 12").is_err());
+    }
+}
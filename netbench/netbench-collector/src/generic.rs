@@ -21,6 +21,10 @@ pub fn run(args: &Args) -> Result<()> {
 
     command.env("TRACE", "disabled").env("SCENARIO", scenario);
 
+    if args.profile.is_some() {
+        eprintln!("profiling requires bpftrace; skipping");
+    }
+
     let mut proc = command.spawn()?;
     let info = Proc::new(proc.id());
 
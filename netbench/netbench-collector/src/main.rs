@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use netbench::{units::parse_duration, Result};
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 use structopt::StructOpt;
 
 mod bpftrace;
 mod generic;
 mod procinfo;
+mod profile;
 
 #[derive(Debug, StructOpt)]
 pub struct Args {
@@ -18,6 +19,15 @@ pub struct Args {
 
     #[structopt(long, short, parse(try_from_str=parse_duration), default_value = "1s")]
     pub interval: Duration,
+
+    /// Captures stack samples and allocation sites into `<PROFILE>.cpu.folded`,
+    /// `<PROFILE>.alloc_bytes.folded` and `<PROFILE>.alloc_count.folded`
+    #[structopt(long)]
+    pub profile: Option<PathBuf>,
+
+    /// The number of stack samples to capture per second when profiling
+    #[structopt(long, default_value = "99")]
+    pub profile_frequency: u64,
 }
 
 fn main() -> Result<()> {
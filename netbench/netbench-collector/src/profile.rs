@@ -0,0 +1,263 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Collects stack samples and allocation sites from the bpftrace output
+//!
+//! The stacks are written in the folded format (`root;...;leaf value`) which can be loaded by
+//! most flamegraph tools, as well as summarized with `netbench-cli profile`.
+
+use crate::Result;
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Kind {
+    Cpu,
+    AllocBytes,
+    AllocCount,
+}
+
+impl Kind {
+    const ALL: [Self; 3] = [Self::Cpu, Self::AllocBytes, Self::AllocCount];
+
+    fn from_prefix(line: &str) -> Option<Self> {
+        match line {
+            "@cpu[" => Some(Self::Cpu),
+            "@alloc_bytes[" => Some(Self::AllocBytes),
+            "@alloc_count[" => Some(Self::AllocCount),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu.folded",
+            Self::AllocBytes => "alloc_bytes.folded",
+            Self::AllocCount => "alloc_count.folded",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Profile {
+    prefix: PathBuf,
+    stacks: HashMap<Kind, HashMap<Vec<String>, u64>>,
+    current: Option<(Kind, Vec<String>)>,
+}
+
+impl Profile {
+    pub fn new(prefix: &Path) -> Self {
+        Self {
+            prefix: prefix.to_owned(),
+            stacks: Default::default(),
+            current: None,
+        }
+    }
+
+    /// Returns `true` if the line was part of a stack
+    pub fn push(&mut self, line: &str) -> Result<bool> {
+        if let Some((kind, mut frames)) = self.current.take() {
+            if let Some(value) = line.strip_prefix("]: ") {
+                // stacks are printed leaf first so flip them for the folded format
+                frames.reverse();
+                let value: u64 = value.parse()?;
+                *self
+                    .stacks
+                    .entry(kind)
+                    .or_default()
+                    .entry(frames)
+                    .or_default() += value;
+            } else {
+                frames.push(parse_frame(line));
+                self.current = Some((kind, frames));
+            }
+            return Ok(true);
+        }
+
+        if let Some(kind) = Kind::from_prefix(line) {
+            self.current = Some((kind, vec![]));
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    pub fn write(&self) -> Result<()> {
+        if let Some(parent) = self.prefix.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        for kind in Kind::ALL {
+            let mut path = self.prefix.clone().into_os_string();
+            path.push(".");
+            path.push(kind.extension());
+
+            let file = std::fs::File::create(&path)?;
+            let mut file = std::io::BufWriter::new(file);
+
+            if let Some(stacks) = self.stacks.get(&kind) {
+                let mut stacks: Vec<_> = stacks.iter().collect();
+                stacks.sort();
+                for (frames, value) in stacks {
+                    writeln!(file, "{} {}", frames.join(";"), value)?;
+                }
+            }
+
+            file.flush()?;
+            eprintln!("created: {}", Path::new(&path).display());
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a frame printed with `ustack(perf)`
+///
+/// The frames are formatted as `<address> <symbol>+<offset> (<module>)`.
+fn parse_frame(line: &str) -> String {
+    let (address, symbol) = line.split_once(' ').unwrap_or((line, ""));
+
+    let symbol = symbol
+        .rsplit_once(" (")
+        .map_or(symbol, |(symbol, _module)| symbol);
+
+    let symbol = match symbol.rsplit_once('+') {
+        Some((symbol, offset)) if offset.bytes().all(|b| b.is_ascii_digit()) => symbol,
+        _ => symbol,
+    };
+
+    if symbol.is_empty() || symbol == "[unknown]" {
+        return format!("0x{}", address);
+    }
+
+    strip_hash(symbol).to_string()
+}
+
+/// Removes the `::h0123456789abcdef` hash rust appends to mangled symbols
+fn strip_hash(symbol: &str) -> &str {
+    if let Some((path, hash)) = symbol.rsplit_once("::h") {
+        if hash.len() == 16 && hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return path;
+        }
+    }
+    symbol
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_all(profile: &mut Profile, output: &str) -> Result<()> {
+        for line in output.lines() {
+            let line = line.trim();
+            assert!(profile.push(line)?, "{:?} was not part of a stack", line);
+        }
+        Ok(())
+    }
+
+    fn folded(profile: &Profile, kind: Kind) -> Vec<(String, u64)> {
+        let mut stacks: Vec<_> = profile.stacks[&kind]
+            .iter()
+            .map(|(frames, value)| (frames.join(";"), *value))
+            .collect();
+        stacks.sort();
+        stacks
+    }
+
+    #[test]
+    fn push_test() {
+        let mut profile = Profile::new(Path::new("profile"));
+
+        push_all(
+            &mut profile,
+            r#"
+            @cpu[
+                55d0c3a7b1e0 netbench::driver::thread::Thread::poll::h0123456789abcdef+120 (/usr/bin/netbench-driver)
+                55d0c3a7a000 netbench_driver::main+16 (/usr/bin/netbench-driver)
+                7f1d2c029d90 [unknown] (/usr/lib/x86_64-linux-gnu/libc.so.6)
+            ]: 3
+            @cpu[
+                55d0c3a7b1e0 netbench::driver::thread::Thread::poll::h0123456789abcdef+240 (/usr/bin/netbench-driver)
+                55d0c3a7a000 netbench_driver::main+16 (/usr/bin/netbench-driver)
+                7f1d2c029d90 [unknown] (/usr/lib/x86_64-linux-gnu/libc.so.6)
+            ]: 4
+            @alloc_bytes[
+                55d0c3a7c000 malloc+0 (/usr/lib/x86_64-linux-gnu/libc.so.6)
+                55d0c3a7d000 bytes::Bytes::copy_from_slice::hfedcba9876543210+32 (/usr/bin/netbench-driver)
+            ]: 1024
+            "#
+            .trim(),
+        )
+        .unwrap();
+
+        // the leaf is last, offsets and hashes are removed and the samples of identical stacks are
+        // combined
+        assert_eq!(
+            folded(&profile, Kind::Cpu),
+            vec![(
+                "0x7f1d2c029d90;netbench_driver::main;netbench::driver::thread::Thread::poll"
+                    .to_string(),
+                7
+            )]
+        );
+        assert_eq!(
+            folded(&profile, Kind::AllocBytes),
+            vec![("bytes::Bytes::copy_from_slice;malloc".to_string(), 1024)]
+        );
+        assert!(!profile.stacks.contains_key(&Kind::AllocCount));
+
+        // lines outside of a stack are left for the caller
+        assert!(!profile.push("@c: 123").unwrap());
+    }
+
+    #[test]
+    fn push_invalid_value_test() {
+        let mut profile = Profile::new(Path::new("profile"));
+
+        assert!(profile.push("@cpu[").unwrap());
+        assert!(profile.push("55d0c3a7a000 main+16 (/usr/bin/netbench-driver)").unwrap());
+        assert!(profile.push("]: lots").is_err());
+        assert!(profile.stacks.is_empty());
+
+        // the next stack is still parsed
+        assert!(!profile.push("@c: 123").unwrap());
+    }
+
+    #[test]
+    fn parse_frame_test() {
+        for (line, expected) in [
+            ("55d0c3a7a000 main+16 (/usr/bin/netbench-driver)", "main"),
+            // the symbol may contain spaces and `+`
+            (
+                "55d0c3a7a000 <T as core::ops::Add>::add+8 (/usr/bin/netbench-driver)",
+                "<T as core::ops::Add>::add",
+            ),
+            ("55d0c3a7a000 main (/usr/bin/netbench-driver)", "main"),
+            ("55d0c3a7a000 main", "main"),
+            // unresolved frames fall back to the address
+            ("7f1d2c029d90 [unknown] (/usr/lib/libc.so.6)", "0x7f1d2c029d90"),
+            ("7f1d2c029d90 [unknown]", "0x7f1d2c029d90"),
+            ("7f1d2c029d90", "0x7f1d2c029d90"),
+        ] {
+            assert_eq!(parse_frame(line), expected, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn strip_hash_test() {
+        for (symbol, expected) in [
+            ("foo::bar::h0123456789abcdef", "foo::bar"),
+            ("foo::bar::h0123456789ABCDEF", "foo::bar"),
+            // not a hash
+            ("foo::bar", "foo::bar"),
+            ("foo::hello", "foo::hello"),
+            ("foo::bar::h0123", "foo::bar::h0123"),
+            ("foo::bar::h0123456789abcdeg", "foo::bar::h0123456789abcdeg"),
+        ] {
+            assert_eq!(strip_hash(symbol), expected, "{:?}", symbol);
+        }
+    }
+}
@@ -3,7 +3,7 @@
 
 use netbench::Result;
 use netbench_driver::Allocator;
-use s2n_quic::provider::io;
+use s2n_quic::provider::{datagram, io};
 use std::collections::HashSet;
 use structopt::StructOpt;
 
@@ -22,6 +22,10 @@ pub struct Client {
 
     #[structopt(long)]
     disable_gso: bool,
+
+    /// The number of datagrams that can be queued in each direction for a connection
+    #[structopt(long, default_value = "1024")]
+    datagram_capacity: usize,
 }
 
 impl Client {
@@ -65,9 +69,15 @@ impl Client {
 
         let io = io_builder.build()?;
 
+        let datagram = datagram::default::Endpoint::builder()
+            .with_send_capacity(self.datagram_capacity)?
+            .with_recv_capacity(self.datagram_capacity)?
+            .build()?;
+
         let client = s2n_quic::Client::builder()
             .with_io(io)?
             .with_tls(tls)?
+            .with_datagram(datagram)?
             .start()
             .unwrap();
 
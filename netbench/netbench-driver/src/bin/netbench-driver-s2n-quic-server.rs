@@ -3,7 +3,10 @@
 
 use netbench::{scenario, Result};
 use netbench_driver::Allocator;
-use s2n_quic::{provider::io, Connection};
+use s2n_quic::{
+    provider::{datagram, endpoint_limits, io},
+    Connection,
+};
 use std::{collections::HashSet, sync::Arc};
 use structopt::StructOpt;
 use tokio::spawn;
@@ -23,6 +26,14 @@ pub struct Server {
 
     #[structopt(long)]
     disable_gso: bool,
+
+    /// The number of datagrams that can be queued in each direction for a connection
+    #[structopt(long, default_value = "1024")]
+    datagram_capacity: usize,
+
+    /// The number of inflight handshakes before the server starts sending Retry packets
+    #[structopt(long)]
+    max_inflight_handshakes: Option<usize>,
 }
 
 impl Server {
@@ -85,9 +96,24 @@ impl Server {
 
         let io = io_builder.build()?;
 
+        let datagram = datagram::default::Endpoint::builder()
+            .with_send_capacity(self.datagram_capacity)?
+            .with_recv_capacity(self.datagram_capacity)?
+            .build()?;
+
+        let mut limits = endpoint_limits::Default::builder();
+
+        if let Some(limit) = self.max_inflight_handshakes {
+            limits = limits.with_inflight_handshake_limit(limit)?;
+        }
+
+        let limits = limits.build()?;
+
         let server = s2n_quic::Server::builder()
             .with_io(io)?
             .with_tls(tls)?
+            .with_datagram(datagram)?
+            .with_endpoint_limits(limits)?
             .start()
             .unwrap();
 
@@ -0,0 +1,315 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A userspace proxy which applies the router operations of a scenario
+//!
+//! Each server `N` in the scenario is proxied on `port + N`. The router operations only apply to
+//! UDP datagrams. TCP connections are forwarded as-is, since the kernel is responsible for their
+//! segments.
+
+use netbench::{
+    router::{self, Rebind, State, Verdict},
+    Result,
+};
+use netbench_driver::Allocator;
+use rand::Rng;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use structopt::StructOpt;
+use tokio::{
+    net::{TcpListener, TcpStream, UdpSocket},
+    spawn,
+    task::JoinHandle,
+    time::{sleep, timeout_at, Instant},
+};
+
+/// The amount of time a client address can go without sending before its flow is evicted
+///
+/// Like a NAT binding, only client->server packets keep the flow alive. The next packet from an
+/// evicted address is forwarded from a new upstream socket.
+const FLOW_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[global_allocator]
+static ALLOCATOR: Allocator = Allocator::new();
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    Router::from_args().run().await
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Router {
+    #[structopt(flatten)]
+    opts: netbench_driver::Router,
+}
+
+impl Router {
+    pub async fn run(&self) -> Result<()> {
+        let scenario = self.opts.scenario()?;
+        let servers = self.opts.servers().await?;
+        let mut trace = self.opts.trace();
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let mut handles = vec![];
+        for (id, upstream) in servers.into_iter().enumerate() {
+            let port = u16::try_from(id)
+                .ok()
+                .and_then(|id| self.opts.port.checked_add(id))
+                .ok_or_else(|| format!("the port for server {} is out of range", id))?;
+            let addr = SocketAddr::new(self.opts.ip, port);
+
+            let socket = UdpSocket::bind(addr).await?;
+            let proxy = Udp {
+                listener: Arc::new(socket),
+                upstream,
+                state: state.clone(),
+            };
+            handles.push(spawn(async move {
+                if let Err(err) = proxy.run().await {
+                    eprintln!("error: {}", err);
+                }
+            }));
+
+            let listener = TcpListener::bind(addr).await?;
+            handles.push(spawn(async move {
+                if let Err(err) = tcp(listener, upstream).await {
+                    eprintln!("error: {}", err);
+                }
+            }));
+        }
+
+        let mut driver = router::Driver::new(&scenario);
+        let mut timer = netbench::timer::Tokio::default();
+        futures::future::poll_fn(|cx| {
+            let mut state = state.lock().unwrap();
+            driver.poll_with_timer(&mut state, &mut trace, &mut timer, cx)
+        })
+        .await?;
+
+        // keep applying the final conditions until the router is stopped
+        for handle in handles {
+            let _ = handle.await;
+        }
+
+        Ok(())
+    }
+}
+
+async fn tcp(listener: TcpListener, upstream: SocketAddr) -> Result<()> {
+    loop {
+        let (mut downstream, _addr) = listener.accept().await?;
+        spawn(async move {
+            let result = async {
+                let mut upstream = TcpStream::connect(upstream).await?;
+                tokio::io::copy_bidirectional(&mut downstream, &mut upstream).await?;
+                Ok::<_, std::io::Error>(())
+            }
+            .await;
+
+            if let Err(err) = result {
+                eprintln!("error: {}", err);
+            }
+        });
+    }
+}
+
+struct Udp {
+    listener: Arc<UdpSocket>,
+    upstream: SocketAddr,
+    state: Arc<Mutex<State>>,
+}
+
+impl Udp {
+    async fn run(self) -> Result<()> {
+        let mut flows: HashMap<SocketAddr, Flow> = HashMap::new();
+        let mut buf = vec![0; u16::MAX as usize];
+        let mut next_eviction = Instant::now() + FLOW_IDLE_TIMEOUT;
+
+        loop {
+            let received = timeout_at(next_eviction, self.listener.recv_from(&mut buf)).await;
+
+            let now = Instant::now();
+            if now >= next_eviction {
+                flows.retain(|_client, flow| {
+                    now.duration_since(flow.last_active) < FLOW_IDLE_TIMEOUT
+                });
+                next_eviction = now + FLOW_IDLE_TIMEOUT;
+            }
+
+            let (len, client) = if let Ok(received) = received {
+                received?
+            } else {
+                continue;
+            };
+            let mut packet = buf[..len].to_vec();
+
+            let (rebind, epoch) = {
+                let state = self.state.lock().unwrap();
+                let rebind = match flows.get(&client) {
+                    // the client hasn't been bound yet
+                    None => Some(None),
+                    Some(flow) => flow.rebind(&state).map(Some),
+                };
+                (rebind, (state.rebind_ports, state.rebind_addresses))
+            };
+
+            if let Some(rebind) = rebind {
+                let prev = flows
+                    .get(&client)
+                    .and_then(|flow| flow.socket.local_addr().ok())
+                    .map(|addr| addr.ip());
+                let socket = bind(self.upstream, rebind, prev).await?;
+                let flow = Flow::new(socket, client, epoch, &self.listener, &self.state);
+                // dropping the previous flow stops forwarding packets sent to the old address
+                flows.insert(client, flow);
+            }
+
+            let flow = flows.get_mut(&client).unwrap();
+            flow.last_active = Instant::now();
+
+            let verdict = {
+                let state = self.state.lock().unwrap();
+                let queued = flow.queued.load(Ordering::Relaxed);
+                state
+                    .client
+                    .process(&mut rand::thread_rng(), &mut packet, queued)
+            };
+
+            if let Verdict::Forward { delay } = verdict {
+                let socket = flow.socket.clone();
+                deliver(socket, packet, self.upstream, delay, &flow.queued).await;
+            }
+        }
+    }
+}
+
+/// The upstream socket for a client address, emulating a NAT binding
+struct Flow {
+    socket: Arc<UdpSocket>,
+    /// The number of client->server packets waiting to be delivered
+    queued: Arc<AtomicUsize>,
+    /// The `RebindAll` counters at the time the flow was bound
+    epoch: (u64, u64),
+    /// The last time the client sent a packet through the flow
+    last_active: Instant,
+    receiver: JoinHandle<()>,
+}
+
+impl Flow {
+    fn new(
+        socket: UdpSocket,
+        client: SocketAddr,
+        epoch: (u64, u64),
+        listener: &Arc<UdpSocket>,
+        state: &Arc<Mutex<State>>,
+    ) -> Self {
+        let socket = Arc::new(socket);
+
+        let receiver = {
+            let socket = socket.clone();
+            let listener = listener.clone();
+            let state = state.clone();
+            spawn(async move {
+                let queued = Arc::new(AtomicUsize::new(0));
+                let mut buf = vec![0; u16::MAX as usize];
+
+                while let Ok((len, _addr)) = socket.recv_from(&mut buf).await {
+                    let mut packet = buf[..len].to_vec();
+
+                    let verdict = {
+                        let state = state.lock().unwrap();
+                        let queued = queued.load(Ordering::Relaxed);
+                        state
+                            .server
+                            .process(&mut rand::thread_rng(), &mut packet, queued)
+                    };
+
+                    if let Verdict::Forward { delay } = verdict {
+                        deliver(listener.clone(), packet, client, delay, &queued).await;
+                    }
+                }
+            })
+        };
+
+        Self {
+            socket,
+            queued: Default::default(),
+            epoch,
+            last_active: Instant::now(),
+            receiver,
+        }
+    }
+
+    fn rebind(&self, state: &State) -> Option<Rebind> {
+        let (ports, addresses) = self.epoch;
+
+        if addresses != state.rebind_addresses {
+            return Some(Rebind::Address);
+        }
+
+        if ports != state.rebind_ports {
+            return Some(Rebind::Port);
+        }
+
+        state.rebind(&mut rand::thread_rng())
+    }
+}
+
+impl Drop for Flow {
+    fn drop(&mut self) {
+        self.receiver.abort();
+    }
+}
+
+/// Binds a new upstream socket
+///
+/// Each binding gets a new port. Binding to a new address is only possible on IPv4 loopback,
+/// where the whole `127.0.0.0/8` range is available without any configuration. Otherwise only
+/// the port is changed.
+async fn bind(
+    upstream: SocketAddr,
+    rebind: Option<Rebind>,
+    prev: Option<IpAddr>,
+) -> Result<UdpSocket> {
+    let ip = match (upstream.ip(), rebind, prev) {
+        (IpAddr::V4(ip), Some(Rebind::Address), _) if ip.is_loopback() => {
+            let mut rng = rand::thread_rng();
+            Ipv4Addr::new(127, rng.gen(), rng.gen(), rng.gen_range(1..u8::MAX)).into()
+        }
+        // keep the previous address when only the port is rebound
+        (_, Some(Rebind::Port), Some(prev)) => prev,
+        (IpAddr::V4(_), _, _) => Ipv4Addr::UNSPECIFIED.into(),
+        (IpAddr::V6(_), _, _) => Ipv6Addr::UNSPECIFIED.into(),
+    };
+
+    let socket = UdpSocket::bind((ip, 0)).await?;
+    Ok(socket)
+}
+
+async fn deliver(
+    socket: Arc<UdpSocket>,
+    packet: Vec<u8>,
+    addr: SocketAddr,
+    delay: Duration,
+    queued: &Arc<AtomicUsize>,
+) {
+    if delay == Duration::ZERO {
+        let _ = socket.send_to(&packet, addr).await;
+        return;
+    }
+
+    queued.fetch_add(1, Ordering::Relaxed);
+    let queued = queued.clone();
+    spawn(async move {
+        sleep(delay).await;
+        let _ = socket.send_to(&packet, addr).await;
+        queued.fetch_sub(1, Ordering::Relaxed);
+    });
+}
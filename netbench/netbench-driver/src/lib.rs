@@ -7,7 +7,14 @@ use netbench::{
     units::Byte,
     Error, Result,
 };
-use std::{net::IpAddr, ops::Deref, path::Path, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    net::{IpAddr, SocketAddr},
+    ops::Deref,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 use structopt::StructOpt;
 
 mod alloc;
@@ -117,6 +124,56 @@ impl Client {
     }
 }
 
+#[derive(Debug, StructOpt)]
+pub struct Router {
+    #[structopt(short, long, default_value = "::")]
+    pub ip: IpAddr,
+
+    /// The port for the first server; each server `N` is proxied on `port + N`
+    #[structopt(short, long, default_value = "4434", env = "PORT")]
+    pub port: u16,
+
+    #[structopt(long, default_value = "0", env = "ROUTER_ID")]
+    pub router_id: usize,
+
+    #[structopt(long, default_value = "throughput", possible_values = TRACE_VALUES, env = "TRACE")]
+    pub trace: Vec<String>,
+
+    #[structopt(long, short = "V")]
+    pub verbose: bool,
+
+    #[structopt(env = "SCENARIO")]
+    pub scenario: Scenario,
+}
+
+impl Router {
+    pub fn scenario(&self) -> Result<Arc<scenario::Router>> {
+        let id = self.router_id;
+        let router = self
+            .scenario
+            .routers
+            .get(id)
+            .ok_or_else(|| format!("the scenario doesn't have a router with id {}", id))?;
+        Ok(router.clone())
+    }
+
+    /// Resolves the address of each of the servers in the scenario
+    pub async fn servers(&self) -> Result<Vec<SocketAddr>> {
+        let mut servers = vec![];
+        for id in 0..self.scenario.servers.len() {
+            let host = client::Resolver::server(&mut Resolver, id as u64)?;
+            let mut addr = tokio::net::lookup_host(host).await?;
+            let addr = addr.next().ok_or("invalid address")?;
+            servers.push(addr);
+        }
+        Ok(servers)
+    }
+
+    pub fn trace(&self) -> impl trace::Trace + Clone {
+        traces(&self.trace[..], self.verbose, &self.scenario.traces)
+    }
+}
+
 struct Resolver;
 
 impl Resolver {
@@ -0,0 +1,62 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use netbench_scenarios::prelude::*;
+
+config!({
+    /// The total number of short-lived connections to open
+    let connections: u64 = 1000;
+
+    /// The amount of time between opening each connection
+    let open_interval: Duration = 10.millis();
+
+    /// The amount of time each connection stays open after its request completes
+    let lifetime: Duration = 100.millis();
+
+    /// The size of the client's request to the server
+    let request_size: Byte = 1.kilobytes();
+
+    /// The size of the server's response to the request
+    let response_size: Byte = 1.kilobytes();
+});
+
+pub fn scenario(config: Config) -> Scenario {
+    let Config {
+        connections,
+        open_interval,
+        lifetime,
+        request_size,
+        response_size,
+    } = config;
+
+    Scenario::build(|scenario| {
+        let server = scenario.create_server();
+
+        scenario.create_client(|client| {
+            client.scope(|scope| {
+                for idx in 0..connections {
+                    scope.spawn(|client| {
+                        // stagger the connections so they are opened at a steady rate
+                        let idx = u32::try_from(idx).unwrap_or(u32::MAX);
+                        client.sleep(open_interval.saturating_mul(idx));
+                        client.connect_to(&server, |conn| {
+                            conn.open_bidirectional_stream(
+                                |local| {
+                                    local.profile("request", |local| {
+                                        local.send(request_size);
+                                        local.receive(response_size);
+                                    });
+                                },
+                                |remote| {
+                                    remote.receive(request_size);
+                                    remote.send(response_size);
+                                },
+                            );
+                            conn.sleep(lifetime);
+                        });
+                    });
+                }
+            });
+        });
+    })
+}
@@ -0,0 +1,60 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use netbench_scenarios::prelude::*;
+
+config!({
+    /// The number of client endpoints connecting to the server
+    ///
+    /// Each client is run by a separate driver process, so the server sees each of them as a
+    /// distinct peer with its own address. See the `netbench-driver` README for running them.
+    let clients: u64 = 10;
+
+    /// The number of connections each client opens concurrently
+    let connections: u64 = 100;
+
+    /// The size of each client's request to the server
+    let request_size: Byte = 1.bytes();
+
+    /// The size of the server's response to each request
+    let response_size: Byte = 1.bytes();
+});
+
+// The server-side handshake limits are configured by the driver, e.g.
+// `netbench-driver-s2n-quic-server --max-inflight-handshakes 100`, so the same scenario can be
+// compared with and without Retry packets.
+pub fn scenario(config: Config) -> Scenario {
+    let Config {
+        clients,
+        connections,
+        request_size,
+        response_size,
+    } = config;
+
+    Scenario::build(|scenario| {
+        let server = scenario.create_server();
+
+        for _ in 0..clients {
+            scenario.create_client(|client| {
+                client.scope(|scope| {
+                    for _ in 0..connections {
+                        scope.spawn(|client| {
+                            client.connect_to(&server, |conn| {
+                                conn.open_bidirectional_stream(
+                                    |local| {
+                                        local.send(request_size);
+                                        local.receive(response_size);
+                                    },
+                                    |remote| {
+                                        remote.receive(request_size);
+                                        remote.send(response_size);
+                                    },
+                                );
+                            });
+                        });
+                    }
+                });
+            });
+        }
+    })
+}
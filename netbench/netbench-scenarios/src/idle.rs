@@ -0,0 +1,73 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use netbench_scenarios::prelude::*;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+config!({
+    /// The number of long-lived connections to hold open concurrently
+    let connections: u64 = 100;
+
+    /// The amount of time each connection stays open
+    let duration: Duration = 60.seconds();
+
+    /// The amount of time between keep-alive requests
+    ///
+    /// This should be lower than the idle timeout of the endpoints to keep the connections open.
+    let keep_alive: Duration = 5.seconds();
+
+    /// The size of each keep-alive request and response
+    let keep_alive_size: Byte = 1.bytes();
+});
+
+pub fn scenario(config: Config) -> Scenario {
+    let Config {
+        connections,
+        duration,
+        keep_alive,
+        keep_alive_size,
+    } = config;
+
+    // the connection idles for whatever is left over after the last keep-alive
+    let (keep_alive_count, idle) = match duration.as_nanos().checked_div(keep_alive.as_nanos()) {
+        Some(count) => {
+            let idle = duration.as_nanos() % keep_alive.as_nanos();
+            let idle = Duration::new((idle / NANOS_PER_SEC) as u64, (idle % NANOS_PER_SEC) as u32);
+            (u64::try_from(count).unwrap_or(u64::MAX), idle)
+        }
+        None => (0, duration),
+    };
+
+    Scenario::build(|scenario| {
+        let server = scenario.create_server();
+
+        scenario.create_client(|client| {
+            client.scope(|scope| {
+                for _ in 0..connections {
+                    scope.spawn(|client| {
+                        client.connect_to(&server, |conn| {
+                            for _ in 0..keep_alive_count {
+                                conn.sleep(keep_alive);
+                                conn.open_bidirectional_stream(
+                                    |local| {
+                                        local.profile("keep_alive", |local| {
+                                            local.send(keep_alive_size);
+                                            local.receive(keep_alive_size);
+                                        });
+                                    },
+                                    |remote| {
+                                        remote.receive(keep_alive_size);
+                                        remote.send(keep_alive_size);
+                                    },
+                                );
+                            }
+
+                            conn.sleep(idle);
+                        });
+                    });
+                }
+            });
+        });
+    })
+}
@@ -1,4 +1,4 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-netbench_scenarios::scenarios!(connect, request_response);
+netbench_scenarios::scenarios!(churn, connect, fan_in, idle, realtime, request_response);
@@ -0,0 +1,77 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use netbench_scenarios::prelude::*;
+
+config!({
+    /// The size of each datagram sent by the client
+    let datagram_size: Byte = 1200.bytes();
+
+    /// The rate at which the client sends datagrams
+    let datagram_rate: Rate = 1.megabytes() / 1.seconds();
+
+    /// The total amount of data the client sends in datagrams
+    let datagram_bytes: Byte = 10.megabytes();
+
+    /// The size of each of the client's requests to the server
+    let request_size: Byte = 1.kilobytes();
+
+    /// The size of the server's response to each request
+    let response_size: Byte = 100.kilobytes();
+
+    /// The number of requests to make while sending datagrams
+    let count: u64 = 10;
+
+    /// The number of separate connections to create
+    let connections: u64 = 1;
+});
+
+pub fn scenario(config: Config) -> Scenario {
+    let Config {
+        datagram_size,
+        datagram_rate,
+        datagram_bytes,
+        request_size,
+        response_size,
+        count,
+        connections,
+    } = config;
+
+    Scenario::build(|scenario| {
+        let server = scenario.create_server();
+
+        // datagrams may be lost so the server receives them until the client closes the connection
+        let server = server.with(|conn| {
+            conn.receive_all_datagrams();
+        });
+
+        scenario.create_client(|client| {
+            for _ in 0..connections {
+                client.connect_to(&server, |conn| {
+                    conn.concurrently(
+                        |conn| {
+                            conn.set_datagram_send_rate(datagram_rate);
+                            conn.send_datagrams(datagram_bytes, datagram_size);
+                        },
+                        |conn| {
+                            for _ in 0..count {
+                                conn.open_bidirectional_stream(
+                                    |local| {
+                                        local.profile("request", |local| {
+                                            local.send(request_size);
+                                            local.receive(response_size);
+                                        });
+                                    },
+                                    |remote| {
+                                        remote.receive(request_size);
+                                        remote.send(response_size);
+                                    },
+                                );
+                            }
+                        },
+                    );
+                });
+            }
+        });
+    })
+}
@@ -74,15 +74,17 @@ pub fn scenario(config: Config) -> Scenario {
                 if let Some(rate) = client_receive_rate {
                     local.set_receive_rate(rate);
                 }
-                local.send(request_size);
-
-                if *response_unblock > 0 {
-                    local.receive(response_unblock);
-                    local.unpark(unpark);
-                    local.receive(response_size - response_unblock);
-                } else {
-                    local.receive(response_size);
-                }
+                local.profile("request", |local| {
+                    local.send(request_size);
+
+                    if *response_unblock > 0 {
+                        local.receive(response_unblock);
+                        local.unpark(unpark);
+                        local.receive(response_size - response_unblock);
+                    } else {
+                        local.receive(response_size);
+                    }
+                });
             },
             |remote| {
                 if let Some(rate) = server_send_rate {
@@ -25,6 +25,13 @@ pub trait Connection {
     ) -> Poll<Result<u64>>;
     fn poll_send_finish(&mut self, owner: Owner, id: u64, cx: &mut Context) -> Poll<Result<()>>;
     fn poll_receive_finish(&mut self, owner: Owner, id: u64, cx: &mut Context) -> Poll<Result<()>>;
+    /// Sends a single unreliable datagram of up to `len` bytes, returning the amount sent
+    fn poll_send_datagram(&mut self, len: u64, cx: &mut Context) -> Poll<Result<u64>>;
+    /// Receives a single unreliable datagram, returning its length or `None` if the connection
+    /// is closed
+    fn poll_receive_datagram(&mut self, cx: &mut Context) -> Poll<Result<Option<u64>>>;
+    /// Migrates the connection to a new local address
+    fn poll_migrate(&mut self, cx: &mut Context) -> Poll<Result<()>>;
     fn poll_progress(&mut self, cx: &mut Context) -> Poll<Result<()>> {
         let _ = cx;
         Ok(()).into()
@@ -18,6 +18,8 @@ pub struct Thread<'a> {
     op: Option<Op<'a>>,
     timer: Timer,
     owner: Owner,
+    /// The start times of the profiled groups
+    profiles: Vec<(u64, Timestamp)>,
 }
 
 impl<'a> Thread<'a> {
@@ -29,6 +31,7 @@ impl<'a> Thread<'a> {
             op: None,
             timer: Timer::default(),
             owner,
+            profiles: vec![],
         }
     }
 
@@ -113,9 +116,40 @@ impl<'a> Thread<'a> {
             ReceiveRate { stream_id, rate } => {
                 rates.receive.insert(*stream_id, *rate);
             }
+            SendDatagrams { bytes, size } => {
+                self.op = Some(Op::SendDatagrams {
+                    remaining: *bytes,
+                    size: *size,
+                    rate: rates.send_datagram,
+                });
+            }
+            SendDatagramRate { rate } => {
+                rates.send_datagram = Some(*rate);
+            }
+            ReceiveDatagrams { bytes } => {
+                self.op = Some(Op::ReceiveDatagrams {
+                    remaining: *bytes,
+                    rate: rates.receive_datagram,
+                });
+            }
+            ReceiveDatagramRate { rate } => {
+                rates.receive_datagram = Some(*rate);
+            }
+            Migrate => {
+                self.op = Some(Op::Migrate);
+            }
             Trace { trace_id } => {
                 trace.trace(now, *trace_id);
             }
+            ProfileStart { trace_id } => {
+                self.profiles.push((*trace_id, now));
+            }
+            ProfileEnd { trace_id } => {
+                if let Some(idx) = self.profiles.iter().rposition(|(id, _)| id == trace_id) {
+                    let (_, start) = self.profiles.remove(idx);
+                    trace.profile(now, *trace_id, now - start);
+                }
+            }
             Park { checkpoint } => {
                 trace.park(now, *checkpoint);
                 self.op = Some(Op::Wait {
@@ -201,6 +235,37 @@ impl<'a> Thread<'a> {
                 ready!(conn.poll_receive_finish(owner, *id, cx))?;
                 trace.receive_finish(now, *id);
             }
+            Op::SendDatagrams {
+                remaining,
+                size,
+                rate,
+            } => {
+                let size = **size;
+                return self.timer.transfer(remaining, rate, now, cx, |bytes, cx| {
+                    let len = (*bytes).min(size);
+                    let amount = ready!(conn.poll_send_datagram(len, cx))?;
+                    trace.send_datagram(now, amount);
+                    Ok(amount).into()
+                });
+            }
+            Op::ReceiveDatagrams { remaining, rate } => {
+                return self.timer.transfer(remaining, rate, now, cx, |bytes, cx| {
+                    // the connection closed so any outstanding datagrams were lost
+                    let len = if let Some(len) = ready!(conn.poll_receive_datagram(cx))? {
+                        len
+                    } else {
+                        return Ok(0).into();
+                    };
+                    trace.receive_datagram(now, len);
+
+                    // only count up to what's remaining since the datagram was received in full
+                    Ok(len.min(*bytes)).into()
+                });
+            }
+            Op::Migrate => {
+                ready!(conn.poll_migrate(cx))?;
+                trace.migrate(now);
+            }
             Op::Wait { checkpoint } => {
                 ready!(checkpoints.park(*checkpoint));
                 trace.unpark(now, *checkpoint);
@@ -272,6 +337,16 @@ enum Op<'a> {
     ReceiveFinish {
         id: u64,
     },
+    SendDatagrams {
+        remaining: Byte,
+        size: Byte,
+        rate: Option<Rate>,
+    },
+    ReceiveDatagrams {
+        remaining: Byte,
+        rate: Option<Rate>,
+    },
+    Migrate,
     Wait {
         checkpoint: u64,
     },
@@ -15,6 +15,7 @@ pub mod duplex;
 pub mod helper;
 pub mod multiplex;
 pub mod operation;
+pub mod router;
 #[cfg(feature = "s2n-quic")]
 pub mod s2n_quic;
 pub mod scenario;
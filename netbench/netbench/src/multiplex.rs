@@ -432,6 +432,18 @@ impl<T: AsyncRead + AsyncWrite> super::Connection for Connection<T> {
         Ok(()).into()
     }
 
+    fn poll_send_datagram(&mut self, _: u64, _: &mut Context) -> Poll<Result<u64>> {
+        Err("datagrams are not supported over a byte stream".into()).into()
+    }
+
+    fn poll_receive_datagram(&mut self, _: &mut Context) -> Poll<Result<Option<u64>>> {
+        Err("datagrams are not supported over a byte stream".into()).into()
+    }
+
+    fn poll_migrate(&mut self, _: &mut Context) -> Poll<Result<()>> {
+        Err("connection migration is not supported over a byte stream".into()).into()
+    }
+
     fn poll_progress(&mut self, cx: &mut Context) -> Poll<Result<()>> {
         loop {
             if let Some(up_to) = self.stream_controller.transmit() {
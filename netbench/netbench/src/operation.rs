@@ -38,12 +38,34 @@ pub enum Connection {
         #[serde(flatten)]
         rate: Rate,
     },
+    /// Send a specific amount of data in unreliable datagrams of up to `size` bytes
+    SendDatagrams { bytes: Byte, size: Byte },
+    /// Sets the send rate for datagrams
+    SendDatagramRate {
+        #[serde(flatten)]
+        rate: Rate,
+    },
+    /// Receive a specific amount of data in unreliable datagrams
+    ///
+    /// Lost datagrams are never retransmitted so this also completes once the connection closes.
+    ReceiveDatagrams { bytes: Byte },
+    /// Sets the receive rate for datagrams
+    ReceiveDatagramRate {
+        #[serde(flatten)]
+        rate: Rate,
+    },
+    /// Migrate the connection to a new local address
+    Migrate,
     /// Parks the current thread and waits for the checkpoint to be unparked
     Park { checkpoint: u64 },
     /// Notifies the parked checkpoint that it can continue
     Unpark { checkpoint: u64 },
     /// Emit a trace event
     Trace { trace_id: u64 },
+    /// Start measuring the latency of the following operations in the trace group
+    ProfileStart { trace_id: u64 },
+    /// Record the latency since the matching `ProfileStart` in the trace group
+    ProfileEnd { trace_id: u64 },
     /// Perform operations concurrently
     Scope { threads: Vec<Vec<Connection>> },
 }
@@ -74,6 +96,10 @@ pub enum Client {
     Scope { threads: Vec<Vec<Client>> },
 }
 
+/// An operation which changes the network conditions applied by a router
+///
+/// `netbench-router` only applies the conditions to UDP traffic. TCP connections are forwarded
+/// without any impairments since the kernel is responsible for their segments.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum Router {
@@ -0,0 +1,325 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Applies the router operations of a scenario to the packets flowing between clients and
+//! servers
+
+use crate::{
+    driver::timer::{self, Timer, Timestamp},
+    operation as op, scenario, Result, Trace,
+};
+use core::{
+    task::{Context, Poll},
+    time::Duration,
+};
+use rand::Rng;
+
+/// The additional amount of time a reordered packet is held so the packets behind it overtake it
+pub const REORDER_DELAY: Duration = Duration::from_millis(10);
+
+/// The current network conditions of a router
+#[derive(Clone, Debug, Default)]
+pub struct State {
+    /// The conditions for server->client packets
+    pub server: Link,
+    /// The conditions for client->server packets
+    pub client: Link,
+    /// The chance of a client->server packet being sent from a new port
+    pub rebind_port_rate: u32,
+    /// The chance of a client->server packet being sent from a new address
+    pub rebind_address_rate: u32,
+    /// Incremented each time all of the ports are rebound
+    pub rebind_ports: u64,
+    /// Incremented each time all of the addresses are rebound
+    pub rebind_addresses: u64,
+}
+
+impl State {
+    /// Updates the conditions with the operation
+    ///
+    /// `Sleep` and `Trace` operations don't change any conditions and are ignored.
+    pub fn apply(&mut self, op: &op::Router) {
+        use op::Router::*;
+        match *op {
+            Sleep { .. } | Trace { .. } => {}
+            ServerBufferCount { packet_count } => self.server.buffer_count = packet_count,
+            ServerDropRate { packet_count } => self.server.drop_rate = packet_count,
+            ServerReorderRate { packet_count } => self.server.reorder_rate = packet_count,
+            ServerCorruptRate { packet_count } => self.server.corrupt_rate = packet_count,
+            ServerDelay { amount } => self.server.delay = amount,
+            ServerJitter { amount } => self.server.jitter = amount,
+            ServerMtu { mtu } => self.server.mtu = mtu,
+            ClientBufferCount { packet_count } => self.client.buffer_count = packet_count,
+            ClientDropRate { packet_count } => self.client.drop_rate = packet_count,
+            ClientReorderRate { packet_count } => self.client.reorder_rate = packet_count,
+            ClientCorruptRate { packet_count } => self.client.corrupt_rate = packet_count,
+            ClientDelay { amount } => self.client.delay = amount,
+            ClientJitter { amount } => self.client.jitter = amount,
+            ClientMtu { mtu } => self.client.mtu = mtu,
+            ClientRebindPortRate { packet_count } => self.rebind_port_rate = packet_count,
+            ClientRebindAddressRate { packet_count } => self.rebind_address_rate = packet_count,
+            RebindAll { ports, addresses } => {
+                if ports {
+                    self.rebind_ports += 1;
+                }
+                if addresses {
+                    self.rebind_addresses += 1;
+                }
+            }
+        }
+    }
+
+    /// Determines if the client->server packet should be sent from a new port or address
+    pub fn rebind<R: Rng>(&self, rng: &mut R) -> Option<Rebind> {
+        if chance(rng, self.rebind_address_rate) {
+            Some(Rebind::Address)
+        } else if chance(rng, self.rebind_port_rate) {
+            Some(Rebind::Port)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rebind {
+    Port,
+    Address,
+}
+
+/// The conditions for a single direction of traffic
+///
+/// Each rate is expressed as a packet count; a rate of `N` applies to 1 in `N` packets on
+/// average and `0` disables it.
+#[derive(Clone, Debug, Default)]
+pub struct Link {
+    /// The number of packets that can be queued; `0` is unlimited
+    pub buffer_count: u32,
+    pub drop_rate: u32,
+    pub reorder_rate: u32,
+    pub corrupt_rate: u32,
+    pub delay: Duration,
+    /// The maximum amount of random delay added to each packet
+    pub jitter: Duration,
+    /// The largest packet that is forwarded; `0` is unlimited
+    pub mtu: u16,
+}
+
+impl Link {
+    /// Applies the conditions to a packet
+    ///
+    /// `queued` is the number of packets currently waiting to be delivered on the link. The
+    /// packet may be corrupted in place.
+    pub fn process<R: Rng>(&self, rng: &mut R, packet: &mut [u8], queued: usize) -> Verdict {
+        if self.mtu > 0 && packet.len() > self.mtu as usize {
+            return Verdict::Drop;
+        }
+
+        if self.buffer_count > 0 && queued >= self.buffer_count as usize {
+            return Verdict::Drop;
+        }
+
+        if chance(rng, self.drop_rate) {
+            return Verdict::Drop;
+        }
+
+        if !packet.is_empty() && chance(rng, self.corrupt_rate) {
+            let idx = rng.gen_range(0..packet.len());
+            // make sure at least one bit is flipped
+            packet[idx] ^= rng.gen_range(1..=u8::MAX);
+        }
+
+        let mut delay = self.delay;
+
+        if self.jitter > Duration::ZERO {
+            delay += rng.gen_range(Duration::ZERO..=self.jitter);
+        }
+
+        if chance(rng, self.reorder_rate) {
+            delay += REORDER_DELAY;
+        }
+
+        Verdict::Forward { delay }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Drop,
+    Forward { delay: Duration },
+}
+
+#[inline]
+fn chance<R: Rng>(rng: &mut R, packet_count: u32) -> bool {
+    packet_count > 0 && rng.gen_range(0..packet_count) == 0
+}
+
+/// Executes the operations of a router scenario
+pub struct Driver<'a> {
+    ops: &'a [op::Router],
+    index: usize,
+    timer: Timer,
+}
+
+impl<'a> Driver<'a> {
+    pub fn new(scenario: &'a scenario::Router) -> Self {
+        Self {
+            ops: &scenario.scenario,
+            index: 0,
+            timer: Timer::default(),
+        }
+    }
+
+    pub fn poll_with_timer<T: Trace, Ti: crate::timer::Timer>(
+        &mut self,
+        state: &mut State,
+        trace: &mut T,
+        timer: &mut Ti,
+        cx: &mut Context,
+    ) -> Poll<Result<()>> {
+        let now = timer.now();
+        let res = self.poll(state, trace, now);
+
+        if let Some(target) = timer::Provider::next_expiration(&self) {
+            // update the timer with the next expiration
+            let _ = timer.poll(target, cx);
+        };
+
+        res
+    }
+
+    pub fn poll<T: Trace>(
+        &mut self,
+        state: &mut State,
+        trace: &mut T,
+        now: Timestamp,
+    ) -> Poll<Result<()>> {
+        loop {
+            if self.timer.poll(now).is_pending() {
+                return Poll::Pending;
+            }
+            self.timer.cancel();
+
+            let op = if let Some(op) = self.ops.get(self.index) {
+                op
+            } else {
+                // we are all done processing the operations
+                return Ok(()).into();
+            };
+            self.index += 1;
+
+            trace.exec_router(now, op);
+
+            match op {
+                op::Router::Sleep { amount } => {
+                    self.timer.sleep(now, *amount);
+                }
+                op::Router::Trace { trace_id } => {
+                    trace.trace(now, *trace_id);
+                }
+                op => state.apply(op),
+            }
+        }
+    }
+}
+
+impl<'a> timer::Provider for Driver<'a> {
+    #[inline]
+    fn timers<Q: timer::Query>(&self, query: &mut Q) -> timer::Result {
+        self.timer.timers(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    /// Returns a generator which always picks the lowest value in a range
+    fn rng() -> StepRng {
+        StepRng::new(0, 0)
+    }
+
+    #[test]
+    fn link_test() {
+        let mut rng = rng();
+        let mut packet = [0u8; 100];
+
+        let link = Link::default();
+        assert_eq!(
+            link.process(&mut rng, &mut packet, 1000),
+            Verdict::Forward {
+                delay: Duration::ZERO
+            }
+        );
+
+        let link = Link {
+            mtu: 99,
+            ..Default::default()
+        };
+        assert_eq!(link.process(&mut rng, &mut packet, 0), Verdict::Drop);
+
+        let link = Link {
+            buffer_count: 2,
+            ..Default::default()
+        };
+        assert_ne!(link.process(&mut rng, &mut packet, 1), Verdict::Drop);
+        assert_eq!(link.process(&mut rng, &mut packet, 2), Verdict::Drop);
+
+        let link = Link {
+            drop_rate: 1,
+            ..Default::default()
+        };
+        assert_eq!(link.process(&mut rng, &mut packet, 0), Verdict::Drop);
+
+        let link = Link {
+            delay: Duration::from_millis(5),
+            reorder_rate: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            link.process(&mut rng, &mut packet, 0),
+            Verdict::Forward {
+                delay: Duration::from_millis(5) + REORDER_DELAY
+            }
+        );
+
+        let link = Link {
+            corrupt_rate: 1,
+            ..Default::default()
+        };
+        link.process(&mut rng, &mut packet, 0);
+        assert_ne!(packet, [0u8; 100]);
+    }
+
+    #[test]
+    fn driver_test() {
+        let scenario = scenario::Router {
+            name: String::new(),
+            scenario: vec![
+                op::Router::ServerDropRate { packet_count: 10 },
+                op::Router::Sleep {
+                    amount: Duration::from_secs(1),
+                },
+                op::Router::RebindAll {
+                    ports: true,
+                    addresses: false,
+                },
+            ],
+            configuration: Default::default(),
+        };
+        let mut driver = Driver::new(&scenario);
+        let mut state = State::default();
+        let mut trace = crate::trace::Disabled::default();
+        let now = unsafe { Timestamp::from_duration(Duration::from_secs(1)) };
+
+        assert!(driver.poll(&mut state, &mut trace, now).is_pending());
+        assert_eq!(state.server.drop_rate, 10);
+        assert_eq!(state.rebind_ports, 0);
+
+        let now = now + Duration::from_secs(1);
+        assert!(driver.poll(&mut state, &mut trace, now).is_ready());
+        assert_eq!(state.rebind_ports, 1);
+        assert_eq!(state.rebind_addresses, 0);
+    }
+}
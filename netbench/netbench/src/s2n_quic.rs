@@ -11,9 +11,10 @@ use core::{
 use futures::ready;
 use s2n_quic::{
     connection,
+    provider::datagram::default::{Receiver, Sender},
     stream::{LocalStream, PeerStream, SplittableStream},
 };
-use s2n_quic_core::stream::testing::Data;
+use s2n_quic_core::{event::query, stream::testing::Data};
 use std::{
     collections::{hash_map::Entry, HashMap},
     sync::Arc,
@@ -33,6 +34,17 @@ fn stream_error(err: s2n_quic::stream::Error) -> Result<()> {
     Err(err.into())
 }
 
+fn datagram_error(err: query::Error) -> crate::Error {
+    if let query::Error::ContextTypeMismatch = err {
+        return "the default datagram provider is not enabled on the endpoint".into();
+    }
+
+    err.into()
+}
+
+/// The payload used for sending datagrams
+static DATAGRAM_PAYLOAD: [u8; u16::MAX as usize] = [0; u16::MAX as usize];
+
 fn conn_error(err: s2n_quic::connection::Error) -> Result<()> {
     if let s2n_quic::connection::Error::Application { error, .. } = err {
         if *error == 0 {
@@ -254,6 +266,44 @@ impl super::Connection for Connection {
 
         Poll::Ready(Ok(()))
     }
+
+    fn poll_send_datagram(&mut self, len: u64, cx: &mut Context) -> Poll<Result<u64>> {
+        let len = len.min(DATAGRAM_PAYLOAD.len() as u64);
+        let mut payload = Bytes::from_static(&DATAGRAM_PAYLOAD[..len as usize]);
+
+        self.conn
+            .datagram_mut(|sender: &mut Sender| sender.poll_send_datagram(&mut payload, cx))
+            .map_err(datagram_error)?
+            .map_ok(|()| len)
+            .map_err(|err| err.to_string().into())
+    }
+
+    fn poll_receive_datagram(&mut self, cx: &mut Context) -> Poll<Result<Option<u64>>> {
+        let datagram = self
+            .conn
+            .datagram_mut(|receiver: &mut Receiver| match receiver.poll_recv_datagram(cx) {
+                Poll::Ready(datagram) => Poll::Ready(Ok(datagram)),
+                // the receiver is woken when the connection closes, which stops the queue
+                Poll::Pending => match receiver.connection_error() {
+                    Some(error) => Poll::Ready(Err(error)),
+                    None => Poll::Pending,
+                },
+            })
+            .map_err(datagram_error)?;
+
+        match ready!(datagram) {
+            Ok(datagram) => Ok(Some(datagram.len() as u64)).into(),
+            // the connection has been closed
+            Err(err) => conn_error(err).map(|()| None).into(),
+        }
+    }
+
+    fn poll_migrate(&mut self, _cx: &mut Context) -> Poll<Result<()>> {
+        // s2n-quic doesn't expose a way for the application to move a connection to a new local
+        // address. Use the router `rebind_all` operation to exercise the peer's migration
+        // handling instead.
+        Err("connection migration is not supported by the s2n-quic client".into()).into()
+    }
 }
 
 macro_rules! chunks {
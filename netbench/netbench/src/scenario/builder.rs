@@ -24,6 +24,21 @@ macro_rules! trace {
     };
 }
 
+macro_rules! profile {
+    () => {
+        /// Records the latency of the operations added by `f` in the named group
+        pub fn profile<F: FnOnce(&mut Self)>(&mut self, name: &str, f: F) -> &mut Self {
+            let trace_id = self.state.trace(name);
+            self.ops
+                .push(crate::operation::Connection::ProfileStart { trace_id });
+            f(self);
+            self.ops
+                .push(crate::operation::Connection::ProfileEnd { trace_id });
+            self
+        }
+    };
+}
+
 #[macro_use]
 pub mod checkpoint;
 
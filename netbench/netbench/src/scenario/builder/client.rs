@@ -7,7 +7,7 @@ use super::{
     Local,
 };
 use crate::operation as op;
-use core::marker::PhantomData;
+use core::{marker::PhantomData, time::Duration};
 
 #[derive(Debug)]
 pub struct Builder {
@@ -47,6 +47,31 @@ impl Builder {
         connection
     }
 
+    /// Pause for the specified duration before processing the next op
+    pub fn sleep(&mut self, timeout: Duration) -> &mut Self {
+        self.ops.push(op::Client::Sleep { timeout });
+        self
+    }
+
+    /// Opens connections concurrently
+    pub fn scope<F: FnOnce(&mut Scope)>(&mut self, f: F) -> &mut Self {
+        let mut scope = Scope::new(self.id, self.state.clone());
+        f(&mut scope);
+
+        let threads = scope.finish();
+
+        if threads.is_empty() {
+            // no-op
+        } else if threads.len() == 1 {
+            // only a single thread was spawned, which is the same as not spawning it
+            self.ops.extend(threads.into_iter().flatten());
+        } else {
+            self.ops.push(op::Client::Scope { threads });
+        }
+
+        self
+    }
+
     pub fn checkpoint(
         &mut self,
     ) -> (
@@ -61,5 +86,33 @@ impl Builder {
     }
 }
 
+#[derive(Debug)]
+pub struct Scope {
+    id: u64,
+    state: super::State,
+    threads: Vec<Vec<op::Client>>,
+}
+
+impl Scope {
+    fn new(id: u64, state: super::State) -> Self {
+        Self {
+            id,
+            state,
+            threads: vec![],
+        }
+    }
+
+    pub fn spawn<F: FnOnce(&mut Builder)>(&mut self, f: F) -> &mut Self {
+        let mut builder = Builder::new(self.id, self.state.clone());
+        f(&mut builder);
+        self.threads.push(builder.finish());
+        self
+    }
+
+    fn finish(self) -> Vec<Vec<op::Client>> {
+        self.threads
+    }
+}
+
 #[derive(Debug)]
 pub struct Client {}
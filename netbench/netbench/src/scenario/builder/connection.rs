@@ -7,7 +7,10 @@ use super::{
     stream::{ReceiveStream, SendStream, Stream},
     Client, Endpoint, Local, Remote, Scope, Server,
 };
-use crate::operation as op;
+use crate::{
+    operation as op,
+    units::{Byte, Rate},
+};
 use core::marker::PhantomData;
 use std::sync::Arc;
 
@@ -63,6 +66,7 @@ impl<E: Endpoint> Builder<E> {
     sync!(E, Local);
     sleep!();
     trace!();
+    profile!();
 
     pub fn scope<F: FnOnce(&mut Scope<E>)>(&mut self, f: F) -> &mut Self {
         let mut scope = Scope::new(self.state.clone());
@@ -143,6 +147,37 @@ impl<E: Endpoint> Builder<E> {
         self
     }
 
+    /// Sends `bytes` of data in unreliable datagrams of up to `size` bytes
+    ///
+    /// The peer should call one of the `receive_datagrams` methods to read them.
+    pub fn send_datagrams(&mut self, bytes: Byte, size: Byte) -> &mut Self {
+        self.ops.push(op::Connection::SendDatagrams { bytes, size });
+        self
+    }
+
+    pub fn set_datagram_send_rate(&mut self, rate: Rate) -> &mut Self {
+        self.ops.push(op::Connection::SendDatagramRate { rate });
+        self
+    }
+
+    /// Receives `bytes` of data in unreliable datagrams
+    ///
+    /// Lost datagrams are never retransmitted so this also completes once the connection closes.
+    pub fn receive_datagrams(&mut self, bytes: Byte) -> &mut Self {
+        self.ops.push(op::Connection::ReceiveDatagrams { bytes });
+        self
+    }
+
+    /// Receives datagrams until the connection closes
+    pub fn receive_all_datagrams(&mut self) -> &mut Self {
+        self.receive_datagrams(Byte::MAX)
+    }
+
+    pub fn set_datagram_receive_rate(&mut self, rate: Rate) -> &mut Self {
+        self.ops.push(op::Connection::ReceiveDatagramRate { rate });
+        self
+    }
+
     pub(crate) fn finish(self) -> crate::scenario::Connection {
         let peer_streams = self.state.peer_streams.take();
         let ops = self.ops;
@@ -154,6 +189,14 @@ impl<E: Endpoint> Builder<E> {
     }
 }
 
+impl Builder<Client> {
+    /// Migrates the connection to a new local address
+    pub fn migrate(&mut self) -> &mut Self {
+        self.ops.push(op::Connection::Migrate);
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct Connection<Endpoint> {
     pub(crate) state: super::State,
@@ -224,6 +267,12 @@ impl Connect<Client> for Connection<Server> {
     }
 }
 
+impl Connect<Client> for &Connection<Server> {
+    fn connect_to(&self, handle: &Connection<Client>) -> op::Client {
+        (*self).connect_to(handle)
+    }
+}
+
 impl Connect<Client> for Server {
     fn connect_to(&self, handle: &Connection<Client>) -> op::Client {
         self.with(|_| {
@@ -46,10 +46,10 @@ impl Server {
         Self { id, state }
     }
 
-    pub(crate) fn with<F: FnOnce(&mut connection::Builder<Server>)>(
-        &self,
-        f: F,
-    ) -> Connection<Server> {
+    /// Creates a connection template with operations performed by the server
+    ///
+    /// Clients can pass the returned connection to `connect_to`.
+    pub fn with<F: FnOnce(&mut connection::Builder<Server>)>(&self, f: F) -> Connection<Server> {
         let mut builder = connection::Builder::new(self.state.connection());
         f(&mut builder);
 
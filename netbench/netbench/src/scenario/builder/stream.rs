@@ -68,6 +68,7 @@ impl<Endpoint, Location> Stream<Endpoint, Location> {
     sync!(Endpoint, Location);
     sleep!();
     trace!();
+    profile!();
 
     pub(crate) fn new(id: u64, state: connection::State) -> Self {
         Self {
@@ -117,6 +118,7 @@ impl<Endpoint, Location> SendStream<Endpoint, Location> {
     sync!(Endpoint, Location);
     sleep!();
     trace!();
+    profile!();
 
     pub(crate) fn new(id: u64, state: connection::State) -> Self {
         Self {
@@ -148,6 +150,7 @@ impl<Endpoint, Location> ReceiveStream<Endpoint, Location> {
     sync!(Endpoint, Location);
     sleep!();
     trace!();
+    profile!();
 
     pub(crate) fn new(id: u64, state: connection::State) -> Self {
         Self {
@@ -190,3 +190,86 @@ scenario_test!(long_chain_cert, |scenario| {
         });
     });
 });
+
+scenario_test!(datagrams, |scenario| {
+    let server = scenario.create_server();
+
+    let server = server.with(|conn| {
+        conn.set_datagram_receive_rate(10.kilobytes() / 50.millis());
+        conn.receive_all_datagrams();
+    });
+
+    scenario.create_client(|client| {
+        client.connect_to(&server, |conn| {
+            conn.set_datagram_send_rate(10.kilobytes() / 50.millis());
+            conn.send_datagrams(100.kilobytes(), 1200.bytes());
+            conn.migrate();
+            conn.send_datagrams(100.kilobytes(), 1200.bytes());
+        });
+    });
+});
+
+scenario_test!(profile, |scenario| {
+    let server = scenario.create_server();
+
+    scenario.create_client(|client| {
+        client.connect_to(server, |conn| {
+            conn.profile("connection", |conn| {
+                conn.open_bidirectional_stream(
+                    |local| {
+                        local.profile("request", |local| {
+                            local.send(1.kilobytes());
+                            local.receive(10.kilobytes());
+                        });
+                    },
+                    |peer| {
+                        peer.receive(1.kilobytes());
+                        peer.send(10.kilobytes());
+                    },
+                );
+            });
+        });
+    });
+});
+
+scenario_test!(client_sleep, |scenario| {
+    let server = scenario.create_server();
+
+    scenario.create_client(|client| {
+        client.sleep(100.millis());
+        client.connect_to(server, |conn| {
+            conn.open_send_stream(
+                |local| {
+                    local.send(1.kilobytes());
+                },
+                |peer| {
+                    peer.receive(1.kilobytes());
+                },
+            );
+        });
+    });
+});
+
+scenario_test!(client_scope, |scenario| {
+    let server = scenario.create_server();
+
+    scenario.create_client(|client| {
+        client.scope(|scope| {
+            for idx in 0..2u32 {
+                scope.spawn(|client| {
+                    client.sleep(10.millis() * idx);
+                    client.connect_to(&server, |conn| {
+                        conn.open_send_stream(
+                            |local| {
+                                local.send(1.kilobytes());
+                            },
+                            |peer| {
+                                peer.receive(1.kilobytes());
+                            },
+                        );
+                    });
+                });
+            }
+        });
+    });
+});
@@ -72,6 +72,8 @@ pub struct Stats {
     pub send: HashMap<StreamId, Stat>,
     #[serde(default, skip_serializing_if = "is_default")]
     pub receive: HashMap<StreamId, Stat>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub latency: HashMap<String, Latency>,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -82,6 +84,26 @@ pub struct Stat {
     pub total: u64,
 }
 
+/// The latency distribution of a profiled group, or the time spent parked at a checkpoint, since
+/// the start of the run, in nanoseconds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Latency {
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub count: u64,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub min: u64,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub p50: u64,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub p90: u64,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub p99: u64,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub p999: u64,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub max: u64,
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct StreamId {
     pub connection_id: u64,
@@ -29,6 +29,12 @@ pub trait Trace {
         let _ = op;
     }
 
+    #[inline(always)]
+    fn exec_router(&mut self, now: Timestamp, op: &op::Router) {
+        let _ = now;
+        let _ = op;
+    }
+
     #[inline(always)]
     fn enter(&mut self, now: Timestamp, scope: u64, thread: usize) {
         let _ = now;
@@ -67,6 +73,23 @@ pub trait Trace {
         let _ = stream_id;
     }
 
+    #[inline(always)]
+    fn send_datagram(&mut self, now: Timestamp, len: u64) {
+        let _ = now;
+        let _ = len;
+    }
+
+    #[inline(always)]
+    fn receive_datagram(&mut self, now: Timestamp, len: u64) {
+        let _ = now;
+        let _ = len;
+    }
+
+    #[inline(always)]
+    fn migrate(&mut self, now: Timestamp) {
+        let _ = now;
+    }
+
     #[inline(always)]
     fn accept(&mut self, now: Timestamp, stream_id: u64) {
         let _ = now;
@@ -85,6 +108,13 @@ pub trait Trace {
         let _ = id;
     }
 
+    #[inline(always)]
+    fn profile(&mut self, now: Timestamp, id: u64, time: Duration) {
+        let _ = now;
+        let _ = id;
+        let _ = time;
+    }
+
     #[inline(always)]
     fn park(&mut self, now: Timestamp, id: u64) {
         let _ = now;
@@ -124,6 +154,12 @@ impl<A: Trace, B: Trace> Trace for (A, B) {
         self.1.exec_client(now, op);
     }
 
+    #[inline(always)]
+    fn exec_router(&mut self, now: Timestamp, op: &op::Router) {
+        self.0.exec_router(now, op);
+        self.1.exec_router(now, op);
+    }
+
     #[inline(always)]
     fn enter(&mut self, now: Timestamp, scope: u64, thread: usize) {
         self.0.enter(now, scope, thread);
@@ -160,6 +196,24 @@ impl<A: Trace, B: Trace> Trace for (A, B) {
         self.1.receive_finish(now, stream_id);
     }
 
+    #[inline(always)]
+    fn send_datagram(&mut self, now: Timestamp, len: u64) {
+        self.0.send_datagram(now, len);
+        self.1.send_datagram(now, len);
+    }
+
+    #[inline(always)]
+    fn receive_datagram(&mut self, now: Timestamp, len: u64) {
+        self.0.receive_datagram(now, len);
+        self.1.receive_datagram(now, len);
+    }
+
+    #[inline(always)]
+    fn migrate(&mut self, now: Timestamp) {
+        self.0.migrate(now);
+        self.1.migrate(now);
+    }
+
     #[inline(always)]
     fn accept(&mut self, now: Timestamp, stream_id: u64) {
         self.0.accept(now, stream_id);
@@ -178,6 +232,12 @@ impl<A: Trace, B: Trace> Trace for (A, B) {
         self.1.trace(now, id)
     }
 
+    #[inline(always)]
+    fn profile(&mut self, now: Timestamp, id: u64, time: Duration) {
+        self.0.profile(now, id, time);
+        self.1.profile(now, id, time);
+    }
+
     #[inline]
     fn park(&mut self, now: Timestamp, id: u64) {
         self.0.park(now, id);
@@ -219,6 +279,13 @@ impl<T: Trace> Trace for Option<T> {
         }
     }
 
+    #[inline(always)]
+    fn exec_router(&mut self, now: Timestamp, op: &op::Router) {
+        if let Some(t) = self.as_mut() {
+            t.exec_router(now, op);
+        }
+    }
+
     #[inline(always)]
     fn enter(&mut self, now: Timestamp, scope: u64, thread: usize) {
         if let Some(t) = self.as_mut() {
@@ -261,6 +328,27 @@ impl<T: Trace> Trace for Option<T> {
         }
     }
 
+    #[inline(always)]
+    fn send_datagram(&mut self, now: Timestamp, len: u64) {
+        if let Some(t) = self.as_mut() {
+            t.send_datagram(now, len);
+        }
+    }
+
+    #[inline(always)]
+    fn receive_datagram(&mut self, now: Timestamp, len: u64) {
+        if let Some(t) = self.as_mut() {
+            t.receive_datagram(now, len);
+        }
+    }
+
+    #[inline(always)]
+    fn migrate(&mut self, now: Timestamp) {
+        if let Some(t) = self.as_mut() {
+            t.migrate(now);
+        }
+    }
+
     #[inline(always)]
     fn accept(&mut self, now: Timestamp, stream_id: u64) {
         if let Some(t) = self.as_mut() {
@@ -282,6 +370,13 @@ impl<T: Trace> Trace for Option<T> {
         }
     }
 
+    #[inline(always)]
+    fn profile(&mut self, now: Timestamp, id: u64, time: Duration) {
+        if let Some(t) = self.as_mut() {
+            t.profile(now, id, time);
+        }
+    }
+
     #[inline]
     fn park(&mut self, now: Timestamp, id: u64) {
         if let Some(t) = self.as_mut() {
@@ -449,6 +544,13 @@ impl<O: Output> Trace for Logger<O> {
         }
     }
 
+    #[inline(always)]
+    fn exec_router(&mut self, now: Timestamp, op: &op::Router) {
+        if self.verbose {
+            self.log(now, format_args!("exec: {:?}", op));
+        }
+    }
+
     #[inline(always)]
     fn enter(&mut self, _now: Timestamp, scope: u64, thread: usize) {
         self.scope.push((scope, thread));
@@ -479,6 +581,21 @@ impl<O: Output> Trace for Logger<O> {
         self.log(now, format_args!("rfin[{}]", stream_id));
     }
 
+    #[inline(always)]
+    fn send_datagram(&mut self, now: Timestamp, len: u64) {
+        self.log(now, format_args!("sdgm={}", len));
+    }
+
+    #[inline(always)]
+    fn receive_datagram(&mut self, now: Timestamp, len: u64) {
+        self.log(now, format_args!("rdgm={}", len));
+    }
+
+    #[inline(always)]
+    fn migrate(&mut self, now: Timestamp) {
+        self.log(now, format_args!("migr"));
+    }
+
     #[inline(always)]
     fn accept(&mut self, now: Timestamp, stream_id: u64) {
         self.log(now, format_args!("acpt[{}]", stream_id));
@@ -507,6 +624,11 @@ impl<O: Output> Trace for Logger<O> {
         }
     }
 
+    #[inline(always)]
+    fn profile(&mut self, now: Timestamp, id: u64, time: Duration) {
+        self.log(now, format_args!("prof[{}]={:?}us", id, time.as_micros()));
+    }
+
     #[inline(always)]
     fn park(&mut self, now: Timestamp, id: u64) {
         self.log(now, format_args!("park[{}]", id));
@@ -570,6 +692,14 @@ impl Trace for Throughput {
     fn receive(&mut self, _now: Timestamp, _stream_id: u64, len: u64) {
         self.0.results.rx.fetch_add(len, Ordering::Relaxed);
     }
+
+    fn send_datagram(&mut self, _now: Timestamp, len: u64) {
+        self.0.results.tx.fetch_add(len, Ordering::Relaxed);
+    }
+
+    fn receive_datagram(&mut self, _now: Timestamp, len: u64) {
+        self.0.results.rx.fetch_add(len, Ordering::Relaxed);
+    }
 }
 
 impl Drop for ThroughputInner {
@@ -55,6 +55,26 @@ impl Trace for Usdt {
         );
     }
 
+    #[inline(never)]
+    fn send_datagram(&mut self, _now: Timestamp, len: u64) {
+        probe!(netbench, netbench__send__datagram, self.connection_id, len);
+    }
+
+    #[inline(never)]
+    fn receive_datagram(&mut self, _now: Timestamp, len: u64) {
+        probe!(
+            netbench,
+            netbench__receive__datagram,
+            self.connection_id,
+            len
+        );
+    }
+
+    #[inline(never)]
+    fn migrate(&mut self, _now: Timestamp) {
+        probe!(netbench, netbench__migrate, self.connection_id);
+    }
+
     #[inline(never)]
     fn accept(&mut self, _now: Timestamp, stream_id: u64) {
         probe!(netbench, netbench__accept, self.connection_id, stream_id);
@@ -70,6 +90,17 @@ impl Trace for Usdt {
         probe!(netbench, netbench__trace, self.connection_id, id);
     }
 
+    #[inline(never)]
+    fn profile(&mut self, _now: Timestamp, id: u64, time: Duration) {
+        probe!(
+            netbench,
+            netbench__profile,
+            self.connection_id,
+            id,
+            time.as_nanos() as u64
+        );
+    }
+
     #[inline(never)]
     fn park(&mut self, _now: Timestamp, id: u64) {
         probe!(netbench, netbench__park, self.connection_id, id);
@@ -110,7 +110,7 @@ impl core::str::FromStr for Byte {
             })
             .unwrap_or(s.len());
 
-        let mut v = Self(s[..number_index].parse()?);
+        let number = &s[..number_index];
 
         let mut suffix = s[number_index..].trim();
         let mut is_bits = false;
@@ -122,7 +122,7 @@ impl core::str::FromStr for Byte {
             suffix = s;
         }
 
-        v.0 *= *match suffix.trim() {
+        let unit = *match suffix.trim() {
             "" => 1.bytes(),
             "K" | "k" => 1.kilobytes(),
             "Ki" | "ki" => 1.kibibytes(),
@@ -135,6 +135,12 @@ impl core::str::FromStr for Byte {
             _ => return Err(format!("invalid bytes: {:?}", s).into()),
         };
 
+        let mut v = match number.parse::<u64>() {
+            Ok(v) => Self(v * unit),
+            // the display format includes fractional values, e.g. `1.20KB`
+            Err(_) => Self((number.parse::<f64>()? * unit as f64).round() as u64),
+        };
+
         if is_bits {
             // round up to the nearest byte
             if v.0 % 8 != 0 {
@@ -227,4 +233,11 @@ mod tests {
             p("42TiB"),
         ]);
     }
+
+    #[test]
+    fn display_round_trip_test() {
+        for value in [1200.bytes(), 1.kilobytes(), 1500.kilobytes(), 42.gigabytes()] {
+            assert_eq!(p(&value.to_string()).unwrap(), value);
+        }
+    }
 }
@@ -57,6 +57,8 @@ impl core::str::FromStr for Rate {
 pub(crate) struct Rates {
     pub send: HashMap<u64, Rate>,
     pub receive: HashMap<u64, Rate>,
+    pub send_datagram: Option<Rate>,
+    pub receive_datagram: Option<Rate>,
 }
 
 #[cfg(test)]
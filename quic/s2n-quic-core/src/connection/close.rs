@@ -158,3 +158,354 @@ impl Formatter for Production {
         transport::Error::APPLICATION_ERROR.into()
     }
 }
+
+#[cfg(feature = "alloc")]
+pub use policy::Policy;
+
+#[cfg(feature = "alloc")]
+pub mod policy {
+    use super::*;
+    use crate::inet::IpAddress;
+    use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+    /// The default maximum length of a reason phrase
+    ///
+    /// This is well below the minimum MTU so the CONNECTION_CLOSE frame always fits in a single
+    /// packet, along with the packet headers and authentication tag.
+    const DEFAULT_MAX_REASON_LEN: usize = 256;
+
+    #[derive(Clone, Debug)]
+    enum Reason {
+        /// Replace the reason with a fixed phrase
+        Phrase(String),
+        /// Send the reason provided by the library
+        Original,
+    }
+
+    /// A formatter that only sends reason phrases included in an allow-list
+    ///
+    /// Error codes are sanitized in the same way as the [`Production`] formatter. If the
+    /// resulting code has an entry in the allow-list, the configured reason phrase is included in
+    /// the frame. All other reasons are removed.
+    ///
+    /// Peers with an address in one of the configured verbose ranges receive the errors
+    /// unmodified, in the same way as the [`Development`] formatter.
+    ///
+    /// All reasons are truncated to the configured maximum length.
+    ///
+    /// ```rust
+    /// use s2n_quic_core::{application, connection::close::Policy, transport};
+    ///
+    /// let formatter = Policy::builder()
+    ///     .with_transport_reason(transport::Error::CONNECTION_REFUSED, "server is overloaded")
+    ///     .with_application_reason(application::Error::new(0x100).unwrap(), "upstream unavailable")
+    ///     .with_verbose_range([10, 0, 0, 0], 8)
+    ///     .build();
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct Policy {
+        transport_reasons: BTreeMap<u64, Reason>,
+        application_reasons: BTreeMap<u64, String>,
+        verbose_ranges: Vec<(IpAddress, u8)>,
+        max_reason_len: usize,
+    }
+
+    impl Default for Policy {
+        fn default() -> Self {
+            Self::builder().build()
+        }
+    }
+
+    impl Policy {
+        pub fn builder() -> Builder {
+            Builder::default()
+        }
+
+        /// Returns `true` if the peer should receive unmodified errors
+        fn is_verbose(&self, context: &Context) -> bool {
+            let remote_ip = context.remote_address.ip().unmap();
+
+            self.verbose_ranges
+                .iter()
+                .any(|(range, prefix_len)| in_range(&remote_ip, range, *prefix_len))
+        }
+
+        /// Truncates the reason to the maximum length without splitting a UTF-8 character
+        fn truncate<'a>(&self, reason: &'a str) -> Option<&'a [u8]> {
+            let mut len = reason.len().min(self.max_reason_len);
+            while !reason.is_char_boundary(len) {
+                len -= 1;
+            }
+
+            let reason = &reason.as_bytes()[..len];
+
+            if reason.is_empty() {
+                None
+            } else {
+                Some(reason)
+            }
+        }
+
+        fn with_reason<'a>(
+            &self,
+            mut frame: ConnectionClose<'a>,
+            reason: &'a str,
+        ) -> ConnectionClose<'a> {
+            frame.reason = self.truncate(reason);
+            frame
+        }
+    }
+
+    impl Formatter for Policy {
+        fn format_transport_error(
+            &self,
+            context: &Context,
+            error: transport::Error,
+        ) -> ConnectionClose {
+            if self.is_verbose(context) {
+                return self.with_reason(error.into(), error.reason);
+            }
+
+            let frame = Production.format_transport_error(context, error);
+
+            match self.transport_reasons.get(&frame.error_code.as_u64()) {
+                Some(Reason::Phrase(phrase)) => self.with_reason(frame, phrase),
+                // only include the original reason if the code wasn't rewritten
+                Some(Reason::Original) if frame.error_code == error.code.as_varint() => {
+                    self.with_reason(frame, error.reason)
+                }
+                _ => frame,
+            }
+        }
+
+        fn format_application_error(
+            &self,
+            context: &Context,
+            error: application::Error,
+        ) -> ConnectionClose {
+            let frame = Production.format_application_error(context, error);
+
+            match self.application_reasons.get(&*error) {
+                Some(phrase) => self.with_reason(frame, phrase),
+                None => frame,
+            }
+        }
+
+        fn format_early_transport_error(
+            &self,
+            context: &Context,
+            error: transport::Error,
+        ) -> ConnectionClose {
+            self.format_transport_error(context, error)
+        }
+
+        fn format_early_application_error(
+            &self,
+            context: &Context,
+            error: application::Error,
+        ) -> ConnectionClose {
+            // The reason phrase MUST be cleared for early packets so always defer to Production
+            Production.format_early_application_error(context, error)
+        }
+    }
+
+    /// Returns `true` if the first `prefix_len` bits of `ip` and `range` are equal
+    fn in_range(ip: &IpAddress, range: &IpAddress, prefix_len: u8) -> bool {
+        fn prefix_eq(a: &[u8], b: &[u8], prefix_len: u8) -> bool {
+            let prefix_len = prefix_len as usize;
+            let bytes = prefix_len / 8;
+            let bits = prefix_len % 8;
+
+            if a[..bytes] != b[..bytes] {
+                return false;
+            }
+
+            if bits == 0 {
+                return true;
+            }
+
+            let mask = !(0xffu8 >> bits);
+            a[bytes] & mask == b[bytes] & mask
+        }
+
+        match (ip, range) {
+            (IpAddress::Ipv4(ip), IpAddress::Ipv4(range)) => {
+                prefix_eq(&ip.octets, &range.octets, prefix_len.min(32))
+            }
+            (IpAddress::Ipv6(ip), IpAddress::Ipv6(range)) => {
+                prefix_eq(&ip.octets, &range.octets, prefix_len.min(128))
+            }
+            _ => false,
+        }
+    }
+
+    /// Builds a [`Policy`] formatter
+    #[derive(Clone, Debug)]
+    pub struct Builder {
+        policy: Policy,
+    }
+
+    impl Default for Builder {
+        fn default() -> Self {
+            Self {
+                policy: Policy {
+                    transport_reasons: BTreeMap::new(),
+                    application_reasons: BTreeMap::new(),
+                    verbose_ranges: Vec::new(),
+                    max_reason_len: DEFAULT_MAX_REASON_LEN,
+                },
+            }
+        }
+    }
+
+    impl Builder {
+        /// Sends `phrase` as the reason for transport errors with the same code as `error`
+        #[must_use]
+        pub fn with_transport_reason<P: Into<String>>(
+            mut self,
+            error: transport::Error,
+            phrase: P,
+        ) -> Self {
+            self.policy
+                .transport_reasons
+                .insert(error.code.as_u64(), Reason::Phrase(phrase.into()));
+            self
+        }
+
+        /// Sends the reason provided by the library for transport errors with the same code as
+        /// `error`
+        #[must_use]
+        pub fn with_original_transport_reason(mut self, error: transport::Error) -> Self {
+            self.policy
+                .transport_reasons
+                .insert(error.code.as_u64(), Reason::Original);
+            self
+        }
+
+        /// Sends `phrase` as the reason for the application error
+        #[must_use]
+        pub fn with_application_reason<P: Into<String>>(
+            mut self,
+            error: application::Error,
+            phrase: P,
+        ) -> Self {
+            self.policy
+                .application_reasons
+                .insert(*error, phrase.into());
+            self
+        }
+
+        /// Sends unmodified errors to peers with an address in the given range
+        ///
+        /// IPv4-mapped IPv6 peer addresses are matched against IPv4 ranges.
+        #[must_use]
+        pub fn with_verbose_range<A: Into<IpAddress>>(mut self, ip: A, prefix_len: u8) -> Self {
+            self.policy
+                .verbose_ranges
+                .push((ip.into().unmap(), prefix_len));
+            self
+        }
+
+        /// Sets the maximum length of reason phrases, in bytes
+        ///
+        /// Longer reasons are truncated. Defaults to 256.
+        #[must_use]
+        pub fn with_max_reason_len(mut self, len: usize) -> Self {
+            self.policy.max_reason_len = len;
+            self
+        }
+
+        pub fn build(self) -> Policy {
+            self.policy
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::inet::SocketAddressV4;
+
+        fn context(ip: [u8; 4]) -> SocketAddress {
+            SocketAddressV4::new(ip, 443).into()
+        }
+
+        #[test]
+        fn allow_list_test() {
+            let formatter = Policy::builder()
+                .with_transport_reason(transport::Error::CONNECTION_REFUSED, "busy")
+                .with_original_transport_reason(transport::Error::FLOW_CONTROL_ERROR)
+                .with_application_reason(application::Error::new(7).unwrap(), "gone")
+                .build();
+
+            let addr = context([192, 0, 2, 1]);
+            let context = Context::new(&addr);
+
+            let error = transport::Error::CONNECTION_REFUSED.with_reason("too many handshakes");
+            let frame = formatter.format_transport_error(&context, error);
+            assert_eq!(frame.reason, Some(&b"busy"[..]));
+
+            let error = transport::Error::FLOW_CONTROL_ERROR.with_reason("stream limit");
+            let frame = formatter.format_transport_error(&context, error);
+            assert_eq!(frame.reason, Some(&b"stream limit"[..]));
+
+            // reasons which aren't allowed are removed
+            let error = transport::Error::PROTOCOL_VIOLATION.with_reason("secret");
+            let frame = formatter.format_transport_error(&context, error);
+            assert_eq!(frame.reason, None);
+
+            // internal errors are still rewritten
+            let error = transport::Error::INTERNAL_ERROR.with_reason("secret");
+            let frame = formatter.format_transport_error(&context, error);
+            assert_eq!(
+                frame.error_code,
+                transport::Error::PROTOCOL_VIOLATION.code.as_varint()
+            );
+            assert_eq!(frame.reason, None);
+
+            let error = application::Error::new(7).unwrap();
+            let frame = formatter.format_application_error(&context, error);
+            assert_eq!(frame.reason, Some(&b"gone"[..]));
+            let frame = formatter.format_early_application_error(&context, error);
+            assert_eq!(frame.reason, None);
+        }
+
+        #[test]
+        fn verbose_range_test() {
+            let formatter = Policy::builder()
+                .with_verbose_range([10, 0, 0, 0], 8)
+                .build();
+
+            let error = transport::Error::INTERNAL_ERROR.with_reason("secret");
+
+            let addr = context([10, 1, 2, 3]);
+            let frame = formatter.format_transport_error(&Context::new(&addr), error);
+            assert_eq!(frame.error_code, error.code.as_varint());
+            assert_eq!(frame.reason, Some(&b"secret"[..]));
+
+            let addr = context([11, 1, 2, 3]);
+            let frame = formatter.format_transport_error(&Context::new(&addr), error);
+            assert_ne!(frame.error_code, error.code.as_varint());
+            assert_eq!(frame.reason, None);
+        }
+
+        #[test]
+        fn truncate_test() {
+            let formatter = Policy::builder().with_max_reason_len(4).build();
+
+            assert_eq!(formatter.truncate("abcdef"), Some(&b"abcd"[..]));
+            assert_eq!(formatter.truncate("abc"), Some(&b"abc"[..]));
+            assert_eq!(formatter.truncate(""), None);
+            // don't split multi-byte characters
+            assert_eq!(formatter.truncate("abcé"), Some(&b"abc"[..]));
+        }
+
+        #[test]
+        fn in_range_test() {
+            let range = IpAddress::from([192, 0, 2, 0]);
+            assert!(in_range(&[192, 0, 2, 255].into(), &range, 24));
+            assert!(!in_range(&[192, 0, 3, 0].into(), &range, 24));
+            assert!(in_range(&[192, 0, 3, 0].into(), &range, 23));
+            assert!(in_range(&[1, 2, 3, 4].into(), &range, 0));
+        }
+    }
+}
@@ -3,7 +3,7 @@
 
 use crate::{
     connection::ProcessingError,
-    crypto::{application::limited, OneRttKey, ProtectedPayload},
+    crypto::{application::limited, BatchPacket, OneRttKey, ProtectedPayload},
     packet::{
         encoding::PacketEncodingError,
         number::PacketNumber,
@@ -31,6 +31,9 @@ pub struct KeySet<K> {
     /// The number of times the key has been rotated
     generation: u16,
 
+    /// Set when the application explicitly requested a key update
+    key_update_requested: bool,
+
     /// Set of keys for the current and next phase
     crypto: KeyArray<K>,
 
@@ -66,6 +69,7 @@ impl<K: OneRttKey> KeySet<K> {
             packet_decryption_failures: 0,
             aead_integrity_limit,
             generation: 0,
+            key_update_requested: false,
             crypto: KeyArray([active_key, next_key]),
             limits,
         }
@@ -75,6 +79,7 @@ impl<K: OneRttKey> KeySet<K> {
     fn rotate_phase(&mut self) {
         self.generation += 1;
         self.key_phase = KeyPhase::next_phase(self.key_phase);
+        self.key_update_requested = false;
     }
 
     /// Derive a new key based on the active key, and store it in the non-active slot
@@ -106,6 +111,13 @@ impl<K: OneRttKey> KeySet<K> {
         self.key_derivation_timer.is_armed()
     }
 
+    /// Initiates a key update, regardless of how many packets the active key has encrypted
+    ///
+    /// The update completes once the peer responds with a packet in the next key phase.
+    pub fn initiate_key_update(&mut self) {
+        self.key_update_requested = true;
+    }
+
     /// Passes the key for the the requested phase to a callback function. Integrity limits are
     /// enforced.
     ///
@@ -208,13 +220,54 @@ impl<K: OneRttKey> KeySet<K> {
         }
     }
 
+    /// Decrypts a batch of packets protected with the active key phase
+    ///
+    /// Packets protected with the other key phase should be passed individually to
+    /// [`Self::decrypt_packet`] so key updates are processed in order.
+    pub fn decrypt_batch(&mut self, packets: &mut [BatchPacket]) -> Result<(), ProcessingError> {
+        let key_phase = self.key_phase();
+        let key = &mut self.crypto[key_phase];
+
+        crate::crypto::decrypt_batch(key.key(), packets);
+
+        for packet in packets.iter() {
+            key.on_packet_decryption(&self.limits);
+
+            //= https://www.rfc-editor.org/rfc/rfc9001#section-6.6
+            //# In addition to counting packets sent, endpoints MUST count the number
+            //# of received packets that fail authentication during the lifetime of a
+            //# connection.
+            if packet.result.is_err() {
+                self.packet_decryption_failures += 1;
+            }
+        }
+
+        //= https://www.rfc-editor.org/rfc/rfc9001#section-6.6
+        //# If the total number of received packets that fail
+        //# authentication within the connection, across all keys, exceeds the
+        //# integrity limit for the selected AEAD, the endpoint MUST immediately
+        //# close the connection with a connection error of type
+        //# AEAD_LIMIT_REACHED and not process any more packets.
+        if self.decryption_error_count() > self.aead_integrity_limit {
+            return Err(transport::Error::AEAD_LIMIT_REACHED.into());
+        }
+
+        Ok(())
+    }
+
     /// This is the KeyPhase that should be used to encrypt a given packet.
     pub fn encryption_phase(&self) -> KeyPhase {
+        // The next key is only derived after the derivation timer expires. Until then, the
+        // non-active slot holds the previous generation of keys, which must not be reused.
+        if self.key_update_in_progress() {
+            return self.key_phase();
+        }
+
         //= https://www.rfc-editor.org/rfc/rfc9001#section-6.6
         //# Endpoints MUST initiate a key update
         //# before sending more protected packets than the confidentiality limit
         //# for the selected AEAD permits.
-        if self.active_key().needs_update(&self.limits) {
+        if self.key_update_requested || self.active_key().needs_update(&self.limits) {
             return KeyPhase::next_phase(self.key_phase());
         }
 
@@ -389,6 +442,31 @@ mod tests {
         assert_eq!(keyset.active_key().key().derivations, 1);
     }
 
+    #[test]
+    fn test_initiate_key_update() {
+        let mut clock = Clock::default();
+        let mut keyset = KeySet::new(TestKey::default(), Default::default());
+
+        assert_eq!(keyset.encryption_phase(), KeyPhase::Zero);
+
+        keyset.initiate_key_update();
+        assert_eq!(keyset.encryption_phase(), KeyPhase::One);
+
+        // the peer responded in the next phase
+        keyset.rotate_phase();
+        keyset.set_derivation_timer(clock.get_time() + Duration::from_millis(10));
+        assert_eq!(keyset.encryption_phase(), KeyPhase::One);
+
+        // subsequent updates wait until the next key is derived
+        keyset.initiate_key_update();
+        assert_eq!(keyset.encryption_phase(), KeyPhase::One);
+
+        clock.inc_by(Duration::from_millis(10));
+        keyset.on_timeout(clock.get_time());
+        assert_eq!(keyset.encryption_phase(), KeyPhase::Zero);
+        assert_eq!(keyset.crypto[KeyPhase::Zero].key().derivations, 2);
+    }
+
     #[test]
     fn test_key_derivation() {
         let mut keyset = KeySet::new(TestKey::default(), Default::default());
@@ -438,6 +516,43 @@ mod tests {
         assert_eq!(keyset.decryption_error_count(), 1);
     }
 
+    #[test]
+    fn test_batch_decryption_failure_counter() {
+        let key = TestKey {
+            fail_on_decrypt: true,
+            ..Default::default()
+        };
+        let mut keyset = KeySet::new(key, Default::default());
+        let mut data = [[0; 128]; 3];
+        let remote_address = SocketAddress::default();
+        let connection_info = ConnectionInfo::new(&remote_address);
+
+        let (headers, mut packets): (Vec<_>, Vec<_>) = data
+            .iter_mut()
+            .map(|data| {
+                let decoder_buffer = DecoderBufferMut::new(data);
+                let (encoded_packet, _remaining) =
+                    ProtectedShort::decode(0, decoder_buffer, &connection_info, &20).unwrap();
+
+                encoded_packet
+                    .unprotect(
+                        &TestHeaderKey::default(),
+                        PacketNumberSpace::ApplicationData.new_packet_number(VarInt::from_u8(0)),
+                    )
+                    .unwrap()
+                    .into_batch()
+            })
+            .unzip();
+
+        assert_eq!(keyset.decryption_error_count(), 0);
+        assert!(keyset.decrypt_batch(&mut packets).is_ok());
+        assert_eq!(keyset.decryption_error_count(), 3);
+
+        for (header, packet) in headers.into_iter().zip(packets) {
+            assert!(header.decrypted(packet).is_err());
+        }
+    }
+
     //= https://www.rfc-editor.org/rfc/rfc9001#section-6.6
     //= type=test
     //# If the total number of received packets that fail
@@ -28,6 +28,39 @@ pub trait HeaderKey: Send {
     /// Returns the sample size needed for the header protection
     /// buffer
     fn sealing_sample_len(&self) -> usize;
+
+    /// Derives header protection masks for a batch of sample buffers, to be
+    /// used for opening packets.
+    ///
+    /// `masks` must be the same length as `ciphertext_samples`. The default derives each mask
+    /// individually; implementations should override this if the masks can be derived together.
+    #[inline]
+    fn opening_header_protection_masks(
+        &self,
+        ciphertext_samples: &[&[u8]],
+        masks: &mut [HeaderProtectionMask],
+    ) {
+        debug_assert_eq!(ciphertext_samples.len(), masks.len());
+        for (sample, mask) in ciphertext_samples.iter().zip(masks.iter_mut()) {
+            *mask = self.opening_header_protection_mask(sample);
+        }
+    }
+
+    /// Derives header protection masks for a batch of sample buffers, to be
+    /// used for sealing packets.
+    ///
+    /// `masks` must be the same length as `ciphertext_samples`.
+    #[inline]
+    fn sealing_header_protection_masks(
+        &self,
+        ciphertext_samples: &[&[u8]],
+        masks: &mut [HeaderProtectionMask],
+    ) {
+        debug_assert_eq!(ciphertext_samples.len(), masks.len());
+        for (sample, mask) in ciphertext_samples.iter().zip(masks.iter_mut()) {
+            *mask = self.sealing_header_protection_mask(sample);
+        }
+    }
 }
 
 //= https://www.rfc-editor.org/rfc/rfc9001#section-5.4.1
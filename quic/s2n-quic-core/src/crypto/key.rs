@@ -31,6 +31,49 @@ pub trait Key: Send {
     fn aead_integrity_limit(&self) -> u64;
 
     fn cipher_suite(&self) -> crate::crypto::tls::CipherSuite;
+
+    /// Decrypt a batch of payloads under the same key
+    ///
+    /// The outcome for each payload is stored in its `result` field. Implementations
+    /// should override this if they can amortize the per-payload setup cost.
+    #[inline]
+    fn decrypt_batch(&self, packets: &mut [BatchPacket]) {
+        for packet in packets.iter_mut() {
+            packet.result = self.decrypt(packet.packet_number, packet.header, packet.payload);
+        }
+    }
+
+    /// Encrypt a batch of payloads under the same key
+    ///
+    /// Each payload must include space for the tag at the end, as with [`Self::encrypt`]. The
+    /// outcome for each payload is stored in its `result` field.
+    #[inline]
+    fn encrypt_batch(&self, packets: &mut [BatchPacket]) {
+        for packet in packets.iter_mut() {
+            packet.result = self.encrypt(packet.packet_number, packet.header, packet.payload);
+        }
+    }
+}
+
+/// A payload which is encrypted or decrypted as part of a batch
+#[derive(Debug)]
+pub struct BatchPacket<'a> {
+    pub packet_number: u64,
+    pub header: &'a [u8],
+    pub payload: &'a mut [u8],
+    pub result: Result<(), CryptoError>,
+}
+
+impl<'a> BatchPacket<'a> {
+    #[inline]
+    pub fn new(packet_number: u64, header: &'a [u8], payload: &'a mut [u8]) -> Self {
+        Self {
+            packet_number,
+            header,
+            payload,
+            result: Ok(()),
+        }
+    }
 }
 
 #[cfg(any(test, feature = "testing"))]
@@ -248,3 +248,19 @@ pub fn decrypt<'a, K: Key>(
 
     Ok((header.into(), payload.into()))
 }
+
+/// Decrypts a batch of payloads under the same key
+///
+/// The tag is removed from each payload that was successfully decrypted.
+#[inline]
+pub fn decrypt_batch<K: Key>(key: &K, packets: &mut [BatchPacket]) {
+    key.decrypt_batch(packets);
+
+    let tag_len = key.tag_len();
+    for packet in packets.iter_mut().filter(|packet| packet.result.is_ok()) {
+        // remove the key tag from payload
+        let payload = core::mem::take(&mut packet.payload);
+        let payload_len = payload.len() - tag_len;
+        packet.payload = &mut payload[0..payload_len];
+    }
+}
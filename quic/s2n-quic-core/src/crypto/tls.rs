@@ -7,6 +7,7 @@ use core::{
     convert::TryFrom,
     fmt::Debug,
     task::{Poll, Waker},
+    time::Duration,
 };
 use s2n_codec::EncoderValue;
 use zerocopy::{AsBytes, FromBytes, Unaligned};
@@ -59,6 +60,13 @@ pub trait Context<Crypto: CryptoSuite> {
         application_protocol: Bytes,
     ) -> Result<(), transport::Error>;
 
+    /// Called once the handshake is complete with the details of the negotiated session
+    #[cfg(feature = "alloc")]
+    fn on_session_info(&mut self, session_info: SessionInfo) -> Result<(), transport::Error> {
+        let _ = session_info;
+        Ok(())
+    }
+
     //= https://www.rfc-editor.org/rfc/rfc9001#section-4.1.1
     //# The TLS handshake is considered complete when the
     //# TLS stack has reported that the handshake is complete.  This happens
@@ -66,6 +74,23 @@ pub trait Context<Crypto: CryptoSuite> {
     //# peer's Finished message.
     fn on_handshake_complete(&mut self) -> Result<(), transport::Error>;
 
+    /// Called when the TLS provider invokes an application callback
+    ///
+    /// Only the s2n-tls provider reports its callbacks; rustls doesn't call this.
+    fn on_tls_callback_started(&mut self, callback: TlsCallback) {
+        let _ = callback;
+    }
+
+    /// Called when the application callback has completed
+    ///
+    /// The `duration` is measured by the TLS provider, which reads the clock around each call
+    /// that makes progress on the handshake. Only the s2n-tls provider reports its callbacks;
+    /// rustls doesn't call this.
+    fn on_tls_callback_completed(&mut self, callback: TlsCallback, duration: Duration) {
+        let _ = callback;
+        let _ = duration;
+    }
+
     /// Receives data from the initial packet space
     ///
     /// A `max_len` may be provided to indicate how many bytes the TLS implementation
@@ -118,7 +143,7 @@ pub trait Session: CryptoSuite + Sized + Send + Debug {
     fn poll<C: Context<Self>>(&mut self, context: &mut C) -> Poll<Result<(), transport::Error>>;
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
 pub enum CipherSuite {
     TLS_AES_128_GCM_SHA256,
@@ -148,6 +173,77 @@ impl crate::event::IntoEvent<crate::event::api::CipherSuite> for CipherSuite {
     }
 }
 
+impl Default for CipherSuite {
+    #[inline]
+    fn default() -> Self {
+        Self::Unknown
+    }
+}
+
+/// An application callback invoked by the TLS provider during the handshake
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TlsCallback {
+    /// The certificate chain is selected for the connection, e.g. based on the server name
+    CertificateSelection,
+    /// An operation is performed with the private key, e.g. signing the CertificateVerify
+    /// message
+    PrivateKeyOperation,
+}
+
+impl crate::event::IntoEvent<crate::event::builder::TlsCallback> for TlsCallback {
+    #[inline]
+    fn into_event(self) -> crate::event::builder::TlsCallback {
+        use crate::event::builder::TlsCallback::*;
+        match self {
+            Self::CertificateSelection => CertificateSelection {},
+            Self::PrivateKeyOperation => PrivateKeyOperation {},
+        }
+    }
+}
+
+impl crate::event::IntoEvent<crate::event::api::TlsCallback> for TlsCallback {
+    #[inline]
+    fn into_event(self) -> crate::event::api::TlsCallback {
+        let builder: crate::event::builder::TlsCallback = self.into_event();
+        builder.into_event()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum ProtocolVersion {
+    TLS_1_3,
+    Unknown,
+}
+
+impl Default for ProtocolVersion {
+    #[inline]
+    fn default() -> Self {
+        Self::Unknown
+    }
+}
+
+/// Details about the TLS session negotiated for a connection
+///
+/// The fields are populated once the handshake is complete.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default)]
+pub struct SessionInfo {
+    pub cipher_suite: CipherSuite,
+    pub protocol_version: ProtocolVersion,
+    /// The certificate chain presented by the peer in DER format, starting with the end-entity
+    /// certificate
+    ///
+    /// This is empty if the peer didn't authenticate with a certificate, which is the case for
+    /// clients unless mutual TLS is enabled.
+    pub peer_certificate_chain: alloc::vec::Vec<Bytes>,
+    /// Set if the session was resumed from a previous connection
+    ///
+    /// rustls doesn't expose whether a session was resumed, so this is always `false` with the
+    /// rustls provider.
+    pub resumed: bool,
+}
+
 macro_rules! handshake_type {
     ($($variant:ident($value:literal)),* $(,)?) => {
         #[derive(Debug, PartialEq, Eq, AsBytes, Unaligned)]
@@ -15,6 +15,7 @@ use core::{
     fmt,
     sync::atomic::{AtomicBool, Ordering},
     task::{Poll, Waker},
+    time::Duration,
 };
 use futures_test::task::new_count_waker;
 use s2n_codec::EncoderValue;
@@ -268,6 +269,14 @@ impl<S: tls::Session, C: tls::Session> Pair<S, C> {
             self.server.context.state,
         );
 
+        let session_info = self.client.context.session_info.as_ref().unwrap();
+        assert_ne!(session_info.cipher_suite, tls::CipherSuite::Unknown);
+        assert_eq!(session_info.protocol_version, tls::ProtocolVersion::TLS_1_3);
+        assert!(
+            !session_info.peer_certificate_chain.is_empty(),
+            "client did not receive the server certificate chain"
+        );
+
         // TODO check 0-rtt keys
     }
 }
@@ -327,7 +336,11 @@ pub struct Context<C: CryptoSuite, State: Debug> {
     pub handshake_complete: bool,
     pub server_name: Option<Bytes>,
     pub application_protocol: Option<Bytes>,
+    pub session_info: Option<tls::SessionInfo>,
     pub transport_parameters: Option<Bytes>,
+    /// The application callbacks which were completed by the TLS provider
+    pub tls_callbacks: Vec<tls::TlsCallback>,
+    pending_tls_callback: Option<tls::TlsCallback>,
     endpoint: endpoint::Type,
     pub state: State,
     waker: Waker,
@@ -343,7 +356,9 @@ impl<C: CryptoSuite, State: Debug> fmt::Debug for Context<C, State> {
             .field("handshake_complete", &self.handshake_complete)
             .field("sni", &self.server_name)
             .field("application_protocol", &self.application_protocol)
+            .field("session_info", &self.session_info)
             .field("transport_parameters", &self.transport_parameters)
+            .field("tls_callbacks", &self.tls_callbacks)
             .field("endpoint", &self.endpoint)
             .finish()
     }
@@ -360,7 +375,10 @@ impl<C: CryptoSuite, State: Debug> Context<C, State> {
             handshake_complete: false,
             server_name: None,
             application_protocol: None,
+            session_info: None,
             transport_parameters: None,
+            tls_callbacks: Vec::new(),
+            pending_tls_callback: None,
             endpoint,
             state,
             waker,
@@ -388,6 +406,21 @@ impl<C: CryptoSuite, State: Debug> Context<C, State> {
             "application_protocol is not consistent between endpoints"
         );
 
+        let session_info = self.session_info.as_ref().unwrap();
+        let other_session_info = other.session_info.as_ref().unwrap();
+        assert_eq!(
+            session_info.cipher_suite, other_session_info.cipher_suite,
+            "cipher_suite is not consistent between endpoints"
+        );
+        assert_eq!(
+            session_info.protocol_version, other_session_info.protocol_version,
+            "protocol_version is not consistent between endpoints"
+        );
+        assert_eq!(
+            session_info.resumed, other_session_info.resumed,
+            "resumed is not consistent between endpoints"
+        );
+
         assert_eq!(
             self.zero_rtt_crypto.is_some(),
             other.zero_rtt_crypto.is_some(),
@@ -408,6 +441,7 @@ impl<C: CryptoSuite, State: Debug> Context<C, State> {
         );
         assert!(self.handshake_complete);
         assert!(self.application_protocol.is_some());
+        assert!(self.session_info.is_some());
         assert!(self.transport_parameters.is_some());
     }
 
@@ -619,6 +653,20 @@ impl<C: CryptoSuite, State: Debug> tls::Context<C> for Context<C, State> {
         Ok(())
     }
 
+    fn on_session_info(&mut self, session_info: tls::SessionInfo) -> Result<(), transport::Error> {
+        self.log("session info");
+        assert!(
+            !self.handshake_complete,
+            "session info should be emitted before handshake complete"
+        );
+        assert!(
+            self.session_info.is_none(),
+            "session info called multiple times"
+        );
+        self.session_info = Some(session_info);
+        Ok(())
+    }
+
     fn on_handshake_complete(&mut self) -> Result<(), transport::Error> {
         assert!(
             !self.handshake_complete,
@@ -628,11 +676,34 @@ impl<C: CryptoSuite, State: Debug> tls::Context<C> for Context<C, State> {
             !self.application_protocol.as_ref().unwrap().is_empty(),
             "application_protocol is empty at handshake complete"
         );
+        assert!(
+            self.pending_tls_callback.is_none(),
+            "tls callback is pending at handshake complete"
+        );
         self.handshake_complete = true;
         self.log("handshake complete");
         Ok(())
     }
 
+    fn on_tls_callback_started(&mut self, callback: tls::TlsCallback) {
+        self.log("tls callback started");
+        assert!(
+            self.pending_tls_callback.is_none(),
+            "tls callbacks should not overlap"
+        );
+        self.pending_tls_callback = Some(callback);
+    }
+
+    fn on_tls_callback_completed(&mut self, callback: tls::TlsCallback, _duration: Duration) {
+        self.log("tls callback completed");
+        assert_eq!(
+            self.pending_tls_callback.take(),
+            Some(callback),
+            "tls callback completed without starting"
+        );
+        self.tls_callbacks.push(callback);
+    }
+
     fn receive_initial(&mut self, max_len: Option<usize>) -> Option<Bytes> {
         self.log("rx initial");
         self.initial.rx(max_len)
@@ -4,6 +4,7 @@
 // s2n-quic's default implementation of the datagram component
 
 use crate::{
+    connection,
     datagram::{ConnectionInfo, Packet, PreConnectionInfo},
     transport::parameters::MaxDatagramFrameSize,
 };
@@ -107,6 +108,8 @@ pub struct Receiver {
     capacity: usize,
     waker: Option<Waker>,
     max_datagram_frame_size: u64,
+    /// Set once the connection is closed
+    error: Option<connection::Error>,
 }
 
 impl Receiver {
@@ -130,6 +133,9 @@ impl Receiver {
     ///   the caller should retry receiving after the [`Waker`](core::task::Waker) on the provided
     ///   [`Context`](core::task::Context) is notified.
     /// - `Poll::Ready(Datagram)` if there exists a datagram to be received.
+    ///
+    /// The waker is also notified when the connection is closed, after which
+    /// [`Self::connection_error`] returns the error.
     pub fn poll_recv_datagram(&mut self, cx: &mut Context) -> Poll<Bytes> {
         if let Some(datagram) = self.queue.pop_front() {
             Poll::Ready(datagram)
@@ -138,6 +144,13 @@ impl Receiver {
             Poll::Pending
         }
     }
+
+    /// Returns the error the connection was closed with, if it has been closed
+    ///
+    /// No more datagrams are added to the queue once the connection is closed.
+    pub fn connection_error(&self) -> Option<connection::Error> {
+        self.error
+    }
 }
 
 impl super::Receiver for Receiver {
@@ -159,6 +172,14 @@ impl super::Receiver for Receiver {
             w.wake();
         }
     }
+
+    fn on_connection_error(&mut self, error: connection::Error) {
+        self.error = Some(error);
+        // Wake the waker to let the user know the connection was closed
+        if let Some(w) = self.waker.take() {
+            w.wake();
+        }
+    }
 }
 
 // A builder for the default datagram receiver
@@ -199,6 +220,7 @@ impl ReceiverBuilder {
             capacity: self.queue_capacity,
             waker: None,
             max_datagram_frame_size: self.max_datagram_frame_size,
+            error: None,
         })
     }
 }
@@ -744,6 +766,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn poll_recv_datagram_connection_error() {
+        let mut receiver = Receiver::builder().build().unwrap();
+
+        let (waker, wake_count) = new_count_waker();
+        let mut cx = Context::from_waker(&waker);
+
+        crate::datagram::Receiver::on_datagram(&mut receiver, &[1, 2, 3]);
+        assert_eq!(
+            receiver.poll_recv_datagram(&mut cx),
+            Poll::Ready(bytes::Bytes::from_static(&[1, 2, 3]))
+        );
+        assert_eq!(receiver.poll_recv_datagram(&mut cx), Poll::Pending);
+        assert!(receiver.connection_error().is_none());
+
+        // Closing the connection wakes up the stored waker
+        let error = connection::Error::idle_timer_expired();
+        crate::datagram::Receiver::on_connection_error(&mut receiver, error);
+        assert_eq!(wake_count, 1);
+
+        // The receiver keeps returning `Pending` and reports the error separately
+        assert_eq!(receiver.poll_recv_datagram(&mut cx), Poll::Pending);
+        assert_eq!(receiver.connection_error(), Some(error));
+    }
+
     // The MockPacket mocks writing datagrams to a packet, but is not
     // a fully functional mock. It is used to test the logic in the
     // on_transmit function.
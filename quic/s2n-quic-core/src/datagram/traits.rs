@@ -1,6 +1,8 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::connection;
+
 /// The datagram endpoint trait provides a way to implement custom unreliable datagram
 /// sending and receiving logic. The Sender type should be implemented for custom
 /// sending behavior, and the Receiver type should be implemented for custom
@@ -55,6 +57,13 @@ impl PreConnectionInfo {
 pub trait Receiver: 'static + Send {
     // A callback that gives users direct access to datagrams as they are read off a packet
     fn on_datagram(&mut self, datagram: &[u8]);
+
+    /// A callback that notifies the receiver that the connection was closed
+    ///
+    /// No more datagrams will be received on the connection after this is called.
+    fn on_connection_error(&mut self, error: connection::Error) {
+        let _ = error;
+    }
 }
 pub trait Sender: 'static + Send {
     /// A callback that allows users to write datagrams directly to the packet
@@ -94,6 +94,49 @@ impl<'a> ConnectionAttempt<'a> {
     }
 }
 
+/// Information about a connection which was accepted by the endpoint
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct ConnectionOpened<'a> {
+    /// The address of the peer that opened the connection
+    ///
+    /// This is the same address that was provided in the [`ConnectionAttempt`].
+    pub remote_address: SocketAddress<'a>,
+    pub timestamp: Timestamp,
+}
+
+impl<'a> ConnectionOpened<'a> {
+    #[doc(hidden)]
+    pub fn new(remote_address: &'a inet::SocketAddress, timestamp: Timestamp) -> Self {
+        Self {
+            remote_address: remote_address.into_event(),
+            timestamp,
+        }
+    }
+}
+
+/// Information about a connection which was removed from the endpoint
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct ConnectionClosed<'a> {
+    /// The address of the peer that opened the connection
+    ///
+    /// Note that this is the address the connection was opened with, even if the peer has since
+    /// migrated to a different address.
+    pub remote_address: SocketAddress<'a>,
+    pub timestamp: Timestamp,
+}
+
+impl<'a> ConnectionClosed<'a> {
+    #[doc(hidden)]
+    pub fn new(remote_address: &'a inet::SocketAddress, timestamp: Timestamp) -> Self {
+        Self {
+            remote_address: remote_address.into_event(),
+            timestamp,
+        }
+    }
+}
+
 pub trait Limiter: 'static + Send {
     /// This trait is used to determine the outcome of connection attempts on an endpoint. The
     /// implementor returns an Outcome based on the ConnectionAttempt, or other information that the
@@ -119,4 +162,20 @@ pub trait Limiter: 'static + Send {
     /// }
     /// ```
     fn on_connection_attempt(&mut self, info: &ConnectionAttempt) -> Outcome;
+
+    /// Called when a connection has been accepted by the endpoint
+    ///
+    /// Every call to this method will be followed by exactly one call to
+    /// [`Limiter::on_connection_closed`] for the same remote address, which allows implementors
+    /// to track the number of active connections.
+    #[inline]
+    fn on_connection_opened(&mut self, info: &ConnectionOpened) {
+        let _ = info;
+    }
+
+    /// Called when a connection has been closed and removed from the endpoint
+    #[inline]
+    fn on_connection_closed(&mut self, info: &ConnectionClosed) {
+        let _ = info;
+    }
 }
@@ -475,6 +475,51 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " The reason a path was deleted"]
+    pub enum PathDeletedReason {
+        #[non_exhaustive]
+        #[doc = " Validation of the path failed or was abandoned"]
+        ValidationFailed {},
+        #[non_exhaustive]
+        #[doc = " No datagrams were received on the path for an extended period of time"]
+        Idle {},
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " A phase of the handshake, named after the milestone which ends it"]
+    pub enum HandshakePhase {
+        #[non_exhaustive]
+        #[doc = " The ServerHello was sent or received"]
+        #[doc = ""]
+        #[doc = " On the server, this includes processing the ClientHello and selecting a certificate."]
+        ServerHello {},
+        #[non_exhaustive]
+        #[doc = " The server's Finished message was sent or received and the 1-RTT keys are available"]
+        #[doc = ""]
+        #[doc = " On the server, this includes signing the CertificateVerify message. On the client, this"]
+        #[doc = " includes verifying the server's certificate chain."]
+        ServerFinished {},
+        #[non_exhaustive]
+        #[doc = " The TLS handshake completed"]
+        Complete {},
+        #[non_exhaustive]
+        #[doc = " The HANDSHAKE_DONE frame was acknowledged by the client or received from the server"]
+        HandshakeDoneAcked {},
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " An application callback invoked by the TLS provider during the handshake"]
+    pub enum TlsCallback {
+        #[non_exhaustive]
+        #[doc = " The certificate chain was selected for the connection"]
+        CertificateSelection {},
+        #[non_exhaustive]
+        #[doc = " An operation was performed with the private key, e.g. signing the CertificateVerify"]
+        #[doc = " message"]
+        PrivateKeyOperation {},
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     #[doc = " Application level protocol"]
     pub struct ApplicationProtocolInformation<'a> {
         pub chosen_application_protocol: &'a [u8],
@@ -814,6 +859,40 @@ pub mod api {
     }
     #[derive(Clone, Debug)]
     #[non_exhaustive]
+    #[doc = " A path was deleted and its resources released"]
+    pub struct PathDeleted<'a> {
+        pub path: Path<'a>,
+        pub reason: PathDeletedReason,
+    }
+    impl<'a> Event for PathDeleted<'a> {
+        const NAME: &'static str = "transport:path_deleted";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " A phase of the handshake has completed"]
+    pub struct HandshakePhaseCompleted {
+        pub phase: HandshakePhase,
+        #[doc = " The time spent in the phase"]
+        pub duration: Duration,
+        #[doc = " The time since the connection started the handshake"]
+        pub elapsed: Duration,
+    }
+    impl Event for HandshakePhaseCompleted {
+        const NAME: &'static str = "connectivity:handshake_phase_completed";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
+    #[doc = " An application callback invoked by the TLS provider has completed"]
+    pub struct TlsCallbackCompleted {
+        pub callback: TlsCallback,
+        #[doc = " The time spent in the callback"]
+        pub duration: Duration,
+    }
+    impl Event for TlsCallbackCompleted {
+        const NAME: &'static str = "connectivity:tls_callback_completed";
+    }
+    #[derive(Clone, Debug)]
+    #[non_exhaustive]
     #[doc = " QUIC version"]
     pub struct VersionInformation<'a> {
         pub server_versions: &'a [u32],
@@ -1833,6 +1912,43 @@ pub mod tracing {
             tracing :: event ! (target : "slow_start_exited" , parent : id , tracing :: Level :: DEBUG , path = tracing :: field :: debug (path) , cause = tracing :: field :: debug (cause) , congestion_window = tracing :: field :: debug (congestion_window));
         }
         #[inline]
+        fn on_path_deleted(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::PathDeleted,
+        ) {
+            let id = context.id();
+            let api::PathDeleted { path, reason } = event;
+            tracing :: event ! (target : "path_deleted" , parent : id , tracing :: Level :: DEBUG , path = tracing :: field :: debug (path) , reason = tracing :: field :: debug (reason));
+        }
+        #[inline]
+        fn on_handshake_phase_completed(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::HandshakePhaseCompleted,
+        ) {
+            let id = context.id();
+            let api::HandshakePhaseCompleted {
+                phase,
+                duration,
+                elapsed,
+            } = event;
+            tracing :: event ! (target : "handshake_phase_completed" , parent : id , tracing :: Level :: DEBUG , phase = tracing :: field :: debug (phase) , duration = tracing :: field :: debug (duration) , elapsed = tracing :: field :: debug (elapsed));
+        }
+        #[inline]
+        fn on_tls_callback_completed(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            _meta: &api::ConnectionMeta,
+            event: &api::TlsCallbackCompleted,
+        ) {
+            let id = context.id();
+            let api::TlsCallbackCompleted { callback, duration } = event;
+            tracing :: event ! (target : "tls_callback_completed" , parent : id , tracing :: Level :: DEBUG , callback = tracing :: field :: debug (callback) , duration = tracing :: field :: debug (duration));
+        }
+        #[inline]
         fn on_version_information(
             &mut self,
             meta: &api::EndpointMeta,
@@ -2862,6 +2978,72 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    #[doc = " The reason a path was deleted"]
+    pub enum PathDeletedReason {
+        #[doc = " Validation of the path failed or was abandoned"]
+        ValidationFailed,
+        #[doc = " No datagrams were received on the path for an extended period of time"]
+        Idle,
+    }
+    impl IntoEvent<api::PathDeletedReason> for PathDeletedReason {
+        #[inline]
+        fn into_event(self) -> api::PathDeletedReason {
+            use api::PathDeletedReason::*;
+            match self {
+                Self::ValidationFailed => ValidationFailed {},
+                Self::Idle => Idle {},
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " A phase of the handshake, named after the milestone which ends it"]
+    pub enum HandshakePhase {
+        #[doc = " The ServerHello was sent or received"]
+        #[doc = ""]
+        #[doc = " On the server, this includes processing the ClientHello and selecting a certificate."]
+        ServerHello,
+        #[doc = " The server's Finished message was sent or received and the 1-RTT keys are available"]
+        #[doc = ""]
+        #[doc = " On the server, this includes signing the CertificateVerify message. On the client, this"]
+        #[doc = " includes verifying the server's certificate chain."]
+        ServerFinished,
+        #[doc = " The TLS handshake completed"]
+        Complete,
+        #[doc = " The HANDSHAKE_DONE frame was acknowledged by the client or received from the server"]
+        HandshakeDoneAcked,
+    }
+    impl IntoEvent<api::HandshakePhase> for HandshakePhase {
+        #[inline]
+        fn into_event(self) -> api::HandshakePhase {
+            use api::HandshakePhase::*;
+            match self {
+                Self::ServerHello => ServerHello {},
+                Self::ServerFinished => ServerFinished {},
+                Self::Complete => Complete {},
+                Self::HandshakeDoneAcked => HandshakeDoneAcked {},
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " An application callback invoked by the TLS provider during the handshake"]
+    pub enum TlsCallback {
+        #[doc = " The certificate chain was selected for the connection"]
+        CertificateSelection,
+        #[doc = " An operation was performed with the private key, e.g. signing the CertificateVerify"]
+        #[doc = " message"]
+        PrivateKeyOperation,
+    }
+    impl IntoEvent<api::TlsCallback> for TlsCallback {
+        #[inline]
+        fn into_event(self) -> api::TlsCallback {
+            use api::TlsCallback::*;
+            match self {
+                Self::CertificateSelection => CertificateSelection {},
+                Self::PrivateKeyOperation => PrivateKeyOperation {},
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     #[doc = " Application level protocol"]
     pub struct ApplicationProtocolInformation<'a> {
         pub chosen_application_protocol: &'a [u8],
@@ -3458,6 +3640,63 @@ pub mod builder {
         }
     }
     #[derive(Clone, Debug)]
+    #[doc = " A path was deleted and its resources released"]
+    pub struct PathDeleted<'a> {
+        pub path: Path<'a>,
+        pub reason: PathDeletedReason,
+    }
+    impl<'a> IntoEvent<api::PathDeleted<'a>> for PathDeleted<'a> {
+        #[inline]
+        fn into_event(self) -> api::PathDeleted<'a> {
+            let PathDeleted { path, reason } = self;
+            api::PathDeleted {
+                path: path.into_event(),
+                reason: reason.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " A phase of the handshake has completed"]
+    pub struct HandshakePhaseCompleted {
+        pub phase: HandshakePhase,
+        #[doc = " The time spent in the phase"]
+        pub duration: Duration,
+        #[doc = " The time since the connection started the handshake"]
+        pub elapsed: Duration,
+    }
+    impl IntoEvent<api::HandshakePhaseCompleted> for HandshakePhaseCompleted {
+        #[inline]
+        fn into_event(self) -> api::HandshakePhaseCompleted {
+            let HandshakePhaseCompleted {
+                phase,
+                duration,
+                elapsed,
+            } = self;
+            api::HandshakePhaseCompleted {
+                phase: phase.into_event(),
+                duration: duration.into_event(),
+                elapsed: elapsed.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    #[doc = " An application callback invoked by the TLS provider has completed"]
+    pub struct TlsCallbackCompleted {
+        pub callback: TlsCallback,
+        #[doc = " The time spent in the callback"]
+        pub duration: Duration,
+    }
+    impl IntoEvent<api::TlsCallbackCompleted> for TlsCallbackCompleted {
+        #[inline]
+        fn into_event(self) -> api::TlsCallbackCompleted {
+            let TlsCallbackCompleted { callback, duration } = self;
+            api::TlsCallbackCompleted {
+                callback: callback.into_event(),
+                duration: duration.into_event(),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
     #[doc = " QUIC version"]
     pub struct VersionInformation<'a> {
         pub server_versions: &'a [u32],
@@ -4305,6 +4544,42 @@ mod traits {
             let _ = meta;
             let _ = event;
         }
+        #[doc = "Called when the `PathDeleted` event is triggered"]
+        #[inline]
+        fn on_path_deleted(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &PathDeleted,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
+        #[doc = "Called when the `HandshakePhaseCompleted` event is triggered"]
+        #[inline]
+        fn on_handshake_phase_completed(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &HandshakePhaseCompleted,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
+        #[doc = "Called when the `TlsCallbackCompleted` event is triggered"]
+        #[inline]
+        fn on_tls_callback_completed(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &TlsCallbackCompleted,
+        ) {
+            let _ = context;
+            let _ = meta;
+            let _ = event;
+        }
         #[doc = "Called when the `VersionInformation` event is triggered"]
         #[inline]
         fn on_version_information(&mut self, meta: &EndpointMeta, event: &VersionInformation) {
@@ -4856,6 +5131,36 @@ mod traits {
             (self.1).on_slow_start_exited(&mut context.1, meta, event);
         }
         #[inline]
+        fn on_path_deleted(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &PathDeleted,
+        ) {
+            (self.0).on_path_deleted(&mut context.0, meta, event);
+            (self.1).on_path_deleted(&mut context.1, meta, event);
+        }
+        #[inline]
+        fn on_handshake_phase_completed(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &HandshakePhaseCompleted,
+        ) {
+            (self.0).on_handshake_phase_completed(&mut context.0, meta, event);
+            (self.1).on_handshake_phase_completed(&mut context.1, meta, event);
+        }
+        #[inline]
+        fn on_tls_callback_completed(
+            &mut self,
+            context: &mut Self::ConnectionContext,
+            meta: &ConnectionMeta,
+            event: &TlsCallbackCompleted,
+        ) {
+            (self.0).on_tls_callback_completed(&mut context.0, meta, event);
+            (self.1).on_tls_callback_completed(&mut context.1, meta, event);
+        }
+        #[inline]
         fn on_version_information(&mut self, meta: &EndpointMeta, event: &VersionInformation) {
             (self.0).on_version_information(meta, event);
             (self.1).on_version_information(meta, event);
@@ -5224,6 +5529,12 @@ mod traits {
         fn on_mtu_updated(&mut self, event: builder::MtuUpdated);
         #[doc = "Publishes a `SlowStartExited` event to the publisher's subscriber"]
         fn on_slow_start_exited(&mut self, event: builder::SlowStartExited);
+        #[doc = "Publishes a `PathDeleted` event to the publisher's subscriber"]
+        fn on_path_deleted(&mut self, event: builder::PathDeleted);
+        #[doc = "Publishes a `HandshakePhaseCompleted` event to the publisher's subscriber"]
+        fn on_handshake_phase_completed(&mut self, event: builder::HandshakePhaseCompleted);
+        #[doc = "Publishes a `TlsCallbackCompleted` event to the publisher's subscriber"]
+        fn on_tls_callback_completed(&mut self, event: builder::TlsCallbackCompleted);
         #[doc = r" Returns the QUIC version negotiated for the current connection, if any"]
         fn quic_version(&self) -> u32;
         #[doc = r" Returns the [`Subject`] for the current publisher"]
@@ -5573,6 +5884,33 @@ mod traits {
             self.subscriber.on_event(&self.meta, &event);
         }
         #[inline]
+        fn on_path_deleted(&mut self, event: builder::PathDeleted) {
+            let event = event.into_event();
+            self.subscriber
+                .on_path_deleted(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
+        fn on_handshake_phase_completed(&mut self, event: builder::HandshakePhaseCompleted) {
+            let event = event.into_event();
+            self.subscriber
+                .on_handshake_phase_completed(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
+        fn on_tls_callback_completed(&mut self, event: builder::TlsCallbackCompleted) {
+            let event = event.into_event();
+            self.subscriber
+                .on_tls_callback_completed(self.context, &self.meta, &event);
+            self.subscriber
+                .on_connection_event(self.context, &self.meta, &event);
+            self.subscriber.on_event(&self.meta, &event);
+        }
+        #[inline]
         fn quic_version(&self) -> u32 {
             self.quic_version
         }
@@ -5623,6 +5961,9 @@ pub mod testing {
         pub keep_alive_timer_expired: u32,
         pub mtu_updated: u32,
         pub slow_start_exited: u32,
+        pub path_deleted: u32,
+        pub handshake_phase_completed: u32,
+        pub tls_callback_completed: u32,
         pub version_information: u32,
         pub endpoint_packet_sent: u32,
         pub endpoint_packet_received: u32,
@@ -5695,6 +6036,9 @@ pub mod testing {
                 keep_alive_timer_expired: 0,
                 mtu_updated: 0,
                 slow_start_exited: 0,
+                path_deleted: 0,
+                handshake_phase_completed: 0,
+                tls_callback_completed: 0,
                 version_information: 0,
                 endpoint_packet_sent: 0,
                 endpoint_packet_received: 0,
@@ -6094,6 +6438,39 @@ pub mod testing {
                 self.output.push(format!("{:?} {:?}", meta, event));
             }
         }
+        fn on_path_deleted(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::PathDeleted,
+        ) {
+            self.path_deleted += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
+        fn on_handshake_phase_completed(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::HandshakePhaseCompleted,
+        ) {
+            self.handshake_phase_completed += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
+        fn on_tls_callback_completed(
+            &mut self,
+            _context: &mut Self::ConnectionContext,
+            meta: &api::ConnectionMeta,
+            event: &api::TlsCallbackCompleted,
+        ) {
+            self.tls_callback_completed += 1;
+            if self.location.is_some() {
+                self.output.push(format!("{:?} {:?}", meta, event));
+            }
+        }
         fn on_version_information(
             &mut self,
             meta: &api::EndpointMeta,
@@ -6229,6 +6606,9 @@ pub mod testing {
         pub keep_alive_timer_expired: u32,
         pub mtu_updated: u32,
         pub slow_start_exited: u32,
+        pub path_deleted: u32,
+        pub handshake_phase_completed: u32,
+        pub tls_callback_completed: u32,
         pub version_information: u32,
         pub endpoint_packet_sent: u32,
         pub endpoint_packet_received: u32,
@@ -6291,6 +6671,9 @@ pub mod testing {
                 keep_alive_timer_expired: 0,
                 mtu_updated: 0,
                 slow_start_exited: 0,
+                path_deleted: 0,
+                handshake_phase_completed: 0,
+                tls_callback_completed: 0,
                 version_information: 0,
                 endpoint_packet_sent: 0,
                 endpoint_packet_received: 0,
@@ -6631,6 +7014,27 @@ pub mod testing {
                 self.output.push(format!("{:?}", event));
             }
         }
+        fn on_path_deleted(&mut self, event: builder::PathDeleted) {
+            self.path_deleted += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
+        fn on_handshake_phase_completed(&mut self, event: builder::HandshakePhaseCompleted) {
+            self.handshake_phase_completed += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
+        fn on_tls_callback_completed(&mut self, event: builder::TlsCallbackCompleted) {
+            self.tls_callback_completed += 1;
+            let event = event.into_event();
+            if self.location.is_some() {
+                self.output.push(format!("{:?}", event));
+            }
+        }
         fn quic_version(&self) -> u32 {
             1
         }
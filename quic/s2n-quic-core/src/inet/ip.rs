@@ -48,6 +48,18 @@ impl From<IpV6Address> for IpAddress {
     }
 }
 
+impl From<[u8; 4]> for IpAddress {
+    fn from(octets: [u8; 4]) -> Self {
+        Self::Ipv4(octets.into())
+    }
+}
+
+impl From<[u8; 16]> for IpAddress {
+    fn from(octets: [u8; 16]) -> Self {
+        Self::Ipv6(octets.into())
+    }
+}
+
 impl Unspecified for IpAddress {
     fn is_unspecified(&self) -> bool {
         match self {
@@ -4,7 +4,10 @@
 use crate::{
     connection,
     connection::id::ConnectionInfo,
-    crypto::{CryptoError, EncryptedPayload, OneRttHeaderKey, OneRttKey, ProtectedPayload},
+    crypto::{
+        BatchPacket, CryptoError, EncryptedPayload, HeaderProtectionMask, OneRttHeaderKey,
+        OneRttKey, ProtectedPayload,
+    },
     packet::{
         decoding::HeaderDecoder,
         encoding::{PacketEncoder, PacketPayloadEncoder},
@@ -126,6 +129,8 @@ pub type ProtectedShort<'a> =
     Short<CheckedRange, ProtectedKeyPhase, ProtectedPacketNumber, ProtectedPayload<'a>>;
 pub type EncryptedShort<'a> = Short<CheckedRange, KeyPhase, PacketNumber, EncryptedPayload<'a>>;
 pub type CleartextShort<'a> = Short<&'a [u8], KeyPhase, PacketNumber, DecoderBufferMut<'a>>;
+/// The header fields of a packet which is being decrypted as part of a batch
+pub type BatchedShort = Short<CheckedRange, KeyPhase, PacketNumber, ()>;
 
 impl<'a> ProtectedShort<'a> {
     #[inline]
@@ -164,6 +169,30 @@ impl<'a> ProtectedShort<'a> {
         self,
         header_key: &H,
         largest_acknowledged_packet_number: PacketNumber,
+    ) -> Result<EncryptedShort<'a>, CryptoError> {
+        let mask = {
+            let sample = self.header_protection_sample(header_key.opening_sample_len())?;
+            header_key.opening_header_protection_mask(sample)
+        };
+
+        self.unprotect_with_mask(mask, largest_acknowledged_packet_number)
+    }
+
+    /// Returns the ciphertext sample used to derive the header protection mask
+    ///
+    /// This can be used to derive masks for several packets in a single call to
+    /// [`crate::crypto::HeaderKey::opening_header_protection_masks`].
+    #[inline]
+    pub fn header_protection_sample(&self, sample_len: usize) -> Result<&[u8], CryptoError> {
+        Ok(self.payload.header_protection_sample(sample_len)?)
+    }
+
+    /// Removes header protection with a mask derived from the packet's
+    /// [`header_protection_sample`](Self::header_protection_sample)
+    pub fn unprotect_with_mask(
+        self,
+        mask: HeaderProtectionMask,
+        largest_acknowledged_packet_number: PacketNumber,
     ) -> Result<EncryptedShort<'a>, CryptoError> {
         let Short {
             spin_bit,
@@ -172,8 +201,11 @@ impl<'a> ProtectedShort<'a> {
             ..
         } = self;
 
-        let (truncated_packet_number, payload) =
-            crate::crypto::unprotect(header_key, PacketNumberSpace::ApplicationData, payload)?;
+        let (truncated_packet_number, payload) = crate::crypto::remove_header_protection(
+            PacketNumberSpace::ApplicationData,
+            mask,
+            payload,
+        )?;
 
         let key_phase = KeyPhase::from_tag(payload.get_tag());
 
@@ -210,16 +242,7 @@ impl<'a> EncryptedShort<'a> {
 
         let header = header.into_less_safe_slice();
 
-        //= https://www.rfc-editor.org/rfc/rfc9000#section-17.3.1
-        //# An endpoint MUST treat receipt of a
-        //# packet that has a non-zero value for these bits, after removing
-        //# both packet and header protection, as a connection error of type
-        //# PROTOCOL_VIOLATION.
-        if header[0] & RESERVED_BITS_MASK != 0 {
-            return Err(
-                transport::Error::PROTOCOL_VIOLATION.with_reason("reserved bits are non-zero")
-            );
-        }
+        check_reserved_bits(header)?;
 
         let destination_connection_id = destination_connection_id.get(header);
 
@@ -232,6 +255,32 @@ impl<'a> EncryptedShort<'a> {
         })
     }
 
+    /// Splits the packet into its header fields and a [`BatchPacket`] so it can be
+    /// decrypted along with other packets under the same key
+    #[inline]
+    pub fn into_batch(self) -> (BatchedShort, BatchPacket<'a>) {
+        let Short {
+            spin_bit,
+            key_phase,
+            destination_connection_id,
+            packet_number,
+            payload,
+        } = self;
+
+        let (header, payload) = payload.split_mut();
+        let packet = BatchPacket::new(packet_number.as_crypto_nonce(), header, payload);
+
+        let packet_header = Short {
+            spin_bit,
+            key_phase,
+            destination_connection_id,
+            packet_number,
+            payload: (),
+        };
+
+        (packet_header, packet)
+    }
+
     #[inline]
     pub fn key_phase(&self) -> KeyPhase {
         self.key_phase
@@ -245,6 +294,59 @@ impl<'a> EncryptedShort<'a> {
     }
 }
 
+impl BatchedShort {
+    /// Finishes decrypting a packet which was split with [`EncryptedShort::into_batch`]
+    ///
+    /// `packet` is expected to have been passed to [`crate::crypto::decrypt_batch`].
+    pub fn decrypted<'a>(
+        self,
+        packet: BatchPacket<'a>,
+    ) -> Result<CleartextShort<'a>, transport::Error> {
+        let Short {
+            spin_bit,
+            key_phase,
+            destination_connection_id,
+            packet_number,
+            ..
+        } = self;
+
+        let BatchPacket {
+            header,
+            payload,
+            result,
+            ..
+        } = packet;
+
+        result?;
+
+        check_reserved_bits(header)?;
+
+        let destination_connection_id = destination_connection_id.get(header);
+
+        Ok(Short {
+            spin_bit,
+            key_phase,
+            destination_connection_id,
+            packet_number,
+            payload: payload.into(),
+        })
+    }
+}
+
+#[inline]
+fn check_reserved_bits(header: &[u8]) -> Result<(), transport::Error> {
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-17.3.1
+    //# An endpoint MUST treat receipt of a
+    //# packet that has a non-zero value for these bits, after removing
+    //# both packet and header protection, as a connection error of type
+    //# PROTOCOL_VIOLATION.
+    if header[0] & RESERVED_BITS_MASK != 0 {
+        return Err(transport::Error::PROTOCOL_VIOLATION.with_reason("reserved bits are non-zero"));
+    }
+
+    Ok(())
+}
+
 impl<'a> CleartextShort<'a> {
     #[inline]
     pub fn destination_connection_id(&self) -> &[u8] {
@@ -5,8 +5,8 @@ use crate::{
     counter::Counter,
     random,
     recovery::{
-        bandwidth, bandwidth::Bandwidth, bbr::probe_bw::CyclePhase, CongestionController,
-        RttEstimator,
+        bandwidth, bandwidth::Bandwidth, bbr::probe_bw::CyclePhase, congestion_controller,
+        CongestionController, RttEstimator,
     },
     time::Timestamp,
 };
@@ -162,7 +162,7 @@ impl State {
 /// Based in part on the Chromium BBRv2 implementation, see <https://source.chromium.org/chromium/chromium/src/+/main:net/third_party/quiche/src/quic/core/congestion_control/bbr2_sender.cc>
 /// and the Linux Kernel TCP BBRv2 implementation, see <https://github.com/google/bbr/blob/v2alpha/net/ipv4/tcp_bbr2.c>
 #[derive(Debug, Clone)]
-struct BbrCongestionController {
+pub struct BbrCongestionController {
     state: State,
     round_counter: round::Counter,
     bw_estimator: bandwidth::Estimator,
@@ -210,7 +210,10 @@ impl CongestionController for BbrCongestionController {
     }
 
     fn is_congestion_limited(&self) -> bool {
-        todo!()
+        let available_congestion_window = self
+            .congestion_window()
+            .saturating_sub(*self.bytes_in_flight);
+        available_congestion_window < self.max_datagram_size as u32
     }
 
     fn is_slow_start(&self) -> bool {
@@ -228,6 +231,12 @@ impl CongestionController for BbrCongestionController {
         app_limited: Option<bool>,
         _rtt_estimator: &RttEstimator,
     ) -> Self::PacketInfo {
+        // The estimator starts a new delivery rate interval if nothing was in flight before
+        // this packet, so it is given the bytes in flight prior to sending
+        let mut packet_info =
+            self.bw_estimator
+                .on_packet_sent(*self.bytes_in_flight, app_limited, time_sent);
+
         if sent_bytes > 0 {
             self.recovery_state.on_packet_sent();
 
@@ -237,8 +246,9 @@ impl CongestionController for BbrCongestionController {
             self.set_next_departure_time(sent_bytes, time_sent);
         }
 
-        self.bw_estimator
-            .on_packet_sent(*self.bytes_in_flight, app_limited, time_sent)
+        // BBR tracks the bytes in flight including this packet (packet.tx_in_flight)
+        packet_info.bytes_in_flight = *self.bytes_in_flight;
+        packet_info
     }
 
     fn on_rtt_update(
@@ -247,7 +257,7 @@ impl CongestionController for BbrCongestionController {
         _now: Timestamp,
         _rtt_estimator: &RttEstimator,
     ) {
-        todo!()
+        // BBR.min_rtt is updated in `on_ack` using the latest RTT sample
     }
 
     fn on_ack<Rnd: random::Generator>(
@@ -285,6 +295,9 @@ impl CongestionController for BbrCongestionController {
         //#     BBRSetSendQuantum()
         //#     BBRSetCwnd()
 
+        self.bytes_in_flight
+            .try_sub(bytes_acknowledged)
+            .expect("bytes_acknowledged should not exceed u32::MAX");
         self.bw_estimator.on_ack(
             bytes_acknowledged,
             newest_acked_time_sent,
@@ -350,6 +363,7 @@ impl CongestionController for BbrCongestionController {
         _random_generator: &mut Rnd,
         timestamp: Timestamp,
     ) {
+        self.bytes_in_flight -= lost_bytes;
         self.bw_estimator.on_loss(lost_bytes as usize);
         self.recovery_state.on_congestion_event(timestamp);
         self.full_pipe_estimator.on_packet_lost(new_loss_burst);
@@ -364,8 +378,10 @@ impl CongestionController for BbrCongestionController {
         self.max_datagram_size = max_datagram_size;
     }
 
-    fn on_packet_discarded(&mut self, _bytes_sent: usize) {
-        todo!()
+    fn on_packet_discarded(&mut self, bytes_sent: usize) {
+        self.bytes_in_flight
+            .try_sub(bytes_sent)
+            .expect("bytes sent should not exceed u32::MAX");
     }
 
     fn earliest_departure_time(&self) -> Option<Timestamp> {
@@ -378,6 +394,43 @@ impl CongestionController for BbrCongestionController {
 }
 
 impl BbrCongestionController {
+    /// Constructs a new `BbrCongestionController`
+    pub fn new(max_datagram_size: u16) -> Self {
+        // The initial values follow BBROnInit() in
+        // https://tools.ietf.org/id/draft-cardwell-iccrg-bbr-congestion-control-02#4.2.1
+        let cwnd = Self::initial_window(max_datagram_size);
+
+        let mut bbr = Self {
+            state: State::Startup,
+            round_counter: Default::default(),
+            bw_estimator: Default::default(),
+            full_pipe_estimator: Default::default(),
+            bytes_in_flight: Counter::new(0),
+            cwnd,
+            prior_cwnd: 0,
+            recovery_state: recovery::State::Recovered,
+            congestion_state: Default::default(),
+            data_rate_model: data_rate::Model::new(),
+            data_volume_model: data_volume::Model::new(),
+            max_datagram_size,
+            idle_restart: false,
+            bw_probe_samples: false,
+            pacing_rate: Self::initial_pacing_rate(cwnd),
+            next_departure_time: None,
+            send_quantum: 0,
+        };
+        bbr.set_send_quantum();
+        bbr
+    }
+
+    /// The pacing rate used until the first bandwidth sample is available
+    fn initial_pacing_rate(initial_cwnd: u32) -> Bandwidth {
+        // BBRInitPacingRate() uses the SRTT if available, otherwise 1ms. The SRTT is not known
+        // when the congestion controller is created, so the 1ms default is always used.
+        let nominal_bandwidth = Bandwidth::new(initial_cwnd as u64, Duration::from_millis(1));
+        nominal_bandwidth * startup::PACING_GAIN
+    }
+
     /// The bandwidth-delay product
     ///
     /// Based on the current estimate of maximum sending bandwidth and minimum RTT
@@ -561,6 +614,12 @@ impl BbrCongestionController {
         // The packet currently being sent has already been delayed by the `next_departure_time`
         // so we only need to base the `next_departure_time` on the current time + pacing_delay
 
+        if self.pacing_rate == Bandwidth::ZERO {
+            // Without a pacing rate there is no delay to apply
+            self.next_departure_time = None;
+            return;
+        }
+
         let pacing_delay = packet_size as u64 / self.pacing_rate;
         self.next_departure_time = Some(now + pacing_delay);
     }
@@ -722,3 +781,78 @@ impl BbrCongestionController {
             .max(self.minimum_window());
     }
 }
+
+#[derive(Debug, Default)]
+pub struct Endpoint {}
+
+impl congestion_controller::Endpoint for Endpoint {
+    type CongestionController = BbrCongestionController;
+
+    fn new_congestion_controller(
+        &mut self,
+        path_info: congestion_controller::PathInfo,
+    ) -> Self::CongestionController {
+        BbrCongestionController::new(path_info.max_datagram_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        path::MINIMUM_MTU,
+        time::{Clock, NoopClock},
+    };
+
+    #[test]
+    fn new() {
+        let bbr = BbrCongestionController::new(MINIMUM_MTU);
+
+        assert!(bbr.state.is_startup());
+        assert!(bbr.is_slow_start());
+        assert_eq!(
+            BbrCongestionController::initial_window(MINIMUM_MTU),
+            bbr.congestion_window()
+        );
+        assert_eq!(0, bbr.bytes_in_flight());
+        assert!(bbr.pacing_rate > Bandwidth::ZERO);
+        assert!(bbr.send_quantum >= MINIMUM_MTU as usize);
+        assert_eq!(None, bbr.earliest_departure_time());
+    }
+
+    #[test]
+    fn bytes_in_flight() {
+        let mut bbr = BbrCongestionController::new(MINIMUM_MTU);
+        let rtt_estimator = RttEstimator::default();
+        let random = &mut random::testing::Generator::default();
+        let now = NoopClock.get_time();
+        let mtu = MINIMUM_MTU as usize;
+
+        let mut packet_info = None;
+        while !bbr.is_congestion_limited() {
+            packet_info = Some(bbr.on_packet_sent(now, mtu, None, &rtt_estimator));
+        }
+        let packet_info = packet_info.unwrap();
+        assert!(bbr.bytes_in_flight() + MINIMUM_MTU as u32 > bbr.congestion_window());
+        assert!(bbr.earliest_departure_time().unwrap() > now);
+
+        let sent = bbr.bytes_in_flight();
+        let ack_time = now + Duration::from_millis(10);
+        bbr.on_ack(now, mtu, packet_info, &rtt_estimator, random, ack_time);
+        assert_eq!(sent - MINIMUM_MTU as u32, bbr.bytes_in_flight());
+        assert!(!bbr.is_congestion_limited());
+
+        bbr.on_packet_lost(
+            MINIMUM_MTU as u32,
+            packet_info,
+            false,
+            true,
+            random,
+            ack_time,
+        );
+        assert_eq!(sent - 2 * MINIMUM_MTU as u32, bbr.bytes_in_flight());
+
+        bbr.on_packet_discarded(mtu);
+        assert_eq!(sent - 3 * MINIMUM_MTU as u32, bbr.bytes_in_flight());
+    }
+}
@@ -123,19 +123,21 @@ impl BbrCongestionController {
                 .set_round_end(self.bw_estimator.delivered_bytes());
         }
 
-        let probe_rtt_cwnd = self.probe_rtt_cwnd();
-        if let bbr::State::ProbeRtt(probe_rtt_state) = &mut self.state {
-            probe_rtt_state.handle_probe_rtt(
-                &mut self.bw_estimator,
-                &mut self.round_counter,
-                probe_rtt_cwnd,
-                *self.bytes_in_flight,
-                now,
-            );
-            // The RFC pseudocode exits `ProbeRTT` internal to `BBRHandleProbeRTT`, whereas this
-            // code checks if the `ProbeRTT` state is ready to exit here
-            if probe_rtt_state.is_done(now) {
-                self.exit_probe_rtt(random_generator, now);
+        if self.state.is_probing_rtt() {
+            let probe_rtt_cwnd = self.probe_rtt_cwnd();
+            if let bbr::State::ProbeRtt(probe_rtt_state) = &mut self.state {
+                probe_rtt_state.handle_probe_rtt(
+                    &mut self.bw_estimator,
+                    &mut self.round_counter,
+                    probe_rtt_cwnd,
+                    *self.bytes_in_flight,
+                    now,
+                );
+                // The RFC pseudocode exits `ProbeRTT` internal to `BBRHandleProbeRTT`, whereas this
+                // code checks if the `ProbeRTT` state is ready to exit here
+                if probe_rtt_state.is_done(now) {
+                    self.exit_probe_rtt(random_generator, now);
+                }
             }
         }
 
@@ -1,6 +1,7 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+pub use bbr::BbrCongestionController;
 pub use congestion_controller::CongestionController;
 pub use cubic::CubicCongestionController;
 pub use rtt_estimator::*;
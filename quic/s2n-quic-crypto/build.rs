@@ -0,0 +1,76 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{io::Error, path::Path, process::Command};
+
+fn main() -> Result<(), Error> {
+    let env = Env::new();
+
+    // The ARMv8 cryptographic extension intrinsics were stabilized after the crate's MSRV so
+    // only enable the aarch64 backend if the current toolchain supports them.
+    if env.target_arch == "aarch64" && env.target_endian == "little" {
+        let path = Path::new("features/aarch64.rs");
+        println!("cargo:rerun-if-changed={}", path.display());
+        if env.check(path)? {
+            supports("aarch64");
+        } else {
+            println!(
+                "cargo:warning=the toolchain doesn't support the aarch64 intrinsics; \
+                 falling back to the portable AES-GCM implementation"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn supports(name: &str) {
+    println!("cargo:rustc-cfg=s2n_quic_crypto_{}", name);
+}
+
+struct Env {
+    rustc: String,
+    out_dir: String,
+    target: String,
+    target_arch: String,
+    target_endian: String,
+}
+
+impl Env {
+    fn new() -> Self {
+        // See https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-build-scripts
+        Self {
+            rustc: env("RUSTC"),
+            out_dir: env("OUT_DIR"),
+            target: env("TARGET"),
+            target_arch: env("CARGO_CFG_TARGET_ARCH"),
+            target_endian: env("CARGO_CFG_TARGET_ENDIAN"),
+        }
+    }
+
+    // Tries to type-check the library and returns if it was successful
+    //
+    // Only the metadata is emitted so the check doesn't depend on a linker for the target,
+    // which usually isn't installed when cross-compiling.
+    fn check(&self, path: &Path) -> Result<bool, Error> {
+        let mut command = Command::new(&self.rustc);
+
+        command
+            .arg("--out-dir")
+            .arg(&self.out_dir)
+            .arg("--target")
+            .arg(&self.target)
+            .arg("--crate-type")
+            .arg("lib")
+            .arg("--emit=metadata")
+            .arg(path);
+
+        Ok(command.spawn()?.wait()?.success())
+    }
+}
+
+fn env(name: &str) -> String {
+    println!("cargo:rerun-if-env-changed={}", name);
+    std::env::var(name)
+        .unwrap_or_else(|_| panic!("build script missing {:?} environment variable", name))
+}
@@ -0,0 +1,24 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Detects toolchain support for the ARMv8 cryptographic extension intrinsics:
+//! * https://developer.arm.com/architectures/instruction-sets/intrinsics/#q=vaeseq_u8
+//! * https://developer.arm.com/architectures/instruction-sets/intrinsics/#q=vmull_p64
+//!
+//! This is only type-checked, so it doesn't require a linker for the target.
+
+use core::arch::aarch64::*;
+
+/// Uses each of the intrinsics required by the aarch64 backend
+///
+/// The build will fail if they aren't available.
+#[target_feature(enable = "neon,aes")]
+pub unsafe fn probe(block: [u8; 16], key: [u8; 16]) -> u128 {
+    let block = vld1q_u8(block.as_ptr());
+    let key = vld1q_u8(key.as_ptr());
+    let block = vaesmcq_u8(vaeseq_u8(block, key));
+    let block = vaesimcq_u8(vaesdq_u8(block, key));
+    let block = veorq_u8(block, vextq_u8(block, key, 8));
+    let lanes = vreinterpretq_u64_u8(block);
+    vmull_p64(vgetq_lane_u64(lanes, 0), vgetq_lane_u64(lanes, 1))
+}
@@ -1,6 +1,10 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::iv::{Iv, NONCE_LEN};
+use core::convert::TryInto;
+use s2n_quic_core::crypto::BatchPacket;
+
 pub use s2n_quic_core::crypto::CryptoError as Error;
 pub type Result<T = (), E = Error> = core::result::Result<T, E>;
 
@@ -24,3 +28,89 @@ pub trait Aead {
         tag: &Self::Tag,
     ) -> Result;
 }
+
+/// Decrypts a packet payload which has the tag appended to the end
+#[inline(always)]
+pub fn decrypt_packet<K, const TAG_LEN: usize>(
+    key: &K,
+    iv: &Iv,
+    packet_number: u64,
+    header: &[u8],
+    payload: &mut [u8],
+) -> Result
+where
+    K: Aead<Nonce = [u8; NONCE_LEN], Tag = [u8; TAG_LEN]>,
+{
+    let nonce = iv.nonce(packet_number);
+
+    let payload_len = payload
+        .len()
+        .checked_sub(TAG_LEN)
+        .ok_or(Error::DECRYPT_ERROR)?;
+
+    let (payload, tag) = payload.split_at_mut(payload_len);
+    let tag = {
+        let res = (&tag[..]).try_into();
+        unsafe {
+            unsafe_assert!(res.is_ok());
+        }
+        res.unwrap()
+    };
+
+    key.decrypt(&nonce, header, payload, tag)
+}
+
+/// Encrypts a packet payload and writes the tag to the end
+#[inline(always)]
+pub fn encrypt_packet<K, const TAG_LEN: usize>(
+    key: &K,
+    iv: &Iv,
+    packet_number: u64,
+    header: &[u8],
+    payload: &mut [u8],
+) -> Result
+where
+    K: Aead<Nonce = [u8; NONCE_LEN], Tag = [u8; TAG_LEN]>,
+{
+    let nonce = iv.nonce(packet_number);
+
+    let payload_len = payload
+        .len()
+        .checked_sub(TAG_LEN)
+        .ok_or(Error::DECRYPT_ERROR)?;
+
+    let (payload, tag) = payload.split_at_mut(payload_len);
+    let tag = {
+        let res = tag.try_into();
+        unsafe {
+            unsafe_assert!(res.is_ok());
+        }
+        res.unwrap()
+    };
+
+    key.encrypt(&nonce, header, payload, tag)
+}
+
+/// Decrypts each packet in the batch, storing the outcome in the packet's `result`
+#[inline(always)]
+pub fn decrypt_batch<K, const TAG_LEN: usize>(key: &K, iv: &Iv, packets: &mut [BatchPacket])
+where
+    K: Aead<Nonce = [u8; NONCE_LEN], Tag = [u8; TAG_LEN]>,
+{
+    for packet in packets.iter_mut() {
+        packet.result =
+            decrypt_packet(key, iv, packet.packet_number, packet.header, packet.payload);
+    }
+}
+
+/// Encrypts each packet in the batch, storing the outcome in the packet's `result`
+#[inline(always)]
+pub fn encrypt_batch<K, const TAG_LEN: usize>(key: &K, iv: &Iv, packets: &mut [BatchPacket])
+where
+    K: Aead<Nonce = [u8; NONCE_LEN], Tag = [u8; TAG_LEN]>,
+{
+    for packet in packets.iter_mut() {
+        packet.result =
+            encrypt_packet(key, iv, packet.packet_number, packet.header, packet.payload);
+    }
+}
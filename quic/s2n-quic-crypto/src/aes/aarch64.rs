@@ -0,0 +1,359 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! AES implementation using the ARMv8 cryptographic extensions
+//!
+//! Unlike AES-NI, `AESE` and `AESD` perform the `AddRoundKey` step _before_ `SubBytes` and
+//! `ShiftRows`, and `MixColumns` is a separate instruction. The `KeyRound` implementation
+//! provides the same semantics as the x86 version but the batched encrypt/decrypt functions
+//! shift the key schedule by a round so each `AESE`/`AESMC` pair can be fused by the CPU.
+
+use crate::{
+    aes,
+    arch::*,
+    block::{BatchMut, Block, Zeroed},
+};
+use core::convert::TryInto;
+use zeroize::Zeroize;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+#[derive(Zeroize)]
+pub struct Key<const ROUNDS: usize> {
+    pub encrypt: EncryptionKey<ROUNDS>,
+    pub decrypt: DecryptionKey<ROUNDS>,
+}
+
+macro_rules! impl_key {
+    ($name:ident) => {
+        impl<const N: usize> super::$name::EncryptionKey for Key<N> {
+            type Block = uint8x16_t;
+            type KeyRound = KeyRound;
+
+            #[inline(always)]
+            fn keyround(&self, index: usize) -> &Self::KeyRound {
+                super::$name::EncryptionKey::keyround(&self.encrypt, index)
+            }
+
+            #[inline(always)]
+            fn encrypt_interleaved<B: BatchMut<Block = uint8x16_t>, F: FnMut(usize)>(
+                &self,
+                block: &mut B,
+                f: F,
+            ) {
+                self.encrypt.encrypt_batch(block, f)
+            }
+        }
+
+        impl<const N: usize> super::$name::DecryptionKey for Key<N> {
+            type Block = uint8x16_t;
+            type KeyRound = KeyRound;
+
+            #[inline(always)]
+            fn keyround(&self, index: usize) -> &Self::KeyRound {
+                super::$name::DecryptionKey::keyround(&self.decrypt, index)
+            }
+
+            #[inline(always)]
+            fn decrypt_interleaved<B: BatchMut<Block = uint8x16_t>, F: FnMut(usize)>(
+                &self,
+                block: &mut B,
+                f: F,
+            ) {
+                self.decrypt.decrypt_batch(block, f)
+            }
+        }
+
+        impl<const N: usize> super::$name::EncryptionKey for EncryptionKey<N> {
+            type Block = uint8x16_t;
+            type KeyRound = KeyRound;
+
+            #[inline(always)]
+            fn keyround(&self, index: usize) -> &Self::KeyRound {
+                unsafe {
+                    unsafe_assert!(index < N);
+                    self.0.get_unchecked(index)
+                }
+            }
+
+            #[inline(always)]
+            fn encrypt_interleaved<B: BatchMut<Block = uint8x16_t>, F: FnMut(usize)>(
+                &self,
+                block: &mut B,
+                f: F,
+            ) {
+                self.encrypt_batch(block, f)
+            }
+        }
+
+        impl<const N: usize> super::$name::DecryptionKey for DecryptionKey<N> {
+            type Block = uint8x16_t;
+            type KeyRound = KeyRound;
+
+            #[inline(always)]
+            fn keyround(&self, index: usize) -> &Self::KeyRound {
+                unsafe {
+                    unsafe_assert!(index < N);
+                    self.0.get_unchecked(index)
+                }
+            }
+
+            #[inline(always)]
+            fn decrypt_interleaved<B: BatchMut<Block = uint8x16_t>, F: FnMut(usize)>(
+                &self,
+                block: &mut B,
+                f: F,
+            ) {
+                self.decrypt_batch(block, f)
+            }
+        }
+    };
+}
+
+impl_key!(aes128);
+impl_key!(aes256);
+
+#[derive(Zeroize)]
+pub struct EncryptionKey<const ROUNDS: usize>([KeyRound; ROUNDS]);
+
+impl<const N: usize> EncryptionKey<N> {
+    #[inline(always)]
+    fn encrypt_batch<B: BatchMut<Block = uint8x16_t>, F: FnMut(usize)>(
+        &self,
+        block: &mut B,
+        mut f: F,
+    ) {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+
+            // NOTE: `N` is a constant so the compiler fully unrolls the loop and the CPU has a
+            //       large run of instructions without any branches.
+            for (idx, key) in self.0[..N - 2].iter().enumerate() {
+                block.update(|_idx, b| *b = vaesmcq_u8(vaeseq_u8(*b, key.0)));
+                f(idx);
+            }
+
+            let key = self.0[N - 2].0;
+            let last = self.0[N - 1].0;
+            block.update(|_idx, b| *b = vaeseq_u8(*b, key).xor(last));
+        }
+    }
+}
+
+#[derive(Zeroize)]
+pub struct DecryptionKey<const ROUNDS: usize>([KeyRound; ROUNDS]);
+
+impl<const N: usize> DecryptionKey<N> {
+    #[inline(always)]
+    fn decrypt_batch<B: BatchMut<Block = uint8x16_t>, F: FnMut(usize)>(
+        &self,
+        block: &mut B,
+        mut f: F,
+    ) {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+
+            // NOTE: `N` is a constant so the compiler fully unrolls the loop and the CPU has a
+            //       large run of instructions without any branches.
+            for (idx, key) in self.0[2..].iter().rev().enumerate() {
+                block.update(|_idx, b| *b = vaesimcq_u8(vaesdq_u8(*b, key.0)));
+                f(idx);
+            }
+
+            let key = self.0[1].0;
+            let last = self.0[0].0;
+            block.update(|_idx, b| *b = vaesdq_u8(*b, key).xor(last));
+        }
+    }
+}
+
+// https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.197.pdf#page=27
+const RCON: [u32; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+/// Expands the key into `N` round keys
+///
+/// ARMv8 doesn't provide an equivalent of `aeskeygenassist` so this follows the pseudo code
+/// in FIPS-197 Section 5.2, using `AESE` for the `SubWord` transformation.
+#[inline(always)]
+unsafe fn expand_key<const N: usize>(key: &[u8]) -> [KeyRound; N] {
+    // the largest schedule is for AES-256
+    let mut words = [0u32; 4 * (aes::aes256::ROUNDS + 1)];
+
+    unsafe_assert!(key.len() == aes::aes128::KEY_LEN || key.len() == aes::aes256::KEY_LEN);
+    unsafe_assert!(4 * N <= words.len());
+
+    let nk = key.len() / 4;
+
+    for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+
+    for idx in nk..(4 * N) {
+        let mut temp = words[idx - 1];
+
+        if idx % nk == 0 {
+            // RotWord is a rotation of the little-endian bytes
+            temp = sub_word(temp.rotate_right(8)) ^ RCON[idx / nk - 1];
+        } else if nk > 6 && idx % nk == 4 {
+            temp = sub_word(temp);
+        }
+
+        words[idx] = words[idx - nk] ^ temp;
+    }
+
+    let mut rounds = [KeyRound(uint8x16_t::zeroed()); N];
+
+    for (round, words) in rounds.iter_mut().zip(words.chunks_exact(4)) {
+        round.0 = vreinterpretq_u8_u32(vld1q_u32(words.as_ptr()));
+    }
+
+    words.zeroize();
+
+    rounds
+}
+
+/// Applies the S-box to each byte in the word
+#[inline(always)]
+unsafe fn sub_word(word: u32) -> u32 {
+    // When every column in the state is the same, `ShiftRows` has no effect. Using a zero key
+    // means `AESE` only applies `SubBytes`.
+    let state = vreinterpretq_u8_u32(vdupq_n_u32(word));
+    let state = vaeseq_u8(state, uint8x16_t::zeroed());
+    let state: [u32; 4] = core::mem::transmute(state);
+    state[0]
+}
+
+/// Derives the decryption round keys for the equivalent inverse cipher
+#[inline(always)]
+fn decryption_key<const N: usize>(enc: &[KeyRound; N]) -> [KeyRound; N] {
+    let mut dec = *enc;
+
+    for round in dec[1..N - 1].iter_mut() {
+        *round = round.inv_mix_columns();
+    }
+
+    dec
+}
+
+pub mod aes128 {
+    use super::*;
+    use crate::aes::aes128::KEY_LEN;
+
+    const ROUNDS: usize = aes::aes128::ROUNDS + 1;
+
+    pub type Key = super::Key<ROUNDS>;
+    pub type EncryptionKey = super::EncryptionKey<ROUNDS>;
+
+    impl Key {
+        #[inline(always)]
+        pub fn new(key: [u8; KEY_LEN]) -> Self {
+            unsafe {
+                debug_assert!(Neon::is_supported());
+
+                let enc = expand_key::<ROUNDS>(&key);
+                let dec = decryption_key(&enc);
+
+                Self {
+                    encrypt: EncryptionKey(enc),
+                    decrypt: DecryptionKey(dec),
+                }
+            }
+        }
+    }
+}
+
+pub mod aes256 {
+    use super::*;
+    use crate::aes::aes256::KEY_LEN;
+
+    const ROUNDS: usize = aes::aes256::ROUNDS + 1;
+
+    pub type Key = super::Key<ROUNDS>;
+    pub type EncryptionKey = super::EncryptionKey<ROUNDS>;
+
+    impl Key {
+        #[inline(always)]
+        pub fn new(key: [u8; KEY_LEN]) -> Self {
+            unsafe {
+                debug_assert!(Neon::is_supported());
+
+                let enc = expand_key::<ROUNDS>(&key);
+                let dec = decryption_key(&enc);
+
+                Self {
+                    encrypt: EncryptionKey(enc),
+                    decrypt: DecryptionKey(dec),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct KeyRound(uint8x16_t);
+
+impl KeyRound {
+    #[inline(always)]
+    fn inv_mix_columns(self) -> Self {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            Self(vaesimcq_u8(self.0))
+        }
+    }
+}
+
+impl Default for KeyRound {
+    #[inline(always)]
+    fn default() -> Self {
+        Self(uint8x16_t::zeroed())
+    }
+}
+
+impl zeroize::DefaultIsZeroes for KeyRound {}
+
+impl super::KeyRound for KeyRound {
+    type Block = uint8x16_t;
+
+    #[inline(always)]
+    fn xor<B: BatchMut<Block = uint8x16_t>>(&self, block: &mut B) {
+        block.update(|_idx, b| *b = b.xor(self.0));
+    }
+
+    #[inline(always)]
+    fn encrypt<B: BatchMut<Block = uint8x16_t>>(&self, block: &mut B) {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            let zero = uint8x16_t::zeroed();
+            block.update(|_idx, b| *b = vaesmcq_u8(vaeseq_u8(*b, zero)).xor(self.0));
+        }
+    }
+
+    #[inline(always)]
+    fn encrypt_finish<B: BatchMut<Block = uint8x16_t>>(&self, block: &mut B) {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            let zero = uint8x16_t::zeroed();
+            block.update(|_idx, b| *b = vaeseq_u8(*b, zero).xor(self.0));
+        }
+    }
+
+    #[inline(always)]
+    fn decrypt<B: BatchMut<Block = uint8x16_t>>(&self, block: &mut B) {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            let zero = uint8x16_t::zeroed();
+            block.update(|_idx, b| *b = vaesimcq_u8(vaesdq_u8(*b, zero)).xor(self.0));
+        }
+    }
+
+    #[inline(always)]
+    fn decrypt_finish<B: BatchMut<Block = uint8x16_t>>(&self, block: &mut B) {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            let zero = uint8x16_t::zeroed();
+            block.update(|_idx, b| *b = vaesdq_u8(*b, zero).xor(self.0));
+        }
+    }
+}
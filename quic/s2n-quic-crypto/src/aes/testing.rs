@@ -32,6 +32,9 @@ macro_rules! aes_impl {
                     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
                     crate::aes::x86::testing::$name::implementations(&mut impls);
 
+                    #[cfg(s2n_quic_crypto_aarch64)]
+                    crate::aes::aarch64::testing::$name::implementations(&mut impls);
+
                     #[cfg(test)]
                     super::rust_crypto::$name::implementations(&mut impls);
 
@@ -4,6 +4,9 @@
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 mod x86;
 
+#[cfg(s2n_quic_crypto_aarch64)]
+mod aarch64;
+
 mod ring;
 
 pub mod generic;
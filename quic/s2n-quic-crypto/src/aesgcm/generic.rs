@@ -11,13 +11,26 @@ use crate::{
     block::{Batch, BatchMut, Block, Zeroed, LEN as BLOCK_LEN},
     ctr::Ctr,
     ghash::GHash,
+    iv::Iv,
 };
 use core::{
     marker::PhantomData,
     sync::atomic::{compiler_fence, Ordering},
 };
+use s2n_quic_core::crypto::BatchPacket;
 use zeroize::Zeroize;
 
+/// The maximum number of packets which are processed together in [`AesGcm::decrypt_batch`] and
+/// [`AesGcm::encrypt_batch`]
+pub const MAX_BATCH_PACKETS: usize = 8;
+
+/// Whether the packets in a batch are being opened or sealed
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Decrypt,
+    Encrypt,
+}
+
 pub struct AesGcm<Aes, GHash, Ctr, const N: usize> {
     aes: Aes,
     ghash: GHash,
@@ -257,6 +270,274 @@ where
         // finalize the ghash and xor the tag with the encrypted ek0
         self.ghash.finish(ghash_state).xor(ek0)
     }
+
+    /// Decrypts a batch of packets, storing the outcome in each packet's `result`
+    ///
+    /// Rather than decrypting each packet on its own, the AES-CTR stream is generated for all of
+    /// the packets in the batch, `N` blocks at a time. The GHash of the ciphertext is interleaved
+    /// with each of the AES rounds so short packets don't leave the pipeline mostly empty.
+    #[inline(always)]
+    pub fn decrypt_batch(&self, iv: &Iv, packets: &mut [BatchPacket])
+    where
+        for<'a> &'a mut [u8]: Payload<B>,
+    {
+        for packets in packets.chunks_mut(MAX_BATCH_PACKETS) {
+            self.process_chunk(iv, packets, Mode::Decrypt);
+        }
+    }
+
+    /// Encrypts a batch of packets, storing the outcome in each packet's `result`
+    ///
+    /// Each payload includes space for the tag at the end. The packets share the AES-CTR and
+    /// GHash pipeline in the same way as [`Self::decrypt_batch`].
+    #[inline(always)]
+    pub fn encrypt_batch(&self, iv: &Iv, packets: &mut [BatchPacket])
+    where
+        for<'a> &'a mut [u8]: Payload<B>,
+    {
+        for packets in packets.chunks_mut(MAX_BATCH_PACKETS) {
+            self.process_chunk(iv, packets, Mode::Encrypt);
+        }
+    }
+
+    #[inline(always)]
+    fn process_chunk(&self, iv: &Iv, packets: &mut [BatchPacket], mode: Mode)
+    where
+        for<'a> &'a mut [u8]: Payload<B>,
+    {
+        assert!(
+            A::ROUNDS >= N,
+            "The number of encryption rounds must be at least the batch size"
+        );
+
+        let count = packets.len();
+
+        unsafe {
+            unsafe_assert!(count <= MAX_BATCH_PACKETS);
+        }
+
+        // the payload length of each packet, excluding the tag
+        let mut payload_lens = [0usize; MAX_BATCH_PACKETS];
+
+        for (packet, payload_len) in packets.iter_mut().zip(payload_lens.iter_mut()) {
+            if let Some(len) = packet.payload.len().checked_sub(TAG_LEN) {
+                *payload_len = len;
+                packet.result = Ok(());
+            } else {
+                packet.result = Err(aead::Error::DECRYPT_ERROR);
+            }
+        }
+
+        // returns the next packet, starting at `idx`, that can be processed
+        let next_packet = |packets: &[BatchPacket], idx: usize| {
+            (idx..count)
+                .find(|idx| packets[*idx].result.is_ok())
+                .unwrap_or(count)
+        };
+
+        let aad_block_count =
+            |packet: &BatchPacket| (packet.header.len() + BLOCK_LEN - 1) / BLOCK_LEN;
+        let payload_block_count = |len: usize| (len + BLOCK_LEN - 1) / BLOCK_LEN;
+
+        // the encrypted ek0 block for each packet
+        let mut ek0s = [B::zeroed(); MAX_BATCH_PACKETS];
+        // the finalized GHash for each packet
+        let mut hashes = [B::zeroed(); MAX_BATCH_PACKETS];
+        // the number of payload blocks which have been encrypted for each packet
+        //
+        // GHash is computed over the ciphertext so, when sealing, it can't hash a payload block
+        // until the AES-CTR stream has been applied to it.
+        let mut sealed_blocks = [0usize; MAX_BATCH_PACKETS];
+
+        // The GHash cursor
+        //
+        // Each packet hashes its AAD blocks, followed by the ciphertext blocks and the bit counts.
+        let mut ghash_packet = next_packet(packets, 0);
+        let mut ghash_block = 0;
+        let mut ghash_state = None;
+
+        /// Hashes the next block in the batch
+        macro_rules! ghash_step {
+            () => {
+                if ghash_packet < count {
+                    let packet = &packets[ghash_packet];
+                    let payload_len = payload_lens[ghash_packet];
+                    let aad_blocks = aad_block_count(packet);
+                    let payload_blocks = payload_block_count(payload_len);
+                    let sealed = sealed_blocks[ghash_packet];
+                    let is_ready = mode == Mode::Decrypt
+                        || ghash_block < aad_blocks + sealed
+                        || sealed == payload_blocks;
+
+                    if is_ready {
+                        let state = ghash_state.get_or_insert_with(|| {
+                            // add 1 for the `bit_counts` block
+                            self.ghash.start(aad_blocks + payload_blocks + 1)
+                        });
+
+                        if ghash_block < aad_blocks {
+                            let block = read_block::<B>(packet.header, ghash_block * BLOCK_LEN);
+                            self.ghash.update(state, &block);
+                            ghash_block += 1;
+                        } else if ghash_block < aad_blocks + payload_blocks {
+                            let offset = (ghash_block - aad_blocks) * BLOCK_LEN;
+                            let block = read_block::<B>(&packet.payload[..payload_len], offset);
+                            self.ghash.update(state, &block);
+                            ghash_block += 1;
+                        } else {
+                            let bit_counts =
+                                B::from_array(bit_counts(packet.header.len(), payload_len));
+                            self.ghash.update(state, &bit_counts);
+
+                            if let Some(state) = ghash_state.take() {
+                                hashes[ghash_packet] = self.ghash.finish(state);
+                            }
+
+                            ghash_packet = next_packet(packets, ghash_packet + 1);
+                            ghash_block = 0;
+                        }
+                    }
+                }
+            };
+        }
+
+        // The AES-CTR cursor
+        //
+        // Each packet encrypts its ek0 block, followed by a block for each of the payload blocks.
+        let mut ctr_packet = next_packet(packets, 0);
+        let mut ctr_block = 0;
+        let mut ctr = C::new(&iv.nonce(packets.get(ctr_packet).map_or(0, |p| p.packet_number)));
+
+        let mut cipher_blocks = [B::zeroed(); N];
+        // the packet and block index for each of the cipher blocks
+        let mut targets = [(0usize, 0usize); N];
+
+        while ctr_packet < count {
+            let mut len = 0;
+
+            // fill the batch with the next counter blocks
+            cipher_blocks.update(
+                #[inline(always)]
+                |idx, block| {
+                    if ctr_packet >= count {
+                        return;
+                    }
+
+                    if ctr_block > 0 {
+                        ctr.increment();
+                    }
+
+                    *block = ctr.block();
+
+                    unsafe {
+                        unsafe_assert!(idx < targets.len());
+                        *targets.get_unchecked_mut(idx) = (ctr_packet, ctr_block);
+                    }
+                    len = idx + 1;
+
+                    ctr_block += 1;
+
+                    if ctr_block > payload_block_count(payload_lens[ctr_packet]) {
+                        ctr_packet = next_packet(packets, ctr_packet + 1);
+                        ctr_block = 0;
+                        if let Some(packet) = packets.get(ctr_packet) {
+                            ctr = C::new(&iv.nonce(packet.packet_number));
+                        }
+                    }
+                },
+            );
+
+            // encrypt the cipher blocks and interleave ghash
+            self.aes.encrypt_interleaved(
+                &mut cipher_blocks,
+                #[inline(always)]
+                |_idx| {
+                    ghash_step!();
+
+                    // force the compiler to interleave the AES and GHash instructions.
+                    // without this, it will reorder and be drastically slower
+                    compiler_fence(Ordering::SeqCst);
+                },
+            );
+
+            // apply the AES-CTR stream to the payloads
+            for (block, (packet_idx, block_idx)) in cipher_blocks.iter().zip(targets).take(len) {
+                if block_idx == 0 {
+                    ek0s[packet_idx] = *block;
+                    continue;
+                }
+
+                let aad_blocks = aad_block_count(&packets[packet_idx]);
+                let payload_block = block_idx - 1;
+
+                // GHash expects the ciphertext so, when opening, make sure it's caught up with
+                // the stream before the block is decrypted
+                while mode == Mode::Decrypt
+                    && (ghash_packet < packet_idx
+                        || (ghash_packet == packet_idx
+                            && ghash_block <= aad_blocks + payload_block))
+                {
+                    ghash_step!();
+                }
+
+                let payload_len = payload_lens[packet_idx];
+                let offset = payload_block * BLOCK_LEN;
+                let rem = (payload_len - offset).min(BLOCK_LEN);
+                let mut payload = &mut packets[packet_idx].payload[offset..offset + rem];
+
+                unsafe {
+                    if rem == BLOCK_LEN {
+                        let payload_block = payload.read_block();
+                        payload.xor_block(payload_block, *block);
+                    } else {
+                        unsafe_assert!(0 < rem && rem < BLOCK_LEN);
+                        let payload_block = payload.read_last_block(rem);
+                        payload.xor_last_block(payload_block, *block, rem);
+                    }
+                }
+
+                sealed_blocks[packet_idx] += 1;
+            }
+        }
+
+        // hash any remaining blocks
+        while ghash_packet < count {
+            ghash_step!();
+        }
+
+        if mode == Mode::Encrypt {
+            for (idx, packet) in packets.iter_mut().enumerate() {
+                if packet.result.is_err() {
+                    continue;
+                }
+
+                let tag = hashes[idx].xor(ek0s[idx]).into_array();
+                packet.payload[payload_lens[idx]..].copy_from_slice(&tag);
+            }
+
+            return;
+        }
+
+        // we don't want the compiler to perform any tag checks until the very end
+        compiler_fence(Ordering::SeqCst);
+
+        for (idx, packet) in packets.iter_mut().enumerate() {
+            if packet.result.is_err() {
+                continue;
+            }
+
+            let tag = &packet.payload[payload_lens[idx]..];
+            let tag = read_block::<B>(tag, 0);
+            let expected_tag = hashes[idx].xor(ek0s[idx]);
+
+            packet.result = tag
+                .ct_ensure_eq(expected_tag)
+                .map_err(|_| aead::Error::DECRYPT_ERROR);
+        }
+
+        // we don't want the compiler to reorder anything from the tag check
+        compiler_fence(Ordering::SeqCst);
+    }
 }
 
 impl<A, G, C, B, const N: usize> aead::Aead for AesGcm<A, G, C, N>
@@ -345,6 +626,16 @@ where
     (blocks, block_count)
 }
 
+/// Reads a single block from `bytes` at `offset`, padding it with zeros if needed
+#[inline(always)]
+fn read_block<B: Block>(bytes: &[u8], offset: usize) -> B {
+    let bytes = &bytes[offset..];
+    let len = bytes.len().min(BLOCK_LEN);
+    let mut block = [0u8; BLOCK_LEN];
+    block[..len].copy_from_slice(&bytes[..len]);
+    B::from_array(block)
+}
+
 #[inline(always)]
 fn bit_counts(aad_len: usize, payload_len: usize) -> [u8; BLOCK_LEN] {
     use core::mem::size_of;
@@ -12,6 +12,10 @@ cfg_if! {
         pub use core::arch::x86_64::*;
         mod x86;
         pub use x86::*;
+    } else if #[cfg(s2n_quic_crypto_aarch64)] {
+        pub use core::arch::aarch64::*;
+        mod aarch64;
+        pub use aarch64::*;
     }
 }
 
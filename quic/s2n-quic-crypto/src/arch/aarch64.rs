@@ -0,0 +1,27 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::arch::Arch;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref IS_SUPPORTED: bool = is_aarch64_feature_detected!("neon")
+        && is_aarch64_feature_detected!("aes")
+        && is_aarch64_feature_detected!("pmull");
+}
+
+pub struct Neon;
+
+impl Arch for Neon {
+    #[inline(always)]
+    fn is_supported() -> bool {
+        *IS_SUPPORTED
+    }
+
+    #[target_feature(enable = "neon,aes")]
+    #[inline]
+    unsafe fn call<F: FnOnce() -> R, R>(f: F) -> R {
+        debug_assert!(Self::is_supported());
+        f()
+    }
+}
@@ -4,6 +4,9 @@
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 pub mod x86;
 
+#[cfg(s2n_quic_crypto_aarch64)]
+pub mod aarch64;
+
 pub const LEN: usize = 16;
 
 pub trait Block: Copy + Zeroed {
@@ -70,3 +73,50 @@ impl_array!(5, [0, 1, 2, 3, 4]);
 impl_array!(6, [0, 1, 2, 3, 4, 5]);
 impl_array!(7, [0, 1, 2, 3, 4, 5, 6]);
 impl_array!(8, [0, 1, 2, 3, 4, 5, 6, 7]);
+
+/// Copies up to 16 bytes from `from` into `to`
+///
+/// This exists to avoid having to call memcpy
+#[allow(dead_code)] // this is only used by the platform-specific block implementations
+#[inline(always)]
+pub(crate) unsafe fn copy_128(mut from: *const u8, mut to: *mut u8, mut len: usize) {
+    macro_rules! copy {
+        ($($len:expr),*) => {
+            $(
+                if let Some(next) = len.checked_sub($len) {
+                    len = next;
+                    *(to as *mut [u8; $len]) = *(from as *const [u8; $len]);
+                    from = from.add($len);
+                    to = to.add($len);
+                }
+            )*
+        }
+    }
+
+    copy!(128, 64, 32, 16, 8, 4, 2, 1);
+    let _ = from;
+    let _ = to;
+    let _ = len;
+}
+
+#[test]
+fn copy_128_test() {
+    for i in 0..LEN {
+        dbg!(i);
+
+        let mut expected = [1u8; LEN];
+
+        let mut source = [0u8; LEN];
+        for (a, b) in source.iter_mut().zip(&mut expected).take(i) {
+            *a = 2;
+            *b = 2;
+        }
+
+        let mut dest = [1u8; LEN];
+        unsafe {
+            copy_128(source.as_ptr(), dest.as_mut_ptr(), i);
+        }
+
+        assert_eq!(dest, expected);
+    }
+}
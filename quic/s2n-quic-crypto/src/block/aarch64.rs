@@ -0,0 +1,186 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    arch::*,
+    block::{copy_128, Batch, BatchMut, Block, Zeroed},
+};
+use core::mem::size_of;
+
+pub const LEN: usize = size_of::<uint8x16_t>();
+
+impl Block for uint8x16_t {
+    #[inline(always)]
+    fn from_array(block: [u8; LEN]) -> Self {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            vld1q_u8(block.as_ptr())
+        }
+    }
+
+    #[inline(always)]
+    fn into_array(self) -> [u8; LEN] {
+        unsafe { core::mem::transmute(self) }
+    }
+
+    #[inline(always)]
+    fn xor(self, x: Self) -> Self {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            veorq_u8(self, x)
+        }
+    }
+
+    #[inline(always)]
+    fn ct_ensure_eq(self, b: Self) -> Result<(), ()> {
+        // `eor` and `orr` are both constant time so the only branch is on the final
+        // scalar value.
+        //
+        // By preventing inlining, we can ensure the compiler doesn't perform a direct jump based
+        // on the comparison at the caller location, but instead reads from the return value.
+        #[inline(never)]
+        #[target_feature(enable = "neon")]
+        unsafe fn neon_ct_eq(a: uint8x16_t, b: uint8x16_t) -> Result<(), ()> {
+            let c = a.xor(b);
+            let c: [u64; 2] = core::mem::transmute(c);
+            let res = c[0] | c[1];
+            if res == 0 {
+                Ok(())
+            } else {
+                Err(())
+            }
+        }
+
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            neon_ct_eq(self, b)
+        }
+    }
+}
+
+impl Batch for uint8x16_t {
+    type Block = uint8x16_t;
+
+    #[inline(always)]
+    fn for_each<F: FnMut(usize, &uint8x16_t)>(&self, mut f: F) {
+        f(0, self);
+    }
+}
+
+impl BatchMut for uint8x16_t {
+    #[inline(always)]
+    fn update<F: FnMut(usize, &mut uint8x16_t)>(&mut self, mut f: F) {
+        f(0, self);
+    }
+}
+
+impl Zeroed for uint8x16_t {
+    #[inline(always)]
+    fn zeroed() -> Self {
+        unsafe { core::mem::transmute([0u8; 16]) }
+    }
+}
+
+pub trait Uint8x16Ext {
+    fn reverse(self) -> Self;
+    fn swap_halves(self) -> Self;
+    fn shift_left_half(self) -> Self;
+    fn shift_right_half(self) -> Self;
+    fn from_slice(bytes: &[u8]) -> Self;
+    fn into_slice(self, bytes: &mut [u8]);
+    fn mask(self, len: usize) -> Self;
+}
+
+impl Uint8x16Ext for uint8x16_t {
+    #[inline(always)]
+    fn reverse(self) -> Self {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+
+            // reverse the bytes in each 64-bit lane and then swap the lanes
+            vrev64q_u8(self).swap_halves()
+        }
+    }
+
+    /// Swaps the upper and lower 64 bits
+    ///
+    /// Equivalent to `_mm_shuffle_epi32(self, 78)`
+    #[inline(always)]
+    fn swap_halves(self) -> Self {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            vextq_u8(self, self, 8)
+        }
+    }
+
+    /// Shifts the lower 64 bits into the upper 64 bits, filling the lower bits with zeros
+    ///
+    /// Equivalent to `_mm_slli_si128(self, 8)`
+    #[inline(always)]
+    fn shift_left_half(self) -> Self {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            vextq_u8(Self::zeroed(), self, 8)
+        }
+    }
+
+    /// Shifts the upper 64 bits into the lower 64 bits, filling the upper bits with zeros
+    ///
+    /// Equivalent to `_mm_srli_si128(self, 8)`
+    #[inline(always)]
+    fn shift_right_half(self) -> Self {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            vextq_u8(self, Self::zeroed(), 8)
+        }
+    }
+
+    #[inline(always)]
+    fn from_slice(bytes: &[u8]) -> Self {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+
+            let mut array = [0u8; LEN];
+            copy_128(bytes.as_ptr(), array.as_mut_ptr(), bytes.len());
+            Self::from_array(array)
+        }
+    }
+
+    #[inline(always)]
+    fn into_slice(self, bytes: &mut [u8]) {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            unsafe_assert!(bytes.len() <= LEN);
+            copy_128(
+                &self as *const _ as *const u8,
+                bytes.as_mut_ptr(),
+                bytes.len(),
+            );
+        }
+    }
+
+    #[inline(always)]
+    fn mask(self, len: usize) -> Self {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            unsafe_assert!(0 < len && len < LEN);
+
+            // compute a mask that can be shifted to only include a `len` of bytes
+            const MASK: [u8; 31] = {
+                let mut mask = [0u8; 31];
+                let mut idx = 0;
+                // only fill in the first `LEN` bytes
+                while idx < LEN {
+                    mask[idx] = 0xff;
+                    idx += 1;
+                }
+                mask
+            };
+
+            let offset = MASK.get_unchecked(LEN - len);
+            let mask = vld1q_u8(offset as *const u8);
+
+            vandq_u8(self, mask)
+        }
+    }
+}
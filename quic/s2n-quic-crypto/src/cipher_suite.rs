@@ -1,19 +1,22 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{aead::Aead, header_key::HeaderKey, iv};
+use crate::{header_key::HeaderKey, iv};
 use ::ring::{aead, hkdf};
 use core::fmt;
-use s2n_quic_core::crypto::{label, CryptoError};
+use s2n_quic_core::crypto::{label, BatchPacket, CryptoError};
 use zeroize::{Zeroize, Zeroizing};
 
 mod negotiated;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 #[path = "cipher_suite/x86.rs"]
 mod platform;
+#[cfg(s2n_quic_crypto_aarch64)]
+#[path = "cipher_suite/aarch64.rs"]
+mod platform;
 mod ring;
 
-#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", s2n_quic_crypto_aarch64)))]
 use self::ring as platform;
 
 pub use negotiated::NegotiatedCipherSuite;
@@ -129,26 +132,7 @@ macro_rules! impl_cipher_suite {
                     header: &[u8],
                     payload: &mut [u8],
                 ) -> Result<(), CryptoError> {
-                    let nonce = self.iv.nonce(packet_number);
-
-                    let payload_len = payload
-                        .len()
-                        .checked_sub(TAG_LEN)
-                        .ok_or_else(|| CryptoError::DECRYPT_ERROR)?;
-
-                    let (payload, tag) = payload.split_at_mut(payload_len);
-                    let tag = {
-                        use core::convert::TryInto;
-                        let res = (&tag[..]).try_into();
-                        unsafe {
-                            unsafe_assert!(res.is_ok());
-                        }
-                        res.unwrap()
-                    };
-
-                    self.key.decrypt(&nonce, header, payload, tag)?;
-
-                    Ok(())
+                    crate::aead::decrypt_packet(&self.key, &self.iv, packet_number, header, payload)
                 }
 
                 #[inline]
@@ -158,26 +142,17 @@ macro_rules! impl_cipher_suite {
                     header: &[u8],
                     payload: &mut [u8],
                 ) -> Result<(), CryptoError> {
-                    let nonce = self.iv.nonce(packet_number);
-
-                    let payload_len = payload
-                        .len()
-                        .checked_sub(TAG_LEN)
-                        .ok_or_else(|| CryptoError::DECRYPT_ERROR)?;
-
-                    let (payload, tag) = payload.split_at_mut(payload_len);
-                    let tag = {
-                        use core::convert::TryInto;
-                        let res = tag.try_into();
-                        unsafe {
-                            unsafe_assert!(res.is_ok());
-                        }
-                        res.unwrap()
-                    };
+                    crate::aead::encrypt_packet(&self.key, &self.iv, packet_number, header, payload)
+                }
 
-                    self.key.encrypt(&nonce, header, payload, tag)?;
+                #[inline]
+                fn decrypt_batch(&self, packets: &mut [BatchPacket]) {
+                    self.key.decrypt_batch(&self.iv, packets)
+                }
 
-                    Ok(())
+                #[inline]
+                fn encrypt_batch(&self, packets: &mut [BatchPacket]) {
+                    self.key.encrypt_batch(&self.iv, packets)
                 }
 
                 #[inline]
@@ -263,6 +238,150 @@ macro_rules! impl_cipher_suite {
                     "key update label mismatch"
                 );
             }
+            #[test]
+            fn decrypt_batch_test() {
+                use s2n_quic_core::crypto::Key as _;
+
+                let secret = hkdf::Prk::new_less_safe($digest, &[1; 48]);
+                let (mut key, _header_key) = $name::new(secret);
+                // enable the precomputed implementation, if the platform supports it
+                key.update_pmtu(1500);
+
+                let header = [2u8; 21];
+                let payload_lens = [0, 1, 15, 16, 17, 48, 100, 1200, 3, 31, 33];
+                // corrupt a packet in the middle of the first batch
+                let corrupted = 3;
+
+                let cleartext: Vec<Vec<u8>> = payload_lens
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, len)| (0..*len).map(|v| (v + idx) as u8).collect())
+                    .collect();
+
+                let mut payloads: Vec<Vec<u8>> = cleartext
+                    .iter()
+                    .enumerate()
+                    .map(|(packet_number, cleartext)| {
+                        let mut payload = cleartext.clone();
+                        payload.extend_from_slice(&[0; TAG_LEN]);
+                        key.encrypt(packet_number as _, &header, &mut payload)
+                            .unwrap();
+                        payload
+                    })
+                    .collect();
+
+                let last = payloads[corrupted].len() - 1;
+                payloads[corrupted][last] ^= 1;
+
+                let mut packets: Vec<_> = payloads
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(packet_number, payload)| {
+                        BatchPacket::new(packet_number as _, &header, payload)
+                    })
+                    .collect();
+
+                key.decrypt_batch(&mut packets);
+
+                for (idx, packet) in packets.iter().enumerate() {
+                    if idx == corrupted {
+                        assert!(packet.result.is_err());
+                    } else {
+                        assert!(packet.result.is_ok(), "packet {} failed", idx);
+                        assert_eq!(
+                            &packet.payload[..payload_lens[idx]],
+                            &cleartext[idx][..],
+                            "packet {} mismatch",
+                            idx
+                        );
+                    }
+                }
+            }
+
+            #[test]
+            fn header_protection_masks_test() {
+                use s2n_quic_core::crypto::{HeaderKey as _, HeaderProtectionMask};
+
+                let secret = hkdf::Prk::new_less_safe($digest, &[1; 48]);
+                let (_key, header_key) = $name::new(secret);
+
+                let sample_len = header_key.opening_sample_len();
+                // use more samples than fit in a single batch
+                let samples: Vec<Vec<u8>> = (0..11u8)
+                    .map(|idx| (0..sample_len).map(|v| v as u8 ^ idx).collect())
+                    .collect();
+                let samples: Vec<&[u8]> = samples.iter().map(|sample| &sample[..]).collect();
+
+                let mut masks = vec![HeaderProtectionMask::default(); samples.len()];
+                header_key.opening_header_protection_masks(&samples, &mut masks);
+                for (sample, mask) in samples.iter().zip(&masks) {
+                    assert_eq!(&header_key.opening_header_protection_mask(sample), mask);
+                }
+
+                let mut masks = vec![HeaderProtectionMask::default(); samples.len()];
+                header_key.sealing_header_protection_masks(&samples, &mut masks);
+                for (sample, mask) in samples.iter().zip(&masks) {
+                    assert_eq!(&header_key.sealing_header_protection_mask(sample), mask);
+                }
+            }
+
+            #[test]
+            fn encrypt_batch_test() {
+                use s2n_quic_core::crypto::Key as _;
+
+                let secret = hkdf::Prk::new_less_safe($digest, &[1; 48]);
+                let (mut key, _header_key) = $name::new(secret);
+                // enable the precomputed implementation, if the platform supports it
+                key.update_pmtu(1500);
+
+                let header = [2u8; 21];
+                // include a payload which is too short to hold the tag
+                let payload_lens = [16, 17, 31, 64, 100, 1200, 5, 48, 19, 33];
+                let too_short = 6;
+
+                let payloads: Vec<Vec<u8>> = payload_lens
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, len)| (0..*len).map(|v| (v + idx) as u8).collect())
+                    .collect();
+
+                // seal each packet individually to compare against the batch
+                let expected: Vec<_> = payloads
+                    .iter()
+                    .enumerate()
+                    .map(|(packet_number, payload)| {
+                        let mut payload = payload.clone();
+                        key.encrypt(packet_number as _, &header, &mut payload)
+                            .map(|_| payload)
+                    })
+                    .collect();
+
+                let mut actual = payloads.clone();
+                let mut packets: Vec<_> = actual
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(packet_number, payload)| {
+                        BatchPacket::new(packet_number as _, &header, payload)
+                    })
+                    .collect();
+
+                key.encrypt_batch(&mut packets);
+
+                for (idx, packet) in packets.iter().enumerate() {
+                    if idx == too_short {
+                        assert!(packet.result.is_err());
+                        assert!(expected[idx].is_err());
+                    } else {
+                        assert!(packet.result.is_ok(), "packet {} failed", idx);
+                        assert_eq!(
+                            &packet.payload[..],
+                            &expected[idx].as_ref().unwrap()[..],
+                            "packet {} mismatch",
+                            idx
+                        );
+                    }
+                }
+            }
         }
 
         pub use $lower::$name;
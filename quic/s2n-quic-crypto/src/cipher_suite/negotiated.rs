@@ -7,7 +7,7 @@ use crate::{
 };
 use core::fmt;
 use ring::{aead, hkdf};
-use s2n_quic_core::crypto::{self, CryptoError};
+use s2n_quic_core::crypto::{self, BatchPacket, CryptoError};
 
 // ignore casing warnings in order to preserve the IANA name
 #[allow(non_camel_case_types, clippy::all)]
@@ -126,6 +126,16 @@ impl crypto::Key for NegotiatedCipherSuite {
     fn cipher_suite(&self) -> s2n_quic_core::crypto::tls::CipherSuite {
         dispatch!(self, |cipher| cipher.cipher_suite())
     }
+
+    #[inline]
+    fn decrypt_batch(&self, packets: &mut [BatchPacket]) {
+        dispatch!(self, |cipher| cipher.decrypt_batch(packets))
+    }
+
+    #[inline]
+    fn encrypt_batch(&self, packets: &mut [BatchPacket]) {
+        dispatch!(self, |cipher| cipher.encrypt_batch(packets))
+    }
 }
 
 impl fmt::Debug for NegotiatedCipherSuite {
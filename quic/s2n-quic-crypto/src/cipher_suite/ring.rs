@@ -5,7 +5,9 @@ macro_rules! key {
     ($name:ident, $ring_cipher:path, $key_size:expr, $tag_len:expr) => {
         pub mod $name {
             use super::super::$name::{KEY_LEN, NONCE_LEN, TAG_LEN};
+            use crate::iv::Iv;
             use ::ring::aead::{self, LessSafeKey, UnboundKey};
+            use s2n_quic_core::crypto::BatchPacket;
             use zeroize::Zeroize;
 
             pub struct Key {
@@ -40,6 +42,16 @@ macro_rules! key {
                 pub fn update_pmtu(&mut self, _secret: &[u8; KEY_LEN], _mtu: u16) {
                     unimplemented!();
                 }
+
+                #[inline]
+                pub fn decrypt_batch(&self, iv: &Iv, packets: &mut [BatchPacket]) {
+                    crate::aead::decrypt_batch(self, iv, packets)
+                }
+
+                #[inline]
+                pub fn encrypt_batch(&self, iv: &Iv, packets: &mut [BatchPacket]) {
+                    crate::aead::encrypt_batch(self, iv, packets)
+                }
             }
 
             impl Zeroize for Key {
@@ -22,7 +22,9 @@ macro_rules! aesgcm {
                 block::{Block, Zeroed as _, LEN as BLOCK_LEN},
                 ctr::x86::Ctr,
                 ghash::x86::{hkey, precomputed},
+                iv::Iv,
             };
+            use s2n_quic_core::crypto::BatchPacket;
             use zeroize::{Zeroize, ZeroizeOnDrop};
 
             // Even though the ring variant is quite large, it's not worth allocating since we will
@@ -105,6 +107,30 @@ macro_rules! aesgcm {
                 }
             }
 
+            impl Key {
+                #[inline]
+                pub fn decrypt_batch(&self, iv: &Iv, packets: &mut [BatchPacket]) {
+                    match self {
+                        Self::Precomputed(key) => unsafe {
+                            debug_assert!(Avx2::is_supported());
+                            key.decrypt_batch_impl(iv, packets)
+                        },
+                        Self::Ring(key) => key.decrypt_batch(iv, packets),
+                    }
+                }
+
+                #[inline]
+                pub fn encrypt_batch(&self, iv: &Iv, packets: &mut [BatchPacket]) {
+                    match self {
+                        Self::Precomputed(key) => unsafe {
+                            debug_assert!(Avx2::is_supported());
+                            key.encrypt_batch_impl(iv, packets)
+                        },
+                        Self::Ring(key) => key.encrypt_batch(iv, packets),
+                    }
+                }
+            }
+
             type PrecomputedGHash = precomputed::Allocated<hkey::H>;
             type PrecomputedAesGcmKey = AesGcm<Wrapper<EncryptionKey>, PrecomputedGHash, Ctr, 6>;
 
@@ -153,6 +179,22 @@ macro_rules! aesgcm {
                 ) -> aead::Result {
                     aead::Aead::decrypt(&self.key, nonce, aad, input, tag)
                 }
+
+                /// Decrypts the entire batch without leaving the target feature context so the
+                /// AES-CTR and GHash pipeline can be shared between the packets
+                #[inline]
+                #[target_feature(enable = "aes,avx2,pclmulqdq")]
+                unsafe fn decrypt_batch_impl(&self, iv: &Iv, packets: &mut [BatchPacket]) {
+                    self.key.decrypt_batch(iv, packets)
+                }
+
+                /// Encrypts the entire batch without leaving the target feature context so the
+                /// AES-CTR and GHash pipeline can be shared between the packets
+                #[inline]
+                #[target_feature(enable = "aes,avx2,pclmulqdq")]
+                unsafe fn encrypt_batch_impl(&self, iv: &Iv, packets: &mut [BatchPacket]) {
+                    self.key.encrypt_batch(iv, packets)
+                }
             }
 
             impl aead::Aead for PrecomputedKey {
@@ -6,6 +6,9 @@ use crate::block::{Batch, Block};
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 pub mod x86;
 
+#[cfg(s2n_quic_crypto_aarch64)]
+pub mod aarch64;
+
 #[cfg(any(test, feature = "testing"))]
 pub mod testing;
 
@@ -0,0 +1,138 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Port of the x86 GHASH algorithms to the ARMv8 `PMULL` instruction
+//!
+//! The operations are kept in the same order as the x86 implementation to make comparing the
+//! two straightforward.
+
+use crate::{
+    arch::*,
+    block::{aarch64::Uint8x16Ext, Block},
+};
+
+// https://github.com/awslabs/aws-lc/blob/5833176448d48aff0c2dc4c1ab745649c769a7a6/crypto/cipher_extra/asm/aes128gcmsiv-x86_64.pl#L58
+// poly:
+// .quad 0x1, 0xc200000000000000
+const POLYNOMIAL: uint8x16_t = unsafe { core::mem::transmute([0x1u64, 0xc200000000000000]) };
+
+/// Carry-less multiplication of the 64-bit lanes selected by `imm`
+///
+/// The lanes are selected in the same way as `_mm_clmulepi64_si128`: bit 0 selects the lane
+/// of `a` and bit 4 selects the lane of `b`.
+#[inline(always)]
+pub unsafe fn clmul(a: uint8x16_t, b: uint8x16_t, imm: u8) -> uint8x16_t {
+    let a = vreinterpretq_u64_u8(a);
+    let b = vreinterpretq_u64_u8(b);
+    let a = if imm & 0x01 == 0 {
+        vgetq_lane_u64(a, 0)
+    } else {
+        vgetq_lane_u64(a, 1)
+    };
+    let b = if imm & 0x10 == 0 {
+        vgetq_lane_u64(b, 0)
+    } else {
+        vgetq_lane_u64(b, 1)
+    };
+    vreinterpretq_u8_p128(vmull_p64(a, b))
+}
+
+// From https://github.com/awslabs/aws-lc/blob/aed75eb04d322d101941e1377f274484f5e4f5b8/crypto/fipsmodule/modes/asm/ghash-x86_64.pl#L717
+#[inline(always)]
+// This implementation is written to closely follow the original code
+#[allow(unknown_lints, clippy::needless_late_init)]
+pub unsafe fn init(mut h: uint8x16_t) -> uint8x16_t {
+    let mut t1;
+    let t2;
+    let mut t3;
+
+    // # <<1 twist
+    // vpshufd		\$0b11111111,$Hkey,$T2	# broadcast uppermost dword
+    t2 = vdupq_laneq_u32(vreinterpretq_u32_u8(h), 3);
+    // vpsrlq		\$63,$Hkey,$T1
+    t1 = vreinterpretq_u8_u64(vshrq_n_u64(vreinterpretq_u64_u8(h), 63));
+    // vpsllq		\$1,$Hkey,$Hkey
+    h = vreinterpretq_u8_u64(vshlq_n_u64(vreinterpretq_u64_u8(h), 1));
+    // vpxor		$T3,$T3,$T3		#
+    // vpcmpgtd	$T2,$T3,$T3		# broadcast carry bit
+    //
+    // an arithmetic shift of the sign bit is equivalent to comparing against zero
+    t3 = vreinterpretq_u8_s32(vshrq_n_s32(vreinterpretq_s32_u32(t2), 31));
+    // vpslldq		\$8,$T1,$T1
+    t1 = t1.shift_left_half();
+    // vpor		$T1,$Hkey,$Hkey		# H<<=1
+    h = vorrq_u8(h, t1);
+
+    // # magic reduction
+    // vpand		.L0x1c2_polynomial(%rip),$T3,$T3
+    t3 = vandq_u8(t3, POLYNOMIAL);
+    // vpxor		$T3,$Hkey,$Hkey		# if(carry) H^=0x1c2_polynomial
+    h = h.xor(t3);
+
+    h
+}
+
+// From https://github.com/awslabs/aws-lc/blob/5833176448d48aff0c2dc4c1ab745649c769a7a6/crypto/cipher_extra/asm/aes128gcmsiv-x86_64.pl#L93
+#[inline(always)]
+// This implementation is written to closely follow the original code
+#[allow(unknown_lints, clippy::needless_late_init)]
+pub unsafe fn gfmul(a: uint8x16_t, b: uint8x16_t) -> uint8x16_t {
+    // # a = T
+    let t = a;
+    // # b = TMP0 - remains unchanged
+    let tmp0 = b;
+
+    let mut tmp1;
+    let mut tmp2;
+    let mut tmp3;
+    let mut tmp4;
+
+    // vpclmulqdq  \$0x00, $TMP0, $T, $TMP1
+    tmp1 = clmul(t, tmp0, 0x00);
+    // vpclmulqdq  \$0x11, $TMP0, $T, $TMP4
+    tmp4 = clmul(t, tmp0, 0x11);
+    // vpclmulqdq  \$0x10, $TMP0, $T, $TMP2
+    tmp2 = clmul(t, tmp0, 0x10);
+    // vpclmulqdq  \$0x01, $TMP0, $T, $TMP3
+    tmp3 = clmul(t, tmp0, 0x01);
+    // vpxor       $TMP3, $TMP2, $TMP2
+    tmp2 = tmp2.xor(tmp3);
+    // vpslldq     \$8, $TMP2, $TMP3
+    tmp3 = tmp2.shift_left_half();
+    // vpsrldq     \$8, $TMP2, $TMP2
+    tmp2 = tmp2.shift_right_half();
+    // vpxor       $TMP3, $TMP1, $TMP1
+    tmp1 = tmp1.xor(tmp3);
+    // vpxor       $TMP2, $TMP4, $TMP4
+    tmp4 = tmp4.xor(tmp2);
+
+    reduce(tmp1, tmp4)
+}
+
+/// Reduction phase of gfmul
+// From https://github.com/awslabs/aws-lc/blob/5833176448d48aff0c2dc4c1ab745649c769a7a6/crypto/cipher_extra/asm/aes128gcmsiv-x86_64.pl#L93
+#[inline(always)]
+// This implementation is written to closely follow the original code
+#[allow(unknown_lints, clippy::needless_late_init)]
+pub unsafe fn reduce(mut tmp1: uint8x16_t, tmp4: uint8x16_t) -> uint8x16_t {
+    let t;
+    let mut tmp2;
+    let mut tmp3;
+
+    // vpclmulqdq  \$0x10, poly(%rip), $TMP1, $TMP2
+    tmp2 = clmul(tmp1, POLYNOMIAL, 0x10);
+    // vpshufd     \$78, $TMP1, $TMP3
+    tmp3 = tmp1.swap_halves();
+    // vpxor       $TMP3, $TMP2, $TMP1
+    tmp1 = tmp2.xor(tmp3);
+    // vpclmulqdq  \$0x10, poly(%rip), $TMP1, $TMP2
+    tmp2 = clmul(tmp1, POLYNOMIAL, 0x10);
+    // vpshufd     \$78, $TMP1, $TMP3
+    tmp3 = tmp1.swap_halves();
+    // vpxor       $TMP3, $TMP2, $TMP1
+    tmp1 = tmp2.xor(tmp3);
+    // vpxor       $TMP4, $TMP1, $T
+    t = tmp1.xor(tmp4);
+    // ret
+    t
+}
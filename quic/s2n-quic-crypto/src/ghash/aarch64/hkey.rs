@@ -0,0 +1,131 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    arch::*,
+    block::{aarch64::Uint8x16Ext, Block, Zeroed},
+    ghash::aarch64::algo,
+};
+use zeroize::DefaultIsZeroes;
+
+pub trait HKey: Copy + Zeroed {
+    fn new(h: uint8x16_t) -> Self;
+    fn derive(&self, initial: &Self) -> Self;
+    fn h(&self) -> uint8x16_t;
+    fn r(&self) -> uint8x16_t;
+}
+
+#[derive(Clone, Copy)]
+pub struct H(uint8x16_t);
+
+impl H {
+    #[inline(always)]
+    pub fn mul(self, y: uint8x16_t) -> uint8x16_t {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+            algo::gfmul(self.0, y)
+        }
+    }
+}
+
+impl Default for H {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl DefaultIsZeroes for H {}
+
+impl Zeroed for H {
+    #[inline(always)]
+    fn zeroed() -> Self {
+        Self(uint8x16_t::zeroed())
+    }
+}
+
+impl HKey for H {
+    #[inline(always)]
+    fn new(mut h: uint8x16_t) -> Self {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+
+            h = h.reverse();
+            h = algo::init(h);
+
+            Self(h)
+        }
+    }
+
+    #[inline(always)]
+    fn derive(&self, first: &Self) -> Self {
+        Self(self.mul(first.0))
+    }
+
+    #[inline(always)]
+    fn h(&self) -> uint8x16_t {
+        self.0
+    }
+
+    #[inline(always)]
+    fn r(&self) -> uint8x16_t {
+        unsafe {
+            debug_assert!(Neon::is_supported());
+
+            let h = self.0;
+            let r = h.swap_halves();
+            r.xor(h)
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Hr {
+    h: H,
+    r: uint8x16_t,
+}
+
+impl Zeroed for Hr {
+    #[inline(always)]
+    fn zeroed() -> Self {
+        Self {
+            h: H::zeroed(),
+            r: uint8x16_t::zeroed(),
+        }
+    }
+}
+
+impl Default for Hr {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl DefaultIsZeroes for Hr {}
+
+impl HKey for Hr {
+    #[inline(always)]
+    fn new(h: uint8x16_t) -> Self {
+        let h = H::new(h);
+        let r = h.r();
+        Self { h, r }
+    }
+
+    #[inline(always)]
+    fn derive(&self, first: &Self) -> Self {
+        let h = self.h.derive(&first.h);
+        let r = h.r();
+        Self { h, r }
+    }
+
+    #[inline(always)]
+    fn h(&self) -> uint8x16_t {
+        self.h.h()
+    }
+
+    #[inline(always)]
+    fn r(&self) -> uint8x16_t {
+        self.r
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    aes::BLOCK_LEN,
+    arch::*,
+    block::Block,
+    ghash::{
+        self,
+        aarch64::{self, hkey, precomputed::Array},
+        testing::{GHash, Implementation},
+    },
+    testing::MAX_BLOCKS,
+};
+use core::{convert::TryInto, marker::PhantomData};
+pub struct Impl<G, A>(G, PhantomData<A>)
+where
+    G: ghash::GHash<Block = uint8x16_t>,
+    A: Arch;
+
+impl<G, A> Impl<G, A>
+where
+    G: ghash::GHash<Block = uint8x16_t>,
+    A: Arch,
+{
+    fn new(key: G) -> Self {
+        Self(key, PhantomData)
+    }
+}
+
+impl<G, A> GHash for Impl<G, A>
+where
+    G: ghash::GHash<Block = uint8x16_t>,
+    A: Arch,
+{
+    fn hash(&self, input: &[u8]) -> [u8; BLOCK_LEN] {
+        unsafe {
+            A::call(
+                #[inline(always)]
+                || {
+                    let blocks = input.len() / BLOCK_LEN;
+
+                    let mut state = self.0.start(blocks);
+
+                    for block in input.chunks_exact(BLOCK_LEN) {
+                        let block: [u8; BLOCK_LEN] = block.try_into().unwrap();
+                        self.0.update(&mut state, &uint8x16_t::from_array(block));
+                    }
+                    self.0.finish(state).into_array()
+                },
+            )
+        }
+    }
+}
+
+pub fn implementations(impls: &mut Vec<Implementation>) {
+    Neon::call_supported(|| {
+        impls.push(Implementation {
+            name: "s2n_quic/std/neon",
+            new: |key| {
+                let ghash = aarch64::GHash::new(key);
+                Box::new(<Impl<_, Neon>>::new(ghash))
+            },
+        });
+        impls.push(Implementation {
+            name: "s2n_quic/pre_h/neon",
+            new: |key| {
+                let ghash = <Array<hkey::H, MAX_BLOCKS>>::new(key);
+                Box::new(<Impl<_, Neon>>::new(ghash))
+            },
+        });
+        impls.push(Implementation {
+            name: "s2n_quic/pre_hr/neon",
+            new: |key| {
+                let ghash = <Array<hkey::Hr, MAX_BLOCKS>>::new(key);
+                Box::new(<Impl<_, Neon>>::new(ghash))
+            },
+        });
+    });
+}
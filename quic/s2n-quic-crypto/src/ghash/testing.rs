@@ -31,6 +31,9 @@ lazy_static! {
         #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
         super::x86::testing::implementations(&mut impls);
 
+        #[cfg(s2n_quic_crypto_aarch64)]
+        super::aarch64::testing::implementations(&mut impls);
+
         #[cfg(test)]
         rust_crypto::implementations(&mut impls);
 
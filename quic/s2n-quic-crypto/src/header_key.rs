@@ -1,11 +1,49 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use core::fmt;
+use crate::{
+    aes::Encrypt,
+    block::{Batch, BatchMut, Block, Zeroed, LEN as BLOCK_LEN},
+};
+use core::{convert::TryInto, fmt};
 use ring::{aead, hkdf};
-use s2n_quic_core::crypto::{self, HeaderProtectionMask};
+use s2n_quic_core::crypto::{self, HeaderProtectionMask, HEADER_PROTECTION_MASK_LEN};
 
-pub struct HeaderKey(pub(crate) aead::quic::HeaderProtectionKey);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[path = "header_key/x86.rs"]
+mod platform;
+#[cfg(s2n_quic_crypto_aarch64)]
+#[path = "header_key/aarch64.rs"]
+mod platform;
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", s2n_quic_crypto_aarch64)))]
+mod platform {
+    use s2n_quic_core::crypto::HeaderProtectionMask;
+
+    /// AES header protection isn't batched on this platform
+    pub enum BatchKey {}
+
+    impl BatchKey {
+        #[inline]
+        pub fn new(_alg: &'static ring::aead::quic::Algorithm, _key: &[u8]) -> Option<Self> {
+            None
+        }
+
+        #[inline]
+        pub fn masks(&self, _samples: &[&[u8]], _masks: &mut [HeaderProtectionMask]) {
+            match *self {}
+        }
+    }
+}
+
+/// The number of samples which are encrypted together when deriving a batch of masks
+const BATCH_LEN: usize = 8;
+
+pub struct HeaderKey {
+    key: aead::quic::HeaderProtectionKey,
+    /// Derives the masks for a batch of samples, if the algorithm and platform support it
+    batch: Option<platform::BatchKey>,
+}
 
 impl crypto::HeaderKey for HeaderKey {
     #[inline]
@@ -15,7 +53,7 @@ impl crypto::HeaderKey for HeaderKey {
 
     #[inline]
     fn opening_sample_len(&self) -> usize {
-        self.0.algorithm().sample_len()
+        self.key.algorithm().sample_len()
     }
 
     #[inline]
@@ -25,7 +63,25 @@ impl crypto::HeaderKey for HeaderKey {
 
     #[inline]
     fn sealing_sample_len(&self) -> usize {
-        self.0.algorithm().sample_len()
+        self.key.algorithm().sample_len()
+    }
+
+    #[inline]
+    fn opening_header_protection_masks(
+        &self,
+        samples: &[&[u8]],
+        masks: &mut [HeaderProtectionMask],
+    ) {
+        self.header_protection_masks(samples, masks)
+    }
+
+    #[inline]
+    fn sealing_header_protection_masks(
+        &self,
+        samples: &[&[u8]],
+        masks: &mut [HeaderProtectionMask],
+    ) {
+        self.header_protection_masks(samples, masks)
     }
 }
 
@@ -45,15 +101,73 @@ impl HeaderKey {
 
         let key = aead::quic::HeaderProtectionKey::new(alg, bytes.as_ref())
             .expect("header secret length already checked");
-        Self(key)
+        let batch = platform::BatchKey::new(alg, bytes.as_ref());
+        Self { key, batch }
     }
 
     #[inline]
     fn header_protection_mask(&self, sample: &[u8]) -> HeaderProtectionMask {
-        self.0
+        self.key
             .new_mask(sample)
             .expect("sample length already checked")
     }
+
+    #[inline]
+    fn header_protection_masks(&self, samples: &[&[u8]], masks: &mut [HeaderProtectionMask]) {
+        debug_assert_eq!(samples.len(), masks.len());
+
+        if let Some(batch) = self.batch.as_ref() {
+            batch.masks(samples, masks);
+            return;
+        }
+
+        for (sample, mask) in samples.iter().zip(masks.iter_mut()) {
+            *mask = self.header_protection_mask(sample);
+        }
+    }
+}
+
+//= https://www.rfc-editor.org/rfc/rfc9001#section-5.4.3
+//# header_protection(hp_key, sample):
+//#   mask = AES-ECB(hp_key, sample)
+/// Derives the AES header protection masks for a batch of samples
+///
+/// The samples are encrypted `BATCH_LEN` blocks at a time so the AES rounds for each of the
+/// blocks are pipelined.
+#[inline(always)]
+fn aes_masks<A, B>(key: &A, samples: &[&[u8]], masks: &mut [HeaderProtectionMask])
+where
+    A: Encrypt<Block = B>,
+    B: Block,
+    [B; BATCH_LEN]: BatchMut<Block = B> + Zeroed,
+{
+    for (samples, masks) in samples.chunks(BATCH_LEN).zip(masks.chunks_mut(BATCH_LEN)) {
+        let mut blocks = <[B; BATCH_LEN]>::zeroed();
+
+        blocks.update(
+            #[inline(always)]
+            |idx, block| {
+                if let Some(sample) = samples.get(idx) {
+                    let sample: [u8; BLOCK_LEN] = sample[..BLOCK_LEN]
+                        .try_into()
+                        .expect("sample length already checked");
+                    *block = B::from_array(sample);
+                }
+            },
+        );
+
+        key.encrypt(&mut blocks);
+
+        blocks.for_each(
+            #[inline(always)]
+            |idx, block| {
+                if let Some(mask) = masks.get_mut(idx) {
+                    let block = block.into_array();
+                    mask.copy_from_slice(&block[..HEADER_PROTECTION_MASK_LEN]);
+                }
+            },
+        );
+    }
 }
 
 impl fmt::Debug for HeaderKey {
@@ -64,7 +178,7 @@ impl fmt::Debug for HeaderKey {
 
 impl From<aead::quic::HeaderProtectionKey> for HeaderKey {
     fn from(key: aead::quic::HeaderProtectionKey) -> Self {
-        Self(key)
+        Self { key, batch: None }
     }
 }
 
@@ -94,6 +208,24 @@ impl crypto::HeaderKey for HeaderKeyPair {
     fn sealing_sample_len(&self) -> usize {
         self.sealer.sealing_sample_len()
     }
+
+    #[inline]
+    fn opening_header_protection_masks(
+        &self,
+        samples: &[&[u8]],
+        masks: &mut [HeaderProtectionMask],
+    ) {
+        self.opener.opening_header_protection_masks(samples, masks)
+    }
+
+    #[inline]
+    fn sealing_header_protection_masks(
+        &self,
+        samples: &[&[u8]],
+        masks: &mut [HeaderProtectionMask],
+    ) {
+        self.sealer.sealing_header_protection_masks(samples, masks)
+    }
 }
 
 macro_rules! header_key {
@@ -127,6 +259,24 @@ macro_rules! header_key {
             fn sealing_sample_len(&self) -> usize {
                 self.0.sealing_sample_len()
             }
+
+            #[inline]
+            fn opening_header_protection_masks(
+                &self,
+                samples: &[&[u8]],
+                masks: &mut [s2n_quic_core::crypto::HeaderProtectionMask],
+            ) {
+                self.0.opening_header_protection_masks(samples, masks)
+            }
+
+            #[inline]
+            fn sealing_header_protection_masks(
+                &self,
+                samples: &[&[u8]],
+                masks: &mut [s2n_quic_core::crypto::HeaderProtectionMask],
+            ) {
+                self.0.sealing_header_protection_masks(samples, masks)
+            }
         }
 
         impl From<crate::header_key::HeaderKeyPair> for $name {
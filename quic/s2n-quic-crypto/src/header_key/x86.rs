@@ -0,0 +1,72 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    aes::{
+        aes128::Key as Aes128,
+        aes256::Key as Aes256,
+        x86::{aes128, aes256},
+    },
+    arch::*,
+};
+use core::convert::TryInto;
+use ring::aead::quic::{Algorithm, AES_128, AES_256};
+use s2n_quic_core::crypto::HeaderProtectionMask;
+use zeroize::Zeroize;
+
+/// An AES header protection key which derives the masks for a batch of samples together
+#[derive(Zeroize)]
+pub enum BatchKey {
+    Aes128(Aes128<aes128::EncryptionKey>),
+    Aes256(Aes256<aes256::EncryptionKey>),
+}
+
+impl BatchKey {
+    /// Returns `None` if the algorithm isn't AES or the platform doesn't support it
+    #[inline]
+    pub fn new(alg: &'static Algorithm, key: &[u8]) -> Option<Self> {
+        if !Avx2::is_supported() || (alg != &AES_128 && alg != &AES_256) {
+            return None;
+        }
+
+        unsafe {
+            debug_assert!(Avx2::is_supported());
+            Self::new_impl(alg, key)
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "aes,avx2,pclmulqdq")]
+    unsafe fn new_impl(alg: &'static Algorithm, key: &[u8]) -> Option<Self> {
+        if alg == &AES_128 {
+            let aes128::Key { encrypt, .. } = aes128::Key::new(key.try_into().ok()?);
+            Some(Self::Aes128(Aes128(encrypt)))
+        } else {
+            let aes256::Key { encrypt, .. } = aes256::Key::new(key.try_into().ok()?);
+            Some(Self::Aes256(Aes256(encrypt)))
+        }
+    }
+
+    #[inline]
+    pub fn masks(&self, samples: &[&[u8]], masks: &mut [HeaderProtectionMask]) {
+        unsafe {
+            debug_assert!(Avx2::is_supported());
+            self.masks_impl(samples, masks)
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "aes,avx2,pclmulqdq")]
+    unsafe fn masks_impl(&self, samples: &[&[u8]], masks: &mut [HeaderProtectionMask]) {
+        match self {
+            Self::Aes128(key) => super::aes_masks(key, samples, masks),
+            Self::Aes256(key) => super::aes_masks(key, samples, masks),
+        }
+    }
+}
+
+impl Drop for BatchKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
     SecretPair,
 };
 use s2n_quic_core::{
-    crypto::{CryptoError, Key},
+    crypto::{BatchPacket, CryptoError, Key},
     endpoint,
 };
 
@@ -90,6 +90,16 @@ impl Key for KeyPair {
     fn cipher_suite(&self) -> s2n_quic_core::crypto::tls::CipherSuite {
         self.opener.cipher_suite()
     }
+
+    #[inline]
+    fn decrypt_batch(&self, packets: &mut [BatchPacket]) {
+        self.opener.decrypt_batch(packets)
+    }
+
+    #[inline]
+    fn encrypt_batch(&self, packets: &mut [BatchPacket]) {
+        self.sealer.encrypt_batch(packets)
+    }
 }
 
 macro_rules! negotiated_crypto {
@@ -177,6 +187,16 @@ macro_rules! negotiated_crypto {
             fn cipher_suite(&self) -> s2n_quic_core::crypto::tls::CipherSuite {
                 self.0.cipher_suite()
             }
+
+            #[inline]
+            fn decrypt_batch(&self, packets: &mut [s2n_quic_core::crypto::BatchPacket]) {
+                self.0.decrypt_batch(packets)
+            }
+
+            #[inline]
+            fn encrypt_batch(&self, packets: &mut [s2n_quic_core::crypto::BatchPacket]) {
+                self.0.encrypt_batch(packets)
+            }
         }
     };
 }
@@ -819,3 +819,37 @@ enum MtuUpdatedCause {
     /// A blackhole was detected
     Blackhole,
 }
+
+/// The reason a path was deleted
+enum PathDeletedReason {
+    /// Validation of the path failed or was abandoned
+    ValidationFailed,
+    /// No datagrams were received on the path for an extended period of time
+    Idle,
+}
+
+/// A phase of the handshake, named after the milestone which ends it
+enum HandshakePhase {
+    /// The ServerHello was sent or received
+    ///
+    /// On the server, this includes processing the ClientHello and selecting a certificate.
+    ServerHello,
+    /// The server's Finished message was sent or received and the 1-RTT keys are available
+    ///
+    /// On the server, this includes signing the CertificateVerify message. On the client, this
+    /// includes verifying the server's certificate chain.
+    ServerFinished,
+    /// The TLS handshake completed
+    Complete,
+    /// The HANDSHAKE_DONE frame was acknowledged by the client or received from the server
+    HandshakeDoneAcked,
+}
+
+/// An application callback invoked by the TLS provider during the handshake
+enum TlsCallback {
+    /// The certificate chain was selected for the connection
+    CertificateSelection,
+    /// An operation was performed with the private key, e.g. signing the CertificateVerify
+    /// message
+    PrivateKeyOperation,
+}
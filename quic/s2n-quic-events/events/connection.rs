@@ -261,3 +261,28 @@ struct SlowStartExited<'a> {
     cause: SlowStartExitCause,
     congestion_window: u32,
 }
+
+#[event("transport:path_deleted")]
+/// A path was deleted and its resources released
+struct PathDeleted<'a> {
+    path: Path<'a>,
+    reason: PathDeletedReason,
+}
+
+#[event("connectivity:handshake_phase_completed")]
+/// A phase of the handshake has completed
+struct HandshakePhaseCompleted {
+    phase: HandshakePhase,
+    /// The time spent in the phase
+    duration: Duration,
+    /// The time since the connection started the handshake
+    elapsed: Duration,
+}
+
+#[event("connectivity:tls_callback_completed")]
+/// An application callback invoked by the TLS provider has completed
+struct TlsCallbackCompleted {
+    callback: TlsCallback,
+    /// The time spent in the callback
+    duration: Duration,
+}
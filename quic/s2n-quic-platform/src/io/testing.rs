@@ -13,12 +13,18 @@ use s2n_quic_core::{
 type Error = std::io::Error;
 type Result<T = (), E = Error> = core::result::Result<T, E>;
 
+mod bottleneck;
 mod model;
+mod nat;
 pub mod network;
+mod profile;
 pub mod time;
 
-pub use model::Model;
+pub use bottleneck::QueueDiscipline;
+pub use model::{GilbertElliott, Model};
+pub use nat::Rebind;
 pub use network::{Network, PathHandle};
+pub use profile::{Change, Profile};
 pub use time::now;
 
 pub use bach::task::{self, primary, spawn};
@@ -230,6 +236,14 @@ pub struct Builder {
 }
 
 impl Builder {
+    /// Binds the endpoint to the given address instead of generating a new one
+    ///
+    /// If another endpoint is already bound to the address, it will be shut down.
+    pub fn with_address(mut self, address: SocketAddress) -> Self {
+        self.address = Some(address);
+        self
+    }
+
     pub fn build(self) -> Result<Io> {
         Ok(Io { builder: self })
     }
@@ -251,11 +265,12 @@ impl Io {
 
         let handle = address.unwrap_or_else(|| buffers.generate_addr());
 
-        buffers.register(handle);
+        let owner = buffers.register(handle);
 
         let instance = Instance {
             buffers,
             handle,
+            owner,
             endpoint,
         };
         let join = executor.spawn(instance.event_loop());
@@ -266,6 +281,7 @@ impl Io {
 struct Instance<E> {
     buffers: network::Buffers,
     handle: SocketAddress,
+    owner: network::Owner,
     endpoint: E,
 }
 
@@ -274,6 +290,7 @@ impl<E: Endpoint<PathHandle = network::PathHandle>> Instance<E> {
         let Self {
             buffers,
             handle,
+            owner,
             mut endpoint,
         } = self;
 
@@ -281,7 +298,7 @@ impl<E: Endpoint<PathHandle = network::PathHandle>> Instance<E> {
         let mut timer = time::Timer::default();
 
         loop {
-            let io_task = buffers.readiness(handle);
+            let io_task = buffers.readiness(handle, owner);
 
             // make a future that never returns since we have a single future that checks both
             let empty_task = futures::future::pending::<()>();
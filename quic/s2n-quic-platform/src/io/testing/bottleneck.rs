@@ -0,0 +1,358 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use core::time::Duration;
+use s2n_quic_core::time::Timestamp;
+use std::collections::VecDeque;
+
+/// Selects which packets are dropped by the bottleneck queue
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueueDiscipline {
+    /// Packets which would exceed the queue capacity are dropped
+    DropTail,
+    /// Packets are dropped once the queueing delay stays above `target` for `interval`
+    ///
+    /// See <https://www.rfc-editor.org/rfc/rfc8289>. The queue capacity is still enforced.
+    CoDel {
+        target: Duration,
+        interval: Duration,
+    },
+}
+
+impl Default for QueueDiscipline {
+    fn default() -> Self {
+        Self::DropTail
+    }
+}
+
+impl QueueDiscipline {
+    //= https://www.rfc-editor.org/rfc/rfc8289#section-4.2
+    //# The
+    //# setpoint target of 5% of the interval is derived from the
+    //# analysis in Appendix A
+    //# and is used with a default interval of 100 ms
+    pub const CODEL: Self = Self::CoDel {
+        target: Duration::from_millis(5),
+        interval: Duration::from_millis(100),
+    };
+}
+
+/// The FIFO queue in front of a link which transmits packets at a fixed rate
+///
+/// The caller transmits the packets returned by `dequeue` and calls it again once each packet
+/// has been serialized onto the link.
+#[derive(Debug)]
+pub(super) struct Bottleneck<T> {
+    pub discipline: QueueDiscipline,
+    queue: VecDeque<Queued<T>>,
+    /// The number of bytes in the queue
+    bytes: u64,
+    /// Set while the link is transmitting the packets in the queue
+    is_busy: bool,
+    codel: CoDel,
+}
+
+impl<T> Default for Bottleneck<T> {
+    fn default() -> Self {
+        Self {
+            discipline: Default::default(),
+            queue: Default::default(),
+            bytes: 0,
+            is_busy: false,
+            codel: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Queued<T> {
+    packet: T,
+    len: usize,
+    enqueued_at: Timestamp,
+}
+
+impl<T> Bottleneck<T> {
+    /// Appends a packet to the queue
+    ///
+    /// Returns `false` if the packet was dropped because it would exceed the queue capacity.
+    pub fn enqueue(&mut self, now: Timestamp, packet: T, len: usize, capacity: u64) -> bool {
+        if self.bytes.saturating_add(len as u64) > capacity {
+            return false;
+        }
+
+        self.bytes += len as u64;
+        self.queue.push_back(Queued {
+            packet,
+            len,
+            enqueued_at: now,
+        });
+
+        true
+    }
+
+    /// Marks the link as transmitting the queue
+    ///
+    /// Returns `false` if the link is already transmitting, in which case the caller must not
+    /// dequeue packets.
+    pub fn start(&mut self) -> bool {
+        !core::mem::replace(&mut self.is_busy, true)
+    }
+
+    /// Removes the next packet to transmit onto the link along with its length
+    ///
+    /// Packets are dropped here as they leave the queue, as selected by the queue discipline.
+    /// The link becomes idle once the queue is empty.
+    pub fn dequeue(&mut self, now: Timestamp, max_packet: usize) -> Option<(T, usize)> {
+        let packet = match self.discipline {
+            QueueDiscipline::DropTail => self.pop().map(|queued| (queued.packet, queued.len)),
+            QueueDiscipline::CoDel { target, interval } => {
+                self.codel_dequeue(now, target, interval, max_packet)
+            }
+        };
+
+        if packet.is_none() {
+            self.is_busy = false;
+        }
+
+        packet
+    }
+
+    fn pop(&mut self) -> Option<Queued<T>> {
+        let queued = self.queue.pop_front()?;
+        self.bytes -= queued.len as u64;
+        Some(queued)
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc8289#section-5.5
+    //# packet_t* CoDelQueue::dequeue()
+    //# {
+    //#     time_t now = clock();
+    //#     dodequeue_result r = dodequeue(now);
+    fn codel_dequeue(
+        &mut self,
+        now: Timestamp,
+        target: Duration,
+        interval: Duration,
+        max_packet: usize,
+    ) -> Option<(T, usize)> {
+        let (mut packet, ok_to_drop) = self.dodequeue(now, target, interval, max_packet);
+
+        if self.codel.dropping {
+            if !ok_to_drop {
+                self.codel.dropping = false;
+            }
+
+            while self.codel.dropping {
+                let drop_next = self.codel.drop_next.unwrap_or(now);
+                if now < drop_next {
+                    break;
+                }
+
+                // drop the packet and try the next one
+                self.codel.count += 1;
+                let (next, ok_to_drop) = self.dodequeue(now, target, interval, max_packet);
+                packet = next;
+                if ok_to_drop {
+                    self.codel.drop_next = Some(control_law(drop_next, interval, self.codel.count));
+                } else {
+                    self.codel.dropping = false;
+                }
+            }
+        } else if ok_to_drop {
+            // drop the packet and enter the dropping state
+            let (next, _) = self.dodequeue(now, target, interval, max_packet);
+            packet = next;
+            self.codel.dropping = true;
+
+            //= https://www.rfc-editor.org/rfc/rfc8289#section-5.5
+            //# If min went above TARGET close to when it last went
+            //# below, assume that the drop rate that controlled the
+            //# queue on the last cycle is a good starting point to
+            //# control it now.
+            let delta = self.codel.count.saturating_sub(self.codel.last_count);
+            let is_recent = self.codel.drop_next.map_or(false, |drop_next| {
+                now.saturating_duration_since(drop_next) < interval * 16
+            });
+            self.codel.count = if delta > 1 && is_recent { delta } else { 1 };
+            self.codel.drop_next = Some(control_law(now, interval, self.codel.count));
+            self.codel.last_count = self.codel.count;
+        }
+
+        packet
+    }
+
+    //= https://www.rfc-editor.org/rfc/rfc8289#section-5.5
+    //# if (sojourn_time < TARGET || bytes() <= MAXPACKET) {
+    //#     first_above_time_ = 0;
+    //# } else {
+    //#     if (first_above_time_ == 0) {
+    //#         // just went above from below. if still above at
+    //#         // first_above_time, will say it's ok to drop.
+    //#         first_above_time_ = now + INTERVAL;
+    //#     } else if (now >= first_above_time_) {
+    //#         r.ok_to_drop = true;
+    //#     }
+    //# }
+    fn dodequeue(
+        &mut self,
+        now: Timestamp,
+        target: Duration,
+        interval: Duration,
+        max_packet: usize,
+    ) -> (Option<(T, usize)>, bool) {
+        let queued = if let Some(queued) = self.pop() {
+            queued
+        } else {
+            // the queue is empty so the delay is below the target
+            self.codel.first_above_time = None;
+            return (None, false);
+        };
+
+        let sojourn = now.saturating_duration_since(queued.enqueued_at);
+
+        let ok_to_drop = if sojourn < target || self.bytes <= max_packet as u64 {
+            self.codel.first_above_time = None;
+            false
+        } else if let Some(first_above_time) = self.codel.first_above_time {
+            now >= first_above_time
+        } else {
+            self.codel.first_above_time = Some(now + interval);
+            false
+        };
+
+        (Some((queued.packet, queued.len)), ok_to_drop)
+    }
+}
+
+#[derive(Debug, Default)]
+struct CoDel {
+    /// The time at which the queueing delay will have been above the target for an interval
+    first_above_time: Option<Timestamp>,
+    dropping: bool,
+    drop_next: Option<Timestamp>,
+    count: u32,
+    last_count: u32,
+}
+
+//= https://www.rfc-editor.org/rfc/rfc8289#section-5.6
+//# time_t control_law(time_t t, uint32_t count)
+//# {
+//#         return t + INTERVAL / sqrt(count);
+//# }
+fn control_law(t: Timestamp, interval: Duration, count: u32) -> Timestamp {
+    t + interval.div_f64((count as f64).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> Timestamp {
+        unsafe { Timestamp::from_duration(Duration::from_secs(1)) }
+    }
+
+    #[test]
+    fn drop_tail_test() {
+        let mut link = Bottleneck::default();
+        let now = now();
+        // room for 2 packets
+        let capacity = 250;
+
+        assert!(link.start());
+        assert!(link.enqueue(now, 1, 100, capacity));
+        assert!(link.enqueue(now, 2, 100, capacity));
+        assert!(!link.enqueue(now, 3, 100, capacity));
+        // the link is already transmitting
+        assert!(!link.start());
+
+        // the queue drains as packets are transmitted
+        assert_eq!(link.dequeue(now, 1200), Some((1, 100)));
+        assert!(link.enqueue(now, 4, 100, capacity));
+        assert_eq!(link.dequeue(now, 1200), Some((2, 100)));
+        assert_eq!(link.dequeue(now, 1200), Some((4, 100)));
+
+        // the link is idle once the queue is empty
+        assert_eq!(link.dequeue(now, 1200), None);
+        assert!(link.start());
+    }
+
+    /// Sends packets from a sender which starts at twice the link rate, halves its rate at most
+    /// once per round trip when its packets are dropped and otherwise keeps increasing it, like
+    /// a congestion controller probing for bandwidth
+    ///
+    /// Returns the queueing delay of each transmitted packet and the number of dropped packets
+    fn overload(discipline: QueueDiscipline, duration: Duration) -> (Vec<Duration>, usize) {
+        const LEN: usize = 1000;
+        // the link transmits 1000 packets per second
+        let serialization = Duration::from_millis(1);
+        let rtt = Duration::from_millis(20);
+
+        let mut link = Bottleneck {
+            discipline,
+            ..Default::default()
+        };
+        let start = now();
+        let end = start + duration;
+        let mut rate = 2000.0f64;
+        let mut next_arrival = start;
+        let mut idle = start;
+        let mut last_decrease = start;
+        let mut sojourns = vec![];
+        let mut drops = 0;
+
+        while next_arrival < end {
+            let now = next_arrival;
+
+            // transmit the packets which reach the front of the queue before the next arrival
+            while idle <= now {
+                let queued = link.queue.len();
+                let transmission = link.dequeue(idle, LEN);
+                let dropped = queued - link.queue.len() - transmission.is_some() as usize;
+                drops += dropped;
+
+                if dropped > 0 && idle >= last_decrease + rtt {
+                    rate /= 2.0;
+                    last_decrease = idle;
+                }
+
+                if let Some((enqueued_at, _len)) = transmission {
+                    sojourns.push(idle - enqueued_at);
+                    idle += serialization;
+                } else {
+                    idle = now;
+                    break;
+                }
+            }
+
+            link.enqueue(now, now, LEN, u64::MAX);
+
+            // grow by a packet per round trip
+            rate += 1.0 / (rate * rtt.as_secs_f64() * rtt.as_secs_f64());
+            next_arrival = now + Duration::from_secs_f64(1.0 / rate);
+        }
+
+        (sojourns, drops)
+    }
+
+    #[test]
+    fn codel_test() {
+        let target = Duration::from_millis(5);
+        let interval = Duration::from_millis(100);
+        let duration = Duration::from_secs(10);
+        let (sojourns, drops) = overload(QueueDiscipline::CoDel { target, interval }, duration);
+
+        assert!(drops > 0);
+
+        // the queueing delay converges near the target once the sender responds to the drops
+        let settled = &sojourns[sojourns.len() / 2..];
+        let average = settled.iter().sum::<Duration>() / settled.len() as u32;
+        assert!(average < target * 3, "{:?}", average);
+        let max = settled.iter().max().unwrap();
+        assert!(*max < interval, "{:?}", max);
+
+        // without CoDel, the sender never sees a drop and the queueing delay keeps growing
+        let (sojourns, drops) = overload(QueueDiscipline::DropTail, duration);
+        assert_eq!(drops, 0);
+        assert!(*sojourns.last().unwrap() > duration / 2);
+    }
+}
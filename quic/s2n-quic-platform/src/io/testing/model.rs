@@ -1,14 +1,18 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::network::{Buffers, Network, Packet};
+use super::{
+    bottleneck::{Bottleneck, QueueDiscipline},
+    nat::{Nat, Rebind},
+    network::{Buffers, Network, Packet},
+};
 use core::time::Duration;
-use s2n_quic_core::{havoc, path::MaxMtu};
+use s2n_quic_core::{havoc, inet::ExplicitCongestionNotification, path::MaxMtu, time::Timestamp};
 use std::{
     borrow::Cow,
     sync::{
-        atomic::{AtomicU16, AtomicU64, Ordering},
-        Arc,
+        atomic::{AtomicBool, AtomicU16, AtomicU64, Ordering},
+        Arc, Mutex,
     },
 };
 
@@ -158,6 +162,146 @@ impl Model {
             .store(value, Ordering::SeqCst);
         self
     }
+
+    pub fn bandwidth(&self) -> u64 {
+        self.0.bandwidth.load(Ordering::SeqCst)
+    }
+
+    /// Sets the number of bytes per second the network can transmit in each direction
+    ///
+    /// Packets are serialized onto the link in the order they are sent, so packets sent faster
+    /// than the bandwidth allows are queued and delayed. Clients and servers each send on their
+    /// own link. Setting the value to `u64::MAX` removes the limit.
+    pub fn set_bandwidth(&self, value: u64) -> &Self {
+        self.0.bandwidth.store(value, Ordering::SeqCst);
+        self
+    }
+
+    pub fn queue_capacity(&self) -> u64 {
+        self.0.queue_capacity.load(Ordering::SeqCst)
+    }
+
+    /// Sets the number of bytes that can be queued behind the bandwidth-limited link
+    ///
+    /// Packets which would exceed the capacity are dropped. The queue only exists when the
+    /// bandwidth is limited. Setting the value to `u64::MAX` removes the limit.
+    pub fn set_queue_capacity(&self, value: u64) -> &Self {
+        self.0.queue_capacity.store(value, Ordering::SeqCst);
+        self
+    }
+
+    pub fn queue_discipline(&self) -> QueueDiscipline {
+        self.0.client_link.lock().unwrap().discipline
+    }
+
+    /// Sets how packets are dropped from the bandwidth-limited links' queues
+    pub fn set_queue_discipline(&self, value: QueueDiscipline) -> &Self {
+        self.0.client_link.lock().unwrap().discipline = value;
+        self.0.server_link.lock().unwrap().discipline = value;
+        self
+    }
+
+    pub fn is_outage(&self) -> bool {
+        self.0.outage.load(Ordering::SeqCst)
+    }
+
+    /// Drops all of the packets sent while the outage is active
+    ///
+    /// Unlike setting the drop rate, this doesn't modify any of the other network conditions so
+    /// they are restored once the outage ends.
+    pub fn set_outage(&self, value: bool) -> &Self {
+        self.0.outage.store(value, Ordering::SeqCst);
+        self
+    }
+
+    pub fn burst_loss(&self) -> GilbertElliott {
+        GilbertElliott {
+            p: u64_to_rate(self.0.burst_enter_rate.load(Ordering::SeqCst)),
+            r: u64_to_rate(self.0.burst_exit_rate.load(Ordering::SeqCst)),
+            good_loss: u64_to_rate(self.0.burst_good_loss.load(Ordering::SeqCst)),
+            bad_loss: u64_to_rate(self.0.burst_bad_loss.load(Ordering::SeqCst)),
+        }
+    }
+
+    /// Drops packets according to the Gilbert-Elliott model, which produces bursts of loss
+    ///
+    /// The loss is applied in addition to the drop rate.
+    pub fn set_burst_loss(&self, value: GilbertElliott) -> &Self {
+        self.0
+            .burst_enter_rate
+            .store(rate_to_u64(value.p), Ordering::SeqCst);
+        self.0
+            .burst_exit_rate
+            .store(rate_to_u64(value.r), Ordering::SeqCst);
+        self.0
+            .burst_good_loss
+            .store(rate_to_u64(value.good_loss), Ordering::SeqCst);
+        self.0
+            .burst_bad_loss
+            .store(rate_to_u64(value.bad_loss), Ordering::SeqCst);
+        self
+    }
+
+    pub fn ce_threshold(&self) -> u64 {
+        self.0.ce_threshold.load(Ordering::SeqCst)
+    }
+
+    /// Sets the number of inflight packets above which ECN-capable packets are marked with CE
+    pub fn set_ce_threshold(&self, value: u64) -> &Self {
+        self.0.ce_threshold.store(value, Ordering::SeqCst);
+        self
+    }
+
+    fn rebind_rate(&self) -> u64 {
+        self.0.rebind_rate.load(Ordering::SeqCst)
+    }
+
+    /// The odds a client will be rebound to a new address by a NAT on each packet it sends
+    ///
+    /// Each packet will make an independent decision with odds of `0.0..1.0`, with `0.0` having no
+    /// chance and `1.0` occurring with each packet. Clients are the endpoints which send a packet
+    /// before receiving one. Once rebound, packets sent to the client's previous address are
+    /// dropped.
+    pub fn set_rebind_rate(&self, value: f64) -> &Self {
+        let value = rate_to_u64(value);
+        self.0.rebind_rate.store(value, Ordering::SeqCst);
+        self
+    }
+
+    pub fn rebind_kind(&self) -> Rebind {
+        self.0.nat.lock().unwrap().kind
+    }
+
+    /// Sets the part of the address which is changed when a client is rebound at random
+    pub fn set_rebind_kind(&self, value: Rebind) -> &Self {
+        self.0.nat.lock().unwrap().kind = value;
+        self
+    }
+
+    /// Rebinds all of the clients to new addresses
+    ///
+    /// Each client is rebound when it sends its next packet.
+    pub fn rebind_all(&self, kind: Rebind) -> &Self {
+        self.0.nat.lock().unwrap().rebind_all(kind);
+        self
+    }
+}
+
+/// The parameters of the Gilbert-Elliott loss model
+///
+/// The network is either in a good or a bad state. Before each packet is sent, the network
+/// transitions between the states and then drops the packet with the odds of the current state.
+/// All of the values are odds in the range of `0.0..1.0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GilbertElliott {
+    /// The odds of the network transitioning from the good to the bad state
+    pub p: f64,
+    /// The odds of the network transitioning from the bad to the good state
+    pub r: f64,
+    /// The odds of a packet being dropped in the good state
+    pub good_loss: f64,
+    /// The odds of a packet being dropped in the bad state
+    pub bad_loss: f64,
 }
 
 fn rate_to_u64(rate: f64) -> u64 {
@@ -166,6 +310,10 @@ fn rate_to_u64(rate: f64) -> u64 {
     value.round() as u64
 }
 
+fn u64_to_rate(value: u64) -> f64 {
+    value as f64 / u64::MAX as f64
+}
+
 struct State {
     delay: AtomicU64,
     jitter: AtomicU64,
@@ -179,6 +327,22 @@ struct State {
     inflight_delay: AtomicU64,
     inflight_delay_threshold: AtomicU64,
     current_inflight: AtomicU64,
+    bandwidth: AtomicU64,
+    queue_capacity: AtomicU64,
+    /// The bandwidth-limited link for packets sent by clients
+    client_link: Mutex<Link>,
+    /// The bandwidth-limited link for packets sent by servers
+    server_link: Mutex<Link>,
+    outage: AtomicBool,
+    burst_enter_rate: AtomicU64,
+    burst_exit_rate: AtomicU64,
+    burst_good_loss: AtomicU64,
+    burst_bad_loss: AtomicU64,
+    /// Set when the burst loss model is in the bad state
+    burst_is_bad: AtomicBool,
+    ce_threshold: AtomicU64,
+    rebind_rate: AtomicU64,
+    nat: Mutex<Nat>,
 }
 
 impl Default for State {
@@ -196,6 +360,19 @@ impl Default for State {
             inflight_delay: AtomicU64::new(0),
             inflight_delay_threshold: AtomicU64::new(u64::MAX),
             current_inflight: AtomicU64::new(0),
+            bandwidth: AtomicU64::new(u64::MAX),
+            queue_capacity: AtomicU64::new(u64::MAX),
+            client_link: Default::default(),
+            server_link: Default::default(),
+            outage: AtomicBool::new(false),
+            burst_enter_rate: AtomicU64::new(0),
+            burst_exit_rate: AtomicU64::new(0),
+            burst_good_loss: AtomicU64::new(0),
+            burst_bad_loss: AtomicU64::new(0),
+            burst_is_bad: AtomicBool::new(false),
+            ce_threshold: AtomicU64::new(u64::MAX),
+            rebind_rate: AtomicU64::new(0),
+            nat: Default::default(),
         }
     }
 }
@@ -209,8 +386,14 @@ impl Network for Model {
         let corrupt_rate = self.corrupt_rate();
         let drop_rate = self.drop_rate();
         let max_udp_payload = self.max_udp_payload() as usize;
-        let inflight_delay = self.inflight_delay();
-        let inflight_delay_threshold = self.inflight_delay_threshold();
+        let bandwidth = self.bandwidth();
+        let queue_capacity = self.queue_capacity();
+        let is_outage = self.is_outage();
+        let burst_enter_rate = self.0.burst_enter_rate.load(Ordering::SeqCst);
+        let burst_exit_rate = self.0.burst_exit_rate.load(Ordering::SeqCst);
+        let burst_good_loss = self.0.burst_good_loss.load(Ordering::SeqCst);
+        let burst_bad_loss = self.0.burst_bad_loss.load(Ordering::SeqCst);
+        let rebind_rate = self.rebind_rate();
 
         let now = super::time::now();
         let mut transmit_time = now + self.delay();
@@ -222,6 +405,27 @@ impl Network for Model {
             rate > 0 && super::rand::gen::<u64>() < rate
         }
 
+        let gen_burst_loss = || {
+            // avoid generating random numbers if the model isn't configured
+            if burst_enter_rate == 0 && burst_good_loss == 0 {
+                return false;
+            }
+
+            let is_bad = self.0.burst_is_bad.load(Ordering::SeqCst);
+            let is_bad = if is_bad {
+                !gen_rate(burst_exit_rate)
+            } else {
+                gen_rate(burst_enter_rate)
+            };
+            self.0.burst_is_bad.store(is_bad, Ordering::SeqCst);
+
+            gen_rate(if is_bad {
+                burst_bad_loss
+            } else {
+                burst_good_loss
+            })
+        };
+
         let mut transmit = |packet: Cow<Packet>| {
             // drop the packet if it's over the current MTU
             if packet.payload.len() > max_udp_payload {
@@ -233,13 +437,39 @@ impl Network for Model {
                 return 0;
             }
 
+            // drop all packets during an outage
+            if is_outage {
+                return 0;
+            }
+
             // drop the packet if enabled
-            if gen_rate(drop_rate) {
+            if gen_rate(drop_rate) || gen_burst_loss() {
+                return 0;
+            }
+
+            // packets can't be sent if the link has no bandwidth
+            if bandwidth == 0 {
                 return 0;
             }
 
             let mut packet = packet.into_owned();
 
+            // translate the addresses for any clients behind the NAT
+            let src = packet.path.local_address.0;
+            let dst = packet.path.remote_address.0;
+            let rebind = gen_rate(rebind_rate);
+            let (nat, is_client) = {
+                let mut nat = self.0.nat.lock().unwrap();
+                let translated = nat.translate(src, dst, rebind, buffers);
+                (translated, nat.is_client(&src))
+            };
+            if let Some((src, dst)) = nat {
+                packet.path.local_address = src.into();
+                packet.path.remote_address = dst.into();
+            } else {
+                return 0;
+            }
+
             if !packet.payload.is_empty() && gen_rate(corrupt_rate) {
                 use havoc::Strategy as _;
 
@@ -267,32 +497,26 @@ impl Network for Model {
                 transmit_time += gen_jitter(network_jitter);
             }
 
-            let model = self.clone();
-            let current_inflight = model.0.current_inflight.fetch_add(1, Ordering::SeqCst);
-
-            // scale the inflight delay by the number above the delay threshold
-            if let Some(mul) = current_inflight.checked_sub(inflight_delay_threshold) {
-                transmit_time += inflight_delay * mul as u32;
-            }
-
-            // reverse the addresses so the dst/src are correct for the receiver
-            packet.switch();
-
-            let buffers = buffers.clone();
+            if bandwidth != u64::MAX {
+                // queue the packet behind any packets that are still being serialized onto the
+                // link. The remaining delay is applied once the packet leaves the link.
+                let len = packet.payload.len();
+                let delay = transmit_time - now;
+                let mut link = self.link(is_client).lock().unwrap();
+                if !link.enqueue(now, (packet, delay), len, queue_capacity) {
+                    return 0;
+                }
 
-            // spawn a task that will push the packet onto the receiver queue at the transit time
-            super::spawn(async move {
-                // if the packet isn't scheduled to transmit immediately, wait until the computed
-                // time
-                if now != transmit_time {
-                    super::time::delay_until(transmit_time).await;
+                if link.start() {
+                    let model = self.clone();
+                    let buffers = buffers.clone();
+                    super::spawn(async move { model.transmit_link(is_client, buffers).await });
                 }
 
-                buffers.rx(*packet.path.local_address, |queue| {
-                    model.0.current_inflight.fetch_sub(1, Ordering::SeqCst);
-                    queue.receive(packet);
-                });
-            });
+                return 1;
+            }
+
+            self.deliver(packet, transmit_time, buffers);
 
             1
         };
@@ -324,6 +548,84 @@ impl Network for Model {
     }
 }
 
+impl Model {
+    fn link(&self, is_client: bool) -> &Mutex<Link> {
+        if is_client {
+            &self.0.client_link
+        } else {
+            &self.0.server_link
+        }
+    }
+
+    /// Serializes the queued packets onto the link until the queue is empty
+    async fn transmit_link(self, is_client: bool, buffers: Buffers) {
+        loop {
+            let now = super::time::now();
+            let max_packet = self.max_udp_payload() as usize;
+            let next = self.link(is_client).lock().unwrap().dequeue(now, max_packet);
+
+            let ((packet, delay), len) = if let Some(next) = next {
+                next
+            } else {
+                return;
+            };
+
+            // packets can't be sent if the link has no bandwidth
+            let bandwidth = self.bandwidth();
+            if bandwidth == 0 {
+                continue;
+            }
+
+            let serialization = len as u128 * 1_000_000_000 / bandwidth as u128;
+            let sent = now + Duration::from_nanos(serialization as u64);
+            self.deliver(packet, sent + delay, &buffers);
+
+            if now != sent {
+                super::time::delay_until(sent).await;
+            }
+        }
+    }
+
+    /// Delivers the packet to the receiver at the transmit time
+    fn deliver(&self, mut packet: Packet, mut transmit_time: Timestamp, buffers: &Buffers) {
+        let now = super::time::now();
+        let model = self.clone();
+        let current_inflight = model.0.current_inflight.fetch_add(1, Ordering::SeqCst);
+
+        // scale the inflight delay by the number above the delay threshold
+        if let Some(mul) = current_inflight.checked_sub(self.inflight_delay_threshold()) {
+            transmit_time += self.inflight_delay() * mul as u32;
+        }
+
+        // mark congestion on ECN-capable packets once the queue exceeds the threshold
+        if current_inflight >= self.ce_threshold() && packet.ecn.using_ecn() {
+            packet.ecn = ExplicitCongestionNotification::Ce;
+        }
+
+        // reverse the addresses so the dst/src are correct for the receiver
+        packet.switch();
+
+        let buffers = buffers.clone();
+
+        // spawn a task that will push the packet onto the receiver queue at the transit time
+        super::spawn(async move {
+            // if the packet isn't scheduled to transmit immediately, wait until the computed
+            // time
+            if now != transmit_time {
+                super::time::delay_until(transmit_time).await;
+            }
+
+            buffers.rx(*packet.path.local_address, |queue| {
+                model.0.current_inflight.fetch_sub(1, Ordering::SeqCst);
+                queue.receive(packet);
+            });
+        });
+    }
+}
+
+/// A bandwidth-limited link along with the remaining delay of each queued packet
+type Link = Bottleneck<(Packet, Duration)>;
+
 fn gen_jitter(max_jitter: Duration) -> Duration {
     let micros = super::rand::gen_range(0..max_jitter.as_micros() as u64);
     let micros = micros as f64;
@@ -0,0 +1,189 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::network::Buffers;
+use s2n_quic_core::inet::SocketAddress;
+use std::collections::HashMap;
+
+/// The parts of a client's address that are changed when a NAT rebinds it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rebind {
+    Port,
+    Address,
+    Both,
+}
+
+impl Default for Rebind {
+    fn default() -> Self {
+        Self::Port
+    }
+}
+
+/// Simulates a NAT in front of each client
+///
+/// Clients are the addresses that send a packet before receiving one. Their packets are
+/// translated to the address currently bound by the NAT and packets sent to the bound address
+/// are translated back. Once a client is rebound, packets sent to its previous binding are
+/// dropped.
+#[derive(Debug, Default)]
+pub(super) struct Nat {
+    roles: HashMap<SocketAddress, Role>,
+    /// Maps a client address to the address observed by its peers
+    bindings: HashMap<SocketAddress, Binding>,
+    /// Maps each current and previous observed address back to the client
+    reverse: HashMap<SocketAddress, SocketAddress>,
+    pub kind: Rebind,
+    /// Incremented each time all of the clients are rebound
+    epoch: u64,
+    epoch_kind: Rebind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Role {
+    Client,
+    Server,
+}
+
+#[derive(Debug)]
+struct Binding {
+    observed: SocketAddress,
+    epoch: u64,
+}
+
+impl Nat {
+    /// Rebinds every client on the next packet it sends
+    pub fn rebind_all(&mut self, kind: Rebind) {
+        self.epoch += 1;
+        self.epoch_kind = kind;
+    }
+
+    /// Returns `true` if the address belongs to a client
+    pub fn is_client(&self, addr: &SocketAddress) -> bool {
+        self.roles.get(addr) == Some(&Role::Client)
+    }
+
+    /// Translates the addresses of a packet
+    ///
+    /// Returns `None` if the packet is addressed to a binding that no longer exists.
+    pub fn translate(
+        &mut self,
+        src: SocketAddress,
+        dst: SocketAddress,
+        rebind: bool,
+        buffers: &Buffers,
+    ) -> Option<(SocketAddress, SocketAddress)> {
+        let dst = if let Some(client) = self.reverse.get(&dst) {
+            // the client has been rebound so the previous address is no longer reachable
+            if self.bindings[client].observed != dst {
+                return None;
+            }
+            *client
+        } else {
+            dst
+        };
+
+        self.roles.entry(src).or_insert(Role::Client);
+        self.roles.entry(dst).or_insert(Role::Server);
+
+        if self.roles[&src] != Role::Client {
+            return Some((src, dst));
+        }
+
+        let epoch = self.epoch;
+        let reverse = &mut self.reverse;
+        let binding = self.bindings.entry(src).or_insert_with(|| {
+            reverse.insert(src, src);
+            Binding {
+                observed: src,
+                epoch,
+            }
+        });
+
+        let kind = if binding.epoch != epoch {
+            Some(self.epoch_kind)
+        } else if rebind {
+            Some(self.kind)
+        } else {
+            None
+        };
+
+        if let Some(kind) = kind {
+            let prev = binding.observed;
+            let fresh = buffers.generate_addr();
+
+            let observed = match kind {
+                Rebind::Port => {
+                    let mut addr = prev;
+                    addr.set_port(fresh.port());
+                    addr
+                }
+                Rebind::Address => {
+                    let mut addr = fresh;
+                    addr.set_port(prev.port());
+                    addr
+                }
+                Rebind::Both => fresh,
+            };
+
+            binding.observed = observed;
+            binding.epoch = epoch;
+            // keep the previous binding so packets sent to it can be dropped
+            self.reverse.insert(observed, src);
+        }
+
+        Some((binding.observed, dst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebind_test() {
+        let buffers = Buffers::default();
+        let mut nat = Nat::default();
+        let client = buffers.generate_addr();
+        let server = buffers.generate_addr();
+
+        // the first packet doesn't change the client address
+        assert_eq!(
+            nat.translate(client, server, false, &buffers),
+            Some((client, server))
+        );
+        assert_eq!(
+            nat.translate(server, client, false, &buffers),
+            Some((server, client))
+        );
+
+        // the server's address is never rebound
+        assert_eq!(
+            nat.translate(server, client, true, &buffers),
+            Some((server, client))
+        );
+
+        let (observed, _) = nat.translate(client, server, true, &buffers).unwrap();
+        assert_ne!(observed, client);
+        assert_eq!(observed.ip(), client.ip());
+
+        // packets to the new binding are translated back to the client
+        assert_eq!(
+            nat.translate(server, observed, false, &buffers),
+            Some((server, client))
+        );
+        // packets to the previous binding are dropped
+        assert_eq!(nat.translate(server, client, false, &buffers), None);
+
+        nat.rebind_all(Rebind::Address);
+        let (next, _) = nat.translate(client, server, false, &buffers).unwrap();
+        assert_ne!(next.ip(), observed.ip());
+        assert_eq!(next.port(), observed.port());
+        assert_eq!(nat.translate(server, observed, false, &buffers), None);
+
+        // the client is only rebound once for each call to `rebind_all`
+        assert_eq!(
+            nat.translate(client, server, false, &buffers),
+            Some((next, server))
+        );
+    }
+}
@@ -124,10 +124,11 @@ impl Buffers {
         n.execute(self);
     }
 
-    pub(crate) fn readiness(&self, handle: SocketAddress) -> Readiness {
+    pub(crate) fn readiness(&self, handle: SocketAddress, owner: Owner) -> Readiness {
         Readiness {
             network: self,
             handle,
+            owner,
         }
     }
 
@@ -144,19 +145,42 @@ impl Buffers {
     }
 
     /// Register an address on the network
-    pub fn register(&self, handle: SocketAddress) {
+    ///
+    /// If the address is already registered, the previous owner is shut down and any of its
+    /// pending packets are discarded. This can be used to simulate an endpoint restarting and
+    /// losing all of its state.
+    pub fn register(&self, handle: SocketAddress) -> Owner {
         let mut lock = self.inner.lock().unwrap();
+        let state = &mut *lock;
+
+        let owner = Owner(state.next_owner);
+        state.next_owner += 1;
+        state.owners.insert(handle, owner);
 
         let queue = Queue::new(handle);
 
-        lock.tx.insert(handle, queue.clone());
-        lock.rx.insert(handle, queue);
+        let prev_tx = state.tx.insert(handle, queue.clone());
+        let prev_rx = state.rx.insert(handle, queue);
+
+        // notify the previous owner so it can observe that it's been replaced
+        for mut prev in prev_tx.into_iter().chain(prev_rx) {
+            if let Some(waker) = prev.waker.take() {
+                waker.wake();
+            }
+        }
+
+        owner
     }
 }
 
+/// Identifies the endpoint that registered an address on the network
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Owner(u64);
+
 pub(crate) struct Readiness<'a> {
     network: &'a Buffers,
     handle: SocketAddress,
+    owner: Owner,
 }
 
 impl<'a> Future for Readiness<'a> {
@@ -165,7 +189,8 @@ impl<'a> Future for Readiness<'a> {
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let mut lock = self.network.inner.lock().unwrap();
 
-        if !lock.is_open {
+        // shut down if the network is closed or another endpoint took over the address
+        if !lock.is_open || lock.owners.get(&self.handle) != Some(&self.owner) {
             return Err(()).into();
         }
 
@@ -203,6 +228,8 @@ struct State {
     is_open: bool,
     tx: HashMap<SocketAddress, Queue>,
     rx: HashMap<SocketAddress, Queue>,
+    owners: HashMap<SocketAddress, Owner>,
+    next_owner: u64,
 }
 
 impl Default for State {
@@ -211,6 +238,8 @@ impl Default for State {
             is_open: true,
             tx: Default::default(),
             rx: Default::default(),
+            owners: Default::default(),
+            next_owner: 0,
         }
     }
 }
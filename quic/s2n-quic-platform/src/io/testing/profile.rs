@@ -0,0 +1,103 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    model::{GilbertElliott, Model},
+    nat::Rebind,
+};
+use core::time::Duration;
+
+/// A script of changes to the network conditions over time
+///
+/// The changes are applied to a [`Model`] by a task driven by the testing clock so the same
+/// profile produces the same transitions for a given seed.
+///
+/// ```rust,ignore
+/// Profile::default()
+///     .with(Duration::from_secs(1), Change::Bandwidth(125_000))
+///     .with_outage(Duration::from_secs(2), Duration::from_millis(500))
+///     .with(Duration::from_secs(4), Change::MaxUdpPayload(1200))
+///     .spawn(model.clone());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    steps: Vec<(Duration, Change)>,
+}
+
+impl Profile {
+    /// Applies `change` at `at`, relative to when the profile is spawned
+    pub fn with(mut self, at: Duration, change: Change) -> Self {
+        self.steps.push((at, change));
+        self
+    }
+
+    /// Drops all packets for `duration`, starting at `at`
+    pub fn with_outage(self, at: Duration, duration: Duration) -> Self {
+        self.with(at, Change::Outage(true))
+            .with(at + duration, Change::Outage(false))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Spawns a task which applies the changes to `model`
+    ///
+    /// Changes scheduled for the same time are applied in the order they were added.
+    pub fn spawn(mut self, model: Model) {
+        if self.steps.is_empty() {
+            return;
+        }
+
+        // the sort is stable so changes for the same time keep their order
+        self.steps.sort_by_key(|(at, _)| *at);
+
+        let start = super::time::now();
+
+        super::spawn(async move {
+            for (at, change) in self.steps {
+                super::time::delay_until(start + at).await;
+                change.apply(&model);
+            }
+        });
+    }
+}
+
+/// A change to the network conditions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Change {
+    /// See [`Model::set_bandwidth`]
+    Bandwidth(u64),
+    /// See [`Model::set_queue_capacity`]
+    QueueCapacity(u64),
+    /// See [`Model::set_outage`]
+    Outage(bool),
+    /// See [`Model::set_max_udp_payload`]
+    MaxUdpPayload(u16),
+    /// See [`Model::set_delay`]
+    Delay(Duration),
+    /// See [`Model::set_drop_rate`]
+    DropRate(f64),
+    /// See [`Model::set_burst_loss`]
+    BurstLoss(GilbertElliott),
+    /// See [`Model::set_ce_threshold`]
+    CeThreshold(u64),
+    /// See [`Model::rebind_all`]
+    RebindAll(Rebind),
+}
+
+impl Change {
+    pub fn apply(&self, model: &Model) {
+        match *self {
+            Self::Bandwidth(value) => model.set_bandwidth(value),
+            Self::QueueCapacity(value) => model.set_queue_capacity(value),
+            Self::Outage(value) => model.set_outage(value),
+            Self::MaxUdpPayload(value) => model.set_max_udp_payload(value),
+            Self::Delay(value) => model.set_delay(value),
+            Self::DropRate(value) => model.set_drop_rate(value),
+            Self::BurstLoss(value) => model.set_burst_loss(value),
+            Self::CeThreshold(value) => model.set_ce_threshold(value),
+            Self::RebindAll(value) => model.rebind_all(value),
+        };
+    }
+}
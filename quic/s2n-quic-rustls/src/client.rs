@@ -81,6 +81,8 @@ impl tls::Endpoint for Client {
 
 pub struct Builder {
     cert_store: rustls::RootCertStore,
+    cert_verifier: Option<Arc<dyn rustls::client::ServerCertVerifier>>,
+    client_identity: Option<(certificate::Certificate, certificate::PrivateKey)>,
     application_protocols: Vec<Vec<u8>>,
     key_log: Option<Arc<dyn rustls::KeyLog>>,
 }
@@ -95,6 +97,8 @@ impl Builder {
     pub fn new() -> Self {
         Self {
             cert_store: rustls::RootCertStore::empty(),
+            cert_verifier: None,
+            client_identity: None,
             application_protocols: vec![b"h3".to_vec()],
             key_log: None,
         }
@@ -114,6 +118,34 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sets a custom verifier for the certificate presented by the server
+    ///
+    /// The verifier replaces the default verification against the trusted certificates
+    /// configured with `with_certificate`.
+    pub fn with_certificate_verifier(
+        mut self,
+        verifier: Arc<dyn rustls::client::ServerCertVerifier>,
+    ) -> Result<Self, rustls::Error> {
+        self.cert_verifier = Some(verifier);
+        Ok(self)
+    }
+
+    /// Sets the certificate chain and private key presented to servers which require client
+    /// authentication (mutual TLS)
+    pub fn with_client_identity<
+        C: certificate::IntoCertificate,
+        PK: certificate::IntoPrivateKey,
+    >(
+        mut self,
+        certificate: C,
+        private_key: PK,
+    ) -> Result<Self, rustls::Error> {
+        let certificate = certificate.into_certificate()?;
+        let private_key = private_key.into_private_key()?;
+        self.client_identity = Some((certificate, private_key));
+        Ok(self)
+    }
+
     pub fn with_max_cert_chain_depth(self, len: u16) -> Result<Self, rustls::Error> {
         // TODO is there a way to configure this?
         let _ = len;
@@ -135,7 +167,7 @@ impl Builder {
 
     pub fn build(self) -> Result<Client, rustls::Error> {
         // TODO load system root store?
-        if self.cert_store.is_empty() {
+        if self.cert_store.is_empty() && self.cert_verifier.is_none() {
             //= https://www.rfc-editor.org/rfc/rfc9001#section-4.4
             //# A client MUST authenticate the identity of the server.
             return Err(rustls::Error::General(
@@ -143,12 +175,21 @@ impl Builder {
             ));
         }
 
-        let mut config = ClientConfig::builder()
+        let builder = ClientConfig::builder()
             .with_cipher_suites(crate::cipher_suite::DEFAULT_CIPHERSUITES)
             .with_safe_default_kx_groups()
             .with_protocol_versions(crate::PROTOCOL_VERSIONS)?
-            .with_root_certificates(self.cert_store)
-            .with_no_client_auth();
+            .with_root_certificates(self.cert_store);
+
+        let mut config = if let Some((certificate, private_key)) = self.client_identity {
+            builder.with_single_cert(certificate.0, private_key.0)?
+        } else {
+            builder.with_no_client_auth()
+        };
+
+        if let Some(cert_verifier) = self.cert_verifier {
+            config.dangerous().set_certificate_verifier(cert_verifier);
+        }
 
         config.max_fragment_size = None;
         config.alpn_protocols = self.application_protocols;
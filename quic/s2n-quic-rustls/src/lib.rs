@@ -57,3 +57,135 @@ fn client_server_test() {
 
     pair.finish();
 }
+
+#[test]
+fn client_server_client_auth_test() {
+    use s2n_quic_core::crypto::tls::{self, testing::certificates::*};
+
+    let mut client = client::Builder::new()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .with_client_identity(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let mut server = server::Builder::new()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_client_authentication()
+        .unwrap()
+        .with_trusted_certificate(CERT_PEM)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let mut pair = tls::testing::Pair::new(&mut server, &mut client, "localhost".into());
+
+    while pair.is_handshaking() {
+        pair.poll(None).unwrap();
+    }
+
+    pair.finish();
+
+    let session_info = pair.server.context.session_info.as_ref().unwrap();
+    assert_eq!(session_info.peer_certificate_chain.len(), 1);
+}
+
+#[test]
+fn client_auth_required_test() {
+    use s2n_quic_core::crypto::tls::{self, testing::certificates::*};
+
+    let mut client = client::Builder::new()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let mut server = server::Builder::new()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_client_authentication()
+        .unwrap()
+        .with_trusted_certificate(CERT_PEM)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let mut pair = tls::testing::Pair::new(&mut server, &mut client, "localhost".into());
+
+    let result = (|| {
+        while pair.is_handshaking() {
+            pair.poll(None)?;
+        }
+        Ok::<_, s2n_quic_core::transport::Error>(())
+    })();
+
+    assert!(
+        result.is_err(),
+        "client without a certificate should be rejected"
+    );
+}
+
+#[test]
+fn custom_server_cert_verifier_test() {
+    use rustls::client::{ServerCertVerified, ServerCertVerifier};
+    use s2n_quic_core::crypto::tls::{self, testing::certificates::*};
+    use std::{sync::Arc, time::SystemTime};
+
+    /// Only accepts a single pinned certificate
+    struct Pinned(Certificate);
+
+    impl ServerCertVerifier for Pinned {
+        fn verify_server_cert(
+            &self,
+            end_entity: &Certificate,
+            _intermediates: &[Certificate],
+            _server_name: &rustls::ServerName,
+            _scts: &mut dyn Iterator<Item = &[u8]>,
+            _ocsp_response: &[u8],
+            _now: SystemTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            if end_entity == &self.0 {
+                Ok(ServerCertVerified::assertion())
+            } else {
+                Err(rustls::Error::InvalidCertificateData(
+                    "certificate is not pinned".to_string(),
+                ))
+            }
+        }
+    }
+
+    let run = |pinned: &str| {
+        let pinned = certificate::IntoCertificate::into_certificate(pinned)
+            .unwrap()
+            .0
+            .remove(0);
+
+        // no trusted certificates are needed since the verifier makes the decision
+        let mut client = client::Builder::new()
+            .with_certificate_verifier(Arc::new(Pinned(pinned)))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut server = server::Builder::new()
+            .with_certificate(CERT_PEM, KEY_PEM)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let mut pair = tls::testing::Pair::new(&mut server, &mut client, "localhost".into());
+
+        while pair.is_handshaking() {
+            pair.poll(None)?;
+        }
+
+        pair.finish();
+
+        Ok::<_, s2n_quic_core::transport::Error>(())
+    };
+
+    run(CERT_PEM).unwrap();
+    assert!(run(UNTRUSTED_CERT_PEM).is_err());
+}
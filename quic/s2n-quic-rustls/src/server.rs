@@ -75,6 +75,9 @@ impl tls::Endpoint for Server {
 
 pub struct Builder {
     cert_resolver: Option<Arc<dyn rustls::server::ResolvesServerCert>>,
+    client_cert_store: rustls::RootCertStore,
+    client_cert_verifier: Option<Arc<dyn rustls::server::ClientCertVerifier>>,
+    client_authentication: bool,
     application_protocols: Vec<Vec<u8>>,
     key_log: Option<Arc<dyn rustls::KeyLog>>,
 }
@@ -89,6 +92,9 @@ impl Builder {
     pub fn new() -> Self {
         Self {
             cert_resolver: None,
+            client_cert_store: rustls::RootCertStore::empty(),
+            client_cert_verifier: None,
+            client_authentication: false,
             application_protocols: vec![b"h3".to_vec()],
             key_log: None,
         }
@@ -114,6 +120,43 @@ impl Builder {
         Ok(self)
     }
 
+    /// Adds a certificate to the trust store used to verify client certificates
+    pub fn with_trusted_certificate<C: certificate::IntoCertificate>(
+        mut self,
+        certificate: C,
+    ) -> Result<Self, rustls::Error> {
+        let certificates = certificate.into_certificate()?;
+        let root_certificate = certificates.0.get(0).ok_or_else(|| {
+            rustls::Error::General("Certificate chain needs to have at least one entry".to_string())
+        })?;
+        self.client_cert_store
+            .add(root_certificate)
+            .map_err(|err| rustls::Error::General(err.to_string()))?;
+        Ok(self)
+    }
+
+    /// Configures this server instance to require client authentication (mutual TLS)
+    ///
+    /// Client certificates are verified against the certificates added with
+    /// `with_trusted_certificate`, unless a verifier is set with
+    /// `with_client_certificate_verifier`.
+    pub fn with_client_authentication(mut self) -> Result<Self, rustls::Error> {
+        self.client_authentication = true;
+        Ok(self)
+    }
+
+    /// Sets a custom verifier for the certificates presented by clients
+    ///
+    /// The verifier decides whether client authentication is offered and required, so this can
+    /// be used without calling `with_client_authentication`.
+    pub fn with_client_certificate_verifier(
+        mut self,
+        verifier: Arc<dyn rustls::server::ClientCertVerifier>,
+    ) -> Result<Self, rustls::Error> {
+        self.client_cert_verifier = Some(verifier);
+        Ok(self)
+    }
+
     pub fn with_application_protocols<P: Iterator<Item = I>, I: AsRef<[u8]>>(
         mut self,
         protocols: P,
@@ -131,8 +174,23 @@ impl Builder {
         let builder = ServerConfig::builder()
             .with_cipher_suites(crate::cipher_suite::DEFAULT_CIPHERSUITES)
             .with_safe_default_kx_groups()
-            .with_protocol_versions(crate::PROTOCOL_VERSIONS)?
-            .with_no_client_auth();
+            .with_protocol_versions(crate::PROTOCOL_VERSIONS)?;
+
+        let builder = if let Some(verifier) = self.client_cert_verifier {
+            builder.with_client_cert_verifier(verifier)
+        } else if self.client_authentication {
+            if self.client_cert_store.is_empty() {
+                return Err(rustls::Error::General(
+                    "missing trusted client certificate(s)".to_string(),
+                ));
+            }
+
+            builder.with_client_cert_verifier(rustls::server::AllowAnyAuthenticatedClient::new(
+                self.client_cert_store,
+            ))
+        } else {
+            builder.with_no_client_auth()
+        };
 
         let mut config = if let Some(cert_resolver) = self.cert_resolver {
             builder.with_cert_resolver(cert_resolver)
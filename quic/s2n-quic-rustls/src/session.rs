@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::cipher_suite::{
-    HeaderProtectionKey, HeaderProtectionKeys, OneRttKey, PacketKey, PacketKeys,
+    into_tls_cipher_suite, HeaderProtectionKey, HeaderProtectionKeys, OneRttKey, PacketKey,
+    PacketKeys,
 };
 use bytes::Bytes;
 use core::{fmt, fmt::Debug, task::Poll};
@@ -124,6 +125,35 @@ impl Session {
         }
     }
 
+    fn session_info(&self) -> tls::SessionInfo {
+        let cipher_suite = self
+            .connection
+            .negotiated_cipher_suite()
+            .map(|cipher_suite| into_tls_cipher_suite(cipher_suite.suite()))
+            .unwrap_or_default();
+
+        let protocol_version = match self.connection.protocol_version() {
+            Some(rustls::ProtocolVersion::TLSv1_3) => tls::ProtocolVersion::TLS_1_3,
+            _ => tls::ProtocolVersion::Unknown,
+        };
+
+        let peer_certificate_chain = self
+            .connection
+            .peer_certificates()
+            .unwrap_or_default()
+            .iter()
+            .map(|certificate| Bytes::copy_from_slice(&certificate.0))
+            .collect();
+
+        tls::SessionInfo {
+            cipher_suite,
+            protocol_version,
+            peer_certificate_chain,
+            // rustls doesn't expose whether the handshake resumed a previous session
+            resumed: false,
+        }
+    }
+
     fn zero_rtt_keys(&mut self) -> Option<quic::DirectionalKeys> {
         if self.emitted_zero_rtt_keys {
             return None;
@@ -149,6 +179,7 @@ impl Session {
             // the handshake is complete!
             if !self.emitted_handshake_complete {
                 self.rx_phase.transition();
+                context.on_session_info(self.session_info())?;
                 context.on_handshake_complete()?;
             }
 
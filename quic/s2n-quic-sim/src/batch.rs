@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    run::Config as Sim,
+    run::{CongestionController, Config as Sim},
     stats::{Filter, Query},
     Result,
 };
@@ -18,6 +18,7 @@ use std::{
 use structopt::StructOpt;
 
 static INDEX: &str = include_str!("./batch.html");
+static COMPARE: &str = include_str!("./compare.html");
 
 #[derive(Debug, StructOpt)]
 pub struct Batch {
@@ -39,17 +40,26 @@ impl Batch {
         fs::write(out.join("index.html"), INDEX)?;
 
         let mut reports = vec![];
+        let mut comparisons = vec![];
 
         for plan in &self.plans {
-            plan.run(out, &command, self.skip_run, &mut reports)?;
+            plan.run(out, &command, self.skip_run, &mut reports, &mut comparisons)?;
         }
 
-        for (_title, report) in reports.iter_mut() {
+        for (_title, report) in reports.iter_mut().chain(comparisons.iter_mut()) {
             *report = report.strip_prefix(&out).unwrap().to_owned();
         }
 
         serde_json::to_writer(fs::File::create(out.join("reports.json"))?, &reports)?;
 
+        if !comparisons.is_empty() {
+            fs::write(out.join("compare.html"), COMPARE)?;
+            serde_json::to_writer(
+                fs::File::create(out.join("comparisons.json"))?,
+                &comparisons,
+            )?;
+        }
+
         Ok(())
     }
 }
@@ -61,6 +71,13 @@ struct Plan {
 
     sim: Sim,
 
+    /// The congestion controllers to compare head to head
+    ///
+    /// Both are simulated with the same seeds and each report is also rendered in
+    /// `compare.html`.
+    #[serde(default)]
+    compare: Option<[CongestionController; 2]>,
+
     report: BTreeMap<String, Report>,
 }
 
@@ -71,31 +88,95 @@ impl Plan {
         command: &str,
         skip_run: bool,
         reports: &mut Vec<(String, PathBuf)>,
+        comparisons: &mut Vec<(String, PathBuf)>,
     ) -> Result {
         let name = self.name.as_ref().unwrap();
         eprintln!("     Running {}", name);
         let out = out.join(name);
         fs::create_dir_all(&out)?;
 
-        let db = out.join("db.proto");
-
-        if !skip_run || !db.exists() {
-            let status = Command::new(command)
-                .arg("run")
-                .arg("--progress")
-                .args(self.sim.args())
-                .stdout(fs::File::create(&db)?)
-                .status()?;
+        let [baseline, candidate] = if let Some(compare) = self.compare {
+            compare
+        } else {
+            let db = out.join("db.proto");
+            self.simulate(command, &self.sim, None, &db, skip_run)?;
+
+            for (report_name, report) in self.report.iter() {
+                let output = out.join(format!("{}.json", report_name));
+                let mut res = report.run(&output, command, &db, report_name)?;
+                res.0 = format!("{} - {}", name, res.0);
+                reports.push(res);
+            }
 
-            if !status.success() {
-                return Err(anyhow!("run did not succeed"));
+            return Ok(());
+        };
+
+        let roles = ["baseline", "candidate"];
+        let dbs = roles.map(|role| out.join(format!("{}.proto", role)));
+        let labels = [
+            format!("{} ({})", roles[0], baseline),
+            format!("{} ({})", roles[1], candidate),
+        ];
+
+        // the results are only comparable if both congestion controllers were simulated together
+        if !(skip_run && dbs.iter().all(|db| db.exists())) {
+            let base_seed = rand::random();
+
+            let congestion_controllers = [baseline, candidate];
+            for ((congestion_controller, db), label) in
+                congestion_controllers.into_iter().zip(&dbs).zip(&labels)
+            {
+                eprintln!("     Running {} - {}", name, label);
+                let mut sim = self.sim.clone();
+                sim.congestion_controller = congestion_controller;
+                self.simulate(command, &sim, Some(base_seed), db, false)?;
             }
         }
 
         for (report_name, report) in self.report.iter() {
-            let mut res = report.run(&out, command, &db, report_name)?;
+            for ((db, label), role) in dbs.iter().zip(&labels).zip(roles) {
+                let output = out.join(format!("{}.{}.json", report_name, role));
+                let mut res = report.run(&output, command, db, report_name)?;
+                res.0 = format!("{} - {} - {}", name, label, res.0);
+                reports.push(res);
+            }
+
+            let output = out.join(format!("{}.compare.json", report_name));
+            let mut res = report.compare(&output, command, &dbs, &labels, report_name)?;
             res.0 = format!("{} - {}", name, res.0);
-            reports.push(res);
+            comparisons.push(res);
+        }
+
+        Ok(())
+    }
+
+    fn simulate(
+        &self,
+        command: &str,
+        sim: &Sim,
+        base_seed: Option<u64>,
+        db: &Path,
+        skip_run: bool,
+    ) -> Result {
+        if skip_run && db.exists() {
+            return Ok(());
+        }
+
+        let mut cmd = Command::new(command);
+
+        cmd.arg("run")
+            .arg("--progress")
+            .args(sim.args())
+            .stdout(fs::File::create(db)?);
+
+        if let Some(base_seed) = base_seed {
+            cmd.arg("--base-seed").arg(base_seed.to_string());
+        }
+
+        let status = cmd.status()?;
+
+        if !status.success() {
+            return Err(anyhow!("run did not succeed"));
         }
 
         Ok(())
@@ -134,9 +215,14 @@ struct Report {
 }
 
 impl Report {
-    fn run(&self, out: &Path, command: &str, db: &Path, name: &str) -> Result<(String, PathBuf)> {
+    fn run(
+        &self,
+        output: &Path,
+        command: &str,
+        db: &Path,
+        name: &str,
+    ) -> Result<(String, PathBuf)> {
         let title = self.title.as_deref().unwrap_or(name).to_owned();
-        let output = out.join(format!("{}.json", name));
 
         let mut cmd = Command::new(command);
 
@@ -148,7 +234,7 @@ impl Report {
             .arg(self.y.to_string())
             .arg("--title")
             .arg(&title)
-            .stdout(fs::File::create(&output)?);
+            .stdout(fs::File::create(output)?);
 
         for filter in &self.filters {
             cmd.arg("--filter").arg(filter.to_string());
@@ -160,6 +246,46 @@ impl Report {
             return Err(anyhow!("{} report did not succeed", name));
         }
 
-        Ok((title, output))
+        Ok((title, output.to_owned()))
+    }
+
+    fn compare(
+        &self,
+        output: &Path,
+        command: &str,
+        [baseline, candidate]: &[PathBuf; 2],
+        [baseline_label, candidate_label]: &[String; 2],
+        name: &str,
+    ) -> Result<(String, PathBuf)> {
+        let title = self.title.as_deref().unwrap_or(name).to_owned();
+
+        let mut cmd = Command::new(command);
+
+        cmd.arg("compare")
+            .arg(baseline)
+            .arg(candidate)
+            .arg("--baseline-label")
+            .arg(baseline_label)
+            .arg("--candidate-label")
+            .arg(candidate_label)
+            .arg("--x")
+            .arg(self.x.to_string())
+            .arg("--y")
+            .arg(self.y.to_string())
+            .arg("--title")
+            .arg(&title)
+            .stdout(fs::File::create(output)?);
+
+        for filter in &self.filters {
+            cmd.arg("--filter").arg(filter.to_string());
+        }
+
+        let status = cmd.status()?;
+
+        if !status.success() {
+            return Err(anyhow!("{} comparison did not succeed", name));
+        }
+
+        Ok((title, output.to_owned()))
     }
 }
@@ -0,0 +1,416 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    report::Bounds,
+    stats::{Connection, Filter, Parameters, Query, Stats, QUERY_NAMES},
+    Result,
+};
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+
+/// Compares the results of two simulation databases head to head
+///
+/// This is used to compare two congestion controllers which were simulated with the same seeds.
+#[derive(Debug, StructOpt)]
+pub struct Compare {
+    #[structopt(long, short)]
+    filter: Vec<Filter>,
+
+    #[structopt(long, short, possible_values = &*QUERY_NAMES)]
+    x: Query,
+
+    #[structopt(long, default_value = "20")]
+    x_width: u32,
+
+    #[structopt(long, short, possible_values = &*QUERY_NAMES)]
+    y: Query,
+
+    #[structopt(long)]
+    title: Option<String>,
+
+    /// The number of simulations from each input with throughput time series
+    #[structopt(long, default_value = "50")]
+    series: usize,
+
+    #[structopt(long, default_value = "baseline")]
+    baseline_label: String,
+
+    #[structopt(long, default_value = "candidate")]
+    candidate_label: String,
+
+    baseline: PathBuf,
+
+    candidate: PathBuf,
+}
+
+impl Compare {
+    pub fn run(&self) -> Result {
+        let inputs = [
+            (&self.baseline_label, load(&self.baseline)?),
+            (&self.candidate_label, load(&self.candidate)?),
+        ];
+
+        let mut x_bounds = Bounds::default();
+        let mut values = vec![];
+        let mut series = vec![];
+        let mut series_len = 0;
+
+        for (input, (label, sims)) in inputs.iter().enumerate() {
+            for (sim, (p, connections)) in sims.iter().enumerate() {
+                for conn in connections {
+                    if !self.filter.iter().all(|f| f.apply(p, conn, connections)) {
+                        continue;
+                    }
+
+                    let x = self.x.apply(p, conn, connections);
+                    let y = self.y.apply(p, conn, connections);
+
+                    if let (Some(x), Some(y)) = (x, y) {
+                        x_bounds.push(x);
+                        values.push((input, x, y));
+                    }
+
+                    if sim < self.series {
+                        series_len = series_len.max(sim + 1);
+                        for (time, throughput) in conn.tx_throughput() {
+                            series.push(json!({
+                                "label": label,
+                                "sim": sim,
+                                "conn": conn.id(),
+                                "time": time.as_secs_f64() * 1000.0,
+                                "throughput": throughput,
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+
+        let x_scale = if self.x.ty.is_duration() { 1000.0 } else { 1.0 };
+        let y_scale = if self.y.ty.is_duration() { 1000.0 } else { 1.0 };
+
+        // group the values for each input by the x bin
+        let x_bin = x_bounds.bin(self.x_width as usize);
+        let mut bins: BTreeMap<(usize, usize), (f64, Vec<f64>)> = BTreeMap::new();
+        for (input, x, y) in values {
+            let entry = bins.entry((input, x_bin.bin(x))).or_default();
+            entry.0 += x;
+            entry.1.push(y);
+        }
+
+        let mut y_bounds = Bounds::default();
+        let summary: Vec<_> = bins
+            .into_iter()
+            .map(|((input, _bin), (x_sum, mut ys))| {
+                ys.sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+                let count = ys.len();
+                let mean = ys.iter().sum::<f64>() / count as f64;
+                let p10 = ys[count / 10];
+                let p90 = ys[count * 9 / 10];
+                y_bounds.push(p10);
+                y_bounds.push(p90);
+
+                json!({
+                    "label": inputs[input].0,
+                    "x": x_sum / count as f64 * x_scale,
+                    "mean": mean * y_scale,
+                    "p10": p10 * y_scale,
+                    "p90": p90 * y_scale,
+                    "count": count,
+                })
+            })
+            .collect();
+
+        let labels = [&self.baseline_label, &self.candidate_label];
+
+        let title = self
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("{} vs {}", self.x, self.y));
+
+        let total_width = 1200;
+        let size = 600;
+        let series_offset = size + 80;
+        let series_height = 300;
+
+        let format_type = |query: &Query| {
+            if query.ty.is_duration() {
+                "time"
+            } else {
+                "number"
+            }
+        };
+
+        let vega = json!({
+            "$schema": "https://vega.github.io/schema/vega/v5.json",
+            "width": total_width,
+            "height": series_offset + series_height,
+            "padding": 5,
+            "background": "white",
+
+            "title": {
+                "text": title,
+                "anchor": "middle",
+                "fontSize": 16,
+                "frame": "group",
+                "offset": 4
+            },
+
+            "signals": [
+                {
+                    "name": "sig$sim",
+                    "value": 0,
+                    "bind": {
+                        "input": "range",
+                        "name": "simulation ",
+                        "min": 0,
+                        "max": series_len.saturating_sub(1),
+                        "step": 1
+                    }
+                }
+            ],
+
+            "data": [
+                {
+                    "name": "data$summary",
+                    "values": summary,
+                },
+                {
+                    "name": "data$series",
+                    "values": series,
+                    "transform": [
+                        { "type": "filter", "expr": "datum.sim == sig$sim" }
+                    ],
+                },
+            ],
+
+            "scales": [
+                {
+                    "name": "scale$x",
+                    "type": "linear",
+                    "domain": x_bounds.domain(self.x.ty),
+                    "range": [0, size],
+                    "nice": true,
+                    "zero": false,
+                },
+                {
+                    "name": "scale$y",
+                    "type": "linear",
+                    "domain": y_bounds.domain(self.y.ty),
+                    "range": [size, 0],
+                    "nice": true,
+                    "zero": false,
+                },
+                {
+                    "name": "scale$label",
+                    "type": "ordinal",
+                    "domain": labels,
+                    "range": { "scheme": "category10" },
+                },
+                {
+                    "name": "scale$panel",
+                    "type": "band",
+                    "domain": labels,
+                    "range": [0, total_width],
+                    "paddingInner": 0.1,
+                },
+                {
+                    "name": "scale$time",
+                    "type": "linear",
+                    "domain": { "data": "data$series", "field": "time" },
+                    "range": [0, { "signal": "bandwidth('scale$panel')" }],
+                },
+                {
+                    "name": "scale$throughput",
+                    "type": "linear",
+                    "domain": { "data": "data$series", "field": "throughput" },
+                    "range": [series_height, 0],
+                    "nice": true,
+                },
+                {
+                    "name": "scale$conn",
+                    "type": "ordinal",
+                    "domain": { "data": "data$series", "field": "conn" },
+                    "range": { "scheme": "category20" },
+                },
+            ],
+
+            "legends": [
+                {
+                    "stroke": "scale$label",
+                    "fill": "scale$label",
+                    "orient": "none",
+                    "legendX": size + 40,
+                    "legendY": 0,
+                }
+            ],
+
+            "axes": [
+                {
+                    "orient": "bottom",
+                    "scale": "scale$x",
+                    "title": self.x.to_string(),
+                    "format": self.x.ty.format(x_bounds.domain(self.x.ty)),
+                    "formatType": format_type(&self.x),
+                },
+                {
+                    "orient": "left",
+                    "scale": "scale$y",
+                    "title": format!("{} (mean, p10-p90)", self.y),
+                    "format": self.y.ty.format(y_bounds.domain(self.y.ty)),
+                    "formatType": format_type(&self.y),
+                },
+            ],
+
+            "marks": [
+                {
+                    "type": "group",
+                    "from": {
+                        "facet": {
+                            "name": "facet$summary",
+                            "data": "data$summary",
+                            "groupby": "label",
+                        }
+                    },
+                    "marks": [
+                        {
+                            "type": "area",
+                            "from": { "data": "facet$summary" },
+                            "encode": {
+                                "enter": {
+                                    "x": { "scale": "scale$x", "field": "x" },
+                                    "y": { "scale": "scale$y", "field": "p10" },
+                                    "y2": { "scale": "scale$y", "field": "p90" },
+                                    "fill": { "scale": "scale$label", "field": "label" },
+                                    "fillOpacity": { "value": 0.2 },
+                                }
+                            }
+                        },
+                        {
+                            "type": "line",
+                            "from": { "data": "facet$summary" },
+                            "encode": {
+                                "enter": {
+                                    "x": { "scale": "scale$x", "field": "x" },
+                                    "y": { "scale": "scale$y", "field": "mean" },
+                                    "stroke": { "scale": "scale$label", "field": "label" },
+                                    "strokeWidth": { "value": 2 },
+                                }
+                            }
+                        },
+                        {
+                            "type": "symbol",
+                            "from": { "data": "facet$summary" },
+                            "encode": {
+                                "enter": {
+                                    "x": { "scale": "scale$x", "field": "x" },
+                                    "y": { "scale": "scale$y", "field": "mean" },
+                                    "fill": { "scale": "scale$label", "field": "label" },
+                                    "size": { "value": 20 },
+                                    "tooltip": { "signal": "datum" },
+                                }
+                            }
+                        },
+                    ]
+                },
+                {
+                    "type": "group",
+                    "from": {
+                        "facet": {
+                            "name": "facet$series",
+                            "data": "data$series",
+                            "groupby": "label",
+                        }
+                    },
+                    "title": { "text": { "signal": "parent.label + ' throughput'" } },
+                    "encode": {
+                        "enter": {
+                            "y": { "value": series_offset },
+                            "height": { "value": series_height },
+                            "width": { "signal": "bandwidth('scale$panel')" },
+                        },
+                        "update": {
+                            "x": { "scale": "scale$panel", "field": "label" },
+                        }
+                    },
+                    "axes": [
+                        {
+                            "orient": "bottom",
+                            "scale": "scale$time",
+                            "title": "time (ms)",
+                        },
+                        {
+                            "orient": "left",
+                            "scale": "scale$throughput",
+                            "title": "bytes/s",
+                            "format": "~s",
+                        },
+                    ],
+                    "marks": [
+                        {
+                            "type": "group",
+                            "from": {
+                                "facet": {
+                                    "name": "facet$conn",
+                                    "data": "facet$series",
+                                    "groupby": "conn",
+                                }
+                            },
+                            "marks": [
+                                {
+                                    "type": "line",
+                                    "from": { "data": "facet$conn" },
+                                    "encode": {
+                                        "update": {
+                                            "x": { "scale": "scale$time", "field": "time" },
+                                            "y": { "scale": "scale$throughput", "field": "throughput" },
+                                            "stroke": { "scale": "scale$conn", "field": "conn" },
+                                            "tooltip": { "signal": "datum" },
+                                        }
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                },
+            ],
+        });
+
+        println!("{}", vega);
+
+        Ok(())
+    }
+}
+
+/// Reads each simulation in the database along with its connections
+fn load(path: &Path) -> Result<Vec<(Parameters, Vec<Connection>)>> {
+    let reader = fs::File::open(path)?;
+    let reader = io::BufReader::new(reader);
+
+    let mut acc: HashMap<u64, Vec<Connection>> = HashMap::new();
+    let mut sims = vec![];
+
+    for stat in Stats::reader(reader) {
+        match stat? {
+            Stats::Setup(_) => {
+                // unused
+            }
+            Stats::Parameters(p) => {
+                if let Some(connections) = acc.remove(&p.seed) {
+                    sims.push((p, connections));
+                }
+            }
+            Stats::Connection(c) => {
+                acc.entry(c.seed).or_default().push(c);
+            }
+        }
+    }
+
+    Ok(sims)
+}
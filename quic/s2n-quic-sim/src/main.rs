@@ -7,6 +7,7 @@ use structopt::StructOpt;
 pub type Result<T = (), E = Error> = core::result::Result<T, E>;
 
 mod batch;
+mod compare;
 mod query;
 mod report;
 mod run;
@@ -18,6 +19,7 @@ enum Args {
     Run(Box<run::Run>),
     Report(report::Report),
     Batch(batch::Batch),
+    Compare(compare::Compare),
 }
 
 fn main() -> Result {
@@ -37,6 +39,7 @@ fn main() -> Result {
         Args::Run(args) => args.run(),
         Args::Report(args) => args.run(),
         Args::Batch(args) => args.run(),
+        Args::Compare(args) => args.run(),
     }
 }
 
@@ -582,7 +582,7 @@ impl Report {
 }
 
 #[derive(Clone, Copy, Debug)]
-struct Bounds {
+pub struct Bounds {
     min: f64,
     max: f64,
 }
@@ -619,7 +619,7 @@ impl Bounds {
 }
 
 #[derive(Clone, Copy, Debug)]
-struct Bin {
+pub struct Bin {
     min: f64,
     step: f64,
 }
@@ -8,11 +8,14 @@ use s2n_quic::provider::io::testing::{test_seed, Model};
 use structopt::StructOpt;
 
 mod config;
-pub use config::Config;
+pub use config::{CongestionController, Config, Rebind, Tls};
 
 mod endpoint;
 mod events;
 
+mod profile;
+use profile::Profile;
+
 mod range;
 use range::CliRange;
 
@@ -24,6 +27,13 @@ pub struct Run {
     #[structopt(long)]
     seed: Vec<u64>,
 
+    /// Derives the seed of each iteration from this value instead of picking it at random
+    ///
+    /// Runs with the same base seed simulate the same networks, which allows the results of
+    /// different configurations to be compared head to head.
+    #[structopt(long)]
+    base_seed: Option<u64>,
+
     #[structopt(long)]
     progress: bool,
 }
@@ -50,10 +60,16 @@ impl Run {
                 let client_len = self.clients.gen();
 
                 let events = self.gen_network(seed, server_len, client_len, &network);
+                self.profile.spawn(&network);
 
                 let mut servers = vec![];
                 for _ in 0..server_len {
-                    servers.push(endpoint::server(handle, events.clone())?);
+                    servers.push(endpoint::server(
+                        handle,
+                        events.clone(),
+                        self.tls,
+                        self.congestion_controller,
+                    )?);
                 }
 
                 for _ in 0..client_len {
@@ -64,6 +80,8 @@ impl Run {
                     endpoint::client(
                         handle,
                         events.clone(),
+                        self.tls,
+                        self.congestion_controller,
                         &servers,
                         count,
                         delay,
@@ -105,9 +123,12 @@ impl Run {
                 .progress_with(pb)
                 .map(|v| if events::is_open() { Some(v) } else { None })
                 .while_some()
-                .for_each(|_| {
+                .for_each(|iteration| {
                     use ::rand::prelude::*;
-                    let seed = thread_rng().gen();
+                    let seed = match self.base_seed {
+                        Some(base_seed) => base_seed.wrapping_add(iteration),
+                        None => thread_rng().gen(),
+                    };
                     test(seed);
                 });
         } else {
@@ -151,6 +172,10 @@ impl Run {
         param!(inflight_delay, set_inflight_delay, gen_duration);
         param!(retransmit_rate, set_retransmit_rate, gen * 100.0);
         param!(max_udp_payload, set_max_udp_payload, gen as _);
+        param!(rebind_rate, set_rebind_rate, gen * 100.0);
+
+        model.set_rebind_kind(self.rebind.into());
+        model.set_queue_discipline(self.queue.into());
 
         macro_rules! zero_param {
             ($name:ident, $set:ident) => {
@@ -167,6 +192,8 @@ impl Run {
         zero_param!(transmit_rate, set_transmit_rate);
         zero_param!(max_inflight, set_max_inflight);
         zero_param!(inflight_delay_threshold, set_inflight_delay_threshold);
+        zero_param!(bandwidth, set_bandwidth);
+        zero_param!(queue_capacity, set_queue_capacity);
 
         events.into()
     }
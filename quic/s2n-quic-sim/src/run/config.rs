@@ -1,8 +1,10 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::CliRange;
+use super::{CliRange, Profile};
+use core::{fmt, str::FromStr};
 use humantime::Duration;
+use s2n_quic::provider::io::testing as io;
 use serde::Deserialize;
 use structopt::StructOpt;
 
@@ -91,6 +93,30 @@ config!(
         #[default = "0"]
         inflight_delay_threshold: CliRange<u64>,
 
+        #[name = "bandwidth"]
+        #[default = "0"]
+        bandwidth: CliRange<u64>,
+
+        #[name = "queue_capacity"]
+        #[default = "0"]
+        queue_capacity: CliRange<u64>,
+
+        #[name = "queue"]
+        #[default = "droptail"]
+        queue: Queue,
+
+        #[name = "rebind_rate"]
+        #[default = "0.0"]
+        rebind_rate: CliRange<f64>,
+
+        #[name = "rebind"]
+        #[default = "port"]
+        rebind: Rebind,
+
+        #[name = "profile"]
+        #[default = ""]
+        profile: Profile,
+
         #[name = "clients"]
         #[default = "1"]
         clients: CliRange<u32>,
@@ -115,8 +141,158 @@ config!(
         #[default = "4096"]
         stream_data: CliRange<u64>,
 
+        #[name = "tls"]
+        #[default = "default"]
+        tls: Tls,
+
+        #[name = "congestion_controller"]
+        #[default = "cubic"]
+        congestion_controller: CongestionController,
+
         #[name = "iterations"]
         #[default = "10000"]
         iterations: u64,
     }
 );
+
+/// The TLS provider used by the simulated endpoints
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tls {
+    /// The default TLS provider with the testing certificates
+    Default,
+    /// The null TLS provider, which removes the cost of cryptography from the simulation
+    Null,
+}
+
+impl FromStr for Tls {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(Self::Default),
+            "null" => Ok(Self::Null),
+            _ => Err(format!("invalid tls provider: {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Tls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => "default".fmt(f),
+            Self::Null => "null".fmt(f),
+        }
+    }
+}
+
+/// The congestion controller used by the simulated endpoints
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CongestionController {
+    Cubic,
+    Bbr,
+}
+
+impl FromStr for CongestionController {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cubic" => Ok(Self::Cubic),
+            "bbr" => Ok(Self::Bbr),
+            _ => Err(format!("invalid congestion controller: {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for CongestionController {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cubic => "cubic".fmt(f),
+            Self::Bbr => "bbr".fmt(f),
+        }
+    }
+}
+
+/// The discipline of the bottleneck queue
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Queue {
+    /// Drops packets which would exceed the queue capacity
+    Droptail,
+    /// Drops packets once the queueing delay stays above 5ms for 100ms
+    Codel,
+}
+
+impl FromStr for Queue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "droptail" => Ok(Self::Droptail),
+            "codel" => Ok(Self::Codel),
+            _ => Err(format!("invalid queue: {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Queue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Droptail => "droptail".fmt(f),
+            Self::Codel => "codel".fmt(f),
+        }
+    }
+}
+
+impl From<Queue> for io::QueueDiscipline {
+    fn from(value: Queue) -> Self {
+        match value {
+            Queue::Droptail => Self::DropTail,
+            Queue::Codel => Self::CODEL,
+        }
+    }
+}
+
+/// The part of a client's address that is changed when a NAT rebinds it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rebind {
+    Port,
+    Address,
+    Both,
+}
+
+impl FromStr for Rebind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "port" => Ok(Self::Port),
+            "address" => Ok(Self::Address),
+            "both" => Ok(Self::Both),
+            _ => Err(format!("invalid rebind: {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Rebind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Port => "port".fmt(f),
+            Self::Address => "address".fmt(f),
+            Self::Both => "both".fmt(f),
+        }
+    }
+}
+
+impl From<Rebind> for io::Rebind {
+    fn from(value: Rebind) -> Self {
+        match value {
+            Rebind::Port => Self::Port,
+            Rebind::Address => Self::Address,
+            Rebind::Both => Self::Both,
+        }
+    }
+}
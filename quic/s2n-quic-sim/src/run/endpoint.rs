@@ -1,24 +1,40 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{events, CliRange};
+use super::{events, CliRange, CongestionController, Tls};
 use s2n_quic::{
-    client::Connect,
+    client::{self, ClientProviders, Connect},
     provider::{
+        congestion_controller::{bbr, cubic},
         event::tracing::Subscriber as Tracing,
         io::testing::{primary, rand, spawn, time, Handle, Result},
+        tls::null,
     },
+    server::{self, ServerProviders},
     Client, Server,
 };
 use s2n_quic_core::{crypto::tls::testing::certificates, stream::testing::Data};
 use std::net::SocketAddr;
 
-pub fn server(handle: &Handle, events: events::Events) -> Result<SocketAddr> {
-    let mut server = Server::builder()
-        .with_io(handle.builder().build().unwrap())?
-        .with_tls((certificates::CERT_PEM, certificates::KEY_PEM))?
-        .with_event((events, Tracing::default()))?
-        .start()?;
+pub fn server(
+    handle: &Handle,
+    events: events::Events,
+    tls: Tls,
+    congestion_controller: CongestionController,
+) -> Result<SocketAddr> {
+    let server = Server::builder().with_io(handle.builder().build().unwrap())?;
+    let mut server = match congestion_controller {
+        CongestionController::Cubic => start_server(
+            server.with_congestion_controller(cubic::Provider::default())?,
+            events,
+            tls,
+        )?,
+        CongestionController::Bbr => start_server(
+            server.with_congestion_controller(bbr::Provider::default())?,
+            events,
+            tls,
+        )?,
+    };
     let server_addr = server.local_addr()?;
 
     // accept connections and echo back
@@ -39,20 +55,50 @@ pub fn server(handle: &Handle, events: events::Events) -> Result<SocketAddr> {
     Ok(server_addr)
 }
 
+fn start_server<P: ServerProviders>(
+    server: server::Builder<P>,
+    events: events::Events,
+    tls: Tls,
+) -> Result<Server> {
+    let events = (events, Tracing::default());
+    let server = match tls {
+        Tls::Default => server
+            .with_tls((certificates::CERT_PEM, certificates::KEY_PEM))?
+            .with_event(events)?
+            .start()?,
+        Tls::Null => server
+            .with_tls(null::Endpoint::default())?
+            .with_event(events)?
+            .start()?,
+    };
+    Ok(server)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn client(
     handle: &Handle,
     events: events::Events,
+    tls: Tls,
+    congestion_controller: CongestionController,
     servers: &[SocketAddr],
     count: usize,
     delay: CliRange<humantime::Duration>,
     streams: CliRange<u32>,
     stream_data: CliRange<u64>,
 ) -> Result {
-    let client = Client::builder()
-        .with_io(handle.builder().build().unwrap())?
-        .with_tls(certificates::CERT_PEM)?
-        .with_event((events, Tracing::default()))?
-        .start()?;
+    let client = Client::builder().with_io(handle.builder().build().unwrap())?;
+    let client = match congestion_controller {
+        CongestionController::Cubic => start_client(
+            client.with_congestion_controller(cubic::Provider::default())?,
+            events,
+            tls,
+        )?,
+        CongestionController::Bbr => start_client(
+            client.with_congestion_controller(bbr::Provider::default())?,
+            events,
+            tls,
+        )?,
+    };
 
     for _ in 0..count {
         let delay = delay.gen_duration();
@@ -100,3 +146,22 @@ pub fn client(
 
     Ok(())
 }
+
+fn start_client<P: ClientProviders>(
+    client: client::Builder<P>,
+    events: events::Events,
+    tls: Tls,
+) -> Result<Client> {
+    let events = (events, Tracing::default());
+    let client = match tls {
+        Tls::Default => client
+            .with_tls(certificates::CERT_PEM)?
+            .with_event(events)?
+            .start()?,
+        Tls::Null => client
+            .with_tls(null::Endpoint::default())?
+            .with_event(events)?
+            .start()?,
+    };
+    Ok(client)
+}
@@ -1,7 +1,7 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::stats::{Connection, Parameters};
+use crate::stats::{Connection, Parameters, Sample};
 use core::time::Duration;
 use once_cell::sync::Lazy;
 use s2n_quic::{
@@ -18,6 +18,14 @@ use std::sync::{
 
 static IDS: Lazy<Arc<AtomicU64>> = Lazy::new(Default::default);
 
+/// The minimum amount of time between recovery samples for each connection
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The maximum number of recovery samples recorded for each connection
+///
+/// This keeps the encoded connection under the maximum record length.
+const MAX_SAMPLES: usize = 1000;
+
 static IS_OPEN: AtomicBool = AtomicBool::new(true);
 
 pub fn is_open() -> bool {
@@ -168,6 +176,42 @@ impl event::Subscriber for Events {
                 .into(),
         );
         context.smoothed_rtt = Some(event.smoothed_rtt.into());
+
+        let now = now();
+        let is_due = context.samples.last().map_or(true, |prev| {
+            now.saturating_sub(prev.time.unwrap_or_default().as_duration()) >= SAMPLE_INTERVAL
+        });
+
+        if is_due && context.samples.len() < MAX_SAMPLES {
+            let tx_bytes = context.tx.unwrap_or_default().stream_progress;
+            context.samples.push(Sample {
+                time: Some(now.into()),
+                tx_bytes,
+                congestion_window: event.congestion_window as _,
+                bytes_in_flight: event.bytes_in_flight as _,
+                smoothed_rtt: Some(event.smoothed_rtt.into()),
+            });
+        }
+    }
+
+    #[inline]
+    fn on_active_path_updated(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &event::ConnectionMeta,
+        _event: &event::events::ActivePathUpdated,
+    ) {
+        context.migrations += 1;
+    }
+
+    #[inline]
+    fn on_connection_migration_denied(
+        &mut self,
+        context: &mut Self::ConnectionContext,
+        _meta: &event::ConnectionMeta,
+        _event: &event::events::ConnectionMigrationDenied,
+    ) {
+        context.migration_denied += 1;
     }
 
     #[inline]
@@ -0,0 +1,155 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use core::{fmt, str::FromStr, time::Duration};
+use s2n_quic::provider::io::testing::{self as io, Change, GilbertElliott};
+use serde::Deserialize;
+
+/// Changes to the network conditions over the course of each simulation
+///
+/// Profiles are written as a list of steps separated by `;`. Each step is a time, relative to the
+/// start of the simulation, followed by a change:
+///
+/// ```text
+/// 0s burst_loss=0.01/0.3/0/1; 2s bandwidth=125000; 5s outage=1s; 8s max_udp_payload=1200
+/// ```
+///
+/// The supported changes are:
+///
+/// * `bandwidth=<bytes per second>` - `0` removes the limit
+/// * `queue_capacity=<bytes>` - `0` removes the limit
+/// * `outage=<duration>` - drops all packets for the duration
+/// * `max_udp_payload=<bytes>`
+/// * `delay=<duration>`
+/// * `drop_rate=<odds>`
+/// * `burst_loss=<p>/<r>/<good loss>/<bad loss>` - see [`GilbertElliott`]
+/// * `ce_threshold=<packets>` - `0` disables CE marking
+/// * `rebind=<port|address|both>` - rebinds all of the clients to new addresses
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    value: String,
+    steps: Vec<Step>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Step {
+    Change(Duration, Change),
+    Outage(Duration, Duration),
+}
+
+impl Profile {
+    /// Spawns a task which applies the profile to the network model
+    pub fn spawn(&self, model: &io::Model) {
+        let mut profile = io::Profile::default();
+
+        for step in self.steps.iter().copied() {
+            profile = match step {
+                Step::Change(at, change) => profile.with(at, change),
+                Step::Outage(at, duration) => profile.with_outage(at, duration),
+            };
+        }
+
+        profile.spawn(model.clone());
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = vec![];
+
+        for step in s.split(';').map(str::trim).filter(|step| !step.is_empty()) {
+            steps.push(parse_step(step).map_err(|err| format!("{:?}: {}", step, err))?);
+        }
+
+        Ok(Self {
+            value: s.to_owned(),
+            steps,
+        })
+    }
+}
+
+fn parse_step(step: &str) -> Result<Step, String> {
+    let (at, change) = step
+        .split_once(char::is_whitespace)
+        .ok_or("expected a time and a change")?;
+    let at = parse_duration(at)?;
+
+    let (name, value) = change
+        .trim()
+        .split_once('=')
+        .ok_or("expected the change to be in the form `name=value`")?;
+
+    fn parse<T: FromStr>(value: &str) -> Result<T, String>
+    where
+        T::Err: fmt::Display,
+    {
+        value.trim().parse().map_err(|err: T::Err| err.to_string())
+    }
+
+    let change = match name.trim() {
+        "bandwidth" => match parse(value)? {
+            0 => Change::Bandwidth(u64::MAX),
+            value => Change::Bandwidth(value),
+        },
+        "queue_capacity" => match parse(value)? {
+            0 => Change::QueueCapacity(u64::MAX),
+            value => Change::QueueCapacity(value),
+        },
+        "outage" => return Ok(Step::Outage(at, parse_duration(value)?)),
+        "max_udp_payload" => Change::MaxUdpPayload(parse(value)?),
+        "delay" => Change::Delay(parse_duration(value)?),
+        "drop_rate" => Change::DropRate(parse(value)?),
+        "burst_loss" => {
+            let values = value
+                .split('/')
+                .map(parse::<f64>)
+                .collect::<Result<Vec<f64>, _>>()?;
+            if let [p, r, good_loss, bad_loss] = values[..] {
+                Change::BurstLoss(GilbertElliott {
+                    p,
+                    r,
+                    good_loss,
+                    bad_loss,
+                })
+            } else {
+                return Err(
+                    "expected burst_loss to be in the form `p/r/good_loss/bad_loss`".into(),
+                );
+            }
+        }
+        "ce_threshold" => match parse(value)? {
+            0 => Change::CeThreshold(u64::MAX),
+            value => Change::CeThreshold(value),
+        },
+        "rebind" => {
+            let value: super::Rebind = value.trim().parse()?;
+            Change::RebindAll(value.into())
+        }
+        name => return Err(format!("invalid change: {:?}", name)),
+    };
+
+    Ok(Step::Change(at, change))
+}
+
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value: humantime::Duration = value.trim().parse().map_err(|err| format!("{}", err))?;
+    Ok(value.into())
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<'de> Deserialize<'de> for Profile {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
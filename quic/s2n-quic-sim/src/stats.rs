@@ -155,6 +155,12 @@ pub struct Parameters {
     pub inflight_delay: Option<Duration>,
     #[prost(uint64, tag = "15")]
     pub inflight_delay_threshold: u64,
+    #[prost(double, tag = "16")]
+    pub rebind_rate: f64,
+    #[prost(uint64, tag = "17")]
+    pub bandwidth: u64,
+    #[prost(uint64, tag = "18")]
+    pub queue_capacity: u64,
 }
 
 impl From<Parameters> for Stats {
@@ -169,7 +175,7 @@ impl Parameters {
     }
 }
 
-#[derive(Clone, Copy, Message)]
+#[derive(Clone, Message)]
 pub struct Connection {
     #[prost(uint64, optional, tag = "1")]
     pub client_id: Option<u64>,
@@ -211,6 +217,12 @@ pub struct Connection {
     pub min_rtt: Option<Duration>,
     #[prost(message, tag = "20")]
     pub smoothed_rtt: Option<Duration>,
+    #[prost(uint64, tag = "21")]
+    pub migrations: u64,
+    #[prost(uint64, tag = "22")]
+    pub migration_denied: u64,
+    #[prost(message, repeated, tag = "23")]
+    pub samples: Vec<Sample>,
 }
 
 impl From<Connection> for Stats {
@@ -246,6 +258,138 @@ impl Connection {
             .as_duration()
             .checked_sub(self.start_time.unwrap_or_default().as_duration())
     }
+
+    /// Returns the sending throughput, in bytes per second, between each of the samples
+    ///
+    /// Each item is the time of the sample, relative to the start of the connection, and the
+    /// throughput since the previous sample.
+    pub fn tx_throughput(&self) -> impl Iterator<Item = (core::time::Duration, f64)> + '_ {
+        let start = self.start_time.unwrap_or_default().as_duration();
+        self.samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .filter_map(move |(prev, sample)| {
+                let prev_time = prev.time?.as_duration();
+                let time = sample.time?.as_duration();
+                let elapsed = time.checked_sub(prev_time)?.as_secs_f64();
+                if elapsed == 0.0 {
+                    return None;
+                }
+                let bytes = sample.tx_bytes.saturating_sub(prev.tx_bytes) as f64;
+                Some((time.checked_sub(start)?, bytes / elapsed))
+            })
+    }
+
+    /// Returns the number of bytes sent by `time`, interpolated between the samples
+    ///
+    /// Returns `None` if `time` is outside of the sampled range of the connection.
+    fn tx_bytes_at(&self, time: core::time::Duration) -> Option<f64> {
+        let sample_time = |sample: &Sample| sample.time.unwrap_or_default().as_duration();
+
+        let idx = self
+            .samples
+            .partition_point(|sample| sample_time(sample) < time);
+        let next = self.samples.get(idx)?;
+        let next_time = sample_time(next);
+
+        if next_time == time {
+            return Some(next.tx_bytes as f64);
+        }
+
+        let prev = self.samples.get(idx.checked_sub(1)?)?;
+        let prev_time = sample_time(prev);
+        let progress = (time - prev_time).as_secs_f64() / (next_time - prev_time).as_secs_f64();
+        let bytes = next.tx_bytes.saturating_sub(prev.tx_bytes) as f64;
+        Some(prev.tx_bytes as f64 + bytes * progress)
+    }
+}
+
+/// A snapshot of the recovery state of a connection
+#[derive(Clone, Copy, Message, PartialEq)]
+pub struct Sample {
+    #[prost(message, tag = "1")]
+    pub time: Option<Duration>,
+    #[prost(uint64, tag = "2")]
+    pub tx_bytes: u64,
+    #[prost(uint64, tag = "3")]
+    pub congestion_window: u64,
+    #[prost(uint64, tag = "4")]
+    pub bytes_in_flight: u64,
+    #[prost(message, tag = "5")]
+    pub smoothed_rtt: Option<Duration>,
+}
+
+/// Computes Jain's fairness index for the values
+///
+/// The index ranges from `1 / n`, where a single value receives everything, to `1.0`, where all
+/// of the values are equal.
+pub fn jain_fairness<I: IntoIterator<Item = f64>>(values: I) -> Option<f64> {
+    let mut count = 0.0;
+    let mut sum = 0.0;
+    let mut sum_squares = 0.0;
+
+    for value in values {
+        count += 1.0;
+        sum += value;
+        sum_squares += value * value;
+    }
+
+    if sum_squares == 0.0 {
+        return None;
+    }
+
+    Some(sum * sum / (count * sum_squares))
+}
+
+/// The width of the intervals in which the throughput of concurrent connections is compared
+const FAIRNESS_INTERVAL: core::time::Duration = core::time::Duration::from_millis(100);
+
+/// Computes Jain's fairness index of the client connections, averaged over each interval in
+/// which at least two of them are sending
+///
+/// Comparing the throughput within the same interval avoids reporting connections which never
+/// overlapped as unfair.
+fn interval_fairness(conns: &[Connection]) -> Option<f64> {
+    let clients: Vec<_> = conns
+        .iter()
+        .filter(|c| c.client_id.is_some() && c.samples.len() > 1)
+        .collect();
+
+    let sample_times = clients
+        .iter()
+        .flat_map(|c| c.samples.iter())
+        .filter_map(|sample| Some(sample.time?.as_duration()));
+    let start = sample_times.clone().min()?;
+    let end = sample_times.max()?;
+
+    let mut sum = 0.0;
+    let mut count = 0.0;
+    let mut interval_start = start;
+
+    while interval_start + FAIRNESS_INTERVAL <= end {
+        let interval_end = interval_start + FAIRNESS_INTERVAL;
+
+        // the intervals are all the same width so the sent bytes are proportional to throughput
+        let sent: Vec<f64> = clients
+            .iter()
+            .filter_map(|c| Some(c.tx_bytes_at(interval_end)? - c.tx_bytes_at(interval_start)?))
+            .collect();
+
+        if sent.len() > 1 {
+            if let Some(index) = jain_fairness(sent) {
+                sum += index;
+                count += 1.0;
+            }
+        }
+
+        interval_start = interval_end;
+    }
+
+    if count == 0.0 {
+        return None;
+    }
+
+    Some(sum / count)
 }
 
 #[derive(Clone, Copy, Message, PartialEq)]
@@ -364,6 +508,7 @@ pub enum Type {
     Percent,
     Duration,
     Throughput,
+    Ratio,
     Bool,
 }
 
@@ -375,6 +520,7 @@ impl Type {
             Self::Duration if max > 2000.0 => "%M:%S",
             Self::Duration => "%Qms",
             Self::Throughput => "~s",
+            Self::Ratio => ".3~f",
             Self::Bool => "c",
         }
     }
@@ -414,6 +560,7 @@ impl Type {
             Self::Throughput => {
                 todo!()
             }
+            Self::Ratio => Ok(value.parse()?),
             Self::Bool => match value {
                 "true" | "TRUE" | "1" => Ok(1.0),
                 "false" | "FALSE" | "0" => Ok(0.0),
@@ -423,7 +570,7 @@ impl Type {
     }
 }
 
-use Type::{Bool as B, Duration as T, Integer as I, Percent as P, Throughput as Tpt};
+use Type::{Bool as B, Duration as T, Integer as I, Percent as P, Ratio as R, Throughput as Tpt};
 
 static QUERIES: &[(&str, Type, Q)] = &[
     ("conn.duration", T, |_params, conn, _conns| {
@@ -480,6 +627,17 @@ static QUERIES: &[(&str, Type, Q)] = &[
     ("conn.smoothed-rtt", T, |_params, conn, _conns| {
         Some(conn.smoothed_rtt?.as_duration().as_secs_f64())
     }),
+    ("conn.queueing-delay", T, |_params, conn, _conns| {
+        let min = conn.min_rtt?.as_duration();
+        let smoothed = conn.smoothed_rtt?.as_duration();
+        Some(smoothed.saturating_sub(min).as_secs_f64())
+    }),
+    ("conn.migrations", I, |_params, conn, _conns| {
+        Some(conn.migrations as _)
+    }),
+    ("conn.migration-denied", I, |_params, conn, _conns| {
+        Some(conn.migration_denied as _)
+    }),
     ("conn.rtt-spread", T, |_params, conn, _conns| {
         let min = conn.min_rtt?.as_duration();
         let max = conn.max_rtt?.as_duration();
@@ -510,6 +668,19 @@ static QUERIES: &[(&str, Type, Q)] = &[
     ("conn.tx.stream-throughput", Tpt, |_params, conn, _conns| {
         conn.tx?.stream_throughput()
     }),
+    ("conn.tx.fair-share", R, |_params, conn, conns| {
+        // compare the connection to the other connections sending in the same direction
+        let throughput = conn.tx?.stream_throughput()?;
+        let (count, sum) = conns
+            .iter()
+            .filter(|c| c.client_id.is_some() == conn.client_id.is_some() && c.is_success())
+            .filter_map(|c| c.tx?.stream_throughput())
+            .fold((0.0, 0.0), |(count, sum), value| (count + 1.0, sum + value));
+        if sum == 0.0 {
+            return None;
+        }
+        Some(throughput * count / sum)
+    }),
     ("conn.rx.packets", I, |_params, conn, _conns| {
         Some(conn.rx.unwrap_or_default().packets() as _)
     }),
@@ -562,6 +733,14 @@ static QUERIES: &[(&str, Type, Q)] = &[
             None
         }
     }),
+    ("sim.fairness", R, |_params, conn, conns| {
+        // only return the value for the first connection
+        if conn.id() == conns[0].id() {
+            interval_fairness(conns)
+        } else {
+            None
+        }
+    }),
     ("net.drop-rate", P, |params, _conn, _conns| {
         Some(params.drop_rate)
     }),
@@ -595,6 +774,15 @@ static QUERIES: &[(&str, Type, Q)] = &[
     ("net.retransmit-rate", P, |params, _conn, _conns| {
         Some(params.retransmit_rate)
     }),
+    ("net.rebind-rate", P, |params, _conn, _conns| {
+        Some(params.rebind_rate)
+    }),
+    ("net.bandwidth", I, |params, _conn, _conns| {
+        Some(params.bandwidth as f64)
+    }),
+    ("net.queue-capacity", I, |params, _conn, _conns| {
+        Some(params.queue_capacity as f64)
+    }),
     ("net.max-udp-payload", I, |params, _conn, _conns| {
         Some(params.max_udp_payload as f64)
     }),
@@ -787,3 +975,73 @@ impl<'de> serde::Deserialize<'de> for Filter {
         deserializer.deserialize_str(StrVisitor::<Self>(Default::default()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("missing fairness index");
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    /// Returns a client connection which sends `rate` bytes every 10ms for a second
+    fn client(id: u64, rate: u64) -> Connection {
+        let samples = (0..=100)
+            .map(|i| Sample {
+                time: Some(core::time::Duration::from_millis(i * 10).into()),
+                tx_bytes: rate * i,
+                ..Default::default()
+            })
+            .collect();
+
+        Connection {
+            client_id: Some(id),
+            samples,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn jain_fairness_test() {
+        assert_close(jain_fairness([100.0; 4]), 1.0);
+
+        for n in 1..10 {
+            let values = (0..n).map(|i| if i == 0 { 100.0 } else { 0.0 });
+            assert_close(jain_fairness(values), 1.0 / n as f64);
+        }
+
+        assert_eq!(jain_fairness([]), None);
+        assert_eq!(jain_fairness([0.0; 3]), None);
+    }
+
+    #[test]
+    fn interval_fairness_test() {
+        let conns: Vec<_> = (0..4).map(|id| client(id, 1000)).collect();
+        assert_close(interval_fairness(&conns), 1.0);
+
+        let conns: Vec<_> = (0..4)
+            .map(|id| client(id, if id == 0 { 1000 } else { 0 }))
+            .collect();
+        assert_close(interval_fairness(&conns), 1.0 / 4.0);
+
+        // connections which never overlap aren't compared
+        let mut late = client(1, 1000);
+        for sample in &mut late.samples {
+            let time = sample.time.unwrap().as_duration() + core::time::Duration::from_secs(5);
+            sample.time = Some(time.into());
+        }
+        assert_eq!(interval_fairness(&[client(0, 1000), late]), None);
+
+        // server connections are ignored
+        let mut server = client(1, 0);
+        server.client_id = None;
+        server.server_id = Some(1);
+        assert_eq!(interval_fairness(&[client(0, 1000), server]), None);
+    }
+}
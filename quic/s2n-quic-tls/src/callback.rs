@@ -2,7 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use bytes::{Bytes, BytesMut};
-use core::{ffi::c_void, marker::PhantomData};
+use core::{
+    ffi::c_void,
+    marker::PhantomData,
+    pin::Pin,
+    task::{self, Poll},
+};
 use s2n_quic_core::{
     application::ServerName,
     crypto::{tls, CryptoError, CryptoSuite},
@@ -14,7 +19,12 @@ use s2n_quic_crypto::{
     ring::{aead, hkdf},
     Prk, SecretPair, Suite,
 };
-use s2n_tls::{connection::Connection, error::Fallible, ffi::*};
+use s2n_tls::{
+    callbacks::ConnectionFuture,
+    connection::Connection,
+    error::{Error, Fallible},
+    ffi::*,
+};
 
 /// The preallocated size of the outgoing buffer
 ///
@@ -22,6 +32,76 @@ use s2n_tls::{connection::Connection, error::Fallible, ffi::*};
 /// s2n-tls sends small chunks
 const SEND_BUFFER_CAPACITY: usize = 2048;
 
+/// The application callbacks which started or completed since the session last polled the
+/// connection
+///
+/// The callbacks configured on the s2n-tls config are only passed the connection, so the queue
+/// is stored in its application context.
+#[derive(Debug, Default)]
+pub struct TlsCallbacks(Vec<(tls::TlsCallback, bool)>);
+
+impl TlsCallbacks {
+    /// Takes the callbacks which were reported by the connection, along with whether each of
+    /// them completed
+    pub fn take(connection: &mut Connection) -> Vec<(tls::TlsCallback, bool)> {
+        connection
+            .application_context_mut::<Self>()
+            .map(|callbacks| core::mem::take(&mut callbacks.0))
+            .unwrap_or_default()
+    }
+
+    fn push(connection: &mut Connection, callback: tls::TlsCallback, is_complete: bool) {
+        // the callbacks aren't reported if the application replaced the context
+        if let Some(callbacks) = connection.application_context_mut::<Self>() {
+            callbacks.0.push((callback, is_complete));
+        }
+    }
+}
+
+/// The result of an asynchronous s2n-tls callback
+pub type ConnectionFutureResult = Result<Option<Pin<Box<dyn ConnectionFuture>>>, Error>;
+
+/// Reports the progress of an application callback to the session
+///
+/// The callback is reported as completed once the future it returns, if any, resolves.
+pub fn report<F: FnOnce(&mut Connection) -> ConnectionFutureResult>(
+    connection: &mut Connection,
+    callback: tls::TlsCallback,
+    f: F,
+) -> ConnectionFutureResult {
+    TlsCallbacks::push(connection, callback, false);
+
+    match f(connection) {
+        Ok(Some(future)) => Ok(Some(Box::pin(Reported { callback, future }))),
+        result => {
+            TlsCallbacks::push(connection, callback, true);
+            result
+        }
+    }
+}
+
+/// The pending future of an application callback
+struct Reported {
+    callback: tls::TlsCallback,
+    future: Pin<Box<dyn ConnectionFuture>>,
+}
+
+impl ConnectionFuture for Reported {
+    fn poll(
+        mut self: Pin<&mut Self>,
+        connection: &mut Connection,
+        cx: &mut task::Context,
+    ) -> Poll<Result<(), Error>> {
+        let result = self.future.as_mut().poll(connection, cx);
+
+        if result.is_ready() {
+            TlsCallbacks::push(connection, self.callback, true);
+        }
+
+        result
+    }
+}
+
 /// Handles all callback contexts for each session
 pub struct Callback<'a, T, C> {
     pub context: &'a mut T,
@@ -89,12 +89,14 @@ macro_rules! cert_type {
             fn $method(self) -> Result<$name, Error> {
                 match self.extension() {
                     Some(ext) if ext == "der" => {
-                        let pem = std::fs::read(self).map_err(|_| Error::InvalidInput)?;
+                        let pem =
+                            std::fs::read(self).map_err(|err| Error::application(Box::new(err)))?;
                         pem.$method()
                     }
                     // assume it's in pem format
                     _ => {
-                        let pem = std::fs::read_to_string(self).map_err(|_| Error::InvalidInput)?;
+                        let pem = std::fs::read_to_string(self)
+                            .map_err(|err| Error::application(Box::new(err)))?;
                         pem.$method()
                     }
                 }
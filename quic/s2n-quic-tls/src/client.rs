@@ -3,7 +3,7 @@
 
 use crate::{
     certificate::{IntoCertificate, IntoPrivateKey},
-    keylog::KeyLogHandle,
+    keylog::{KeyLog, KeyLogHandle},
     params::Params,
     session::Session,
 };
@@ -14,7 +14,7 @@ use s2n_tls::{
     enums::ClientAuthType,
     error::Error,
 };
-use std::sync::Arc;
+use std::io::Write;
 
 pub struct Client {
     config: Config,
@@ -123,24 +123,30 @@ impl Builder {
         Ok(self)
     }
 
+    /// Writes the secrets of each connection to the file in the `SSLKEYLOGFILE` environment
+    /// variable
     pub fn with_key_logging(mut self) -> Result<Self, Error> {
-        use crate::keylog::KeyLog;
-
+        // key logging is disabled if it failed to create a file
         self.keylog = KeyLog::try_open();
+        self.set_key_log()?;
+        Ok(self)
+    }
 
+    /// Writes the secrets of each connection to `writer` in the NSS key log format
+    ///
+    /// Anyone with access to the secrets can decrypt the connections, so the output must be
+    /// protected accordingly.
+    pub fn with_key_log<W: 'static + Write + Send>(mut self, writer: W) -> Result<Self, Error> {
+        self.keylog = Some(KeyLog::new(writer));
+        self.set_key_log()?;
+        Ok(self)
+    }
+
+    fn set_key_log(&mut self) -> Result<(), Error> {
         unsafe {
             // Safety: the KeyLog is stored on `self` to ensure it outlives `config`
-            if let Some(keylog) = self.keylog.as_ref() {
-                self.config
-                    .set_key_log_callback(Some(KeyLog::callback), Arc::as_ptr(keylog) as *mut _)?;
-            } else {
-                // disable key logging if it failed to create a file
-                self.config
-                    .set_key_log_callback(None, core::ptr::null_mut())?;
-            }
+            KeyLog::register(&mut self.config, self.keylog.as_ref())
         }
-
-        Ok(self)
     }
 
     pub fn build(self) -> Result<Client, Error> {
@@ -2,16 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use libc::{c_int, c_void};
-use s2n_tls::ffi::*;
+use s2n_tls::{config, error::Error, ffi::*};
 use std::{
-    fs::{File, OpenOptions},
+    fs::OpenOptions,
     io::{BufWriter, Write},
     sync::{Arc, Mutex},
 };
 
 pub type KeyLogHandle = Arc<KeyLog>;
 
-pub struct KeyLog(Mutex<BufWriter<File>>);
+pub struct KeyLog(Mutex<Box<dyn Write + Send>>);
 
 impl KeyLog {
     pub fn try_open() -> Option<KeyLogHandle> {
@@ -22,10 +22,30 @@ impl KeyLog {
             .open(path)
             .ok()?;
         let file = BufWriter::new(file);
-        let file = Mutex::new(file);
-        let keylog = Self(file);
-        let keylog = Arc::new(keylog);
-        Some(keylog)
+        Some(Self::new(file))
+    }
+
+    pub fn new<W: 'static + Write + Send>(writer: W) -> KeyLogHandle {
+        let writer = Mutex::new(Box::new(writer) as Box<dyn Write + Send>);
+        Arc::new(Self(writer))
+    }
+
+    /// Sets the key log on the config, or disables key logging if it's `None`
+    ///
+    /// # Safety
+    ///
+    /// The key log must outlive the config
+    pub unsafe fn register(
+        config: &mut config::Builder,
+        keylog: Option<&KeyLogHandle>,
+    ) -> Result<(), Error> {
+        if let Some(keylog) = keylog {
+            config.set_key_log_callback(Some(Self::callback), Arc::as_ptr(keylog) as *mut _)?;
+        } else {
+            config.set_key_log_callback(None, core::ptr::null_mut())?;
+        }
+
+        Ok(())
     }
 
     pub unsafe extern "C" fn callback(
@@ -2,22 +2,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    callback::{self, ConnectionFutureResult},
     certificate::{IntoCertificate, IntoPrivateKey},
-    keylog::KeyLogHandle,
+    keylog::{KeyLog, KeyLogHandle},
     params::Params,
     session::Session,
 };
 use s2n_codec::EncoderValue;
 use s2n_quic_core::{application::ServerName, crypto::tls, endpoint};
-#[cfg(any(test, all(s2n_quic_unstable, feature = "unstable_client_hello")))]
-use s2n_tls::callbacks::ClientHelloCallback;
 use s2n_tls::{
-    callbacks::VerifyHostNameCallback,
+    callbacks::{ClientHelloCallback, VerifyHostNameCallback},
     config::{self, Config},
+    connection::Connection,
     enums::ClientAuthType,
     error::Error,
 };
-use std::sync::Arc;
+use std::io::Write;
+
+pub mod private_key;
+pub mod resolver;
+
+pub use private_key::{PrivateKeyHandler, PrivateKeyOperation};
+pub use resolver::SniResolver;
 
 pub struct Server {
     config: Config,
@@ -43,6 +49,12 @@ impl Default for Server {
 pub struct Builder {
     config: config::Builder,
     keylog: Option<KeyLogHandle>,
+    /// Set once the client hello callback is configured, since s2n-tls only supports one
+    has_client_hello_callback: bool,
+    /// Set once a certificate with a private key is loaded
+    has_certificate: bool,
+    /// Set once the private key handler is configured, since s2n-tls only supports one
+    has_private_key_handler: bool,
 }
 
 impl Default for Builder {
@@ -58,20 +70,52 @@ impl Default for Builder {
         Self {
             config,
             keylog: None,
+            has_client_hello_callback: false,
+            has_certificate: false,
+            has_private_key_handler: false,
         }
     }
 }
 
 impl Builder {
+    /// Sets the client hello handler
+    ///
+    /// Returns an error if a handler or a resolver was already configured.
     #[cfg(any(test, all(s2n_quic_unstable, feature = "unstable_client_hello")))]
     pub fn with_client_hello_handler<T: 'static + ClientHelloCallback>(
         mut self,
         handler: T,
     ) -> Result<Self, Error> {
-        self.config.set_client_hello_callback(handler)?;
+        self.set_client_hello_callback(handler)?;
+        Ok(self)
+    }
+
+    /// Selects the certificate for each connection based on the server name sent by the client
+    ///
+    /// The resolver is invoked from the client hello callback, so it can't be combined with
+    /// `with_client_hello_handler`. Returns an error if a handler or a resolver was already
+    /// configured.
+    pub fn with_sni_resolver(mut self, resolver: SniResolver) -> Result<Self, Error> {
+        self.set_client_hello_callback(resolver)?;
         Ok(self)
     }
 
+    fn set_client_hello_callback<T: 'static + ClientHelloCallback>(
+        &mut self,
+        callback: T,
+    ) -> Result<(), Error> {
+        if self.has_client_hello_callback {
+            return Err(Error::application(
+                "a client hello handler or resolver is already configured".into(),
+            ));
+        }
+
+        self.config
+            .set_client_hello_callback(CertificateSelection(callback))?;
+        self.has_client_hello_callback = true;
+        Ok(())
+    }
+
     pub fn with_application_protocols<P: IntoIterator<Item = I>, I: AsRef<[u8]>>(
         mut self,
         protocols: P,
@@ -80,11 +124,21 @@ impl Builder {
         Ok(self)
     }
 
+    /// Sets the certificate chain and private key presented to clients
+    ///
+    /// Use `with_private_key_handler` for keys which aren't available in-process. Returns an
+    /// error if a private key handler was already configured.
     pub fn with_certificate<C: IntoCertificate, PK: IntoPrivateKey>(
         mut self,
         certificate: C,
         private_key: PK,
     ) -> Result<Self, Error> {
+        if self.has_private_key_handler {
+            return Err(Error::application(
+                "certificates can't be combined with a private key handler".into(),
+            ));
+        }
+
         let certificate = certificate.into_certificate()?;
         let private_key = private_key.into_private_key()?;
         self.config.load_pem(
@@ -97,6 +151,30 @@ impl Builder {
                 .as_pem()
                 .expect("pem is currently the only certificate format supported"),
         )?;
+        self.has_certificate = true;
+        Ok(self)
+    }
+
+    /// Sets the certificate chain presented to clients and performs its private key operations
+    /// with `handler`
+    ///
+    /// The handshake is parked in `tls::Session::poll` until each operation completes, so
+    /// signing latency doesn't block the endpoint. s2n-tls passes every private key operation
+    /// on the configuration to the handler, so only one handler may be configured and it can't
+    /// be combined with `with_certificate`.
+    pub fn with_private_key_handler<C: IntoCertificate, H: PrivateKeyHandler>(
+        mut self,
+        certificate: C,
+        handler: H,
+    ) -> Result<Self, Error> {
+        if self.has_private_key_handler || self.has_certificate {
+            return Err(Error::application(
+                "a private key handler can't be combined with other certificates".into(),
+            ));
+        }
+
+        private_key::Handler::register(&mut self.config, certificate, handler)?;
+        self.has_private_key_handler = true;
         Ok(self)
     }
 
@@ -156,24 +234,30 @@ impl Builder {
         Ok(self)
     }
 
+    /// Writes the secrets of each connection to the file in the `SSLKEYLOGFILE` environment
+    /// variable
     pub fn with_key_logging(mut self) -> Result<Self, Error> {
-        use crate::keylog::KeyLog;
-
+        // key logging is disabled if it failed to create a file
         self.keylog = KeyLog::try_open();
+        self.set_key_log()?;
+        Ok(self)
+    }
+
+    /// Writes the secrets of each connection to `writer` in the NSS key log format
+    ///
+    /// Anyone with access to the secrets can decrypt the connections, so the output must be
+    /// protected accordingly.
+    pub fn with_key_log<W: 'static + Write + Send>(mut self, writer: W) -> Result<Self, Error> {
+        self.keylog = Some(KeyLog::new(writer));
+        self.set_key_log()?;
+        Ok(self)
+    }
 
+    fn set_key_log(&mut self) -> Result<(), Error> {
         unsafe {
             // Safety: the KeyLog is stored on `self` to ensure it outlives `config`
-            if let Some(keylog) = self.keylog.as_ref() {
-                self.config
-                    .set_key_log_callback(Some(KeyLog::callback), Arc::as_ptr(keylog) as *mut _)?;
-            } else {
-                // disable key logging if it failed to create a file
-                self.config
-                    .set_key_log_callback(None, core::ptr::null_mut())?;
-            }
+            KeyLog::register(&mut self.config, self.keylog.as_ref())
         }
-
-        Ok(self)
     }
 
     pub fn build(self) -> Result<Server, Error> {
@@ -185,6 +269,20 @@ impl Builder {
     }
 }
 
+/// Reports the time spent in the client hello callback, which selects the certificate, to the
+/// connection
+struct CertificateSelection<T>(T);
+
+impl<T: ClientHelloCallback> ClientHelloCallback for CertificateSelection<T> {
+    fn on_client_hello(&self, connection: &mut Connection) -> ConnectionFutureResult {
+        callback::report(
+            connection,
+            tls::TlsCallback::CertificateSelection,
+            |connection| self.0.on_client_hello(connection),
+        )
+    }
+}
+
 impl tls::Endpoint for Server {
     type Session = Session;
 
@@ -0,0 +1,175 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Performs private key operations outside of the handshake, e.g. with an external signer
+
+use crate::{
+    callback::{self, ConnectionFutureResult},
+    certificate::IntoCertificate,
+};
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use s2n_quic_core::crypto::tls::TlsCallback;
+use s2n_tls::{
+    callbacks::{self, ConnectionFuture, PrivateKeyCallback},
+    config,
+    connection::Connection,
+    error::Error,
+};
+use std::sync::Mutex;
+
+/// A private key operation which is in progress
+pub type PendingOperation =
+    Pin<Box<dyn Future<Output = Result<PrivateKeyOperation, Error>> + Send>>;
+
+/// Handles the private key operations for the certificate configured with
+/// `with_private_key_handler`
+///
+/// s2n-tls passes every private key operation on the server's configuration to the handler, so
+/// it can't be combined with certificates loaded with `with_certificate`. Configurations
+/// selected by a `SniResolver` perform their operations with their own private keys.
+pub trait PrivateKeyHandler: 'static + Send + Sync {
+    /// Starts a private key operation
+    ///
+    /// The handshake is parked until the returned future completes. The future is polled from
+    /// `tls::Session::poll` with the connection's waker, so it must arrange for the waker to be
+    /// notified when it can make progress. Once the output is set, the future should resolve
+    /// to the operation to resume the handshake.
+    fn handle_operation(&self, operation: PrivateKeyOperation) -> PendingOperation;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperationType {
+    /// The input is a digest which should be signed
+    Sign,
+    /// The input is data which should be decrypted
+    Decrypt,
+}
+
+/// A private key operation requested by s2n-tls
+pub struct PrivateKeyOperation {
+    operation: callbacks::PrivateKeyOperation,
+    kind: OperationType,
+    output: Option<Vec<u8>>,
+}
+
+impl fmt::Debug for PrivateKeyOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PrivateKeyOperation")
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+impl PrivateKeyOperation {
+    fn new(operation: callbacks::PrivateKeyOperation) -> Result<Self, Error> {
+        let kind = match operation.kind()? {
+            callbacks::OperationType::Sign(..) => OperationType::Sign,
+            callbacks::OperationType::Decrypt => OperationType::Decrypt,
+            _ => {
+                return Err(Error::application(
+                    "unsupported private key operation".into(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            operation,
+            kind,
+            output: None,
+        })
+    }
+
+    /// Returns the type of the operation
+    pub fn kind(&self) -> OperationType {
+        self.kind
+    }
+
+    /// Returns the input of the operation
+    pub fn input(&self) -> Result<Vec<u8>, Error> {
+        let mut input = vec![0; self.operation.input_size()?];
+        self.operation.input(&mut input)?;
+        Ok(input)
+    }
+
+    /// Sets the output of the operation, e.g. the signature of the digest
+    ///
+    /// The output is applied to the handshake once the pending operation completes.
+    pub fn set_output(&mut self, output: &[u8]) -> Result<(), Error> {
+        self.output = Some(output.to_vec());
+        Ok(())
+    }
+
+    /// Resumes the handshake with the output of the operation
+    fn apply(self, connection: &mut Connection) -> Result<(), Error> {
+        let output = self.output.ok_or_else(|| {
+            Error::application("the private key operation completed without an output".into())
+        })?;
+        self.operation.set_output(connection, &output)
+    }
+}
+
+/// Routes the private key operations of a configuration to a `PrivateKeyHandler`
+pub(crate) struct Handler<H>(H);
+
+impl<H: PrivateKeyHandler> Handler<H> {
+    /// Adds the public certificate chain to the config and routes its private key operations to
+    /// `handler`
+    pub fn register<C: IntoCertificate>(
+        config: &mut config::Builder,
+        certificate: C,
+        handler: H,
+    ) -> Result<(), Error> {
+        let certificate = certificate.into_certificate()?;
+        config.load_public_pem(
+            certificate
+                .0
+                .as_pem()
+                .expect("pem is currently the only certificate format supported"),
+        )?;
+        config.set_private_key_callback(Self(handler))?;
+        Ok(())
+    }
+}
+
+impl<H: PrivateKeyHandler> PrivateKeyCallback for Handler<H> {
+    fn handle_operation(
+        &self,
+        connection: &mut Connection,
+        operation: callbacks::PrivateKeyOperation,
+    ) -> ConnectionFutureResult {
+        callback::report(connection, TlsCallback::PrivateKeyOperation, |_| {
+            let operation = PrivateKeyOperation::new(operation)?;
+            let operation = self.0.handle_operation(operation);
+            Ok(Some(Box::pin(Pending(Mutex::new(operation)))))
+        })
+    }
+}
+
+/// Applies the output of a pending operation to the connection once it completes
+///
+/// s2n-tls requires the future to be `Sync`, which the operation doesn't need to be since it's
+/// only polled through a mutable reference.
+struct Pending(Mutex<PendingOperation>);
+
+impl ConnectionFuture for Pending {
+    fn poll(
+        mut self: Pin<&mut Self>,
+        connection: &mut Connection,
+        cx: &mut Context,
+    ) -> Poll<Result<(), Error>> {
+        let operation = self
+            .0
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match operation.as_mut().poll(cx) {
+            Poll::Ready(operation) => Poll::Ready(operation?.apply(connection)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
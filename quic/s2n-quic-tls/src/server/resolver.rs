@@ -0,0 +1,241 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Selects the certificate chain for a connection based on the server name indication (SNI)
+
+use super::{Builder, Server};
+use crate::{
+    callback::ConnectionFutureResult,
+    certificate::{IntoCertificate, IntoPrivateKey},
+    keylog::KeyLogHandle,
+};
+use s2n_tls::{
+    callbacks::ClientHelloCallback, config::Config, connection::Connection, error::Error,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+type Template = dyn Fn() -> Result<Builder, Error> + Send + Sync;
+
+/// Resolves the certificate chain and private key for each connection based on the server
+/// name sent by the client
+///
+/// Each server name is assigned its own configuration, which is derived from the resolver's
+/// template. The mappings can be updated at any time, including after the server has been
+/// built; new handshakes will use the updated mappings.
+///
+/// Lookups are first performed on the exact server name and then on a wildcard entry for the
+/// parent domain (e.g. `*.example.com` for `www.example.com`). If no entry matches, or the
+/// client doesn't send a server name, the certificate configured on the server builder with
+/// `with_certificate` is used.
+#[derive(Clone)]
+pub struct SniResolver {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    template: Box<Template>,
+    entries: RwLock<HashMap<String, Entry>>,
+}
+
+struct Entry {
+    config: Config,
+    #[allow(dead_code)] // we need to hold on to the handle to ensure it is cleaned up correctly
+    keylog: Option<KeyLogHandle>,
+}
+
+impl Default for SniResolver {
+    fn default() -> Self {
+        Self::new(|| Ok(Builder::default()))
+    }
+}
+
+impl SniResolver {
+    /// Creates a resolver which derives the configuration for each server name from `template`
+    ///
+    /// The template should apply all of the settings that aren't specific to the certificate,
+    /// such as application protocols and client authentication, since the configuration
+    /// selected for a connection replaces the one from the server builder.
+    pub fn new<F>(template: F) -> Self
+    where
+        F: 'static + Fn() -> Result<Builder, Error> + Send + Sync,
+    {
+        Self {
+            inner: Arc::new(Inner {
+                template: Box::new(template),
+                entries: Default::default(),
+            }),
+        }
+    }
+
+    /// Assigns a certificate chain and private key to `server_name`, replacing any previous
+    /// assignment
+    ///
+    /// `server_name` may be a wildcard (e.g. `*.example.com`) to match all of the direct
+    /// subdomains of a domain.
+    pub fn insert<C: IntoCertificate, PK: IntoPrivateKey>(
+        &self,
+        server_name: &str,
+        certificate: C,
+        private_key: PK,
+    ) -> Result<(), Error> {
+        self.insert_chains(server_name, core::iter::once((certificate, private_key)))
+    }
+
+    /// Assigns multiple certificate chains to `server_name`, replacing any previous assignment
+    ///
+    /// This can be used to provide chains with different key types (e.g. RSA and ECDSA) for the
+    /// same server name. s2n-tls selects the chain based on the signature algorithms the client
+    /// supports.
+    pub fn insert_chains<I, C, PK>(&self, server_name: &str, chains: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (C, PK)>,
+        C: IntoCertificate,
+        PK: IntoPrivateKey,
+    {
+        let mut builder = (self.inner.template)()?;
+        for (certificate, private_key) in chains {
+            builder = builder.with_certificate(certificate, private_key)?;
+        }
+
+        // s2n-tls finishes the client hello callback with the configuration that was selected,
+        // which is only allowed if the configuration also has a non-blocking callback
+        if !builder.has_client_hello_callback {
+            builder.set_client_hello_callback(Selected)?;
+        }
+
+        let Server { config, keylog, .. } = builder.build()?;
+
+        let entry = Entry { config, keylog };
+        self.inner
+            .entries
+            .write()
+            .unwrap()
+            .insert(normalize(server_name), entry);
+
+        Ok(())
+    }
+
+    /// Removes the assignment for `server_name`, returning `true` if it existed
+    pub fn remove(&self, server_name: &str) -> bool {
+        self.inner
+            .entries
+            .write()
+            .unwrap()
+            .remove(&normalize(server_name))
+            .is_some()
+    }
+
+    /// Removes all of the assignments
+    pub fn clear(&self) {
+        self.inner.entries.write().unwrap().clear();
+    }
+
+    /// Returns the configuration assigned to `server_name`, if any
+    fn resolve(&self, server_name: &str) -> Option<Config> {
+        let server_name = normalize(server_name);
+        let entries = self.inner.entries.read().unwrap();
+
+        if let Some(entry) = entries.get(&server_name) {
+            return Some(entry.config.clone());
+        }
+
+        let (_, parent) = server_name.split_once('.')?;
+        entries
+            .get(&format!("*.{}", parent))
+            .map(|entry| entry.config.clone())
+    }
+}
+
+/// Server names are compared without regard to case or a trailing root label
+fn normalize(server_name: &str) -> String {
+    server_name.trim_end_matches('.').to_ascii_lowercase()
+}
+
+impl ClientHelloCallback for SniResolver {
+    fn on_client_hello(&self, connection: &mut Connection) -> ConnectionFutureResult {
+        let config = connection
+            .server_name()
+            .and_then(|server_name| self.resolve(server_name));
+
+        if let Some(config) = config {
+            connection.set_config(config)?;
+        }
+
+        // the resolver never blocks so the handshake can always proceed
+        Ok(None)
+    }
+}
+
+/// The client hello callback for the configurations selected by the resolver
+///
+/// The certificate was already resolved by the time the configuration is applied so there's
+/// nothing left to do.
+struct Selected;
+
+impl ClientHelloCallback for Selected {
+    fn on_client_hello(&self, _connection: &mut Connection) -> ConnectionFutureResult {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_core::crypto::tls::testing::certificates::{CERT_PEM, KEY_PEM};
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn resolve_test() {
+        let resolver = SniResolver::default();
+        resolver.insert("example.com", CERT_PEM, KEY_PEM).unwrap();
+        resolver.insert("*.example.org", CERT_PEM, KEY_PEM).unwrap();
+
+        assert!(resolver.resolve("example.com").is_some());
+        assert!(resolver.resolve("EXAMPLE.com.").is_some());
+        assert!(resolver.resolve("www.example.com").is_none());
+
+        assert!(resolver.resolve("www.example.org").is_some());
+        assert!(resolver.resolve("a.b.example.org").is_none());
+        assert!(resolver.resolve("example.org").is_none());
+
+        assert!(resolver.remove("example.com"));
+        assert!(!resolver.remove("example.com"));
+        assert!(resolver.resolve("example.com").is_none());
+
+        resolver.clear();
+        assert!(resolver.resolve("www.example.org").is_none());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn client_hello_handler_conflict_test() {
+        struct Handler;
+
+        impl ClientHelloCallback for Handler {
+            fn on_client_hello(&self, _connection: &mut Connection) -> ConnectionFutureResult {
+                Ok(None)
+            }
+        }
+
+        assert!(Builder::default()
+            .with_sni_resolver(SniResolver::default())
+            .unwrap()
+            .with_client_hello_handler(Handler)
+            .is_err());
+
+        assert!(Builder::default()
+            .with_client_hello_handler(Handler)
+            .unwrap()
+            .with_sni_resolver(SniResolver::default())
+            .is_err());
+
+        assert!(Builder::default()
+            .with_sni_resolver(SniResolver::default())
+            .unwrap()
+            .with_sni_resolver(SniResolver::default())
+            .is_err());
+    }
+}
@@ -1,8 +1,8 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::callback::{self, Callback};
-use bytes::BytesMut;
+use crate::callback::{self, Callback, TlsCallbacks};
+use bytes::{Bytes, BytesMut};
 use core::{marker::PhantomData, task::Poll};
 use s2n_quic_core::{
     application::ServerName,
@@ -13,9 +13,10 @@ use s2n_quic_crypto::Suite;
 use s2n_tls::{
     config::Config,
     connection::Connection,
-    enums::{Blinding, Mode},
+    enums::{Blinding, Mode, Version},
     error::Error,
 };
+use std::time::Instant;
 
 #[derive(Debug)]
 pub struct Session {
@@ -27,10 +28,13 @@ pub struct Session {
     emitted_server_name: bool,
     // This is only set for the client to avoid an extra allocation
     server_name: Option<ServerName>,
+    /// The application callback which is pending, along with the start of the poll which invoked
+    /// it
+    tls_callback: Option<(tls::TlsCallback, Instant)>,
 }
 
 impl Session {
-    pub fn new(
+    pub(crate) fn new(
         endpoint: endpoint::Type,
         config: Config,
         params: &[u8],
@@ -46,6 +50,7 @@ impl Session {
         connection.set_quic_transport_parameters(params)?;
         // QUIC handles sending alerts, so no need to apply TLS blinding
         connection.set_blinding(Blinding::SelfService)?;
+        connection.set_application_context(TlsCallbacks::default());
 
         if let Some(server_name) = server_name.as_ref() {
             connection
@@ -61,8 +66,61 @@ impl Session {
             send_buffer: BytesMut::new(),
             emitted_server_name: false,
             server_name,
+            tls_callback: None,
         })
     }
+
+    /// Returns the details of the negotiated session
+    fn session_info(&self) -> tls::SessionInfo {
+        let cipher_suite = match self.connection.cipher_suite() {
+            Ok("TLS_AES_128_GCM_SHA256") => tls::CipherSuite::TLS_AES_128_GCM_SHA256,
+            Ok("TLS_AES_256_GCM_SHA384") => tls::CipherSuite::TLS_AES_256_GCM_SHA384,
+            Ok("TLS_CHACHA20_POLY1305_SHA256") => tls::CipherSuite::TLS_CHACHA20_POLY1305_SHA256,
+            _ => tls::CipherSuite::Unknown,
+        };
+
+        let protocol_version = match self.connection.actual_protocol_version() {
+            Ok(Version::TLS13) => tls::ProtocolVersion::TLS_1_3,
+            _ => tls::ProtocolVersion::Unknown,
+        };
+
+        tls::SessionInfo {
+            cipher_suite,
+            protocol_version,
+            // the peer didn't present a certificate if the chain isn't available
+            peer_certificate_chain: self.peer_certificate_chain().unwrap_or_default(),
+            resumed: self.connection.resumed(),
+        }
+    }
+
+    /// Returns the certificate chain presented by the peer in DER format
+    fn peer_certificate_chain(&self) -> Result<Vec<Bytes>, Error> {
+        self.connection
+            .peer_cert_chain()?
+            .iter()
+            .map(|certificate| Ok(Bytes::copy_from_slice(certificate?.der()?)))
+            .collect()
+    }
+
+    /// Reports the application callbacks which started or completed while polling the
+    /// connection
+    ///
+    /// The clock is only read around each poll, so a callback which completes within a single
+    /// poll is measured as the duration of that poll.
+    fn on_tls_callbacks<W>(&mut self, context: &mut W, poll_start: Instant, poll_end: Instant)
+    where
+        W: tls::Context<Self>,
+    {
+        for (callback, is_complete) in TlsCallbacks::take(&mut self.connection) {
+            if !is_complete {
+                self.tls_callback = Some((callback, poll_start));
+                context.on_tls_callback_started(callback);
+            } else if let Some((_, start)) = self.tls_callback.take() {
+                let duration = poll_end.saturating_duration_since(start);
+                context.on_tls_callback_completed(callback, duration);
+            }
+        }
+    }
 }
 
 impl CryptoSuite for Session {
@@ -99,15 +157,19 @@ impl tls::Session for Session {
             callback.set(&mut self.connection);
         }
 
+        let poll_start = Instant::now();
         let result = self.connection.poll_negotiate().map_ok(|_| ());
+        let poll_end = Instant::now();
 
         callback.unset(&mut self.connection)?;
+        self.on_tls_callbacks(context, poll_start, poll_end);
 
         match result {
             Poll::Ready(Ok(())) => {
                 // s2n-tls has indicated that the handshake is complete
                 if !self.handshake_complete {
                     self.state.on_handshake_complete();
+                    context.on_session_info(self.session_info())?;
                     context.on_handshake_complete()?;
                     self.handshake_complete = true;
                 }
@@ -1,23 +1,31 @@
 // Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{client, server};
+use crate::{callback::ConnectionFutureResult, client, server};
 use core::{
+    future::Future,
+    pin::Pin,
+    ptr::NonNull,
     sync::atomic::{AtomicBool, AtomicU8, Ordering},
-    task::Poll,
+    task::{Context, Poll},
 };
 use s2n_quic_core::{
     crypto::tls::{
         self,
-        testing::certificates::{CERT_PEM, KEY_PEM, UNTRUSTED_CERT_PEM, UNTRUSTED_KEY_PEM},
+        testing::certificates::{
+            CERT_PEM, KEY_DER, KEY_PEM, UNTRUSTED_CERT_PEM, UNTRUSTED_KEY_PEM,
+        },
         Endpoint,
     },
     transport,
 };
-#[cfg(any(test, all(s2n_quic_unstable, feature = "unstable_client_hello")))]
-use s2n_tls::{callbacks::ClientHelloCallback, connection::Connection};
 use s2n_tls::{callbacks::VerifyHostNameCallback, error::Error};
-use std::sync::Arc;
+#[cfg(any(test, all(s2n_quic_unstable, feature = "unstable_client_hello")))]
+use s2n_tls::{
+    callbacks::{ClientHelloCallback, ConnectionFuture},
+    connection::Connection,
+};
+use std::sync::{Arc, Mutex};
 
 pub struct MyClientHelloHandler {
     done: Arc<AtomicBool>,
@@ -35,19 +43,145 @@ impl MyClientHelloHandler {
 
 #[cfg(any(test, all(s2n_quic_unstable, feature = "unstable_client_hello")))]
 impl ClientHelloCallback for MyClientHelloHandler {
-    fn poll_client_hello(
-        &self,
+    fn on_client_hello(&self, _connection: &mut Connection) -> ConnectionFutureResult {
+        Ok(Some(Box::pin(PendingClientHello {
+            done: self.done.clone(),
+            wait_counter: self.wait_counter.clone(),
+        })))
+    }
+}
+
+/// Completes the client hello callback after a number of polls
+#[cfg(any(test, all(s2n_quic_unstable, feature = "unstable_client_hello")))]
+struct PendingClientHello {
+    done: Arc<AtomicBool>,
+    wait_counter: Arc<AtomicU8>,
+}
+
+#[cfg(any(test, all(s2n_quic_unstable, feature = "unstable_client_hello")))]
+impl ConnectionFuture for PendingClientHello {
+    fn poll(
+        self: Pin<&mut Self>,
         _connection: &mut Connection,
-    ) -> core::task::Poll<Result<(), Error>> {
+        cx: &mut Context,
+    ) -> Poll<Result<(), Error>> {
         if self.wait_counter.fetch_sub(1, Ordering::SeqCst) == 0 {
             self.done.store(true, Ordering::SeqCst);
             return Poll::Ready(Ok(()));
         }
 
+        cx.waker().wake_by_ref();
         Poll::Pending
     }
 }
 
+/// Signs with a separately loaded private key after a number of polls, like an external signer
+pub struct MyPrivateKeyHandler {
+    key: Arc<PrivateKey>,
+    done: Arc<AtomicBool>,
+    wait_counter: u8,
+}
+
+impl MyPrivateKeyHandler {
+    fn new(wait_counter: u8) -> Self {
+        MyPrivateKeyHandler {
+            key: Arc::new(PrivateKey::new(KEY_DER)),
+            done: Arc::new(AtomicBool::new(false)),
+            wait_counter,
+        }
+    }
+}
+
+impl server::PrivateKeyHandler for MyPrivateKeyHandler {
+    fn handle_operation(
+        &self,
+        operation: server::PrivateKeyOperation,
+    ) -> server::private_key::PendingOperation {
+        Box::pin(PendingSign {
+            operation: Some(operation),
+            key: self.key.clone(),
+            done: self.done.clone(),
+            wait_counter: self.wait_counter,
+        })
+    }
+}
+
+struct PendingSign {
+    operation: Option<server::PrivateKeyOperation>,
+    key: Arc<PrivateKey>,
+    done: Arc<AtomicBool>,
+    wait_counter: u8,
+}
+
+impl Future for PendingSign {
+    type Output = Result<server::PrivateKeyOperation, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if let Some(wait_counter) = self.wait_counter.checked_sub(1) {
+            self.wait_counter = wait_counter;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        let mut operation = self.operation.take().unwrap();
+        assert_eq!(operation.kind(), server::private_key::OperationType::Sign);
+        let digest = operation.input()?;
+        assert!(!digest.is_empty());
+        operation.set_output(&self.key.sign(&digest))?;
+
+        self.done.store(true, Ordering::SeqCst);
+        Poll::Ready(Ok(operation))
+    }
+}
+
+/// The ECDSA key of the test certificate, loaded outside of s2n-tls
+struct PrivateKey(NonNull<openssl_sys::EC_KEY>);
+
+// Safety: the key isn't modified after it's loaded
+unsafe impl Send for PrivateKey {}
+unsafe impl Sync for PrivateKey {}
+
+impl PrivateKey {
+    fn new(der: &[u8]) -> Self {
+        openssl_sys::init();
+
+        unsafe {
+            let mut ptr = der.as_ptr();
+            let key =
+                openssl_sys::d2i_AutoPrivateKey(core::ptr::null_mut(), &mut ptr, der.len() as _);
+            assert!(!key.is_null());
+            let ec_key = openssl_sys::EVP_PKEY_get1_EC_KEY(key);
+            openssl_sys::EVP_PKEY_free(key);
+            Self(NonNull::new(ec_key).expect("the test key should be an EC key"))
+        }
+    }
+
+    /// Signs the digest and returns the DER encoded signature
+    fn sign(&self, digest: &[u8]) -> Vec<u8> {
+        unsafe {
+            let signature =
+                openssl_sys::ECDSA_do_sign(digest.as_ptr(), digest.len() as _, self.0.as_ptr());
+            assert!(!signature.is_null());
+
+            let len = openssl_sys::i2d_ECDSA_SIG(signature, core::ptr::null_mut());
+            let mut der = vec![0; len as usize];
+            let mut ptr = der.as_mut_ptr();
+            openssl_sys::i2d_ECDSA_SIG(signature, &mut ptr);
+            openssl_sys::ECDSA_SIG_free(signature);
+
+            der
+        }
+    }
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        unsafe {
+            openssl_sys::EC_KEY_free(self.0.as_ptr());
+        }
+    }
+}
+
 pub struct VerifyHostNameClientCertVerifier {
     host_name: String,
 }
@@ -140,6 +274,25 @@ fn s2n_server_with_client_hello_callback(wait_counter: u8) -> (server::Server, A
     (tls, done)
 }
 
+fn s2n_server_with_private_key_handler(wait_counter: u8) -> (server::Server, Arc<AtomicBool>) {
+    let handler = MyPrivateKeyHandler::new(wait_counter);
+    let done = handler.done.clone();
+    let tls = server::Builder::default()
+        .with_private_key_handler(CERT_PEM, handler)
+        .unwrap()
+        .build()
+        .unwrap();
+    (tls, done)
+}
+
+fn s2n_server_with_sni_resolver(resolver: server::SniResolver) -> server::Server {
+    server::Builder::default()
+        .with_sni_resolver(resolver)
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
 fn rustls_server() -> s2n_quic_rustls::server::Server {
     s2n_quic_rustls::server::Builder::default()
         .with_certificate(CERT_PEM, KEY_PEM)
@@ -163,16 +316,80 @@ fn s2n_client_s2n_server_ch_callback_test() {
         let mut client_endpoint = s2n_client();
         let (mut server_endpoint, done) = s2n_server_with_client_hello_callback(wait_counter);
 
-        run(&mut server_endpoint, &mut client_endpoint, Some(done));
+        let callbacks = run(&mut server_endpoint, &mut client_endpoint, Some(done));
+        assert_eq!(callbacks, [tls::TlsCallback::CertificateSelection]);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_s2n_server_private_key_handler_test() {
+    for wait_counter in 0..=10 {
+        let mut client_endpoint = s2n_client();
+        let (mut server_endpoint, done) = s2n_server_with_private_key_handler(wait_counter);
+
+        let callbacks = run(
+            &mut server_endpoint,
+            &mut client_endpoint,
+            Some(done.clone()),
+        );
+        assert!(done.load(Ordering::SeqCst));
+        assert_eq!(callbacks, [tls::TlsCallback::PrivateKeyOperation]);
     }
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn private_key_handler_conflict_test() {
+    // s2n-tls passes the operations for every certificate to the handler
+    assert!(server::Builder::default()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_private_key_handler(CERT_PEM, MyPrivateKeyHandler::new(0))
+        .is_err());
+
+    assert!(server::Builder::default()
+        .with_private_key_handler(CERT_PEM, MyPrivateKeyHandler::new(0))
+        .unwrap()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .is_err());
+
+    assert!(server::Builder::default()
+        .with_private_key_handler(CERT_PEM, MyPrivateKeyHandler::new(0))
+        .unwrap()
+        .with_private_key_handler(CERT_PEM, MyPrivateKeyHandler::new(0))
+        .is_err());
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn s2n_client_s2n_server_test() {
     let mut client_endpoint = s2n_client();
     let mut server_endpoint = s2n_server();
 
+    // the certificate and private key are used synchronously
+    let callbacks = run(&mut server_endpoint, &mut client_endpoint, None);
+    assert!(callbacks.is_empty());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_s2n_server_sni_resolver_test() {
+    let resolver = server::SniResolver::default();
+    let mut server_endpoint = s2n_server_with_sni_resolver(resolver.clone());
+
+    // the server doesn't have a certificate for the requested name
+    resolver.insert("example.com", CERT_PEM, KEY_PEM).unwrap();
+    let mut client_endpoint = s2n_client();
+    assert!(run_result(&mut server_endpoint, &mut client_endpoint, None).is_err());
+
+    // mappings added after the server is built are used for new handshakes
+    resolver.insert("localhost", CERT_PEM, KEY_PEM).unwrap();
+    let mut client_endpoint = s2n_client();
+    let callbacks = run(&mut server_endpoint, &mut client_endpoint, None);
+    assert_eq!(callbacks, [tls::TlsCallback::CertificateSelection]);
+
+    let mut client_endpoint = rustls_client();
     run(&mut server_endpoint, &mut client_endpoint, None);
 }
 
@@ -203,6 +420,30 @@ fn s2n_client_s2n_server_client_auth_test() {
     run(&mut server_endpoint, &mut client_endpoint, None);
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_s2n_server_client_auth_peer_certificate_test() {
+    let mut client_endpoint = s2n_client_with_client_auth().unwrap();
+    let mut server_endpoint = s2n_server_with_client_auth().unwrap();
+
+    let mut pair = tls::testing::Pair::new(
+        &mut server_endpoint,
+        &mut client_endpoint,
+        "localhost".into(),
+    );
+
+    while pair.is_handshaking() {
+        pair.poll(None).unwrap();
+    }
+
+    pair.finish();
+
+    // the server should have received the client certificate
+    let session_info = pair.server.context.session_info.as_ref().unwrap();
+    assert_eq!(session_info.peer_certificate_chain.len(), 1);
+    assert!(!session_info.resumed);
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn s2n_client_no_client_auth_s2n_server_requires_client_auth_test() {
@@ -215,7 +456,7 @@ fn s2n_client_no_client_auth_s2n_server_requires_client_auth_test() {
     // but the client does not support it.
     assert!(test_result.is_err());
     let e = test_result.unwrap_err();
-    assert_eq!(e.description().unwrap(), "HANDSHAKE_FAILURE");
+    assert_eq!(e.description().unwrap(), "CERTIFICATE_REQUIRED");
 }
 
 #[test]
@@ -230,7 +471,7 @@ fn s2n_client_with_client_auth_s2n_server_does_not_require_client_auth_test() {
     // but the server does not support it.
     assert!(test_result.is_err());
     let e = test_result.unwrap_err();
-    assert_eq!(e.description().unwrap(), "HANDSHAKE_FAILURE");
+    assert_eq!(e.description().unwrap(), "UNEXPECTED_MESSAGE");
 }
 
 #[test]
@@ -263,12 +504,81 @@ fn s2n_client_with_client_auth_s2n_server_does_not_trust_issuer() {
     assert_eq!(e.description().unwrap(), "HANDSHAKE_FAILURE");
 }
 
-/// Executes the handshake to completion
+/// Collects the lines written to a key log
+#[derive(Clone, Default)]
+struct KeyLogOutput(Arc<Mutex<Vec<u8>>>);
+
+impl KeyLogOutput {
+    fn lines(&self) -> Vec<String> {
+        let output = self.0.lock().unwrap();
+        let mut lines: Vec<_> = String::from_utf8(output.clone())
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+        lines.sort();
+        lines
+    }
+}
+
+impl std::io::Write for KeyLogOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn s2n_client_s2n_server_key_log_test() {
+    let client_log = KeyLogOutput::default();
+    let server_log = KeyLogOutput::default();
+
+    let mut client_endpoint = client::Builder::default()
+        .with_certificate(CERT_PEM)
+        .unwrap()
+        .with_key_log(client_log.clone())
+        .unwrap()
+        .build()
+        .unwrap();
+    let mut server_endpoint = server::Builder::default()
+        .with_certificate(CERT_PEM, KEY_PEM)
+        .unwrap()
+        .with_key_log(server_log.clone())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    run(&mut server_endpoint, &mut client_endpoint, None);
+
+    let lines = server_log.lines();
+    for label in [
+        "CLIENT_HANDSHAKE_TRAFFIC_SECRET",
+        "SERVER_HANDSHAKE_TRAFFIC_SECRET",
+        "CLIENT_TRAFFIC_SECRET_0",
+        "SERVER_TRAFFIC_SECRET_0",
+    ] {
+        assert!(
+            lines.iter().any(|line| line.starts_with(label)),
+            "missing {} in {:?}",
+            label,
+            lines
+        );
+    }
+
+    // both endpoints derive the same secrets
+    assert_eq!(client_log.lines(), lines);
+}
+
+/// Executes the handshake to completion and returns the callbacks reported by the server
 fn run_result<S: Endpoint, C: Endpoint>(
     server: &mut S,
     client: &mut C,
     client_hello_cb_done: Option<Arc<AtomicBool>>,
-) -> Result<(), transport::Error> {
+) -> Result<Vec<tls::TlsCallback>, transport::Error> {
     let mut pair = tls::testing::Pair::new(server, client, "localhost".into());
 
     while pair.is_handshaking() {
@@ -276,7 +586,7 @@ fn run_result<S: Endpoint, C: Endpoint>(
     }
 
     pair.finish();
-    Ok(())
+    Ok(pair.server.context.tls_callbacks)
 }
 
 /// Executes the handshake to completion
@@ -284,6 +594,6 @@ fn run<S: Endpoint, C: Endpoint>(
     server: &mut S,
     client: &mut C,
     client_hello_cb_done: Option<Arc<AtomicBool>>,
-) {
-    run_result(server, client, client_hello_cb_done).unwrap();
+) -> Vec<tls::TlsCallback> {
+    run_result(server, client, client_hello_cb_done).unwrap()
 }
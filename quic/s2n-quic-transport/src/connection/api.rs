@@ -16,6 +16,7 @@ use core::{
 use s2n_quic_core::{
     application,
     application::ServerName,
+    crypto::tls,
     event::query::{Query, QueryMut},
     inet::SocketAddress,
     stream::StreamType,
@@ -175,6 +176,11 @@ impl Connection {
         self.api.application_protocol()
     }
 
+    #[inline]
+    pub fn tls_session(&self) -> Result<tls::SessionInfo, connection::Error> {
+        self.api.tls_session()
+    }
+
     #[inline]
     pub fn id(&self) -> u64 {
         self.api.id()
@@ -189,6 +195,11 @@ impl Connection {
         self.api.keep_alive(enabled)
     }
 
+    #[inline]
+    pub fn initiate_key_update(&self) -> Result<(), connection::Error> {
+        self.api.initiate_key_update()
+    }
+
     #[inline]
     pub fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         self.api.local_address()
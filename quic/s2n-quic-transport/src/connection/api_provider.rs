@@ -17,6 +17,7 @@ use core::{
 use s2n_quic_core::{
     application,
     application::ServerName,
+    crypto::tls,
     event::query::{Query, QueryMut},
     inet::SocketAddress,
     stream::{ops, StreamId, StreamType},
@@ -58,12 +59,16 @@ pub(crate) trait ConnectionApiProvider: Sync + Send {
 
     fn application_protocol(&self) -> Result<Bytes, connection::Error>;
 
+    fn tls_session(&self) -> Result<tls::SessionInfo, connection::Error>;
+
     fn id(&self) -> u64;
 
     fn ping(&self) -> Result<(), connection::Error>;
 
     fn keep_alive(&self, enabled: bool) -> Result<(), connection::Error>;
 
+    fn initiate_key_update(&self) -> Result<(), connection::Error>;
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error>;
 
     fn remote_address(&self) -> Result<SocketAddress, connection::Error>;
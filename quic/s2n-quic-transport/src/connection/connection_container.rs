@@ -33,6 +33,7 @@ use intrusive_collections::{
 use s2n_quic_core::{
     application,
     application::ServerName,
+    crypto::tls,
     event::{
         query::{Query, QueryMut},
         supervisor,
@@ -93,6 +94,10 @@ struct ConnectionNode<C: connection::Trait, L: connection::Lock<C>> {
     timeout: Cell<Option<Timestamp>>,
     /// The count of outstanding application handles
     application_handle_count: AtomicUsize,
+    /// The remote address of the peer at the time the connection was inserted
+    ///
+    /// This is only set for server connections, which are the ones tracked by the endpoint limits.
+    remote_address: Option<SocketAddress>,
     /// The inner connection type
     _connection: PhantomData<C>,
 }
@@ -102,6 +107,7 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionNode<C, L> {
     pub fn new(
         connection_impl: L,
         internal_connection_id: InternalConnectionId,
+        remote_address: Option<SocketAddress>,
     ) -> ConnectionNode<C, L> {
         ConnectionNode {
             inner: connection_impl,
@@ -113,6 +119,7 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionNode<C, L> {
             waiting_for_timeout_link: RBTreeLink::new(),
             timeout: Cell::new(None),
             application_handle_count: AtomicUsize::new(0),
+            remote_address,
             _connection: PhantomData,
         }
     }
@@ -311,6 +318,10 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionApiProvider for Con
         self.api_read_call(|conn| Ok(conn.application_protocol()))
     }
 
+    fn tls_session(&self) -> Result<tls::SessionInfo, connection::Error> {
+        self.api_read_call(|conn| Ok(conn.tls_session()))
+    }
+
     fn id(&self) -> u64 {
         self.internal_connection_id.into()
     }
@@ -323,6 +334,10 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionApiProvider for Con
         self.api_write_call(|conn| conn.keep_alive(enabled))
     }
 
+    fn initiate_key_update(&self) -> Result<(), connection::Error> {
+        self.api_write_call(|conn| conn.initiate_key_update())
+    }
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         self.api_read_call(|conn| conn.local_address())
     }
@@ -611,6 +626,11 @@ pub struct ConnectionContainer<C: connection::Trait, L: connection::Lock<C>> {
     ///
     /// This is only used by clients
     connector_receiver: ConnectorReceiver,
+    /// The remote addresses of connections which have been removed from the container
+    ///
+    /// These are drained by the endpoint with [`Self::drain_closed_connections`] in order to
+    /// notify the endpoint limiter.
+    closed_connections: Vec<SocketAddress>,
 }
 
 macro_rules! iterate_interruptible {
@@ -670,6 +690,7 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
             interest_lists: InterestLists::new(),
             accept_queue,
             connector_receiver,
+            closed_connections: Vec::new(),
         }
     }
 
@@ -745,24 +766,28 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
     }
 
     /// Insert a new server Connection into the container
+    ///
+    /// Returns `true` if the connection was inserted
     pub fn insert_server_connection(
         &mut self,
         connection: C,
         internal_connection_id: InternalConnectionId,
-    ) {
+    ) -> bool {
         debug_assert!(<C::Config as endpoint::Config>::ENDPOINT_TYPE.is_server());
 
         self.insert_connection(connection, internal_connection_id)
     }
 
     /// Insert a new client Connection into the container
+    ///
+    /// Returns `true` if the connection was inserted
     #[allow(dead_code)]
     pub fn insert_client_connection(
         &mut self,
         connection: C,
         internal_connection_id: InternalConnectionId,
         connection_sender: ConnectionSender,
-    ) {
+    ) -> bool {
         debug_assert!(<C::Config as endpoint::Config>::ENDPOINT_TYPE.is_client());
 
         self.interest_lists
@@ -784,11 +809,28 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
         futures_core::Stream::poll_next(Pin::new(&mut self.connector_receiver), cx)
     }
 
-    fn insert_connection(&mut self, connection: C, internal_connection_id: InternalConnectionId) {
+    /// Inserts the connection, returning `true` if it was accepted into the container
+    ///
+    /// The connection is dropped if its interests could not be registered.
+    fn insert_connection(
+        &mut self,
+        connection: C,
+        internal_connection_id: InternalConnectionId,
+    ) -> bool {
         let interests = connection.interests();
+        // Only server connections are reported to the endpoint limits
+        let remote_address = if <C::Config as endpoint::Config>::ENDPOINT_TYPE.is_server() {
+            connection.remote_address().ok()
+        } else {
+            None
+        };
 
         let connection = L::new(connection);
-        let connection = Arc::new(ConnectionNode::new(connection, internal_connection_id));
+        let connection = Arc::new(ConnectionNode::new(
+            connection,
+            internal_connection_id,
+            remote_address,
+        ));
 
         if self
             .interest_lists
@@ -805,7 +847,10 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
             self.interest_lists.handshake_connections += 1;
             self.interest_lists.connection_count += 1;
             self.ensure_counter_consistency();
+            return true;
         }
+
+        false
     }
 
     pub fn handshake_connections(&self) -> usize {
@@ -817,6 +862,11 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
         self.interest_lists.connection_count
     }
 
+    /// Returns the remote addresses of all connections which were removed since the last call
+    pub fn drain_closed_connections(&mut self) -> impl Iterator<Item = SocketAddress> + '_ {
+        self.closed_connections.drain(..)
+    }
+
     /// Looks up the `Connection` with the given ID and executes the provided function
     /// on it.
     ///
@@ -1046,6 +1096,9 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
 
         if let Some(connection) = remove_result {
             self.interest_lists.remove_node(&connection);
+            if let Some(remote_address) = connection.remote_address {
+                self.closed_connections.push(remote_address);
+            }
         }
     }
 
@@ -1067,6 +1120,9 @@ impl<C: connection::Trait, L: connection::Lock<C>> ConnectionContainer<C, L> {
         debug_assert!(remove_result.is_some());
 
         self.interest_lists.remove_node(connection);
+        if let Some(remote_address) = connection.remote_address {
+            self.closed_connections.push(remote_address);
+        }
     }
 }
 
@@ -188,6 +188,25 @@ impl connection::Trait for TestConnection {
         Ok(())
     }
 
+    /// Is called when short packets had been received in consecutive datagrams on the same path
+    fn decrypt_short_packets(
+        &mut self,
+        _path_id: path::Id,
+        _packets: &mut [endpoint::batch::Packet],
+    ) {
+    }
+
+    /// Is called when a short packet from a batch had been received
+    fn handle_batched_short_packet(
+        &mut self,
+        _path_id: path::Id,
+        _packet: &mut endpoint::batch::Packet,
+        _random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
+        _subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
+        _packet_interceptor: &mut <Self::Config as endpoint::Config>::PacketInterceptor,
+    ) {
+    }
+
     /// Is called when a version negotiation packet had been received
     fn handle_version_negotiation_packet(
         &mut self,
@@ -285,6 +304,10 @@ impl connection::Trait for TestConnection {
         todo!()
     }
 
+    fn tls_session(&self) -> tls::SessionInfo {
+        todo!()
+    }
+
     fn ping(&mut self) -> Result<(), connection::Error> {
         todo!()
     }
@@ -293,6 +316,10 @@ impl connection::Trait for TestConnection {
         todo!()
     }
 
+    fn initiate_key_update(&mut self) -> Result<(), connection::Error> {
+        todo!()
+    }
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         todo!()
     }
@@ -308,12 +308,15 @@ impl ConnectionIdMapper {
     /// Creates a `LocalIdRegistry` for a new internal connection ID, which allows that
     /// connection to modify the mappings of it's Connection ID aliases. The provided
     /// `initial_connection_id` will be registered in the returned registry.
+    ///
+    /// Clients pass `None` for the `local_stateless_reset_token`, as they have no way of
+    /// transmitting a token for the `initial_connection_id`.
     pub fn create_local_id_registry(
         &mut self,
         internal_id: InternalConnectionId,
         initial_connection_id: &connection::LocalId,
         initial_connection_id_expiration_time: Option<Timestamp>,
-        local_stateless_reset_token: stateless_reset::Token,
+        local_stateless_reset_token: Option<stateless_reset::Token>,
     ) -> LocalIdRegistry {
         LocalIdRegistry::new(
             internal_id,
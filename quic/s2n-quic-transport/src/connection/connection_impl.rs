@@ -48,7 +48,7 @@ use s2n_quic_core::{
         initial::{CleartextInitial, ProtectedInitial},
         number::PacketNumberSpace,
         retry::ProtectedRetry,
-        short::ProtectedShort,
+        short::{CleartextShort, ProtectedShort},
         version_negotiation::ProtectedVersionNegotiation,
         zero_rtt::ProtectedZeroRtt,
     },
@@ -386,6 +386,60 @@ impl<Config: endpoint::Config> ConnectionImpl<Config> {
         Ok(())
     }
 
+    /// Processes a short packet after its packet protection has been removed
+    fn handle_cleartext_short_packet(
+        &mut self,
+        datagram: &DatagramInfo,
+        path_id: path::Id,
+        packet: CleartextShort,
+        random_generator: &mut Config::RandomGenerator,
+        subscriber: &mut Config::EventSubscriber,
+        packet_interceptor: &mut Config::PacketInterceptor,
+    ) -> Result<(), ProcessingError> {
+        let mut publisher = self.event_context.publisher(datagram.timestamp, subscriber);
+
+        if let Some((space, handshake_status)) = self.space_manager.application_mut() {
+            publisher.on_packet_received(event::builder::PacketReceived {
+                packet_header: event::builder::PacketHeader::new(
+                    packet.packet_number,
+                    publisher.quic_version(),
+                ),
+            });
+
+            // Connection Ids are issued to the peer after the handshake is
+            // confirmed and the handshake space is discarded. Therefore only
+            // short packets need to be processed for local_connection_id changes.
+            self.path_manager[path_id].on_process_local_connection_id(
+                path_id,
+                &packet,
+                &datagram.destination_connection_id,
+                &mut publisher,
+            );
+
+            let processed_packet = space.handle_cleartext_payload(
+                packet.packet_number,
+                packet.payload,
+                datagram,
+                path_id,
+                &mut self.path_manager,
+                handshake_status,
+                &mut self.local_id_registry,
+                random_generator,
+                &mut publisher,
+                packet_interceptor,
+            )?;
+
+            // HANDSHAKE_DONE frames and their acknowledgements are only carried in 1-RTT packets
+            self.space_manager
+                .on_handshake_done_timing(datagram.timestamp, &mut publisher);
+
+            // notify the connection a packet was processed
+            self.on_processed_packet(&processed_packet, subscriber)?;
+        }
+
+        Ok(())
+    }
+
     fn on_ack_eliciting_packet_sent(&mut self, timestamp: Timestamp) {
         //= https://www.rfc-editor.org/rfc/rfc9000#section-10.1
         //# An endpoint also restarts its
@@ -998,8 +1052,12 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
 
         let mut publisher = self.event_context.publisher(timestamp, subscriber);
 
-        self.path_manager
-            .on_timeout(timestamp, random_generator, &mut publisher)?;
+        let result = self
+            .path_manager
+            .on_timeout(timestamp, random_generator, &mut publisher);
+        self.space_manager
+            .on_paths_deleted(&mut self.path_manager, timestamp);
+        result?;
         self.local_id_registry.on_timeout(timestamp);
         self.space_manager.on_timeout(
             &mut self.local_id_registry,
@@ -1111,7 +1169,7 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         //# size of packets it receives from that address.
         let handshake_confirmed = self.space_manager.is_handshake_confirmed();
 
-        let (id, unblocked) = self.path_manager.on_datagram_received(
+        let result = self.path_manager.on_datagram_received(
             path_handle,
             datagram,
             handshake_confirmed,
@@ -1119,7 +1177,11 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             path_migration,
             max_mtu,
             &mut publisher,
-        )?;
+        );
+        // Creating a path may reuse the slot of a deleted path
+        self.space_manager
+            .on_paths_deleted(&mut self.path_manager, datagram.timestamp);
+        let (id, unblocked) = result?;
 
         publisher.on_datagram_received(event::builder::DatagramReceived {
             len: datagram.payload_len as u16,
@@ -1398,50 +1460,95 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
             return Ok(());
         }
 
-        if let Some((space, handshake_status)) = self.space_manager.application_mut() {
-            let packet = space.validate_and_decrypt_packet(
+        let packet = if let Some((space, _)) = self.space_manager.application_mut() {
+            space.validate_and_decrypt_packet(
                 packet,
                 datagram,
                 path_id,
                 &self.path_manager[path_id],
                 &mut publisher,
-            )?;
+            )?
+        } else {
+            return Ok(());
+        };
 
-            publisher.on_packet_received(event::builder::PacketReceived {
-                packet_header: event::builder::PacketHeader::new(
-                    packet.packet_number,
-                    publisher.quic_version(),
-                ),
-            });
+        self.handle_cleartext_short_packet(
+            datagram,
+            path_id,
+            packet,
+            random_generator,
+            subscriber,
+            packet_interceptor,
+        )
+    }
 
-            // Connection Ids are issued to the peer after the handshake is
-            // confirmed and the handshake space is discarded. Therefore only
-            // short packets need to be processed for local_connection_id changes.
-            self.path_manager[path_id].on_process_local_connection_id(
-                path_id,
-                &packet,
-                &datagram.destination_connection_id,
-                &mut publisher,
-            );
+    fn decrypt_short_packets(
+        &mut self,
+        path_id: path::Id,
+        packets: &mut [endpoint::batch::Packet],
+    ) {
+        // Packets are only decrypted together after the handshake is complete. Before that,
+        // each packet is handled individually so it's dropped in the same way.
+        if packets.len() < 2 || !self.space_manager.is_handshake_complete() {
+            return;
+        }
 
-            let processed_packet = space.handle_cleartext_payload(
-                packet.packet_number,
-                packet.payload,
-                datagram,
+        let timestamp = packets[0].datagram.timestamp;
+        if let Some((space, _)) = self.space_manager.application_mut() {
+            space.decrypt_packets(packets, timestamp, &self.path_manager[path_id]);
+        }
+    }
+
+    fn handle_batched_short_packet(
+        &mut self,
+        path_id: path::Id,
+        packet: &mut endpoint::batch::Packet,
+        random_generator: &mut Config::RandomGenerator,
+        subscriber: &mut Config::EventSubscriber,
+        packet_interceptor: &mut Config::PacketInterceptor,
+    ) {
+        let datagram = packet.datagram;
+
+        packet.result = match packet.take() {
+            endpoint::batch::State::Protected(protected) => self.handle_short_packet(
+                &datagram,
                 path_id,
-                &mut self.path_manager,
-                handshake_status,
-                &mut self.local_id_registry,
+                protected,
                 random_generator,
-                &mut publisher,
+                subscriber,
                 packet_interceptor,
-            )?;
-
-            // notify the connection a packet was processed
-            self.on_processed_packet(&processed_packet, subscriber)?;
-        }
+            ),
+            endpoint::batch::State::Decrypted(decrypted) => {
+                let cleartext = {
+                    let mut publisher =
+                        self.event_context.publisher(datagram.timestamp, subscriber);
+
+                    if let Some((space, _)) = self.space_manager.application_mut() {
+                        space.on_decrypted_packet(
+                            decrypted,
+                            &datagram,
+                            path_id,
+                            &self.path_manager[path_id],
+                            &mut publisher,
+                        )
+                    } else {
+                        return;
+                    }
+                };
 
-        Ok(())
+                cleartext.and_then(|cleartext| {
+                    self.handle_cleartext_short_packet(
+                        &datagram,
+                        path_id,
+                        cleartext,
+                        random_generator,
+                        subscriber,
+                        packet_interceptor,
+                    )
+                })
+            }
+            endpoint::batch::State::Processed => unreachable!("packet was already processed"),
+        };
     }
 
     /// Is called when a version negotiation packet had been received
@@ -1781,6 +1888,10 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         self.space_manager.application_protocol.clone()
     }
 
+    fn tls_session(&self) -> tls::SessionInfo {
+        self.space_manager.tls_session.clone()
+    }
+
     fn ping(&mut self) -> Result<(), connection::Error> {
         self.error?;
 
@@ -1817,6 +1928,24 @@ impl<Config: endpoint::Config> connection::Trait for ConnectionImpl<Config> {
         Ok(())
     }
 
+    fn initiate_key_update(&mut self) -> Result<(), connection::Error> {
+        self.error?;
+
+        if let Some((space, handshake_status)) = self.space_manager.application_mut() {
+            space.initiate_key_update(handshake_status);
+
+            self.wakeup_handle.wakeup();
+        } else {
+            debug_assert!(
+                false,
+                "applications can't interact with the connection until the application space is available"
+            );
+            return Err(connection::Error::unspecified());
+        }
+
+        Ok(())
+    }
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error> {
         Ok(*self.path_manager.active_path().handle.local_address())
     }
@@ -20,6 +20,7 @@ use s2n_codec::DecoderBufferMut;
 use s2n_quic_core::{
     application,
     application::ServerName,
+    crypto::tls,
     event::{self, builder::DatagramDropReason, supervisor, ConnectionPublisher, IntoEvent},
     inet::{DatagramInfo, SocketAddress},
     io::tx,
@@ -159,6 +160,25 @@ pub trait ConnectionTrait: 'static + Send + Sized {
         packet_interceptor: &mut <Self::Config as endpoint::Config>::PacketInterceptor,
     ) -> Result<(), ProcessingError>;
 
+    /// Is called when short packets had been received in consecutive datagrams on the same path
+    ///
+    /// The packet protection is removed from all of the packets in a single call, if possible.
+    /// Each packet still needs to be passed to `handle_batched_short_packet` after its datagram
+    /// has been received so the packets are processed in the order they arrived.
+    fn decrypt_short_packets(&mut self, path_id: path::Id, packets: &mut [endpoint::batch::Packet]);
+
+    /// Is called when a short packet from a batch had been received
+    ///
+    /// The outcome is stored in the packet's `result`.
+    fn handle_batched_short_packet(
+        &mut self,
+        path_id: path::Id,
+        packet: &mut endpoint::batch::Packet,
+        random_generator: &mut <Self::Config as endpoint::Config>::RandomGenerator,
+        subscriber: &mut <Self::Config as endpoint::Config>::EventSubscriber,
+        packet_interceptor: &mut <Self::Config as endpoint::Config>::PacketInterceptor,
+    );
+
     /// Is called when a version negotiation packet had been received
     fn handle_version_negotiation_packet(
         &mut self,
@@ -433,10 +453,14 @@ pub trait ConnectionTrait: 'static + Send + Sized {
 
     fn application_protocol(&self) -> Bytes;
 
+    fn tls_session(&self) -> tls::SessionInfo;
+
     fn ping(&mut self) -> Result<(), connection::Error>;
 
     fn keep_alive(&mut self, enabled: bool) -> Result<(), connection::Error>;
 
+    fn initiate_key_update(&mut self) -> Result<(), connection::Error>;
+
     fn local_address(&self) -> Result<SocketAddress, connection::Error>;
 
     fn remote_address(&self) -> Result<SocketAddress, connection::Error>;
@@ -90,7 +90,9 @@ struct LocalIdInfo {
     //# to the same value.
     sequence_number: u32,
     retirement_time: Option<Timestamp>,
-    stateless_reset_token: stateless_reset::Token,
+    // The token is only present for IDs that will be issued with a NEW_CONNECTION_ID frame and
+    // is cleared once that frame has been acknowledged.
+    stateless_reset_token: Option<stateless_reset::Token>,
     status: LocalIdStatus,
 }
 
@@ -222,12 +224,15 @@ impl Drop for LocalIdRegistry {
 
 impl LocalIdRegistry {
     /// Constructs a new `LocalIdRegistry` and registers the provided `handshake_connection_id`
+    ///
+    /// The `stateless_reset_token` for the handshake connection ID is optional since only
+    /// servers are able to communicate it to the peer, through the transport parameters.
     pub(crate) fn new(
         internal_id: InternalConnectionId,
         state: Arc<Mutex<ConnectionIdMapperState>>,
         handshake_connection_id: &connection::LocalId,
         handshake_connection_id_expiration_time: Option<Timestamp>,
-        stateless_reset_token: stateless_reset::Token,
+        stateless_reset_token: Option<stateless_reset::Token>,
     ) -> Self {
         let mut registry = Self {
             internal_id,
@@ -241,7 +246,7 @@ impl LocalIdRegistry {
             expiration_timer: Timer::default(),
         };
 
-        let _ = registry.register_connection_id(
+        let _ = registry.register(
             handshake_connection_id,
             handshake_connection_id_expiration_time,
             stateless_reset_token,
@@ -290,6 +295,15 @@ impl LocalIdRegistry {
         id: &connection::LocalId,
         expiration: Option<Timestamp>,
         stateless_reset_token: stateless_reset::Token,
+    ) -> Result<(), LocalIdRegistrationError> {
+        self.register(id, expiration, Some(stateless_reset_token))
+    }
+
+    fn register(
+        &mut self,
+        id: &connection::LocalId,
+        expiration: Option<Timestamp>,
+        stateless_reset_token: Option<stateless_reset::Token>,
     ) -> Result<(), LocalIdRegistrationError> {
         if self.registered_ids.iter().any(|id_info| id_info.id == *id) {
             //= https://www.rfc-editor.org/rfc/rfc9000#section-5.1
@@ -484,12 +498,16 @@ impl LocalIdRegistry {
             .iter_mut()
             .filter(|id_info| id_info.status.can_transmit(constraint))
         {
+            let stateless_reset_token = id_info
+                .stateless_reset_token
+                .as_ref()
+                .expect("IDs pending issuance are registered with a stateless reset token");
+
             if let Some(packet_number) = context.write_frame(&frame::NewConnectionId {
                 sequence_number: id_info.sequence_number.into(),
                 retire_prior_to: self.retire_prior_to.into(),
                 connection_id: id_info.id.as_bytes(),
-                stateless_reset_token: id_info
-                    .stateless_reset_token
+                stateless_reset_token: stateless_reset_token
                     .as_ref()
                     .try_into()
                     .expect("Length is already checked"),
@@ -507,7 +525,7 @@ impl LocalIdRegistry {
                     id_info.status = Active;
                     // Once the NEW_CONNECTION_ID is acknowledged, we don't need the
                     // stateless reset token anymore.
-                    id_info.stateless_reset_token = stateless_reset::Token::ZEROED;
+                    id_info.stateless_reset_token = None;
                 }
             }
         }
@@ -603,7 +621,7 @@ impl LocalIdRegistry {
         }
     }
 
-    fn validate_new_connection_id(&self, new_token: stateless_reset::Token) {
+    fn validate_new_connection_id(&self, new_token: Option<stateless_reset::Token>) {
         if cfg!(debug_assertions) {
             assert!(
                 self.registered_ids
@@ -615,14 +633,16 @@ impl LocalIdRegistry {
                 self.active_connection_id_limit
             );
 
-            assert!(
-                !self
-                    .registered_ids
-                    .iter()
-                    .map(|id_info| id_info.stateless_reset_token)
-                    .any(|token| token == new_token),
-                "Registered a duplicate stateless reset token"
-            );
+            if let Some(new_token) = new_token {
+                assert!(
+                    !self
+                        .registered_ids
+                        .iter()
+                        .filter_map(|id_info| id_info.stateless_reset_token)
+                        .any(|token| token == new_token),
+                    "Registered a duplicate stateless reset token"
+                );
+            }
         }
     }
 }
@@ -57,7 +57,7 @@ fn mapper(
         InternalConnectionIdGenerator::new().generate_id(),
         &handshake_id,
         handshake_id_expiration_time,
-        token,
+        Some(token),
     );
     (mapper, registry)
 }
@@ -151,13 +151,13 @@ fn connection_mapper_test() {
         id1,
         &ext_id_1,
         Some(handshake_id_expiration_time),
-        TEST_TOKEN_1,
+        Some(TEST_TOKEN_1),
     );
     let mut reg2 = mapper.create_local_id_registry(
         id2,
         &ext_id_3,
         Some(handshake_id_expiration_time),
-        TEST_TOKEN_3,
+        Some(TEST_TOKEN_3),
     );
 
     reg1.set_active_connection_id_limit(3);
@@ -177,7 +177,7 @@ fn connection_mapper_test() {
     );
     assert_eq!(Some(id1), mapper.lookup_internal_connection_id(&ext_id_1));
     assert_eq!(
-        TEST_TOKEN_1,
+        Some(TEST_TOKEN_1),
         reg1.get_connection_id_info(&ext_id_1)
             .unwrap()
             .stateless_reset_token
@@ -504,6 +504,60 @@ fn endpoint_may_limit_connection_ids() {
     );
 }
 
+#[test]
+fn client_handshake_id_without_token() {
+    let ext_id_1 = id(b"id01");
+    let ext_id_2 = id(b"id02");
+
+    let mut random_generator = random::testing::Generator(123);
+    let mut mapper = ConnectionIdMapper::new(&mut random_generator, endpoint::Type::Client);
+    let mut reg1 = mapper.create_local_id_registry(
+        InternalConnectionIdGenerator::new().generate_id(),
+        &ext_id_1,
+        None,
+        None,
+    );
+
+    assert_eq!(
+        None,
+        reg1.get_connection_id_info(&ext_id_1)
+            .unwrap()
+            .stateless_reset_token
+    );
+
+    reg1.set_active_connection_id_limit(2);
+
+    // Connection IDs issued by the client after the handshake carry a token
+    assert!(reg1
+        .register_connection_id(&ext_id_2, None, TEST_TOKEN_2)
+        .is_ok());
+
+    let mut frame_buffer = OutgoingFrameBuffer::new();
+    let mut write_context = MockWriteContext::new(
+        s2n_quic_platform::time::now(),
+        &mut frame_buffer,
+        transmission::Constraint::None,
+        transmission::Mode::Normal,
+        endpoint::Type::Client,
+    );
+    reg1.on_transmit(&mut write_context);
+
+    let expected_frame = Frame::NewConnectionId(NewConnectionId {
+        sequence_number: VarInt::from_u32(1),
+        retire_prior_to: VarInt::from_u32(0),
+        connection_id: ext_id_2.as_bytes(),
+        stateless_reset_token: TEST_TOKEN_2.as_ref().try_into().unwrap(),
+    });
+
+    assert_eq!(
+        expected_frame,
+        write_context.frame_buffer.pop_front().unwrap().as_frame()
+    );
+
+    // Only the issued connection ID was transmitted
+    assert!(write_context.frame_buffer.is_empty());
+}
+
 #[test]
 fn on_transmit() {
     let ext_id_1 = id(b"id01");
@@ -712,7 +766,7 @@ fn on_packet_ack_and_loss() {
         reg1.get_connection_id_info(&ext_id_2).unwrap().status
     );
     assert_eq!(
-        stateless_reset::Token::ZEROED,
+        None,
         reg1.get_connection_id_info(&ext_id_2)
             .unwrap()
             .stateless_reset_token
@@ -525,6 +525,22 @@ impl PeerIdRegistry {
         self.consume_new_id_inner()
     }
 
+    /// Retires a peer_id that is no longer used by any path
+    ///
+    /// Only IDs in the `InUse` status are retired; the initial connection ID is
+    /// retired once a NEW_CONNECTION_ID frame is received from the peer.
+    pub fn retire_id(&mut self, peer_id: &connection::PeerId) {
+        for id_info in self.registered_ids.iter_mut() {
+            if id_info.id == *peer_id && id_info.status == InUse {
+                //= https://www.rfc-editor.org/rfc/rfc9000#section-19.16
+                //# An endpoint sends a RETIRE_CONNECTION_ID frame (type=0x19) to
+                //# indicate that it will no longer use a connection ID that was issued
+                //# by its peer.
+                id_info.status = PendingRetirement;
+            }
+        }
+    }
+
     // Validate that the ACTIVE_CONNECTION_ID_LIMIT has not been exceeded
     fn check_active_connection_id_limit(
         &self,
@@ -429,6 +429,30 @@ pub fn consume_new_id_should_return_id() {
         .is_some());
 }
 
+#[test]
+pub fn retire_id_should_only_retire_in_use_ids() {
+    let id_1 = id(b"id01");
+    let mut random_generator = random::testing::Generator(123);
+    let mut mapper = ConnectionIdMapper::new(&mut random_generator, endpoint::Type::Server);
+    let mut reg = mapper
+        .create_server_peer_id_registry(InternalConnectionIdGenerator::new().generate_id(), id_1);
+
+    let id_2 = id(b"id02");
+    let id_3 = id(b"id03");
+    assert!(reg.on_new_connection_id(&id_2, 1, 0, &TEST_TOKEN_2).is_ok());
+    assert!(reg.on_new_connection_id(&id_3, 2, 0, &TEST_TOKEN_3).is_ok());
+    assert_eq!(Some(id_2), reg.consume_new_id_for_new_path());
+
+    // New ids have not been used yet so there is nothing to retire
+    reg.retire_id(&id_3);
+    assert_eq!(New, reg.registered_ids[2].status);
+    assert!(reg.is_active(&id_3));
+
+    reg.retire_id(&id_2);
+    assert_eq!(PendingRetirement, reg.registered_ids[1].status);
+    assert!(!reg.is_active(&id_2));
+}
+
 #[test]
 pub fn consume_new_id_should_error_if_no_ids_are_available() {
     let id_1 = id(b"id01");
@@ -0,0 +1,140 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Groups short header packets which are received for the same connection in consecutive
+//! datagrams so the packet protection can be removed in a single call.
+
+use crate::{
+    connection::{self, InternalConnectionId, ProcessingError},
+    space::{DecryptedShort, DECRYPT_BATCH_LEN},
+};
+use s2n_codec::DecoderBufferMut;
+use s2n_quic_core::{
+    inet::DatagramInfo, packet::short::ProtectedShort, path::Handle,
+    stateless_reset::token::LEN as StatelessResetTokenLen,
+};
+use smallvec::SmallVec;
+
+/// The processing stage of a batched packet
+#[derive(Debug)]
+pub enum State<'a> {
+    /// The packet protection hasn't been removed yet
+    Protected(ProtectedShort<'a>),
+    /// The packet protection was removed together with the other packets in the batch
+    Decrypted(DecryptedShort<'a>),
+    /// The packet was taken by the connection
+    Processed,
+}
+
+/// A short header packet which was the only packet in its datagram
+#[derive(Debug)]
+pub struct Packet<'a> {
+    pub datagram: DatagramInfo,
+    /// The packet, which is taken by the connection once it is processed
+    pub state: State<'a>,
+    /// The outcome of processing the packet
+    pub result: Result<(), ProcessingError>,
+    /// The last bytes of the datagram, which are compared to the stateless reset tokens if
+    /// the packet can't be decrypted
+    stateless_reset_candidate: Option<[u8; StatelessResetTokenLen]>,
+}
+
+impl<'a> Packet<'a> {
+    #[inline]
+    pub fn new(
+        datagram: DatagramInfo,
+        packet: ProtectedShort<'a>,
+        stateless_reset_candidate: Option<[u8; StatelessResetTokenLen]>,
+    ) -> Self {
+        Self {
+            datagram,
+            state: State::Protected(packet),
+            result: Ok(()),
+            stateless_reset_candidate,
+        }
+    }
+
+    /// Takes the packet so it can be processed by the connection
+    #[inline]
+    pub fn take(&mut self) -> State<'a> {
+        core::mem::replace(&mut self.state, State::Processed)
+    }
+
+    /// Returns the error if the packet exceeded the integrity limit when the batch was decrypted
+    ///
+    /// The failure was counted even if the datagram is rejected afterwards, so the error still
+    /// needs to close the connection.
+    #[inline]
+    pub fn integrity_limit_error(&self) -> Option<connection::Error> {
+        match &self.state {
+            State::Decrypted(decrypted) => decrypted.integrity_limit_error(),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes which should be checked for a stateless reset token
+    ///
+    /// This is only the case when the packet failed to decrypt.
+    #[inline]
+    pub fn stateless_reset_candidate(&self) -> Option<&[u8]> {
+        match self.result {
+            Err(ProcessingError::CryptoError(_)) => {
+                self.stateless_reset_candidate.as_ref().map(|c| &c[..])
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Copies the trailing bytes of a datagram before it is decoded
+///
+/// Decoding the packet borrows the datagram until the batch is processed so the bytes need to
+/// be copied in case they turn out to be a stateless reset.
+#[inline]
+pub fn stateless_reset_candidate(
+    payload: &DecoderBufferMut,
+) -> Option<[u8; StatelessResetTokenLen]> {
+    let payload = payload.peek().into_less_safe_slice();
+    let index = payload.len().checked_sub(StatelessResetTokenLen)?;
+    let mut candidate = [0; StatelessResetTokenLen];
+    candidate.copy_from_slice(&payload[index..]);
+    Some(candidate)
+}
+
+/// Short header packets which were received for the same connection on the same path
+#[derive(Debug)]
+pub struct Batch<'a, H: Handle> {
+    pub internal_id: InternalConnectionId,
+    pub path_handle: H,
+    pub packets: SmallVec<[Packet<'a>; DECRYPT_BATCH_LEN]>,
+}
+
+impl<'a, H: Handle> Batch<'a, H> {
+    #[inline]
+    pub fn new(internal_id: InternalConnectionId, path_handle: H) -> Self {
+        Self {
+            internal_id,
+            path_handle,
+            packets: SmallVec::new(),
+        }
+    }
+
+    /// Returns `true` if the packet can be added to the batch
+    #[inline]
+    pub fn can_push(&self, internal_id: InternalConnectionId, path_handle: &H) -> bool {
+        self.internal_id == internal_id
+            && self.path_handle.strict_eq(path_handle)
+            && !self.is_full()
+    }
+
+    #[inline]
+    pub fn push(&mut self, packet: Packet<'a>) {
+        debug_assert!(!self.is_full());
+        self.packets.push(packet);
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.packets.len() >= DECRYPT_BATCH_LEN
+    }
+}
@@ -17,6 +17,7 @@ use s2n_codec::DecoderBufferMut;
 use s2n_quic_core::{
     crypto::{tls, tls::Endpoint as TLSEndpoint, CryptoSuite, InitialKey},
     datagram::{Endpoint, PreConnectionInfo},
+    endpoint::{limits::ConnectionOpened, Limiter as _},
     event::{self, supervisor, ConnectionPublisher, IntoEvent, Subscriber as _},
     inet::{datagram, DatagramInfo},
     packet::initial::ProtectedInitial,
@@ -134,7 +135,7 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
             internal_connection_id,
             &initial_connection_id,
             initial_connection_id_expiration_time,
-            stateless_reset_token,
+            Some(stateless_reset_token),
         );
 
         let peer_id_registry = self
@@ -404,8 +405,18 @@ impl<Config: endpoint::Config> endpoint::Endpoint<Config> {
         // Otherwise the connection will automatically get dropped. This
         // will also clean up all state which was already allocated for
         // the connection
-        self.connections
-            .insert_server_connection(connection, internal_connection_id);
+        if self
+            .connections
+            .insert_server_connection(connection, internal_connection_id)
+        {
+            // The limiter is only notified about connections which were actually inserted,
+            // since it won't be notified when a dropped connection closes
+            let info = ConnectionOpened::new(&remote_address, datagram.timestamp.into_event());
+            self.config
+                .context()
+                .endpoint_limits
+                .on_connection_opened(&info);
+        }
 
         Ok(())
     }
@@ -29,7 +29,10 @@ use s2n_quic_core::{
     },
     crypto::{tls, tls::Endpoint as _, CryptoSuite, InitialKey},
     datagram::{Endpoint as DatagramEndpoint, PreConnectionInfo},
-    endpoint::{limits::Outcome, Limiter as _},
+    endpoint::{
+        limits::{ConnectionClosed, Outcome},
+        Limiter as _,
+    },
     event::{
         self, supervisor, ConnectionPublisher, EndpointPublisher as _, IntoEvent, Subscriber as _,
     },
@@ -45,6 +48,7 @@ use s2n_quic_core::{
     transport::parameters::ClientTransportParameters,
 };
 
+pub(crate) mod batch;
 pub mod close;
 mod config;
 pub mod connect;
@@ -104,24 +108,38 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
 
         let local_address = queue.local_address();
         let entries = queue.as_slice_mut();
+        let len = entries.len();
         let mut now: Option<Timestamp> = None;
 
-        for entry in entries.iter_mut() {
-            let timestamp = match now {
-                Some(time) => time,
-                None => {
-                    now = Some(clock.get_time());
-                    now.expect("value should be set")
+        {
+            // The batch borrows the entries so it needs to be processed before the queue is
+            // finished
+            let mut batch = None;
+
+            for entry in entries.iter_mut() {
+                let timestamp = match now {
+                    Some(time) => time,
+                    None => {
+                        now = Some(clock.get_time());
+                        now.expect("value should be set")
+                    }
+                };
+
+                if let Some((header, payload)) = entry.read(&local_address) {
+                    self.receive_datagram(&header, payload, timestamp, &mut batch)
                 }
-            };
+            }
 
-            if let Some((header, payload)) = entry.read(&local_address) {
-                self.receive_datagram(&header, payload, timestamp)
+            if let Some(batch) = batch {
+                self.receive_batch(batch);
             }
         }
 
-        let len = entries.len();
         queue.finish(len);
+
+        if let Some(timestamp) = now {
+            self.on_closed_connections(timestamp);
+        }
     }
 
     fn transmit<Tx, C>(&mut self, queue: &mut Tx, clock: &C)
@@ -166,6 +184,8 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
             self.stateless_reset_dispatch
                 .on_transmit(queue, &mut publisher);
         }
+
+        self.on_closed_connections(timestamp);
     }
 
     fn poll_wakeups<C: Clock>(
@@ -248,6 +268,10 @@ impl<Cfg: Config> s2n_quic_core::endpoint::Endpoint for Endpoint<Cfg> {
             }
         }
 
+        if let Some(timestamp) = now {
+            self.on_closed_connections(timestamp);
+        }
+
         if wakeup_count > 0 {
             Poll::Ready(Ok(wakeup_count))
         } else {
@@ -319,6 +343,16 @@ impl<Cfg: Config> Endpoint<Cfg> {
         (endpoint, handle)
     }
 
+    /// Notifies the endpoint limiter of all connections which have been removed from the container
+    fn on_closed_connections(&mut self, timestamp: Timestamp) {
+        let endpoint_limits = self.config.context().endpoint_limits;
+
+        for remote_address in self.connections.drain_closed_connections() {
+            let info = ConnectionClosed::new(&remote_address, timestamp.into_event());
+            endpoint_limits.on_connection_closed(&info);
+        }
+    }
+
     /// Determine the next step when a peer attempts a connection
     fn connection_allowed(
         &mut self,
@@ -414,12 +448,130 @@ impl<Cfg: Config> Endpoint<Cfg> {
         }
     }
 
+    /// Dispatches a batch of short packets to the connection they were received for
+    fn receive_batch(&mut self, mut batch: batch::Batch<Cfg::PathHandle>) {
+        let endpoint_context = self.config.context();
+        let close_packet_buffer = &mut self.close_packet_buffer;
+        let max_mtu = self.max_mtu;
+        let path_handle = &batch.path_handle;
+        let packets = &mut batch.packets;
+
+        let _ = self.connections.with_connection(batch.internal_id, |conn| {
+            let mut is_decrypted = false;
+
+            // Each datagram is received right before its packet is processed so events and
+            // anti-amplification credits are ordered in the same way as unbatched datagrams
+            for index in 0..packets.len() {
+                let datagram = packets[index].datagram;
+
+                // The path `Id` needs to be passed around instead of the path to get around `&mut self` and
+                // `&mut self.path_manager` being borrowed at the same time
+                let path_id = match conn.on_datagram_received(
+                    path_handle,
+                    &datagram,
+                    endpoint_context.congestion_controller,
+                    endpoint_context.path_migration,
+                    max_mtu,
+                    endpoint_context.event_subscriber,
+                ) {
+                    Ok(path_id) => path_id,
+                    Err(datagram_drop_reason) => {
+                        // An error received at this point was caused by a datagram that has not
+                        // been authenticated yet, and thus the connection should not be closed.
+                        conn.with_event_publisher(
+                            datagram.timestamp,
+                            None,
+                            endpoint_context.event_subscriber,
+                            |publisher, _path| {
+                                publisher.on_datagram_dropped(event::builder::DatagramDropped {
+                                    len: datagram.payload_len as u16,
+                                    reason: datagram_drop_reason,
+                                });
+                            },
+                        );
+
+                        //= https://www.rfc-editor.org/rfc/rfc9001#section-6.6
+                        //# If the total number of received packets that fail
+                        //# authentication within the connection, across all keys, exceeds the
+                        //# integrity limit for the selected AEAD, the endpoint MUST immediately
+                        //# close the connection with a connection error of type
+                        //# AEAD_LIMIT_REACHED and not process any more packets.
+                        if let Some(err) = packets[index].integrity_limit_error() {
+                            conn.close(
+                                err,
+                                endpoint_context.connection_close_formatter,
+                                close_packet_buffer,
+                                datagram.timestamp,
+                                endpoint_context.event_subscriber,
+                                endpoint_context.packet_interceptor,
+                            );
+                            return Err(());
+                        }
+
+                        continue;
+                    }
+                };
+
+                // The remaining packets were received with the same path handle so they're
+                // decrypted together once the first datagram is accepted. Their authentication
+                // failures are counted even if a later datagram is rejected, in which case the
+                // integrity limit error is carried by the rejected packet.
+                if !is_decrypted {
+                    conn.decrypt_short_packets(path_id, &mut packets[index..]);
+                    is_decrypted = true;
+                }
+
+                let packet = &mut packets[index];
+
+                //= https://www.rfc-editor.org/rfc/rfc9000#section-10.2.1
+                //# An endpoint
+                //# that is closing is not required to process any received frame.
+                conn.handle_batched_short_packet(
+                    path_id,
+                    packet,
+                    endpoint_context.random_generator,
+                    endpoint_context.event_subscriber,
+                    endpoint_context.packet_interceptor,
+                );
+
+                // Other errors are handled in the same way as a single packet: duplicates
+                // are discarded and decryption failures are checked for a stateless reset.
+                // Once the connection is closed, the rest of the batch is not processed.
+                if let Err(ProcessingError::ConnectionError(err)) = packet.result {
+                    conn.close(
+                        err,
+                        endpoint_context.connection_close_formatter,
+                        close_packet_buffer,
+                        datagram.timestamp,
+                        endpoint_context.event_subscriber,
+                        endpoint_context.packet_interceptor,
+                    );
+                    return Err(());
+                }
+            }
+
+            Ok(())
+        });
+
+        //= https://www.rfc-editor.org/rfc/rfc9000#section-10.3.1
+        //# Endpoints MAY skip this check if any packet from a datagram is
+        //# successfully processed.  However, the comparison MUST be performed
+        //# when the first packet in an incoming datagram either cannot be
+        //# associated with a connection, or cannot be decrypted.
+        for packet in batch.packets.iter() {
+            if let Some(candidate) = packet.stateless_reset_candidate() {
+                self.close_on_matching_stateless_reset(candidate, packet.datagram.timestamp);
+            }
+        }
+    }
+
     /// Ingests a single datagram
-    fn receive_datagram(
+    fn receive_datagram<'a>(
         &mut self,
         header: &datagram::Header<Cfg::PathHandle>,
-        payload: &mut [u8],
+        payload: &'a mut [u8],
         timestamp: Timestamp,
+        batch: &mut Option<batch::Batch<'a, Cfg::PathHandle>>,
     ) {
         let endpoint_context = self.config.context();
 
@@ -445,6 +597,13 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 .intercept_rx_datagram(&subject, &datagram, buffer)
         };
 
+        // Decoding the packet borrows the payload so the bytes which may contain a stateless
+        // reset token are copied in case the packet ends up in a batch
+        let stateless_reset_candidate = batch::stateless_reset_candidate(&buffer);
+        let stateless_reset_bytes = stateless_reset_candidate
+            .as_ref()
+            .map_or(&[][..], |candidate| &candidate[..]);
+
         let connection_info = ConnectionInfo::new(&remote_address);
         let (packet, remaining) = if let Ok((packet, remaining)) = ProtectedPacket::decode(
             buffer,
@@ -461,8 +620,14 @@ impl<Cfg: Config> Endpoint<Cfg> {
             //# valid stateless reset token as a Stateless Reset, as other QUIC
             //# versions might allow the use of a long header.
 
+            // Any pending packets were received before this datagram so they are processed first
+            if let Some(batch) = batch.take() {
+                self.receive_batch(batch);
+            }
+
             // The packet may be a stateless reset, check before returning.
-            let internal_connection_id = self.close_on_matching_stateless_reset(payload, timestamp);
+            let internal_connection_id =
+                self.close_on_matching_stateless_reset(stateless_reset_bytes, timestamp);
 
             if internal_connection_id.is_none() {
                 // The packet didn't contain a valid stateless token
@@ -536,14 +701,60 @@ impl<Cfg: Config> Endpoint<Cfg> {
         };
 
         // TODO validate the connection ID before looking up the connection in the map
+        let internal_id = self
+            .connection_id_mapper
+            .lookup_internal_connection_id(&datagram.destination_connection_id);
+
+        // Short packets which are the only packet in a datagram are batched with the packets
+        // from the following datagrams for the same connection so the packet protection can be
+        // removed in a single call.
+        let packet = match (internal_id, packet) {
+            (Some(internal_id), ProtectedPacket::Short(packet)) if remaining.is_empty() => {
+                let packet = batch::Packet::new(*datagram, packet, stateless_reset_candidate);
+
+                match batch {
+                    Some(batch) if batch.can_push(internal_id, &header.path) => batch.push(packet),
+                    _ => {
+                        if let Some(batch) = batch.take() {
+                            self.receive_batch(batch);
+                        }
+                        let mut pending = batch::Batch::new(internal_id, header.path);
+                        pending.push(packet);
+                        *batch = Some(pending);
+                    }
+                }
+
+                if batch.as_ref().map_or(false, |batch| batch.is_full()) {
+                    if let Some(batch) = batch.take() {
+                        self.receive_batch(batch);
+                    }
+                }
+
+                return;
+            }
+            (_, packet) => packet,
+        };
+
+        // Any pending packets were received before this datagram so they are processed first
+        if let Some(batch) = batch.take() {
+            self.receive_batch(batch);
+        }
+
+        // processing the batch required the whole endpoint so the context is reacquired
+        let endpoint_context = self.config.context();
+        let mut publisher = event::EndpointPublisherSubscriber::new(
+            event::builder::EndpointMeta {
+                endpoint_type: Cfg::ENDPOINT_TYPE,
+                timestamp,
+            },
+            packet.version(),
+            endpoint_context.event_subscriber,
+        );
         let close_packet_buffer = &mut self.close_packet_buffer;
 
         // Try to lookup the internal connection ID and dispatch the packet
         // to the Connection
-        if let Some(internal_id) = self
-            .connection_id_mapper
-            .lookup_internal_connection_id(&datagram.destination_connection_id)
-        {
+        if let Some(internal_id) = internal_id {
             let mut check_for_stateless_reset = false;
             let max_mtu = self.max_mtu;
 
@@ -670,7 +881,7 @@ impl<Cfg: Config> Endpoint<Cfg> {
             });
 
             if check_for_stateless_reset {
-                self.close_on_matching_stateless_reset(payload, timestamp);
+                self.close_on_matching_stateless_reset(stateless_reset_bytes, timestamp);
             }
 
             return;
@@ -803,7 +1014,7 @@ impl<Cfg: Config> Endpoint<Cfg> {
                 //# valid stateless reset token as a Stateless Reset, as other QUIC
                 //# versions might allow the use of a long header.
                 let is_stateless_reset = self
-                    .close_on_matching_stateless_reset(payload, timestamp)
+                    .close_on_matching_stateless_reset(stateless_reset_bytes, timestamp)
                     .is_some();
 
                 //= https://www.rfc-editor.org/rfc/rfc9000#section-9.3.2
@@ -986,22 +1197,15 @@ impl<Cfg: Config> Endpoint<Cfg> {
             .lifetime()
             .map(|duration| timestamp + duration);
 
-        let local_id_registry = {
-            // TODO: the client currently generates a random stateless_reset_token but doesnt
-            // transmit it. Refactor `create_local_id_registry` to instead accept None for
-            // stateless_reset_token.
-            let stateless_reset_token = self
-                .config
-                .context()
-                .stateless_reset_token_generator
-                .generate(local_connection_id.as_bytes());
-            self.connection_id_mapper.create_local_id_registry(
-                internal_connection_id,
-                &local_connection_id,
-                local_connection_id_expiration_time,
-                stateless_reset_token,
-            )
-        };
+        // The client has no way of communicating a stateless reset token for the handshake
+        // connection ID so it is registered without one. Connection IDs issued later on with
+        // NEW_CONNECTION_ID frames carry a token from the configured generator.
+        let local_id_registry = self.connection_id_mapper.create_local_id_registry(
+            internal_connection_id,
+            &local_connection_id,
+            local_connection_id_expiration_time,
+            None,
+        );
 
         let endpoint_context = self.config.context();
 
@@ -1144,6 +1348,7 @@ impl<Cfg: Config> Endpoint<Cfg> {
         let connection = <Cfg as crate::endpoint::Config>::Connection::new(connection_parameters)?;
         self.connections
             .insert_client_connection(connection, internal_connection_id, sender);
+
         Ok(())
     }
 }
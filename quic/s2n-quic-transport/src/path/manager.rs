@@ -9,6 +9,7 @@ use crate::{
     path::{challenge, Path},
     transmission,
 };
+use core::time::Duration;
 use s2n_quic_core::{
     ack,
     connection::{self, PeerId},
@@ -33,7 +34,8 @@ use s2n_quic_core::{
 use smallvec::SmallVec;
 
 /// The amount of Paths that can be maintained without using the heap.
-/// This value is also used to limit the number of connection migrations.
+/// This value is also used to limit the number of concurrent paths; paths that
+/// are no longer in use are deleted and their slots are reused.
 const MAX_ALLOWED_PATHS: usize = 5;
 
 /// The PathManager handles paths for a specific connection.
@@ -63,11 +65,15 @@ pub struct Manager<Config: endpoint::Config> {
     /// creating new paths with garbage data and preventing the peer to migrate paths.
     ///
     /// Note that it doesn't prevent an on-path attacker from observing/forwarding
-    /// authenticated packets from bogus addresses. Because of the current hard limit
-    /// of `MAX_ALLOWED_PATHS`, this will prevent the peer from migrating, if it needs to.
-    /// The `paths` data structure will need to be enhanced to include garbage collection
-    /// of old paths to overcome this limitation.
+    /// authenticated packets from bogus addresses. Such paths fail validation and are
+    /// eventually deleted, freeing their slot for the peer to migrate.
     pending_packet_authentication: Option<u8>,
+
+    /// Paths which were deleted since the last call to `drain_deleted_paths`
+    ///
+    /// Other components reference paths by id, which will be reused for the next path, so
+    /// they are notified before any packets are sent on it.
+    deleted_paths: SmallVec<[u8; MAX_ALLOWED_PATHS]>,
 }
 
 impl<Config: endpoint::Config> Manager<Config> {
@@ -78,6 +84,7 @@ impl<Config: endpoint::Config> Manager<Config> {
             active: 0,
             last_known_active_validated_path: None,
             pending_packet_authentication: None,
+            deleted_paths: SmallVec::new(),
         };
         manager.paths[0].activated = true;
         manager.paths[0].is_active = true;
@@ -197,7 +204,7 @@ impl<Config: endpoint::Config> Manager<Config> {
         self.paths
             .iter()
             .enumerate()
-            .find(|(_id, path)| Path::eq_by_handle(path, handle))
+            .find(|(_id, path)| !path.is_deleted() && Path::eq_by_handle(path, handle))
             .map(|(id, path)| (path_id(id as u8), path))
     }
 
@@ -207,7 +214,7 @@ impl<Config: endpoint::Config> Manager<Config> {
         self.paths
             .iter_mut()
             .enumerate()
-            .find(|(_id, path)| Path::eq_by_handle(path, handle))
+            .find(|(_id, path)| !path.is_deleted() && Path::eq_by_handle(path, handle))
             .map(|(id, path)| (path_id(id as u8), path))
     }
 
@@ -258,6 +265,7 @@ impl<Config: endpoint::Config> Manager<Config> {
                 return Err(DatagramDropReason::InvalidSourceConnectionId);
             }
 
+            path.on_datagram_received(datagram.timestamp);
             let unblocked = path.on_bytes_received(datagram.payload_len);
             return Ok((id, unblocked));
         }
@@ -351,19 +359,20 @@ impl<Config: endpoint::Config> Manager<Config> {
         // Determine which index will be used for the newly created path
         //
         // If a previously allocated path failed to contain an authenticated packet, we
-        // use that index instead of pushing on to the end.
+        // use that index instead of pushing on to the end. Otherwise the slot of a deleted
+        // path is reused, if one is available.
         let new_path_idx = if let Some(idx) = self.pending_packet_authentication {
             idx as _
         } else {
-            let idx = self.paths.len();
-            self.pending_packet_authentication = Some(idx as _);
-            idx
+            self.delete_expired_paths(datagram.timestamp, publisher);
+            self.paths
+                .iter()
+                .position(|path| path.is_deleted())
+                .unwrap_or_else(|| self.paths.len())
         };
 
-        // TODO: Support deletion of old paths: https://github.com/aws/s2n-quic/issues/741
-        // The current path manager implementation does not delete or reuse indices
-        // in the path array. This can result in an unbounded number of paths. To prevent
-        // this we limit the max number of paths per connection.
+        // Path ids are used to reference paths from other components so the path array is
+        // bounded rather than growing as the peer migrates.
         if new_path_idx >= MAX_ALLOWED_PATHS {
             return Err(DatagramDropReason::PathLimitExceeded);
         }
@@ -433,6 +442,7 @@ impl<Config: endpoint::Config> Manager<Config> {
             max_mtu,
         );
 
+        path.on_datagram_received(datagram.timestamp);
         let unblocked = path.on_bytes_received(datagram.payload_len);
 
         let active_path = self.active_path();
@@ -450,10 +460,17 @@ impl<Config: endpoint::Config> Manager<Config> {
 
         // create a new path
         if new_path_idx < self.paths.len() {
+            // The connection id of a path which failed packet authentication is no longer used
+            if !self.paths[new_path_idx].is_deleted()
+                && self.paths[new_path_idx].peer_connection_id != path.peer_connection_id
+            {
+                self.retire_peer_connection_id(new_path_idx);
+            }
             self.paths[new_path_idx] = path;
         } else {
             self.paths.push(path);
         }
+        self.pending_packet_authentication = Some(new_path_idx as u8);
 
         Ok((new_path_id, unblocked))
     }
@@ -475,11 +492,7 @@ impl<Config: endpoint::Config> Manager<Config> {
         //# could have a longer round-trip time than the original.  A value of
         //# three times the larger of the current PTO or the PTO for the new path
         //# (using kInitialRtt, as defined in [QUIC-RECOVERY]) is RECOMMENDED.
-        let abandon_duration = self[path_id].pto_period(PacketNumberSpace::ApplicationData);
-        let abandon_duration = 3 * abandon_duration.max(
-            self.active_path()
-                .pto_period(PacketNumberSpace::ApplicationData),
-        );
+        let abandon_duration = self.path_timeout(path_id);
 
         //= https://www.rfc-editor.org/rfc/rfc9000#section-9
         //# An endpoint MUST
@@ -493,6 +506,78 @@ impl<Config: endpoint::Config> Manager<Config> {
         self[path_id].set_challenge(challenge);
     }
 
+    /// Returns three times the larger of the PTO for the active path or the given path
+    #[inline]
+    fn path_timeout(&self, path_id: Id) -> Duration {
+        let pto = self[path_id].pto_period(PacketNumberSpace::ApplicationData);
+        3 * pto.max(
+            self.active_path()
+                .pto_period(PacketNumberSpace::ApplicationData),
+        )
+    }
+
+    /// Deletes paths that are no longer in use
+    ///
+    /// A path is deleted if it is not the active, last known validated, or pending
+    /// authentication path, has no outstanding path validation or data in flight, and
+    /// either failed validation or has been idle for longer than the path timeout.
+    fn delete_expired_paths<Pub: event::ConnectionPublisher>(
+        &mut self,
+        timestamp: Timestamp,
+        publisher: &mut Pub,
+    ) {
+        for idx in 0..self.paths.len() {
+            let id = path_id(idx as u8);
+
+            if self.last_known_active_validated_path == Some(idx as u8)
+                || self.pending_packet_authentication == Some(idx as u8)
+                || !self[id].can_delete()
+            {
+                continue;
+            }
+
+            let reason = if self[id].failed_validation() {
+                PathDeletedReason::ValidationFailed
+            } else if self[id].is_idle(timestamp, self.path_timeout(id)) {
+                PathDeletedReason::Idle
+            } else {
+                continue;
+            };
+
+            self.retire_peer_connection_id(idx);
+
+            let path = &self[id];
+            publisher.on_path_deleted(event::builder::PathDeleted {
+                path: path_event!(path, id),
+                reason,
+            });
+
+            self[id].on_deleted();
+            self.deleted_paths.push(idx as u8);
+        }
+    }
+
+    /// Returns the ids of the paths which were deleted since the last call
+    #[inline]
+    pub fn drain_deleted_paths(&mut self) -> impl Iterator<Item = Id> + '_ {
+        self.deleted_paths.drain(..).map(path_id)
+    }
+
+    /// Retires the peer connection id of the given path if no other path is using it
+    fn retire_peer_connection_id(&mut self, idx: usize) {
+        let peer_connection_id = self.paths[idx].peer_connection_id;
+
+        // Paths created from NAT rebinding may share a connection id, which must
+        // remain active until all of the paths using it have been deleted.
+        let is_shared = self.paths.iter().enumerate().any(|(other_idx, path)| {
+            other_idx != idx && !path.is_deleted() && path.peer_connection_id == peer_connection_id
+        });
+
+        if !is_shared {
+            self.peer_id_registry.retire_id(&peer_connection_id);
+        }
+    }
+
     /// Returns true if a valid initial packet has been received
     pub fn valid_initial_received(&self) -> bool {
         if Config::ENDPOINT_TYPE.is_server() {
@@ -719,7 +804,9 @@ impl<Config: endpoint::Config> Manager<Config> {
         publisher: &mut Pub,
     ) -> Result<(), connection::Error> {
         for (id, path) in self.paths.iter_mut().enumerate() {
-            path.on_timeout(timestamp, path_id(id as u8), random_generator, publisher);
+            if !path.is_deleted() {
+                path.on_timeout(timestamp, path_id(id as u8), random_generator, publisher);
+            }
         }
 
         if self.active_path().failed_validation() {
@@ -759,13 +846,16 @@ impl<Config: endpoint::Config> Manager<Config> {
             }
         }
 
+        self.delete_expired_paths(timestamp, publisher);
+
         Ok(())
     }
 
     /// Notifies the path manager of the connection closing event
     pub fn on_closing(&mut self) {
-        self.active_path_mut().on_closing();
-        // TODO clean up other paths
+        for path in self.paths.iter_mut().filter(|path| !path.is_deleted()) {
+            path.on_closing();
+        }
     }
 
     /// true if ALL paths are amplification_limited
@@ -773,16 +863,20 @@ impl<Config: endpoint::Config> Manager<Config> {
     pub fn is_amplification_limited(&self) -> bool {
         self.paths
             .iter()
+            .filter(|path| !path.is_deleted())
             .all(|path| path.transmission_constraint().is_amplification_limited())
     }
 
     /// true if ANY of the paths can transmit
     #[inline]
     pub fn can_transmit(&self, interest: transmission::Interest) -> bool {
-        self.paths.iter().any(|path| {
-            let constraint = path.transmission_constraint();
-            interest.can_transmit(constraint)
-        })
+        self.paths
+            .iter()
+            .filter(|path| !path.is_deleted())
+            .any(|path| {
+                let constraint = path.transmission_constraint();
+                interest.can_transmit(constraint)
+            })
     }
 
     #[inline]
@@ -790,6 +884,7 @@ impl<Config: endpoint::Config> Manager<Config> {
         // Return the lowest constraint which will ensure we don't get blocked on transmission by a single path
         self.paths
             .iter()
+            .filter(|path| !path.is_deleted())
             .map(|path| path.transmission_constraint())
             .min()
             .unwrap_or(transmission::Constraint::None)
@@ -802,7 +897,7 @@ impl<Config: endpoint::Config> Manager<Config> {
 
         // This value is the same for each path so just return the active value
         if cfg!(debug_assertions) {
-            for path in self.paths.iter() {
+            for path in self.paths.iter().filter(|path| !path.is_deleted()) {
                 assert_eq!(value, path.max_mtu());
             }
         }
@@ -821,7 +916,7 @@ fn path_id(id: u8) -> path::Id {
 impl<Config: endpoint::Config> timer::Provider for Manager<Config> {
     #[inline]
     fn timers<Q: timer::Query>(&self, query: &mut Q) -> timer::Result {
-        for path in self.paths.iter() {
+        for path in self.paths.iter().filter(|path| !path.is_deleted()) {
             path.timers(query)?;
         }
 
@@ -910,7 +1005,7 @@ macro_rules! path_event {
 }
 
 pub(crate) use path_event;
-use s2n_quic_core::event::builder::MtuUpdatedCause;
+use s2n_quic_core::event::builder::{MtuUpdatedCause, PathDeletedReason};
 
 #[cfg(test)]
 mod tests;
@@ -114,12 +114,20 @@ impl Oracle {
         }
     }
 
-    fn on_timeout(&self, _millis: &u16) {
-        // TODO implement
-        // call timeout on each Path
-        //
-        // if active path is not validated and validation failed
-        //   set the last_known_active_validated_path as active path
+    /// Remove a path which was deleted by the path manager
+    ///
+    /// Paths are deleted after failing validation or being idle for the path timeout, both of
+    /// which depend on the RTT estimate of each path. Rather than modeling the timers, the
+    /// oracle checks that only paths which are no longer in use are deleted.
+    fn on_path_deleted(&mut self, handle: &Handle) {
+        assert_ne!(
+            *handle, self.active,
+            "the active path should never be deleted"
+        );
+        assert!(
+            self.paths.remove(handle).is_some(),
+            "only known paths can be deleted"
+        );
     }
 
     fn on_processed_packet(&mut self, handle: &Handle, probe: &path_validation::Probe) {
@@ -241,7 +249,7 @@ impl Model {
         // timestamp should be monotonically increasing
         self.timestamp += Duration::from_millis(*millis as u64);
 
-        self.oracle.on_timeout(millis);
+        let handles = self.subject_handles();
 
         self.subject
             .on_timeout(
@@ -250,6 +258,48 @@ impl Model {
                 &mut Publisher::no_snapshot(),
             )
             .unwrap();
+
+        self.on_paths_deleted(&handles);
+    }
+
+    /// Returns the handles of the subject's paths, indexed by path id
+    ///
+    /// A deleted path's slot can be reused by the operation which deletes it so the handles
+    /// are captured beforehand.
+    fn subject_handles(&self) -> Vec<Option<Handle>> {
+        self.subject
+            .paths
+            .iter()
+            .map(|path| {
+                if path.is_deleted() {
+                    None
+                } else {
+                    Some(path.remote_address())
+                }
+            })
+            .collect()
+    }
+
+    /// Checks the paths the subject reports as deleted and removes them from the oracle
+    fn on_paths_deleted(&mut self, handles: &[Option<Handle>]) {
+        let deleted: Vec<path::Id> = self.subject.drain_deleted_paths().collect();
+
+        for id in deleted {
+            let handle = handles[id.as_u8() as usize].expect("only existing paths can be deleted");
+
+            assert_ne!(id, self.subject.active_path_id());
+            assert_ne!(
+                Some(id.as_u8()),
+                self.subject.last_known_active_validated_path
+            );
+
+            // the slot is either still deleted or was reused for a new path
+            let path = &self.subject[id];
+            assert!(path.is_deleted() || path.remote_address() != handle);
+            assert!(self.subject.path(&handle).is_none());
+
+            self.oracle.on_path_deleted(&handle);
+        }
     }
 
     fn on_datagram_received(
@@ -272,6 +322,7 @@ impl Model {
         let mut migration_validator = path::migration::default::Validator;
         let mut random_generator = Generator::default();
         let mut publisher = Publisher::no_snapshot();
+        let handles = self.subject_handles();
 
         self.oracle.prev_state_amplification_limited = self
             .subject
@@ -288,6 +339,9 @@ impl Model {
             &mut publisher,
         ) {
             Ok((id, _)) => {
+                // Creating a new path may delete expired paths to reuse their slot
+                self.on_paths_deleted(&handles);
+
                 // Only call oracle if the subject can process on_datagram_received without errors
                 self.oracle.on_datagram_received(
                     &handle,
@@ -311,6 +365,8 @@ impl Model {
                 }
             }
             Err(datagram_drop_reason) => {
+                self.on_paths_deleted(&handles);
+
                 match datagram_drop_reason {
                     // Ignore errors emitted by the migration::validator and peer_id_registry
                     DatagramDropReason::InsufficientConnectionIds => {}
@@ -325,7 +381,7 @@ impl Model {
         let id = path_id_generator as usize % self.subject.paths.len();
 
         let path = &mut self.subject[path_id(id as u8)];
-        if !path.at_amplification_limit() {
+        if !path.is_deleted() && !path.at_amplification_limit() {
             path.on_bytes_transmitted(bytes as usize);
         }
     }
@@ -333,7 +389,14 @@ impl Model {
     /// Check that the subject and oracle match.
     pub fn invariants(&self) {
         // compare total paths
-        assert_eq!(self.oracle.paths.len(), self.subject.paths.len());
+        let subject_paths = self
+            .subject
+            .paths
+            .iter()
+            .filter(|path| !path.is_deleted())
+            .count();
+        assert_eq!(self.oracle.paths.len(), subject_paths);
+        assert!(self.subject.paths.len() <= MAX_ALLOWED_PATHS);
 
         // compare active path
         assert_eq!(
@@ -348,7 +411,13 @@ impl Model {
         );
 
         // compare path properties
-        for (path_id, s_path) in self.subject.paths.iter().enumerate() {
+        for (path_id, s_path) in self
+            .subject
+            .paths
+            .iter()
+            .enumerate()
+            .filter(|(_path_id, path)| !path.is_deleted())
+        {
             let o_path = self.oracle.paths.get(&s_path.remote_address()).unwrap();
 
             assert_eq!(
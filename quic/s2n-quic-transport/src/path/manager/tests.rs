@@ -1677,6 +1677,197 @@ fn last_known_validated_path_should_update_on_path_response() {
     assert_eq!(manager.last_known_active_validated_path, None);
 }
 
+#[test]
+// Paths which have not received a datagram within the path timeout should be deleted
+//
+// Setup:
+// - create manager with an active path
+// - add a second path which received a datagram
+//
+// Trigger 1:
+// - call on_timeout just BEFORE the path timeout
+//
+// Expectation 1:
+// - verify second path is NOT deleted
+//
+// Trigger 2:
+// - call on_timeout just AFTER the path timeout
+//
+// Expectation 2:
+// - verify second path is deleted and can no longer be looked up by address
+fn delete_idle_path() {
+    // Setup:
+    let mut publisher = Publisher::snapshot();
+    let now = NoopClock {}.get_time();
+    let first_addr: SocketAddr = "127.0.0.1:8001".parse().unwrap();
+    let first_addr = RemoteAddress::from(SocketAddress::from(first_addr));
+    let first_path = ServerPath::new(
+        first_addr,
+        connection::PeerId::try_from_bytes(&[1]).unwrap(),
+        connection::LocalId::TEST_ID,
+        RttEstimator::default(),
+        Default::default(),
+        false,
+        DEFAULT_MAX_MTU,
+    );
+    let mut manager = manager_server(first_path);
+
+    let second_addr: SocketAddr = "127.0.0.2:8001".parse().unwrap();
+    let second_addr = RemoteAddress::from(SocketAddress::from(second_addr));
+    let mut second_path = ServerPath::new(
+        second_addr,
+        connection::PeerId::try_from_bytes(&[2]).unwrap(),
+        connection::LocalId::TEST_ID,
+        RttEstimator::default(),
+        Default::default(),
+        false,
+        DEFAULT_MAX_MTU,
+    );
+    second_path.on_datagram_received(now);
+    manager.paths.push(second_path);
+    let second_path_id = path_id(1);
+    let path_timeout = manager.path_timeout(second_path_id);
+
+    // Trigger 1:
+    manager
+        .on_timeout(
+            now + path_timeout - Duration::from_millis(10),
+            &mut random::testing::Generator(123),
+            &mut publisher,
+        )
+        .unwrap();
+
+    // Expectation 1:
+    assert!(!manager[second_path_id].is_deleted());
+    assert!(manager.path(&second_addr).is_some());
+
+    // Trigger 2:
+    manager
+        .on_timeout(
+            now + path_timeout + Duration::from_millis(10),
+            &mut random::testing::Generator(123),
+            &mut publisher,
+        )
+        .unwrap();
+
+    // Expectation 2:
+    assert!(manager[second_path_id].is_deleted());
+    assert!(manager.path(&second_addr).is_none());
+    assert!(!manager.active_path().is_deleted());
+}
+
+#[test]
+// The slots of deleted paths should be reused for new paths
+//
+// Setup:
+// - create manager with an active path
+// - create paths until MAX_ALLOWED_PATHS is reached
+//
+// Trigger 1:
+// - receive a datagram from a new address
+//
+// Expectation 1:
+// - verify the path limit is exceeded
+//
+// Trigger 2:
+// - abandon all path challenges
+// - receive a datagram from a new address
+//
+// Expectation 2:
+// - verify the failed paths are deleted and the first slot is reused
+// - verify the connection id shared with the active path is not retired
+fn reuse_deleted_path_slots() {
+    // Setup:
+    let mut publisher = Publisher::no_snapshot();
+    let now = NoopClock {}.get_time();
+    let first_conn_id = connection::PeerId::try_from_bytes(&[1]).unwrap();
+    let first_addr: SocketAddr = "127.0.0.1:8001".parse().unwrap();
+    let first_addr = RemoteAddress::from(SocketAddress::from(first_addr));
+    let first_path = ServerPath::new(
+        first_addr,
+        first_conn_id,
+        connection::LocalId::TEST_ID,
+        RttEstimator::default(),
+        Default::default(),
+        false,
+        DEFAULT_MAX_MTU,
+    );
+    let mut manager = manager_server(first_path);
+    let datagram = DatagramInfo {
+        timestamp: now,
+        payload_len: 0,
+        ecn: ExplicitCongestionNotification::default(),
+        destination_connection_id: connection::LocalId::TEST_ID,
+        source_connection_id: None,
+    };
+
+    for i in 1..MAX_ALLOWED_PATHS {
+        let addr: SocketAddr = format!("127.0.0.2:{}", 8001 + i).parse().unwrap();
+        let addr = RemoteAddress::from(SocketAddress::from(addr));
+        let (id, _unblocked) = manager
+            .on_datagram_received(
+                &addr,
+                &datagram,
+                true,
+                &mut Default::default(),
+                &mut migration::default::Validator::default(),
+                DEFAULT_MAX_MTU,
+                &mut publisher,
+            )
+            .unwrap();
+        manager
+            .on_processed_packet(
+                id,
+                None,
+                path_validation::Probe::Probing,
+                &mut random::testing::Generator(123),
+                &mut publisher,
+            )
+            .unwrap();
+        assert!(manager[id].is_challenge_pending());
+    }
+    assert_eq!(manager.paths.len(), MAX_ALLOWED_PATHS);
+
+    // Trigger 1:
+    let new_addr: SocketAddr = "127.0.0.3:8001".parse().unwrap();
+    let new_addr = RemoteAddress::from(SocketAddress::from(new_addr));
+    let res = manager.on_datagram_received(
+        &new_addr,
+        &datagram,
+        true,
+        &mut Default::default(),
+        &mut migration::default::Validator::default(),
+        DEFAULT_MAX_MTU,
+        &mut publisher,
+    );
+
+    // Expectation 1:
+    assert!(matches!(res, Err(DatagramDropReason::PathLimitExceeded)));
+
+    // Trigger 2:
+    manager.abandon_all_path_challenges(&mut publisher);
+    let (new_path_id, _unblocked) = manager
+        .on_datagram_received(
+            &new_addr,
+            &datagram,
+            true,
+            &mut Default::default(),
+            &mut migration::default::Validator::default(),
+            DEFAULT_MAX_MTU,
+            &mut publisher,
+        )
+        .unwrap();
+
+    // Expectation 2:
+    assert_eq!(new_path_id, path_id(1));
+    assert_eq!(manager.paths.len(), MAX_ALLOWED_PATHS);
+    assert!(!manager[new_path_id].is_deleted());
+    for idx in 2..MAX_ALLOWED_PATHS {
+        assert!(manager[path_id(idx as u8)].is_deleted());
+    }
+    assert!(manager.peer_id_registry.is_active(&first_conn_id));
+}
+
 // creates a test path_manager. also check out `helper_manager_with_paths`
 // which calls this helper with preset options
 pub fn helper_manager_with_paths_base(
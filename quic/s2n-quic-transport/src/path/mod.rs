@@ -80,6 +80,15 @@ pub struct Path<Config: endpoint::Config> {
 
     /// True if the path is currently active
     is_active: bool,
+
+    /// The last time a datagram was received on the path
+    last_activity: Option<Timestamp>,
+
+    /// True if the path has been deleted by the path manager
+    ///
+    /// Path ids are referenced by other components, such as recovery, so deleted paths
+    /// remain in place until their slot is reused by a new path.
+    is_deleted: bool,
 }
 
 impl<Config: endpoint::Config> Clone for Path<Config> {
@@ -99,6 +108,8 @@ impl<Config: endpoint::Config> Clone for Path<Config> {
             response_data: self.response_data,
             activated: self.activated,
             is_active: self.is_active,
+            last_activity: self.last_activity,
+            is_deleted: self.is_deleted,
         }
     }
 }
@@ -146,6 +157,8 @@ impl<Config: endpoint::Config> Path<Config> {
             response_data: None,
             activated: false,
             is_active: false,
+            last_activity: None,
+            is_deleted: false,
         }
     }
 
@@ -223,6 +236,45 @@ impl<Config: endpoint::Config> Path<Config> {
         was_at_amplification_limit && !self.at_amplification_limit()
     }
 
+    /// Called when a datagram has been received on this path
+    #[inline]
+    pub fn on_datagram_received(&mut self, timestamp: Timestamp) {
+        self.last_activity = Some(timestamp);
+    }
+
+    /// Returns true if no datagrams have been received on this path for `idle_timeout`
+    #[inline]
+    pub fn is_idle(&self, timestamp: Timestamp, idle_timeout: core::time::Duration) -> bool {
+        self.last_activity.map_or(false, |last_activity| {
+            last_activity + idle_timeout <= timestamp
+        })
+    }
+
+    /// Returns true if the path has no outstanding state that would prevent it from being
+    /// deleted
+    #[inline]
+    pub fn can_delete(&self) -> bool {
+        !self.is_active
+            && !self.is_deleted
+            && !self.is_challenge_pending()
+            && !self.is_response_pending()
+            && self.congestion_controller.bytes_in_flight() == 0
+    }
+
+    /// Marks the path as deleted
+    #[inline]
+    pub fn on_deleted(&mut self) {
+        debug_assert!(!self.is_active);
+        self.is_deleted = true;
+        self.challenge = Challenge::disabled();
+        self.response_data = None;
+    }
+
+    #[inline]
+    pub fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+
     #[inline]
     pub fn on_timeout<Rnd: random::Generator, Pub: event::ConnectionPublisher>(
         &mut self,
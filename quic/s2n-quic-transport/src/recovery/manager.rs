@@ -750,6 +750,62 @@ impl<Config: endpoint::Config> Manager<Config> {
             .on_packet_discarded(discarded_bytes);
     }
 
+    /// Removes the packets which were sent on a path that has been deleted
+    ///
+    /// The path id is reused by the next path that is created, so acknowledgements for these
+    /// packets would otherwise be attributed to the new path.
+    pub fn on_path_deleted(
+        &mut self,
+        path_id: path::Id,
+        path_manager: &mut path::Manager<Config>,
+        timestamp: Timestamp,
+        is_handshake_confirmed: bool,
+    ) {
+        let stale_packets: SmallVec<[PacketNumber; 16]> = self
+            .sent_packets
+            .iter()
+            .filter(|(_, sent_info)| sent_info.path_id == path_id)
+            .map(|(packet_number, _)| packet_number)
+            .collect();
+
+        if stale_packets.is_empty() {
+            return;
+        }
+
+        let mut discarded_bytes = 0;
+        for packet_number in stale_packets {
+            if let Some(sent_info) = self.sent_packets.remove(packet_number) {
+                discarded_bytes += sent_info.sent_bytes as usize;
+            }
+        }
+
+        // The slot may have already been reused by a new path, which never counted these
+        // packets as being in flight
+        let path = &mut path_manager[path_id];
+        if path.is_deleted() {
+            path.congestion_controller
+                .on_packet_discarded(discarded_bytes);
+        }
+
+        // The loss timer is only armed for packets sent before the largest acknowledged packet,
+        // which may have all been removed
+        let has_loss_candidates = self.largest_acked_packet.map_or(false, |largest_acked| {
+            self.sent_packets
+                .iter()
+                .any(|(packet_number, _)| packet_number < largest_acked)
+        });
+        if !has_loss_candidates {
+            self.loss_timer.cancel();
+        }
+
+        // The PTO timer is only queried when the loss timer isn't armed
+        self.update_pto_timer(
+            path_manager.active_path(),
+            timestamp,
+            is_handshake_confirmed,
+        );
+    }
+
     //= https://www.rfc-editor.org/rfc/rfc9002#section-A.10
     //# DetectAndRemoveLostPackets is called every time an ACK is received or the time threshold
     //# loss detection timer expires. This function operates on the sent_packets for that packet
@@ -328,6 +328,189 @@ fn on_packet_sent_across_multiple_paths() {
     assert_eq!(Some(expected_pto), manager.pto.timer.next_expiration());
 }
 
+#[test]
+fn on_path_deleted() {
+    let space = PacketNumberSpace::ApplicationData;
+    let ecn = ExplicitCongestionNotification::default();
+    let now = s2n_quic_platform::time::now();
+    let mut publisher = Publisher::snapshot();
+    let (first_addr, first_path_id, _second_addr, second_path_id, mut manager, mut path_manager) =
+        helper_generate_multi_path_manager(space, &mut publisher);
+    let mut context = MockContext::new(&mut path_manager);
+
+    let outcome = transmission::Outcome {
+        ack_elicitation: AckElicitation::Eliciting,
+        is_congestion_controlled: true,
+        bytes_sent: 128,
+        bytes_progressed: 0,
+    };
+
+    // Setup:
+    // send a packet on each path
+    let first_packet = space.new_packet_number(VarInt::from_u8(1));
+    context.set_path_id(first_path_id);
+    manager.on_packet_sent(
+        first_packet,
+        outcome,
+        now,
+        ecn,
+        transmission::Mode::Normal,
+        None,
+        &mut context,
+        &mut publisher,
+    );
+
+    let second_packet = space.new_packet_number(VarInt::from_u8(2));
+    context.set_path_id(second_path_id);
+    manager.on_packet_sent(
+        second_packet,
+        outcome,
+        now,
+        ecn,
+        transmission::Mode::Normal,
+        None,
+        &mut context,
+        &mut publisher,
+    );
+
+    // Trigger 1:
+    context.path_manager[second_path_id].on_deleted();
+    manager.on_path_deleted(second_path_id, context.path_manager, now, true);
+
+    // Expectation 1:
+    // only the packets sent on the deleted path are removed and the bytes are no longer
+    // counted as in flight
+    assert!(manager.sent_packets.get(first_packet).is_some());
+    assert!(manager.sent_packets.get(second_packet).is_none());
+    assert_eq!(
+        context.path_manager[first_path_id]
+            .congestion_controller
+            .bytes_in_flight,
+        128
+    );
+    assert_eq!(
+        context.path_manager[second_path_id]
+            .congestion_controller
+            .bytes_in_flight,
+        0
+    );
+    assert!(manager.pto.timer.is_armed());
+
+    // Trigger 2:
+    // acknowledge the packet sent on the deleted path
+    helper_ack_packets_on_path(
+        2..=2,
+        now + Duration::from_millis(10),
+        &mut context,
+        &mut manager,
+        first_addr,
+        None,
+        &mut publisher,
+    );
+
+    // Expectation 2:
+    // the acknowledgement isn't attributed to any path
+    assert_eq!(context.on_new_packet_ack_count, 0);
+    assert_eq!(context.on_rtt_update_count, 0);
+    assert!(manager.sent_packets.get(first_packet).is_some());
+
+    // Trigger 3:
+    helper_ack_packets_on_path(
+        1..=1,
+        now + Duration::from_millis(10),
+        &mut context,
+        &mut manager,
+        first_addr,
+        None,
+        &mut publisher,
+    );
+
+    // Expectation 3:
+    assert_eq!(context.on_new_packet_ack_count, 1);
+    assert!(manager.sent_packets.get(first_packet).is_none());
+}
+
+#[test]
+fn on_path_deleted_updates_timers() {
+    let space = PacketNumberSpace::ApplicationData;
+    let ecn = ExplicitCongestionNotification::default();
+    let now = s2n_quic_platform::time::now();
+    let mut publisher = Publisher::snapshot();
+    let (first_addr, first_path_id, _second_addr, second_path_id, mut manager, mut path_manager) =
+        helper_generate_multi_path_manager(space, &mut publisher);
+    let mut context = MockContext::new(&mut path_manager);
+
+    let outcome = transmission::Outcome {
+        ack_elicitation: AckElicitation::Eliciting,
+        is_congestion_controlled: true,
+        bytes_sent: 128,
+        bytes_progressed: 0,
+    };
+
+    // Setup:
+    // send a packet on the second path followed by a packet on the first path
+    let second_packet = space.new_packet_number(VarInt::from_u8(1));
+    context.set_path_id(second_path_id);
+    manager.on_packet_sent(
+        second_packet,
+        outcome,
+        now,
+        ecn,
+        transmission::Mode::Normal,
+        None,
+        &mut context,
+        &mut publisher,
+    );
+
+    let first_packet = space.new_packet_number(VarInt::from_u8(2));
+    context.set_path_id(first_path_id);
+    manager.on_packet_sent(
+        first_packet,
+        outcome,
+        now,
+        ecn,
+        transmission::Mode::Normal,
+        None,
+        &mut context,
+        &mut publisher,
+    );
+
+    // acknowledge the packet sent on the first path before the packet on the second path can
+    // be declared lost, which arms the loss timer
+    helper_ack_packets_on_path(
+        2..=2,
+        now + Duration::from_millis(10),
+        &mut context,
+        &mut manager,
+        first_addr,
+        None,
+        &mut publisher,
+    );
+    assert!(manager.sent_packets.get(second_packet).is_some());
+    assert!(manager.loss_timer.is_armed());
+
+    // Trigger:
+    context.path_manager[second_path_id].on_deleted();
+    manager.on_path_deleted(
+        second_path_id,
+        context.path_manager,
+        now + Duration::from_millis(10),
+        true,
+    );
+
+    // Expectation:
+    // nothing is left in flight, so neither timer is armed
+    assert!(manager.sent_packets.get(second_packet).is_none());
+    assert_eq!(
+        context.path_manager[second_path_id]
+            .congestion_controller
+            .bytes_in_flight,
+        0
+    );
+    assert!(!manager.loss_timer.is_armed());
+    assert!(!manager.pto.timer.is_armed());
+}
+
 //= https://www.rfc-editor.org/rfc/rfc9002#section-A.7
 //= type=test
 #[test]
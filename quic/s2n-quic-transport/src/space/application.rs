@@ -18,7 +18,10 @@ use core::{convert::TryInto, fmt, marker::PhantomData};
 use once_cell::sync::OnceCell;
 use s2n_codec::EncoderBuffer;
 use s2n_quic_core::{
-    crypto::{application::KeySet, limited, tls, CryptoSuite},
+    crypto::{
+        application::KeySet, limited, tls, BatchPacket, CryptoSuite, HeaderKey as _,
+        HeaderProtectionMask, OneRttKey,
+    },
     event::{self, ConnectionPublisher as _, IntoEvent},
     frame::{
         ack::AckRanges, crypto::CryptoRef, datagram::DatagramRef, stream::StreamRef, Ack,
@@ -30,12 +33,39 @@ use s2n_quic_core::{
     packet::{
         encoding::{PacketEncoder, PacketEncodingError},
         number::{PacketNumber, PacketNumberRange, PacketNumberSpace, SlidingWindow},
-        short::{CleartextShort, ProtectedShort, Short, SpinBit},
+        short::{BatchedShort, CleartextShort, EncryptedShort, ProtectedShort, Short, SpinBit},
     },
     path::MaxMtu,
     time::{timer, Timestamp},
     transport,
 };
+use smallvec::{smallvec, SmallVec};
+
+/// The maximum number of packets which are decrypted in a single batch
+pub const DECRYPT_BATCH_LEN: usize = 16;
+
+/// A packet which has had its packet protection removed as part of a batch
+#[derive(Debug)]
+pub struct DecryptedShort<'a> {
+    /// The packet number, if header protection was removed
+    pub packet_number: Option<PacketNumber>,
+    /// The generation of the key, if the packet caused a key update
+    pub key_generation: Option<u16>,
+    pub result: Result<CleartextShort<'a>, ProcessingError>,
+}
+
+impl<'a> DecryptedShort<'a> {
+    /// Returns the error if the packet exceeded the integrity limit when the batch was decrypted
+    #[inline]
+    pub fn integrity_limit_error(&self) -> Option<connection::Error> {
+        match self.result {
+            Err(ProcessingError::ConnectionError(
+                error @ connection::Error::Transport { code, .. },
+            )) if code == transport::Error::AEAD_LIMIT_REACHED.code => Some(error),
+            _ => None,
+        }
+    }
+}
 
 pub struct ApplicationSpace<Config: endpoint::Config> {
     /// Transmission Packet numbers
@@ -58,6 +88,8 @@ pub struct ApplicationSpace<Config: endpoint::Config> {
     //# the handshake (Section 4.1.2).
     key_set: KeySet<<<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::OneRttKey>,
     header_key: <<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::OneRttHeaderKey,
+    /// Set when a key update was requested before the handshake was confirmed
+    pending_key_update: bool,
 
     ping: flag::Ping,
     keep_alive: KeepAlive,
@@ -100,6 +132,7 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
             stream_manager,
             key_set,
             header_key,
+            pending_key_update: false,
             ping: flag::Ping::default(),
             keep_alive,
             processed_packet_numbers: SlidingWindow::default(),
@@ -343,6 +376,10 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         // Retire the local connection ID used during the handshake to reduce linkability
         local_id_registry.retire_handshake_connection_id();
 
+        if core::mem::take(&mut self.pending_key_update) {
+            self.key_set.initiate_key_update();
+        }
+
         //= https://www.rfc-editor.org/rfc/rfc9002#section-6.2.1
         //# A sender SHOULD restart its PTO timer every time an ack-eliciting
         //# packet is sent or acknowledged, or when Initial or Handshake keys are
@@ -394,6 +431,22 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         }
     }
 
+    /// Called when a path is deleted by the path manager
+    pub fn on_path_deleted(
+        &mut self,
+        path_id: path::Id,
+        path_manager: &mut path::Manager<Config>,
+        timestamp: Timestamp,
+        is_handshake_confirmed: bool,
+    ) {
+        self.recovery_manager.on_path_deleted(
+            path_id,
+            path_manager,
+            timestamp,
+            is_handshake_confirmed,
+        );
+    }
+
     /// Returns `true` if the recovery manager for this packet space requires a probe
     /// packet to be sent.
     pub fn requires_probe(&self) -> bool {
@@ -408,6 +461,15 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         self.keep_alive.update(enabled);
     }
 
+    /// Initiates a key update, deferring it until the handshake is confirmed if needed
+    pub fn initiate_key_update(&mut self, handshake_status: &HandshakeStatus) {
+        if handshake_status.is_confirmed() {
+            self.key_set.initiate_key_update();
+        } else {
+            self.pending_key_update = true;
+        }
+    }
+
     /// Returns the Packet Number to be used when encoding outgoing packets
     fn packet_number_encoder(&self) -> PacketNumber {
         self.tx_packet_numbers.largest_sent_packet_number_acked()
@@ -459,54 +521,131 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
         let packet = protected
             .unprotect(&self.header_key, largest_acked)
             .map_err(|err| {
-                publisher.on_packet_dropped(event::builder::PacketDropped {
-                    reason: event::builder::PacketDropReason::UnprotectFailed {
-                        space: event::builder::KeySpace::OneRtt,
-                        path: path_event!(path, path_id),
-                    },
-                });
+                Self::on_unprotect_failed(path_id, path, publisher);
                 err
             })?;
 
         let packet_number = packet.packet_number;
-        let packet_header =
-            event::builder::PacketHeader::new(packet.packet_number, publisher.quic_version());
         let decrypted = self.key_set.decrypt_packet(
             packet,
             largest_acked,
-            //= https://www.rfc-editor.org/rfc/rfc9001#section-6.3
-            //# For a short period after a key
-            //# update completes, up to the PTO, endpoints MAY defer generation of
-            //# the next set of receive packet protection keys.  This allows
-            //# endpoints to retain only two sets of receive keys; see Section 6.5.
-
-            //= https://www.rfc-editor.org/rfc/rfc9001#section-6.5
-            //# An endpoint MAY allow a period of approximately the Probe Timeout
-            //# (PTO; see [QUIC-RECOVERY]) after promoting the next set of receive
-            //# keys to be current before it creates the subsequent set of packet
-            //# protection keys.
-            datagram.timestamp
-                + path
-                    .rtt_estimator
-                    .pto_period(1, PacketNumberSpace::ApplicationData),
+            Self::key_derivation_timer(datagram.timestamp, path),
         );
-        match decrypted {
-            Ok((_, Some(generation))) => {
-                publisher.on_key_update(event::builder::KeyUpdate {
-                    key_type: event::builder::KeyType::OneRtt { generation },
-                    cipher_suite: self.key_set.cipher_suite().into_event(),
-                });
-            }
-            Ok(_) => {}
-            Err(_) => {
-                publisher.on_packet_dropped(event::builder::PacketDropped {
-                    reason: event::builder::PacketDropReason::DecryptionFailed {
-                        packet_header,
-                        path: path_event!(path, path_id),
-                    },
-                });
-            }
+        let (result, key_generation) = match decrypted {
+            Ok((packet, key_generation)) => (Ok(packet), key_generation),
+            Err(err) => (Err(err), None),
+        };
+
+        self.on_decrypted_packet(
+            DecryptedShort {
+                packet_number: Some(packet_number),
+                key_generation,
+                result,
+            },
+            datagram,
+            path_id,
+            path,
+            publisher,
+        )
+    }
+
+    /// Removes packet protection from a batch of packets in the Application packet space
+    ///
+    /// The header protection masks for all of the packets are derived in a single call and the
+    /// leading packets protected with the active key phase are decrypted together. Packets after
+    /// a key phase change are decrypted individually so key updates are processed in order.
+    ///
+    /// Each packet is left in the [`endpoint::batch::State::Decrypted`] state and must be passed
+    /// to [`Self::on_decrypted_packet`], in order, before it is processed. No events are
+    /// published here so they are emitted alongside the datagram which carried each packet.
+    pub fn decrypt_packets(
+        &mut self,
+        packets: &mut [endpoint::batch::Packet],
+        timestamp: Timestamp,
+        path: &path::Path<Config>,
+    ) {
+        debug_assert!(packets.len() <= DECRYPT_BATCH_LEN);
+
+        let largest_acked = self.ack_manager.largest_received_packet_number_acked();
+        let sample_len = self.header_key.opening_sample_len();
+
+        let mut masks = {
+            let samples: SmallVec<[&[u8]; DECRYPT_BATCH_LEN]> = packets
+                .iter()
+                .filter_map(|packet| match &packet.state {
+                    endpoint::batch::State::Protected(packet) => Some(packet),
+                    _ => None,
+                })
+                .filter_map(|packet| packet.header_protection_sample(sample_len).ok())
+                .collect();
+            let mut masks: SmallVec<[HeaderProtectionMask; DECRYPT_BATCH_LEN]> =
+                smallvec![Default::default(); samples.len()];
+            self.header_key
+                .opening_header_protection_masks(&samples, &mut masks);
+            masks
         }
+        .into_iter();
+
+        let unprotected = packets
+            .iter_mut()
+            .map(|packet| {
+                let packet = match packet.take() {
+                    endpoint::batch::State::Protected(packet) => packet,
+                    _ => panic!("packet was already processed"),
+                };
+
+                let packet = if packet.header_protection_sample(sample_len).is_ok() {
+                    let mask = masks.next().expect("a mask is derived for each sample");
+                    packet.unprotect_with_mask(mask, largest_acked)
+                } else {
+                    // fall back to the individual path so the packet fails in the same way
+                    packet.unprotect(&self.header_key, largest_acked)
+                };
+
+                packet.map_err(|err| err.into())
+            })
+            .collect();
+
+        let pto = Self::key_derivation_timer(timestamp, path);
+        let decrypted = decrypt_batch(&mut self.key_set, unprotected, largest_acked, pto);
+
+        for (packet, decrypted) in packets.iter_mut().zip(decrypted) {
+            packet.state = endpoint::batch::State::Decrypted(decrypted);
+        }
+    }
+
+    /// Validates a packet after its packet protection has been removed
+    pub fn on_decrypted_packet<'a, Pub: event::ConnectionPublisher>(
+        &mut self,
+        decrypted: DecryptedShort<'a>,
+        datagram: &DatagramInfo,
+        path_id: path::Id,
+        path: &path::Path<Config>,
+        publisher: &mut Pub,
+    ) -> Result<CleartextShort<'a>, ProcessingError> {
+        let DecryptedShort {
+            packet_number,
+            key_generation,
+            result,
+        } = decrypted;
+
+        // header protection couldn't be removed so there's nothing else to validate
+        let packet_number = if let Some(packet_number) = packet_number {
+            packet_number
+        } else {
+            Self::on_unprotect_failed(path_id, path, publisher);
+            return result;
+        };
+
+        Self::on_decrypt_result(
+            self.key_set.cipher_suite(),
+            result.as_ref().map(|_| key_generation),
+            packet_number,
+            path_id,
+            path,
+            publisher,
+        );
+
         //= https://www.rfc-editor.org/rfc/rfc9001#section-9.5
         //# For authentication to be
         //# free from side channels, the entire process of header protection
@@ -519,12 +658,74 @@ impl<Config: endpoint::Config> ApplicationSpace<Config> {
             return Err(ProcessingError::DuplicatePacket);
         }
 
-        if decrypted.is_ok() {
+        if result.is_ok() {
             // reset the keep alive timer after receiving a packet
             self.keep_alive.reset(datagram.timestamp);
         }
 
-        decrypted.map(|x| x.0)
+        result
+    }
+
+    /// Returns the time at which the next key can be derived after a key update
+    fn key_derivation_timer(timestamp: Timestamp, path: &path::Path<Config>) -> Timestamp {
+        //= https://www.rfc-editor.org/rfc/rfc9001#section-6.3
+        //# For a short period after a key
+        //# update completes, up to the PTO, endpoints MAY defer generation of
+        //# the next set of receive packet protection keys.  This allows
+        //# endpoints to retain only two sets of receive keys; see Section 6.5.
+
+        //= https://www.rfc-editor.org/rfc/rfc9001#section-6.5
+        //# An endpoint MAY allow a period of approximately the Probe Timeout
+        //# (PTO; see [QUIC-RECOVERY]) after promoting the next set of receive
+        //# keys to be current before it creates the subsequent set of packet
+        //# protection keys.
+        timestamp
+            + path
+                .rtt_estimator
+                .pto_period(1, PacketNumberSpace::ApplicationData)
+    }
+
+    fn on_decrypt_result<Pub: event::ConnectionPublisher>(
+        cipher_suite: tls::CipherSuite,
+        decrypted: Result<Option<u16>, &ProcessingError>,
+        packet_number: PacketNumber,
+        path_id: path::Id,
+        path: &path::Path<Config>,
+        publisher: &mut Pub,
+    ) {
+        match decrypted {
+            Ok(Some(generation)) => {
+                publisher.on_key_update(event::builder::KeyUpdate {
+                    key_type: event::builder::KeyType::OneRtt { generation },
+                    cipher_suite: cipher_suite.into_event(),
+                });
+            }
+            Ok(None) => {}
+            Err(_) => {
+                publisher.on_packet_dropped(event::builder::PacketDropped {
+                    reason: event::builder::PacketDropReason::DecryptionFailed {
+                        packet_header: event::builder::PacketHeader::new(
+                            packet_number,
+                            publisher.quic_version(),
+                        ),
+                        path: path_event!(path, path_id),
+                    },
+                });
+            }
+        }
+    }
+
+    fn on_unprotect_failed<Pub: event::ConnectionPublisher>(
+        path_id: path::Id,
+        path: &path::Path<Config>,
+        publisher: &mut Pub,
+    ) {
+        publisher.on_packet_dropped(event::builder::PacketDropped {
+            reason: event::builder::PacketDropReason::UnprotectFailed {
+                space: event::builder::KeySpace::OneRtt,
+                path: path_event!(path, path_id),
+            },
+        });
     }
 
     fn key_limits(max_mtu: MaxMtu) -> limited::Limits {
@@ -593,6 +794,104 @@ impl<Config: endpoint::Config> connection::finalization::Provider for Applicatio
     }
 }
 
+/// Decrypts a batch of packets, returning the results in the same order
+///
+/// The leading packets protected with the active key phase are decrypted together. The first
+/// packet with a different key phase, and all of the packets after it, are decrypted
+/// individually so key updates are processed in order.
+fn decrypt_batch<'a, K: OneRttKey>(
+    key_set: &mut KeySet<K>,
+    packets: SmallVec<[Result<EncryptedShort<'a>, ProcessingError>; DECRYPT_BATCH_LEN]>,
+    largest_acked: PacketNumber,
+    key_derivation_timer: Timestamp,
+) -> SmallVec<[DecryptedShort<'a>; DECRYPT_BATCH_LEN]> {
+    enum Slot<'a> {
+        UnprotectFailed(ProcessingError),
+        Batched(BatchedShort),
+        Pending(EncryptedShort<'a>),
+    }
+
+    let key_phase = key_set.key_phase();
+    let mut is_batching = true;
+    let mut slots = SmallVec::<[Slot; DECRYPT_BATCH_LEN]>::new();
+    let mut batch = SmallVec::<[BatchPacket; DECRYPT_BATCH_LEN]>::new();
+
+    for packet in packets {
+        let slot = match packet {
+            Err(err) => Slot::UnprotectFailed(err),
+            Ok(packet) if is_batching && packet.key_phase() == key_phase => {
+                let (header, packet) = packet.into_batch();
+                batch.push(packet);
+                Slot::Batched(header)
+            }
+            Ok(packet) => {
+                is_batching = false;
+                Slot::Pending(packet)
+            }
+        };
+
+        slots.push(slot);
+    }
+
+    // The integrity limit is checked after the whole batch is decrypted so it is reported on
+    // the last packet that failed to decrypt.
+    let mut limit_error = if batch.is_empty() {
+        None
+    } else {
+        key_set.decrypt_batch(&mut batch).err()
+    };
+    let mut remaining_failures = batch.iter().filter(|packet| packet.result.is_err()).count();
+    let mut batch = batch.into_iter();
+
+    slots
+        .into_iter()
+        .map(|slot| match slot {
+            Slot::UnprotectFailed(err) => DecryptedShort {
+                packet_number: None,
+                key_generation: None,
+                result: Err(err),
+            },
+            Slot::Batched(header) => {
+                let packet_number = header.packet_number;
+                let packet = batch.next().expect("a packet is batched for each header");
+                // Only authentication failures are counted, since an authenticated packet can
+                // still be rejected for its reserved bits
+                let is_decrypt_failure = packet.result.is_err();
+                let mut result = header.decrypted(packet).map_err(ProcessingError::from);
+
+                if is_decrypt_failure {
+                    remaining_failures -= 1;
+                    if remaining_failures == 0 {
+                        if let Some(err) = limit_error.take() {
+                            result = Err(err);
+                        }
+                    }
+                }
+
+                DecryptedShort {
+                    packet_number: Some(packet_number),
+                    key_generation: None,
+                    result,
+                }
+            }
+            Slot::Pending(packet) => {
+                let packet_number = packet.packet_number;
+                let (result, key_generation) =
+                    match key_set.decrypt_packet(packet, largest_acked, key_derivation_timer) {
+                        Ok((packet, key_generation)) => (Ok(packet), key_generation),
+                        Err(err) => (Err(err), None),
+                    };
+
+                DecryptedShort {
+                    packet_number: Some(packet_number),
+                    key_generation,
+                    result,
+                }
+            }
+        })
+        .collect()
+}
+
 struct RecoveryContext<'a, Config: endpoint::Config> {
     ack_manager: &'a mut AckManager,
     handshake_status: &'a mut HandshakeStatus,
@@ -949,3 +1248,6 @@ impl<Config: endpoint::Config> PacketSpace<Config> for ApplicationSpace<Config>
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests;
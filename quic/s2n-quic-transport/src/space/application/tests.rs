@@ -0,0 +1,360 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use s2n_codec::DecoderBufferMut;
+use s2n_quic_core::{
+    connection::id::ConnectionInfo,
+    crypto::{self, testing::HeaderKey, CryptoError},
+    inet::SocketAddress,
+    packet::{KeyPhase, ProtectedPacket},
+    time::{testing::Clock, Clock as _},
+    varint::VarInt,
+};
+
+const DESTINATION_CONNECTION_ID: [u8; 4] = [1, 2, 3, 4];
+
+/// A key which XORs the payload with a mask derived from the packet number and generation
+///
+/// The mask is appended as a single byte tag so packets fail to decrypt if they were corrupted
+/// or protected with a different generation.
+#[derive(Debug)]
+struct Key {
+    generation: u8,
+    integrity_limit: u64,
+}
+
+impl Default for Key {
+    fn default() -> Self {
+        Self {
+            generation: 0,
+            integrity_limit: u64::MAX,
+        }
+    }
+}
+
+impl Key {
+    fn mask(&self, packet_number: u64) -> u8 {
+        packet_number as u8 ^ self.generation.wrapping_mul(0x10)
+    }
+}
+
+impl crypto::Key for Key {
+    fn decrypt(
+        &self,
+        packet_number: u64,
+        _header: &[u8],
+        payload: &mut [u8],
+    ) -> Result<(), CryptoError> {
+        let mask = self.mask(packet_number);
+        let (tag, payload) = payload.split_last_mut().ok_or(CryptoError::DECRYPT_ERROR)?;
+
+        if *tag != mask {
+            return Err(CryptoError::DECRYPT_ERROR);
+        }
+
+        for byte in payload {
+            *byte ^= mask;
+        }
+
+        Ok(())
+    }
+
+    fn encrypt(
+        &self,
+        packet_number: u64,
+        _header: &[u8],
+        payload: &mut [u8],
+    ) -> Result<(), CryptoError> {
+        let mask = self.mask(packet_number);
+        let (tag, payload) = payload.split_last_mut().ok_or(CryptoError::DECRYPT_ERROR)?;
+
+        *tag = mask;
+
+        for byte in payload {
+            *byte ^= mask;
+        }
+
+        Ok(())
+    }
+
+    fn tag_len(&self) -> usize {
+        1
+    }
+
+    fn aead_confidentiality_limit(&self) -> u64 {
+        u64::MAX
+    }
+
+    fn aead_integrity_limit(&self) -> u64 {
+        self.integrity_limit
+    }
+
+    fn cipher_suite(&self) -> tls::CipherSuite {
+        tls::CipherSuite::Unknown
+    }
+}
+
+impl OneRttKey for Key {
+    fn derive_next_key(&self) -> Self {
+        Self {
+            generation: self.generation + 1,
+            integrity_limit: self.integrity_limit,
+        }
+    }
+
+    fn update_sealer_pmtu(&mut self, _pmtu: u16) {}
+
+    fn update_opener_pmtu(&mut self, _pmtu: u16) {}
+}
+
+/// Encodes a short packet with a payload filled with the packet number
+fn packet(packet_number: u8, key_phase: KeyPhase, generation: u8) -> Vec<u8> {
+    let mut bytes = vec![0x40 | key_phase.into_packet_tag_mask()];
+    bytes.extend_from_slice(&DESTINATION_CONNECTION_ID);
+    bytes.push(packet_number);
+
+    let header_len = bytes.len();
+    // include space for the tag
+    bytes.extend_from_slice(&[packet_number; 9]);
+
+    let key = Key {
+        generation,
+        ..Default::default()
+    };
+    let (header, payload) = bytes.split_at_mut(header_len);
+    crypto::Key::encrypt(&key, packet_number as _, header, payload).unwrap();
+
+    bytes
+}
+
+fn largest_acked() -> PacketNumber {
+    PacketNumberSpace::ApplicationData.new_packet_number(VarInt::from_u8(0))
+}
+
+fn unprotect(bytes: &mut [u8]) -> Result<EncryptedShort<'_>, ProcessingError> {
+    let remote_address = SocketAddress::default();
+    let connection_info = ConnectionInfo::new(&remote_address);
+    let buffer = DecoderBufferMut::new(bytes);
+    let (packet, _remaining) =
+        ProtectedPacket::decode(buffer, &connection_info, &DESTINATION_CONNECTION_ID.len())
+            .unwrap();
+
+    if let ProtectedPacket::Short(packet) = packet {
+        Ok(packet.unprotect(&HeaderKey::default(), largest_acked())?)
+    } else {
+        panic!("expected a short packet")
+    }
+}
+
+type Packets<'a> = SmallVec<[Result<EncryptedShort<'a>, ProcessingError>; DECRYPT_BATCH_LEN]>;
+type Decrypted<'a> = SmallVec<[DecryptedShort<'a>; DECRYPT_BATCH_LEN]>;
+
+/// Decrypts the packets and returns the packet number and key generation for each packet
+/// which had its header protection removed
+fn decrypt<'a>(
+    key_set: &mut KeySet<Key>,
+    packets: Packets<'a>,
+) -> (Decrypted<'a>, Vec<(u64, Option<u16>)>) {
+    let now = Clock::default().get_time();
+
+    let decrypted = decrypt_batch(key_set, packets, largest_acked(), now);
+
+    let reported = decrypted
+        .iter()
+        .filter_map(|decrypted| {
+            let packet_number = decrypted.packet_number?.as_u64();
+            let generation = decrypted.result.as_ref().ok().and(decrypted.key_generation);
+            Some((packet_number, generation))
+        })
+        .collect();
+
+    (decrypted, reported)
+}
+
+#[track_caller]
+fn assert_decrypted(decrypted: &DecryptedShort, packet_number: u8) {
+    assert_eq!(
+        decrypted.packet_number.map(|pn| pn.as_u64()),
+        Some(packet_number as u64)
+    );
+
+    let packet = decrypted.result.as_ref().unwrap();
+    assert_eq!(packet.packet_number.as_u64(), packet_number as u64);
+    assert_eq!(
+        packet.payload.as_less_safe_slice(),
+        &[packet_number; 8][..],
+        "packet {} was not decrypted with its own key stream",
+        packet_number
+    );
+}
+
+#[test]
+fn decrypt_batch_order_test() {
+    let mut key_set = KeySet::new(Key::default(), Default::default());
+    let mut datagrams: Vec<_> = (1..=8).map(|pn| packet(pn, KeyPhase::Zero, 0)).collect();
+    let packets = datagrams.iter_mut().map(|bytes| unprotect(bytes)).collect();
+
+    let (decrypted, reported) = decrypt(&mut key_set, packets);
+
+    assert_eq!(decrypted.len(), 8);
+    for (decrypted, packet_number) in decrypted.iter().zip(1..) {
+        assert_decrypted(decrypted, packet_number);
+    }
+
+    let expected: Vec<_> = (1..=8).map(|pn| (pn, None)).collect();
+    assert_eq!(reported, expected);
+    assert_eq!(key_set.key_phase(), KeyPhase::Zero);
+}
+
+#[test]
+fn decrypt_batch_key_phase_change_test() {
+    let mut key_set = KeySet::new(Key::default(), Default::default());
+    let mut datagrams = [
+        packet(1, KeyPhase::Zero, 0),
+        packet(2, KeyPhase::Zero, 0),
+        // the peer initiated a key update
+        packet(3, KeyPhase::One, 1),
+        packet(4, KeyPhase::One, 1),
+    ];
+    let packets = datagrams.iter_mut().map(|bytes| unprotect(bytes)).collect();
+
+    let (decrypted, reported) = decrypt(&mut key_set, packets);
+
+    for (decrypted, packet_number) in decrypted.iter().zip(1..) {
+        assert_decrypted(decrypted, packet_number);
+    }
+
+    // the key update is reported on the first packet with the new phase
+    assert_eq!(
+        reported,
+        vec![(1, None), (2, None), (3, Some(1)), (4, None)]
+    );
+    assert_eq!(key_set.key_phase(), KeyPhase::One);
+}
+
+#[test]
+fn decrypt_batch_failure_test() {
+    let mut key_set = KeySet::new(Key::default(), Default::default());
+    let mut datagrams: Vec<_> = (1..=5).map(|pn| packet(pn, KeyPhase::Zero, 0)).collect();
+
+    // corrupt the tag of a packet in the middle of the batch
+    *datagrams[2].last_mut().unwrap() ^= 1;
+
+    let mut packets: Packets = datagrams.iter_mut().map(|bytes| unprotect(bytes)).collect();
+    // a packet which couldn't have its header protection removed
+    packets.insert(1, Err(CryptoError::DECRYPT_ERROR.into()));
+
+    let (decrypted, reported) = decrypt(&mut key_set, packets);
+
+    assert_eq!(decrypted.len(), 6);
+    assert_decrypted(&decrypted[0], 1);
+
+    assert!(decrypted[1].packet_number.is_none());
+    assert!(decrypted[1].result.is_err());
+
+    assert_decrypted(&decrypted[2], 2);
+
+    assert_eq!(decrypted[3].packet_number.map(|pn| pn.as_u64()), Some(3));
+    assert!(matches!(
+        decrypted[3].result,
+        Err(ProcessingError::CryptoError(_))
+    ));
+
+    assert_decrypted(&decrypted[4], 4);
+    assert_decrypted(&decrypted[5], 5);
+
+    // the failure is reported for the corrupted packet only
+    let expected: Vec<_> = (1..=5).map(|pn| (pn, None)).collect();
+    assert_eq!(reported, expected);
+}
+
+#[test]
+fn decrypt_batch_reserved_bits_test() {
+    let mut key_set = KeySet::new(Key::default(), Default::default());
+    let mut datagrams: Vec<_> = (1..=4).map(|pn| packet(pn, KeyPhase::Zero, 0)).collect();
+
+    // set the reserved bits on an authenticated packet
+    datagrams[1][0] |= 0x18;
+    // corrupt the tag of the packet after it
+    *datagrams[2].last_mut().unwrap() ^= 1;
+
+    let packets = datagrams.iter_mut().map(|bytes| unprotect(bytes)).collect();
+
+    let (decrypted, reported) = decrypt(&mut key_set, packets);
+
+    assert_eq!(decrypted.len(), 4);
+    assert_decrypted(&decrypted[0], 1);
+
+    //= https://www.rfc-editor.org/rfc/rfc9000#section-17.3.1
+    //= type=test
+    //# An endpoint MUST treat receipt of a
+    //# packet that has a non-zero value for these bits, after removing
+    //# both packet and header protection, as a connection error of type
+    //# PROTOCOL_VIOLATION.
+    assert_eq!(decrypted[1].packet_number.map(|pn| pn.as_u64()), Some(2));
+    assert!(matches!(
+        decrypted[1].result,
+        Err(ProcessingError::ConnectionError(_))
+    ));
+
+    assert_eq!(decrypted[2].packet_number.map(|pn| pn.as_u64()), Some(3));
+    assert!(matches!(
+        decrypted[2].result,
+        Err(ProcessingError::CryptoError(_))
+    ));
+
+    assert_decrypted(&decrypted[3], 4);
+
+    let expected: Vec<_> = (1..=4).map(|pn| (pn, None)).collect();
+    assert_eq!(reported, expected);
+}
+
+#[test]
+fn decrypt_batch_integrity_limit_test() {
+    let key = Key {
+        integrity_limit: 1,
+        ..Default::default()
+    };
+    let mut key_set = KeySet::new(key, Default::default());
+    let mut datagrams: Vec<_> = (1..=4).map(|pn| packet(pn, KeyPhase::Zero, 0)).collect();
+
+    // set the reserved bits on an authenticated packet
+    datagrams[0][0] |= 0x18;
+    // corrupt the tags of the packets after it
+    *datagrams[1].last_mut().unwrap() ^= 1;
+    *datagrams[3].last_mut().unwrap() ^= 1;
+
+    let packets = datagrams.iter_mut().map(|bytes| unprotect(bytes)).collect();
+
+    let (decrypted, _reported) = decrypt(&mut key_set, packets);
+
+    // the protocol violation is only reported if the datagram is accepted
+    assert!(matches!(
+        decrypted[0].result,
+        Err(ProcessingError::ConnectionError(_))
+    ));
+    assert!(decrypted[0].integrity_limit_error().is_none());
+
+    assert!(matches!(
+        decrypted[1].result,
+        Err(ProcessingError::CryptoError(_))
+    ));
+    assert!(decrypted[1].integrity_limit_error().is_none());
+    assert_decrypted(&decrypted[2], 3);
+
+    //= https://www.rfc-editor.org/rfc/rfc9001#section-6.6
+    //= type=test
+    //# If the total number of received packets that fail
+    //# authentication within the connection, across all keys, exceeds the
+    //# integrity limit for the selected AEAD, the endpoint MUST immediately
+    //# close the connection with a connection error of type
+    //# AEAD_LIMIT_REACHED and not process any more packets.
+    // the limit is reported on the last failure so the connection is closed even if that
+    // packet's datagram is rejected after the batch is decrypted
+    let error = decrypted[3].integrity_limit_error().unwrap();
+    assert!(matches!(
+        decrypted[3].result,
+        Err(ProcessingError::ConnectionError(err)) if err == error
+    ));
+}
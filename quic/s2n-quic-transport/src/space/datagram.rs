@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    endpoint,
+    connection, endpoint,
     stream::{AbstractStreamManager, StreamTrait as Stream},
     transmission::{interest, WriteContext},
 };
@@ -57,6 +57,11 @@ impl<Config: endpoint::Config> Manager<Config> {
     pub fn on_datagram_frame(&mut self, datagram: DatagramRef) {
         self.receiver.on_datagram(datagram.data);
     }
+
+    // Notifies the receiver that the connection was closed
+    pub fn on_connection_error(&mut self, error: connection::Error) {
+        self.receiver.on_connection_error(error);
+    }
 }
 
 impl<Config: endpoint::Config> interest::Provider for Manager<Config> {
@@ -85,6 +85,12 @@ impl HandshakeStatus {
         }
     }
 
+    /// Returns `true` if the HANDSHAKE_DONE frame has been acknowledged by the client or
+    /// received from the server
+    pub fn is_handshake_done_acked(&self) -> bool {
+        matches!(self, HandshakeStatus::Confirmed)
+    }
+
     /// This method is called on the client when the HANDSHAKE_DONE
     /// frame has been received
     pub fn on_handshake_done_received<Pub: ConnectionPublisher>(&mut self, publisher: &mut Pub) {
@@ -0,0 +1,115 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+use core::time::Duration;
+use s2n_quic_core::{
+    crypto::tls::TlsCallback,
+    event::{self, builder::HandshakePhase, ConnectionPublisher, IntoEvent},
+    time::Timestamp,
+};
+
+/// Measures the time spent in each phase of the handshake
+///
+/// The handshake starts when the connection is created, which is when the client sends its
+/// first Initial packet and when the server receives it. Each phase ends at a milestone and the
+/// next phase starts immediately after it:
+///
+/// ```text
+/// start -> ServerHello -> ServerFinished -> Complete -> HandshakeDoneAcked
+/// ```
+///
+/// The application callbacks invoked by the TLS provider, such as selecting a certificate or
+/// signing with the private key, happen within a phase and are measured by the provider.
+#[derive(Debug)]
+pub struct HandshakeTiming {
+    start: Timestamp,
+    /// The start of the current phase or `None` once the handshake has finished
+    phase_start: Option<Timestamp>,
+}
+
+impl HandshakeTiming {
+    pub fn new(now: Timestamp) -> Self {
+        Self {
+            start: now,
+            phase_start: Some(now),
+        }
+    }
+
+    /// Publishes the time spent in a TLS callback
+    pub fn on_callback_completed<Pub: ConnectionPublisher>(
+        &self,
+        callback: TlsCallback,
+        duration: Duration,
+        publisher: &mut Pub,
+    ) {
+        publisher.on_tls_callback_completed(event::builder::TlsCallbackCompleted {
+            callback: callback.into_event(),
+            duration,
+        });
+    }
+
+    /// Publishes the time spent in `phase` and starts the next phase
+    pub fn on_phase_completed<Pub: ConnectionPublisher>(
+        &mut self,
+        phase: HandshakePhase,
+        now: Timestamp,
+        publisher: &mut Pub,
+    ) {
+        let phase_start = if let Some(phase_start) = self.phase_start {
+            phase_start
+        } else {
+            return;
+        };
+
+        self.phase_start = if matches!(phase, HandshakePhase::HandshakeDoneAcked) {
+            None
+        } else {
+            Some(now)
+        };
+
+        publisher.on_handshake_phase_completed(event::builder::HandshakePhaseCompleted {
+            phase,
+            duration: now.saturating_duration_since(phase_start),
+            elapsed: now.saturating_duration_since(self.start),
+        });
+    }
+
+    /// Returns `true` if all of the phases have completed
+    pub fn is_finished(&self) -> bool {
+        self.phase_start.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+    use s2n_quic_core::event::testing::Publisher;
+    use s2n_quic_platform::time;
+
+    #[test]
+    fn phases_test() {
+        let mut publisher = Publisher::no_snapshot();
+        let mut now = time::now();
+        let mut timing = HandshakeTiming::new(now);
+
+        for phase in [
+            HandshakePhase::ServerHello,
+            HandshakePhase::ServerFinished,
+            HandshakePhase::Complete,
+        ] {
+            now += Duration::from_millis(10);
+            timing.on_phase_completed(phase, now, &mut publisher);
+            assert!(!timing.is_finished());
+        }
+
+        now += Duration::from_millis(10);
+        timing.on_phase_completed(HandshakePhase::HandshakeDoneAcked, now, &mut publisher);
+        assert!(timing.is_finished());
+        assert_eq!(publisher.handshake_phase_completed, 4);
+
+        // nothing is published after the handshake finishes
+        timing.on_phase_completed(HandshakePhase::HandshakeDoneAcked, now, &mut publisher);
+        assert_eq!(publisher.handshake_phase_completed, 4);
+    }
+}
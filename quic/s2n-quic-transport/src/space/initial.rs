@@ -327,6 +327,22 @@ impl<Config: endpoint::Config> InitialSpace<Config> {
         recovery_manager.on_timeout(timestamp, random_generator, &mut context, publisher);
     }
 
+    /// Called when a path is deleted by the path manager
+    pub fn on_path_deleted(
+        &mut self,
+        path_id: path::Id,
+        path_manager: &mut path::Manager<Config>,
+        timestamp: Timestamp,
+        is_handshake_confirmed: bool,
+    ) {
+        self.recovery_manager.on_path_deleted(
+            path_id,
+            path_manager,
+            timestamp,
+            is_handshake_confirmed,
+        );
+    }
+
     /// Called before the Initial packet space is discarded
     pub fn on_discard<Pub: event::ConnectionPublisher>(
         &mut self,
@@ -31,21 +31,24 @@ use s2n_quic_core::{
     time::{timer, Timestamp},
     transport,
 };
+use smallvec::SmallVec;
 
 mod application;
 mod crypto_stream;
 pub(crate) mod datagram;
 mod handshake;
 mod handshake_status;
+mod handshake_timing;
 mod initial;
 mod keep_alive;
 mod session_context;
 mod tx_packet_numbers;
 
-pub(crate) use application::ApplicationSpace;
+pub(crate) use application::{ApplicationSpace, DecryptedShort, DECRYPT_BATCH_LEN};
 pub(crate) use crypto_stream::CryptoStream;
 pub(crate) use handshake::HandshakeSpace;
 pub(crate) use handshake_status::HandshakeStatus;
+pub(crate) use handshake_timing::HandshakeTiming;
 pub(crate) use initial::InitialSpace;
 pub(crate) use session_context::SessionContext;
 pub(crate) use tx_packet_numbers::TxPacketNumbers;
@@ -64,6 +67,7 @@ pub struct PacketSpaceManager<Config: endpoint::Config> {
     zero_rtt_crypto:
         Option<Box<<<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttKey>>,
     handshake_status: HandshakeStatus,
+    handshake_timing: HandshakeTiming,
     /// Server Name Indication
     pub server_name: Option<ServerName>,
     //= https://www.rfc-editor.org/rfc/rfc9000#section-7
@@ -74,6 +78,8 @@ pub struct PacketSpaceManager<Config: endpoint::Config> {
     //# another mechanism is used for agreeing on an application protocol,
     //# endpoints MUST use ALPN for this purpose.
     pub application_protocol: Bytes,
+    /// Details about the negotiated TLS session
+    pub tls_session: tls::SessionInfo,
 }
 
 impl<Config: endpoint::Config> fmt::Debug for PacketSpaceManager<Config> {
@@ -166,8 +172,10 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
             application: None,
             zero_rtt_crypto: None,
             handshake_status: HandshakeStatus::default(),
+            handshake_timing: HandshakeTiming::new(now),
             server_name: None,
             application_protocol: Bytes::new(),
+            tls_session: tls::SessionInfo::default(),
         }
     }
 
@@ -216,10 +224,12 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
                 zero_rtt_crypto: &mut self.zero_rtt_crypto,
                 path_manager,
                 handshake_status: &mut self.handshake_status,
+                handshake_timing: &mut self.handshake_timing,
                 local_id_registry,
                 limits,
                 server_name: &mut self.server_name,
                 application_protocol: &mut self.application_protocol,
+                tls_session: &mut self.tls_session,
                 waker,
                 publisher,
                 datagram,
@@ -288,6 +298,35 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
         path.pto_backoff = path.pto_backoff.min(max_backoff);
     }
 
+    /// Removes any state which references paths that have been deleted by the path manager
+    pub fn on_paths_deleted(
+        &mut self,
+        path_manager: &mut path::Manager<Config>,
+        timestamp: Timestamp,
+    ) {
+        let deleted_paths: SmallVec<[path::Id; 4]> = path_manager.drain_deleted_paths().collect();
+
+        for path_id in deleted_paths {
+            // Paths are normally only deleted after the handshake is confirmed and the Initial
+            // and Handshake spaces are discarded, but every space is checked in case they still
+            // hold packets which were sent on the path
+            if let Some((space, handshake_status)) = self.initial_mut() {
+                let is_handshake_confirmed = handshake_status.is_confirmed();
+                space.on_path_deleted(path_id, path_manager, timestamp, is_handshake_confirmed);
+            }
+
+            if let Some((space, handshake_status)) = self.handshake_mut() {
+                let is_handshake_confirmed = handshake_status.is_confirmed();
+                space.on_path_deleted(path_id, path_manager, timestamp, is_handshake_confirmed);
+            }
+
+            if let Some((space, handshake_status)) = self.application_mut() {
+                let is_handshake_confirmed = handshake_status.is_confirmed();
+                space.on_path_deleted(path_id, path_manager, timestamp, is_handshake_confirmed);
+            }
+        }
+    }
+
     /// Signals the connection was previously blocked by anti-amplification limits
     /// but is now no longer limited.
     pub fn on_amplification_unblocked(&mut self, path: &Path<Config>, timestamp: Timestamp) {
@@ -320,6 +359,22 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
         self.handshake_status.is_complete()
     }
 
+    /// Publishes the duration of the final handshake phase once the HANDSHAKE_DONE frame has
+    /// been delivered
+    pub fn on_handshake_done_timing<Pub: event::ConnectionPublisher>(
+        &mut self,
+        timestamp: Timestamp,
+        publisher: &mut Pub,
+    ) {
+        if !self.handshake_timing.is_finished() && self.handshake_status.is_handshake_done_acked() {
+            self.handshake_timing.on_phase_completed(
+                event::builder::HandshakePhase::HandshakeDoneAcked,
+                timestamp,
+                publisher,
+            );
+        }
+    }
+
     pub(crate) fn on_transmit_close(
         &mut self,
         early_connection_close: &ConnectionClose,
@@ -435,6 +490,7 @@ impl<Config: endpoint::Config> PacketSpaceManager<Config> {
 
             // Close all streams with the derived error
             application.stream_manager.close(error);
+            application.datagram_manager.on_connection_error(error);
         }
     }
 
@@ -7,12 +7,12 @@ use crate::{
     endpoint, path,
     space::{
         datagram, keep_alive::KeepAlive, ApplicationSpace, HandshakeSpace, HandshakeStatus,
-        InitialSpace,
+        HandshakeTiming, InitialSpace,
     },
     stream::AbstractStreamManager,
 };
 use bytes::Bytes;
-use core::{ops::Not, task::Waker};
+use core::{ops::Not, task::Waker, time::Duration};
 use s2n_codec::{DecoderBuffer, DecoderValue};
 use s2n_quic_core::{
     ack,
@@ -48,10 +48,12 @@ pub struct SessionContext<'a, Config: endpoint::Config, Pub: event::ConnectionPu
         Box<<<Config::TLSEndpoint as tls::Endpoint>::Session as CryptoSuite>::ZeroRttKey>,
     >,
     pub handshake_status: &'a mut HandshakeStatus,
+    pub handshake_timing: &'a mut HandshakeTiming,
     pub local_id_registry: &'a mut connection::LocalIdRegistry,
     pub limits: &'a mut Limits,
     pub server_name: &'a mut Option<ServerName>,
     pub application_protocol: &'a mut Bytes,
+    pub tls_session: &'a mut tls::SessionInfo,
     pub waker: &'a Waker,
     pub publisher: &'a mut Pub,
     pub datagram: &'a mut Config::DatagramEndpoint,
@@ -326,6 +328,14 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             key_type: event::builder::KeyType::Handshake,
             cipher_suite,
         });
+
+        // the handshake keys are derived as the server sends the ServerHello and as the client
+        // receives it
+        self.handshake_timing.on_phase_completed(
+            event::builder::HandshakePhase::ServerHello,
+            self.now,
+            self.publisher,
+        );
         Ok(())
     }
 
@@ -429,6 +439,12 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
             cipher_suite,
         });
 
+        self.handshake_timing.on_phase_completed(
+            event::builder::HandshakePhase::ServerFinished,
+            self.now,
+            self.publisher,
+        );
+
         Ok(())
     }
 
@@ -456,6 +472,12 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
         Ok(())
     }
 
+    fn on_session_info(&mut self, session_info: tls::SessionInfo) -> Result<(), transport::Error> {
+        *self.tls_session = session_info;
+
+        Ok(())
+    }
+
     fn on_handshake_complete(&mut self) -> Result<(), transport::Error> {
         // After the handshake is complete, the handshake crypto stream should be completely
         // finished
@@ -482,6 +504,11 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
 
         self.handshake_status
             .on_handshake_complete(Config::ENDPOINT_TYPE, self.publisher);
+        self.handshake_timing.on_phase_completed(
+            event::builder::HandshakePhase::Complete,
+            self.now,
+            self.publisher,
+        );
 
         if let Some(application) = self.application.as_mut() {
             if Config::ENDPOINT_TYPE.is_server() {
@@ -504,6 +531,11 @@ impl<'a, Config: endpoint::Config, Pub: event::ConnectionPublisher>
         }
     }
 
+    fn on_tls_callback_completed(&mut self, callback: tls::TlsCallback, duration: Duration) {
+        self.handshake_timing
+            .on_callback_completed(callback, duration, self.publisher);
+    }
+
     fn receive_initial(&mut self, max_len: Option<usize>) -> Option<Bytes> {
         let space = self.initial.as_deref_mut()?;
 
@@ -161,6 +161,44 @@ macro_rules! impl_handle_api {
             self.0.application_protocol()
         }
 
+        /// Returns the cipher suite negotiated by the TLS handshake
+        ///
+        /// [`CipherSuite::Unknown`]($crate::provider::tls::CipherSuite::Unknown) is returned
+        /// until the handshake is complete.
+        #[inline]
+        pub fn cipher_suite(
+            &self,
+        ) -> $crate::connection::Result<$crate::provider::tls::CipherSuite> {
+            Ok(self.0.tls_session()?.cipher_suite)
+        }
+
+        /// Returns the TLS protocol version negotiated by the TLS handshake
+        ///
+        /// [`ProtocolVersion::Unknown`]($crate::provider::tls::ProtocolVersion::Unknown) is
+        /// returned until the handshake is complete.
+        #[inline]
+        pub fn tls_version(
+            &self,
+        ) -> $crate::connection::Result<$crate::provider::tls::ProtocolVersion> {
+            Ok(self.0.tls_session()?.protocol_version)
+        }
+
+        /// Returns the certificate chain presented by the peer in DER format, starting with the
+        /// end-entity certificate
+        ///
+        /// The chain is empty until the handshake is complete, or if the peer didn't present a
+        /// certificate.
+        #[inline]
+        pub fn peer_certificate_chain(&self) -> $crate::connection::Result<Vec<::bytes::Bytes>> {
+            Ok(self.0.tls_session()?.peer_certificate_chain)
+        }
+
+        /// Returns `true` if the TLS session was resumed from a previous connection
+        #[inline]
+        pub fn is_resumed(&self) -> $crate::connection::Result<bool> {
+            Ok(self.0.tls_session()?.resumed)
+        }
+
         /// Returns the internal identifier for the [`Connection`](`crate::Connection`)
         ///
         /// Note: This internal identifier is not the same as the connection ID included in packet
@@ -186,6 +224,17 @@ macro_rules! impl_handle_api {
             self.0.keep_alive(enabled)
         }
 
+        /// Initiates an update of the 1-RTT packet protection keys
+        ///
+        /// Key updates are performed automatically before the confidentiality limit of the
+        /// negotiated AEAD is reached, so this is mostly useful for testing. The update is
+        /// deferred until the handshake is confirmed and completes once the peer responds with
+        /// the new keys.
+        #[inline]
+        pub fn initiate_key_update(&mut self) -> $crate::connection::Result<()> {
+            self.0.initiate_key_update()
+        }
+
         /// Closes the Connection with the provided error code
         ///
         /// This will immediately terminate all outstanding streams.
@@ -78,10 +78,13 @@ mod tests;
         // add new unstable features to this list
         any(
             feature = "unstable_client_hello",
+            feature = "unstable-provider-congestion-controller",
             feature = "unstable-provider-datagram",
+            feature = "unstable-provider-io-record",
             feature = "unstable-provider-io-testing",
             feature = "unstable-provider-packet-interceptor",
             feature = "unstable-provider-random",
+            feature = "unstable-provider-tls-null",
         ),
         // any unstable features requires at least one of the following conditions
         not(any(
@@ -33,3 +33,22 @@ pub mod cubic {
         }
     }
 }
+
+pub mod bbr {
+    use s2n_quic_core::recovery::bbr::Endpoint;
+
+    /// Provides the BBRv2 congestion controller
+    ///
+    /// The implementation is still being evaluated so it isn't the default controller.
+    #[derive(Debug, Default)]
+    pub struct Provider(());
+
+    impl super::Provider for Provider {
+        type Endpoint = Endpoint;
+        type Error = core::convert::Infallible;
+
+        fn start(self) -> Result<Self::Endpoint, Self::Error> {
+            Ok(Endpoint::default())
+        }
+    }
+}
@@ -4,11 +4,13 @@
 //! Allows applications to limit peer's ability to open new connections
 
 pub use s2n_quic_core::endpoint::{
-    limits::{ConnectionAttempt, Outcome},
+    limits::{ConnectionAttempt, ConnectionClosed, ConnectionOpened, Outcome},
     Limiter,
 };
 use s2n_quic_core::{event::Timestamp, path::THROTTLED_PORTS_LEN};
 
+pub mod source_address;
+
 pub trait Provider: 'static {
     type Limits: 'static + Limiter;
     type Error: core::fmt::Display;
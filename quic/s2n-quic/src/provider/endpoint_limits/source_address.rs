@@ -0,0 +1,374 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Endpoint limits provider which restricts the number of active connections per source address
+//!
+//! Connections are grouped by the address prefix of the peer that opened them. By default, IPv4
+//! connections are grouped by the full address and IPv6 connections are grouped by the `/64`
+//! prefix. When the number of active connections for a prefix reaches one of the configured
+//! thresholds, new connection attempts from that prefix are dropped, closed or retried.
+//!
+//! Any attempt which is not limited by the source address is forwarded to an inner [`Limiter`],
+//! which defaults to the [`Default`](super::Default) endpoint limits.
+
+use super::{ConnectionAttempt, ConnectionClosed, ConnectionOpened, Limiter, Outcome};
+use core::fmt;
+use s2n_quic_core::event::api::SocketAddress;
+use std::collections::HashMap;
+
+const DEFAULT_IPV4_PREFIX_LEN: u8 = 32;
+const DEFAULT_IPV6_PREFIX_LEN: u8 = 64;
+
+/// An error returned when the [`Limits`] are misconfigured
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Error(&'static str);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Allows the source address limits to be built with specific values
+///
+/// # Examples
+///
+/// Send a Retry once a single address has 50 connections and drop attempts once it has 100.
+///
+/// ```rust
+/// use s2n_quic::provider::endpoint_limits::source_address;
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let limits = source_address::Limits::builder()
+///     .with_retry_threshold(50)?
+///     .with_drop_threshold(100)?
+///     .build()?;
+/// #   Ok(())
+/// # }
+/// ```
+pub struct Builder<L = super::Default> {
+    ipv4_prefix_len: u8,
+    ipv6_prefix_len: u8,
+    retry_threshold: Option<usize>,
+    close_threshold: Option<usize>,
+    drop_threshold: Option<usize>,
+    limiter: L,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            ipv4_prefix_len: DEFAULT_IPV4_PREFIX_LEN,
+            ipv6_prefix_len: DEFAULT_IPV6_PREFIX_LEN,
+            retry_threshold: None,
+            close_threshold: None,
+            drop_threshold: None,
+            limiter: super::Default::default(),
+        }
+    }
+}
+
+impl<L: Limiter> Builder<L> {
+    /// Sets the number of leading bits used to group IPv4 peers
+    ///
+    /// Defaults to `32`, which limits each individual address.
+    pub fn with_ipv4_prefix_len(mut self, len: u8) -> Result<Self, Error> {
+        if len == 0 || len > 32 {
+            return Err(Error("IPv4 prefix length must be between 1 and 32"));
+        }
+        self.ipv4_prefix_len = len;
+        Ok(self)
+    }
+
+    /// Sets the number of leading bits used to group IPv6 peers
+    ///
+    /// Defaults to `64`, since a single host is usually assigned an entire `/64` network.
+    pub fn with_ipv6_prefix_len(mut self, len: u8) -> Result<Self, Error> {
+        if len == 0 || len > 128 {
+            return Err(Error("IPv6 prefix length must be between 1 and 128"));
+        }
+        self.ipv6_prefix_len = len;
+        Ok(self)
+    }
+
+    /// Sends a Retry packet for attempts from a prefix with at least `threshold` active connections
+    pub fn with_retry_threshold(mut self, threshold: usize) -> Result<Self, Error> {
+        self.retry_threshold = Some(threshold);
+        Ok(self)
+    }
+
+    /// Closes attempts from a prefix with at least `threshold` active connections
+    pub fn with_close_threshold(mut self, threshold: usize) -> Result<Self, Error> {
+        self.close_threshold = Some(threshold);
+        Ok(self)
+    }
+
+    /// Silently drops attempts from a prefix with at least `threshold` active connections
+    pub fn with_drop_threshold(mut self, threshold: usize) -> Result<Self, Error> {
+        self.drop_threshold = Some(threshold);
+        Ok(self)
+    }
+
+    /// Sets the limiter which is consulted for attempts that are within the source address limits
+    pub fn with_limiter<T: Limiter>(self, limiter: T) -> Result<Builder<T>, Error> {
+        Ok(Builder {
+            ipv4_prefix_len: self.ipv4_prefix_len,
+            ipv6_prefix_len: self.ipv6_prefix_len,
+            retry_threshold: self.retry_threshold,
+            close_threshold: self.close_threshold,
+            drop_threshold: self.drop_threshold,
+            limiter,
+        })
+    }
+
+    /// Build the limits
+    pub fn build(self) -> Result<Limits<L>, Error> {
+        Ok(Limits {
+            ipv4_prefix_len: self.ipv4_prefix_len,
+            ipv6_prefix_len: self.ipv6_prefix_len,
+            retry_threshold: self.retry_threshold,
+            close_threshold: self.close_threshold,
+            drop_threshold: self.drop_threshold,
+            active_connections: HashMap::new(),
+            limiter: self.limiter,
+        })
+    }
+}
+
+/// The masked address of a peer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Prefix {
+    V4([u8; 4]),
+    V6([u8; 16]),
+}
+
+pub struct Limits<L = super::Default> {
+    ipv4_prefix_len: u8,
+    ipv6_prefix_len: u8,
+    retry_threshold: Option<usize>,
+    close_threshold: Option<usize>,
+    drop_threshold: Option<usize>,
+    /// The number of active connections for each prefix
+    ///
+    /// Prefixes are removed once their last connection closes.
+    active_connections: HashMap<Prefix, usize>,
+    limiter: L,
+}
+
+impl Limits {
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+}
+
+impl<L> Limits<L> {
+    /// Returns the number of active connections opened from the prefix of `remote_address`
+    pub fn active_connections(&self, remote_address: &SocketAddress) -> usize {
+        self.prefix(remote_address)
+            .and_then(|prefix| self.active_connections.get(&prefix))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn prefix(&self, remote_address: &SocketAddress) -> Option<Prefix> {
+        const IPV4_MAPPED: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff];
+
+        match remote_address {
+            SocketAddress::IpV4 { ip, .. } => Some(Prefix::V4(mask(**ip, self.ipv4_prefix_len))),
+            // group IPv4-mapped addresses with their IPv4 equivalent
+            SocketAddress::IpV6 { ip, .. } if ip[..12] == IPV4_MAPPED => {
+                let mut v4 = [0; 4];
+                v4.copy_from_slice(&ip[12..]);
+                Some(Prefix::V4(mask(v4, self.ipv4_prefix_len)))
+            }
+            SocketAddress::IpV6 { ip, .. } => Some(Prefix::V6(mask(**ip, self.ipv6_prefix_len))),
+            // SocketAddress is non_exhaustive so don't track anything we don't understand
+            _ => None,
+        }
+    }
+}
+
+/// Clears all of the bits in `ip` after the first `prefix_len` bits
+#[inline]
+fn mask<const N: usize>(mut ip: [u8; N], prefix_len: u8) -> [u8; N] {
+    let mut remaining = prefix_len as usize;
+
+    for byte in ip.iter_mut() {
+        if remaining >= 8 {
+            remaining -= 8;
+        } else {
+            *byte &= !(0xffu8 >> remaining);
+            remaining = 0;
+        }
+    }
+
+    ip
+}
+
+impl<L: Limiter> Limiter for Limits<L> {
+    fn on_connection_attempt(&mut self, info: &ConnectionAttempt) -> Outcome {
+        let active_connections = self.active_connections(&info.remote_address);
+
+        let exceeds = |threshold: Option<usize>| {
+            threshold.map_or(false, |threshold| active_connections >= threshold)
+        };
+
+        // check the most restrictive outcomes first
+        if exceeds(self.drop_threshold) {
+            return Outcome::drop();
+        }
+
+        if exceeds(self.close_threshold) {
+            return Outcome::close();
+        }
+
+        if exceeds(self.retry_threshold) {
+            return Outcome::retry();
+        }
+
+        self.limiter.on_connection_attempt(info)
+    }
+
+    fn on_connection_opened(&mut self, info: &ConnectionOpened) {
+        if let Some(prefix) = self.prefix(&info.remote_address) {
+            *self.active_connections.entry(prefix).or_default() += 1;
+        }
+
+        self.limiter.on_connection_opened(info);
+    }
+
+    fn on_connection_closed(&mut self, info: &ConnectionClosed) {
+        if let Some(prefix) = self.prefix(&info.remote_address) {
+            if let Some(count) = self.active_connections.get_mut(&prefix) {
+                *count -= 1;
+                if *count == 0 {
+                    self.active_connections.remove(&prefix);
+                }
+            } else {
+                debug_assert!(false, "connection closed without being opened");
+            }
+        }
+
+        self.limiter.on_connection_closed(info);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_core::{
+        event::IntoEvent,
+        inet::{SocketAddress, SocketAddressV4, SocketAddressV6},
+        time::{testing::Clock as MockClock, Clock},
+    };
+
+    fn v4(ip: [u8; 4]) -> SocketAddress {
+        SocketAddressV4::new(ip, 443).into()
+    }
+
+    fn v6(ip: [u8; 16]) -> SocketAddress {
+        SocketAddressV6::new(ip, 443).into()
+    }
+
+    fn attempt(limits: &mut Limits, remote_address: &SocketAddress) -> Outcome {
+        let clock = MockClock::default();
+        let info = ConnectionAttempt::new(0, 0, remote_address, clock.get_time().into_event());
+        limits.on_connection_attempt(&info)
+    }
+
+    fn open(limits: &mut Limits, remote_address: &SocketAddress) {
+        let clock = MockClock::default();
+        let info = ConnectionOpened::new(remote_address, clock.get_time().into_event());
+        limits.on_connection_opened(&info);
+    }
+
+    fn close(limits: &mut Limits, remote_address: &SocketAddress) {
+        let clock = MockClock::default();
+        let info = ConnectionClosed::new(remote_address, clock.get_time().into_event());
+        limits.on_connection_closed(&info);
+    }
+
+    #[test]
+    fn thresholds_test() {
+        let mut limits = Limits::builder()
+            .with_retry_threshold(2)
+            .unwrap()
+            .with_close_threshold(3)
+            .unwrap()
+            .with_drop_threshold(4)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let addr = v4([192, 0, 2, 1]);
+        let expected = [
+            Outcome::allow(),
+            Outcome::allow(),
+            Outcome::retry(),
+            Outcome::close(),
+            Outcome::drop(),
+        ];
+
+        for outcome in expected.iter() {
+            assert_eq!(&attempt(&mut limits, &addr), outcome);
+            open(&mut limits, &addr);
+        }
+
+        // other addresses are not affected
+        assert_eq!(attempt(&mut limits, &v4([192, 0, 2, 2])), Outcome::allow());
+
+        // closing connections frees up the quota
+        for _ in 0..4 {
+            close(&mut limits, &addr);
+        }
+        assert_eq!(attempt(&mut limits, &addr), Outcome::allow());
+
+        close(&mut limits, &addr);
+        assert!(limits.active_connections.is_empty());
+    }
+
+    #[test]
+    fn prefix_test() {
+        let mut limits = Limits::builder()
+            .with_ipv4_prefix_len(24)
+            .unwrap()
+            .with_drop_threshold(1)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        open(&mut limits, &v4([192, 0, 2, 1]));
+        assert_eq!(attempt(&mut limits, &v4([192, 0, 2, 200])), Outcome::drop());
+        assert_eq!(attempt(&mut limits, &v4([192, 0, 3, 1])), Outcome::allow());
+
+        // IPv4-mapped addresses share the quota of the IPv4 address
+        let mut mapped = [0; 16];
+        mapped[10..].copy_from_slice(&[0xff, 0xff, 192, 0, 2, 7]);
+        assert_eq!(attempt(&mut limits, &v6(mapped)), Outcome::drop());
+
+        // IPv6 addresses are grouped by /64 by default
+        let mut ip = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        open(&mut limits, &v6(ip));
+        ip[15] = 2;
+        assert_eq!(attempt(&mut limits, &v6(ip)), Outcome::drop());
+        ip[7] = 1;
+        assert_eq!(attempt(&mut limits, &v6(ip)), Outcome::allow());
+    }
+
+    #[test]
+    fn mask_test() {
+        assert_eq!(mask([255, 255, 255, 255], 32), [255, 255, 255, 255]);
+        assert_eq!(mask([255, 255, 255, 255], 24), [255, 255, 255, 0]);
+        assert_eq!(mask([255, 255, 255, 255], 20), [255, 255, 240, 0]);
+        assert_eq!(mask([255, 255, 255, 255], 1), [128, 0, 0, 0]);
+    }
+
+    #[test]
+    fn invalid_prefix_test() {
+        assert!(Limits::builder().with_ipv4_prefix_len(0).is_err());
+        assert!(Limits::builder().with_ipv4_prefix_len(33).is_err());
+        assert!(Limits::builder().with_ipv6_prefix_len(129).is_err());
+    }
+}
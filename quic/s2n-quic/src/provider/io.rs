@@ -19,6 +19,9 @@ pub trait Provider: 'static {
 #[cfg(any(test, all(not(docdiff), feature = "unstable-provider-io-testing")))]
 pub mod testing;
 
+#[cfg(any(test, all(not(docdiff), feature = "unstable-provider-io-record")))]
+pub mod record;
+
 pub mod tokio;
 
 pub use self::tokio as default;
@@ -0,0 +1,726 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Records the traffic of an endpoint so it can be replayed
+//!
+//! The [`Recorder`] wraps the IO, random and TLS providers of an endpoint. Every datagram
+//! received and transmitted by the endpoint is written to the recording with its timestamp,
+//! addresses and ECN markings, along with every random value the endpoint draws and the
+//! messages exchanged by each TLS session. The [`replay`] module feeds the recording back
+//! through the testing IO provider with a deterministic clock so a failure captured in
+//! production can be reproduced under a debugger.
+//!
+//! TLS providers draw their own random values, which can't be recorded. Instead, the provider
+//! writes the negotiated secrets to the recording with its key log, which the replayed sessions
+//! use to derive the same keys.
+//!
+//! ```rust,ignore
+//! let recorder = Recorder::create("connection.rec")?;
+//! let tls = tls::default::Server::builder()
+//!     .with_certificate(cert, key)?
+//!     .with_key_log(recorder.key_log())?
+//!     .build()?;
+//! let server = Server::builder()
+//!     .with_io(recorder.io(io::Default::new("0.0.0.0:443")?))?
+//!     .with_random(recorder.random(random::Default::default()))?
+//!     .with_tls(recorder.tls(tls))?
+//!     .start()?;
+//! ```
+//!
+//! The recording contains the secrets of every connection, so it must be protected like a key.
+
+use crate::provider::random;
+use core::time::Duration;
+use s2n_quic_core::{
+    endpoint,
+    inet::{ExplicitCongestionNotification, SocketAddress},
+    io::{
+        rx::{self, Entry as _},
+        tx,
+    },
+    path::{Handle as _, MaxMtu},
+    time::{Clock, Timestamp},
+};
+use std::{
+    fs,
+    io::{self, Read, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    path::Path,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+pub mod replay;
+pub mod tls;
+
+const MAGIC: &[u8; 8] = b"s2nqrec\x01";
+
+/// The largest payload of a UDP datagram, which also bounds random values and key log lines
+const MAX_DATAGRAM_LEN: usize = u16::MAX as usize;
+/// TLS handshake messages have a 24-bit length
+const MAX_TLS_MESSAGE_LEN: usize = (1 << 24) - 1;
+
+const DATAGRAM: u8 = 0;
+const PUBLIC_RANDOM: u8 = 1;
+const PRIVATE_RANDOM: u8 = 2;
+const TRANSMIT: u8 = 3;
+const KEY_LOG: u8 = 4;
+const TLS: u8 = 5;
+
+/// An event captured by the [`Recorder`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Record {
+    /// A datagram received by the endpoint
+    Datagram(Datagram),
+    /// Bytes returned from [`random::Generator::public_random_fill`]
+    PublicRandom(Vec<u8>),
+    /// Bytes returned from [`random::Generator::private_random_fill`]
+    PrivateRandom(Vec<u8>),
+    /// A datagram transmitted by the endpoint
+    Transmit(Datagram),
+    /// A line of the TLS provider's key log, without the trailing newline
+    KeyLog(Vec<u8>),
+    /// An interaction between a TLS session and its connection
+    ///
+    /// Sessions are numbered in the order they were created by the endpoint.
+    Tls { session: u32, event: tls::Event },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Datagram {
+    /// The time the datagram was received or transmitted, relative to when the endpoint started
+    pub time: Duration,
+    pub local_address: SocketAddress,
+    pub remote_address: SocketAddress,
+    pub ecn: ExplicitCongestionNotification,
+    pub payload: Vec<u8>,
+}
+
+impl Record {
+    pub fn encode<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Datagram(datagram) => {
+                w.write_all(&[DATAGRAM])?;
+                datagram.encode(w)
+            }
+            Self::PublicRandom(bytes) => {
+                w.write_all(&[PUBLIC_RANDOM])?;
+                encode_bytes(w, bytes)
+            }
+            Self::PrivateRandom(bytes) => {
+                w.write_all(&[PRIVATE_RANDOM])?;
+                encode_bytes(w, bytes)
+            }
+            Self::Transmit(datagram) => {
+                w.write_all(&[TRANSMIT])?;
+                datagram.encode(w)
+            }
+            Self::KeyLog(line) => {
+                w.write_all(&[KEY_LOG])?;
+                encode_bytes(w, line)
+            }
+            Self::Tls { session, event } => {
+                w.write_all(&[TLS])?;
+                w.write_all(&session.to_le_bytes())?;
+                event.encode(w)
+            }
+        }
+    }
+
+    /// Decodes the next record
+    ///
+    /// Returns `None` at the end of the recording.
+    pub fn decode<R: Read>(r: &mut R) -> io::Result<Option<Self>> {
+        let mut tag = [0];
+        if r.read(&mut tag)? == 0 {
+            return Ok(None);
+        }
+
+        let record = match tag[0] {
+            DATAGRAM => Self::Datagram(Datagram::decode(r)?),
+            PUBLIC_RANDOM => Self::PublicRandom(decode_bytes(r, MAX_DATAGRAM_LEN)?),
+            PRIVATE_RANDOM => Self::PrivateRandom(decode_bytes(r, MAX_DATAGRAM_LEN)?),
+            TRANSMIT => Self::Transmit(Datagram::decode(r)?),
+            KEY_LOG => Self::KeyLog(decode_bytes(r, MAX_DATAGRAM_LEN)?),
+            TLS => {
+                let session = u32::from_le_bytes(read_array(r)?);
+                let event = tls::Event::decode(r)?;
+                Self::Tls { session, event }
+            }
+            _ => return Err(invalid_data("invalid record tag")),
+        };
+
+        Ok(Some(record))
+    }
+}
+
+impl Datagram {
+    fn encode<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&(self.time.as_micros() as u64).to_le_bytes())?;
+        encode_address(w, self.local_address)?;
+        encode_address(w, self.remote_address)?;
+        w.write_all(&[self.ecn as u8])?;
+        encode_bytes(w, &self.payload)
+    }
+
+    fn decode<R: Read>(r: &mut R) -> io::Result<Self> {
+        let time = Duration::from_micros(u64::from_le_bytes(read_array(r)?));
+        let local_address = decode_address(r)?;
+        let remote_address = decode_address(r)?;
+        let [ecn] = read_array::<_, 1>(r)?;
+        let ecn = ExplicitCongestionNotification::new(ecn);
+        let payload = decode_bytes(r, MAX_DATAGRAM_LEN)?;
+        Ok(Self {
+            time,
+            local_address,
+            remote_address,
+            ecn,
+            payload,
+        })
+    }
+}
+
+fn encode_address<W: Write>(w: &mut W, address: SocketAddress) -> io::Result<()> {
+    let address: SocketAddr = address.into();
+    match address.ip() {
+        IpAddr::V4(ip) => {
+            w.write_all(&[4])?;
+            w.write_all(&ip.octets())?;
+        }
+        IpAddr::V6(ip) => {
+            w.write_all(&[6])?;
+            w.write_all(&ip.octets())?;
+        }
+    }
+    w.write_all(&address.port().to_le_bytes())
+}
+
+fn decode_address<R: Read>(r: &mut R) -> io::Result<SocketAddress> {
+    let ip: IpAddr = match read_array::<_, 1>(r)? {
+        [4] => Ipv4Addr::from(read_array::<_, 4>(r)?).into(),
+        [6] => Ipv6Addr::from(read_array::<_, 16>(r)?).into(),
+        _ => return Err(invalid_data("invalid address family")),
+    };
+    let port = u16::from_le_bytes(read_array(r)?);
+    Ok(SocketAddr::new(ip, port).into())
+}
+
+fn encode_bytes<W: Write>(w: &mut W, bytes: &[u8]) -> io::Result<()> {
+    w.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    w.write_all(bytes)
+}
+
+/// Decodes a length-prefixed field, which is rejected if it's longer than `max_len`
+fn decode_bytes<R: Read>(r: &mut R, max_len: usize) -> io::Result<Vec<u8>> {
+    let len = u32::from_le_bytes(read_array(r)?) as usize;
+    if len > max_len {
+        return Err(invalid_data("field exceeds the maximum length"));
+    }
+
+    // grow the buffer as the field is read in case the recording is truncated
+    let mut bytes = vec![];
+    r.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    Ok(bytes)
+}
+
+fn read_array<R: Read, const N: usize>(r: &mut R) -> io::Result<[u8; N]> {
+    let mut value = [0; N];
+    r.read_exact(&mut value)?;
+    Ok(value)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Reads the records from a recording
+pub struct Reader<R: Read> {
+    read: R,
+}
+
+impl Reader<io::BufReader<fs::File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::File::open(path)?;
+        Self::new(io::BufReader::new(file))
+    }
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(mut read: R) -> io::Result<Self> {
+        let magic: [u8; 8] = read_array(&mut read)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a recording"));
+        }
+
+        Ok(Self { read })
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Record::decode(&mut self.read).transpose()
+    }
+}
+
+/// Records the datagrams, random values and TLS sessions of a single endpoint
+///
+/// Records are flushed to the output after each batch of datagrams received or transmitted by
+/// the endpoint, so the recording is intact if the endpoint crashes.
+#[derive(Clone)]
+pub struct Recorder(Arc<Mutex<State>>);
+
+struct State {
+    out: Box<dyn Write + Send>,
+    /// The time of the first clock reading by the endpoint
+    start: Option<Timestamp>,
+    /// The number of TLS sessions created by the endpoint
+    sessions: u32,
+}
+
+impl State {
+    fn write(&mut self, record: &Record) {
+        if record.encode(&mut self.out).is_err() {
+            // stop recording rather than interrupting the endpoint
+            self.out = Box::new(io::sink());
+        }
+    }
+
+    /// Makes sure the records are captured in case the endpoint crashes while processing them
+    fn flush(&mut self) {
+        if self.out.flush().is_err() {
+            self.out = Box::new(io::sink());
+        }
+    }
+
+    fn time<C: Clock>(&mut self, clock: &C) -> Duration {
+        let now = clock.get_time();
+        let start = *self.start.get_or_insert(now);
+        now.saturating_duration_since(start)
+    }
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        let _ = self.out.flush();
+    }
+}
+
+impl Recorder {
+    /// Creates a recorder which writes to a file at `path`
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::File::create(path)?;
+        Self::new(io::BufWriter::new(file))
+    }
+
+    /// Creates a recorder which writes to `out`
+    pub fn new<W: 'static + Write + Send>(mut out: W) -> io::Result<Self> {
+        out.write_all(MAGIC)?;
+        let state = State {
+            out: Box::new(out),
+            start: None,
+            sessions: 0,
+        };
+        Ok(Self(Arc::new(Mutex::new(state))))
+    }
+
+    /// Wraps an IO provider so the received and transmitted datagrams are recorded
+    pub fn io<P: super::Provider>(&self, io: P) -> Io<P> {
+        Io {
+            io,
+            recorder: self.clone(),
+        }
+    }
+
+    /// Wraps a random provider so the generated values are recorded
+    pub fn random<P: random::Provider>(&self, random: P) -> Random<P> {
+        Random {
+            random,
+            recorder: self.clone(),
+        }
+    }
+
+    /// Wraps a TLS provider so the sessions are recorded
+    ///
+    /// The provider also needs to write its key log to [`Self::key_log`] for the sessions to
+    /// be replayed.
+    pub fn tls<P: crate::provider::tls::Provider>(&self, tls: P) -> tls::Provider<P> {
+        tls::Provider {
+            provider: tls,
+            recorder: self.clone(),
+        }
+    }
+
+    /// Returns a writer for the key log of the TLS provider
+    pub fn key_log(&self) -> KeyLog {
+        KeyLog {
+            recorder: self.clone(),
+            line: vec![],
+        }
+    }
+
+    /// Flushes any buffered records to the output
+    pub fn flush(&self) -> io::Result<()> {
+        self.0.lock().unwrap().out.flush()
+    }
+
+    fn write(&self, record: &Record) {
+        self.0.lock().unwrap().write(record);
+    }
+
+    fn time<C: Clock>(&self, clock: &C) -> Duration {
+        self.0.lock().unwrap().time(clock)
+    }
+
+    fn next_session(&self) -> u32 {
+        let mut state = self.0.lock().unwrap();
+        let id = state.sessions;
+        state.sessions += 1;
+        id
+    }
+
+    fn on_tls(&self, session: u32, event: tls::Event) {
+        self.write(&Record::Tls { session, event });
+    }
+
+    fn on_receive<Q: rx::Queue, C: Clock>(&self, queue: &mut Q, clock: &C) {
+        let mut state = self.0.lock().unwrap();
+        let time = state.time(clock);
+        let local_address = queue.local_address();
+
+        for entry in queue.as_slice_mut() {
+            if let Some((header, payload)) = entry.read(&local_address) {
+                let record = Record::Datagram(Datagram {
+                    time,
+                    local_address: header.path.local_address().0,
+                    remote_address: header.path.remote_address().0,
+                    ecn: header.ecn,
+                    payload: payload.to_vec(),
+                });
+                state.write(&record);
+            }
+        }
+
+        state.flush();
+    }
+}
+
+/// Records the key log of a TLS provider
+pub struct KeyLog {
+    recorder: Recorder,
+    line: Vec<u8>,
+}
+
+impl Write for KeyLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for byte in buf {
+            if *byte == b'\n' {
+                let line = core::mem::take(&mut self.line);
+                self.recorder.write(&Record::KeyLog(line));
+            } else {
+                self.line.push(*byte);
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// An IO provider which records the datagrams received and transmitted by the endpoint
+pub struct Io<P> {
+    io: P,
+    recorder: Recorder,
+}
+
+impl<P: super::Provider> super::Provider for Io<P> {
+    type PathHandle = P::PathHandle;
+    type Error = P::Error;
+
+    fn start<E: endpoint::Endpoint<PathHandle = Self::PathHandle>>(
+        self,
+        endpoint: E,
+    ) -> Result<SocketAddress, Self::Error> {
+        let endpoint = Endpoint {
+            endpoint,
+            recorder: self.recorder,
+        };
+        self.io.start(endpoint)
+    }
+}
+
+struct Endpoint<E> {
+    endpoint: E,
+    recorder: Recorder,
+}
+
+impl<E: endpoint::Endpoint> endpoint::Endpoint for Endpoint<E> {
+    type PathHandle = E::PathHandle;
+    type Subscriber = E::Subscriber;
+
+    const ENDPOINT_TYPE: endpoint::Type = E::ENDPOINT_TYPE;
+
+    fn receive<Rx, C>(&mut self, rx: &mut Rx, clock: &C)
+    where
+        Rx: rx::Queue<Handle = Self::PathHandle>,
+        C: Clock,
+    {
+        self.recorder.on_receive(rx, clock);
+        self.endpoint.receive(rx, clock)
+    }
+
+    fn transmit<Tx, C>(&mut self, tx: &mut Tx, clock: &C)
+    where
+        Tx: tx::Queue<Handle = Self::PathHandle>,
+        C: Clock,
+    {
+        // clients transmit before they receive anything so this also starts the recording clock
+        let mut tx = TxQueue {
+            queue: tx,
+            recorder: &self.recorder,
+            time: self.recorder.time(clock),
+        };
+        self.endpoint.transmit(&mut tx, clock);
+        self.recorder.0.lock().unwrap().flush();
+    }
+
+    fn poll_wakeups<C: Clock>(
+        &mut self,
+        cx: &mut Context<'_>,
+        clock: &C,
+    ) -> Poll<Result<usize, endpoint::CloseError>> {
+        self.endpoint.poll_wakeups(cx, clock)
+    }
+
+    fn timeout(&self) -> Option<Timestamp> {
+        self.endpoint.timeout()
+    }
+
+    fn set_max_mtu(&mut self, max_mtu: MaxMtu) {
+        self.endpoint.set_max_mtu(max_mtu)
+    }
+
+    fn subscriber(&mut self) -> &mut Self::Subscriber {
+        self.endpoint.subscriber()
+    }
+}
+
+/// Records the datagrams pushed to the transmission queue
+struct TxQueue<'a, Q> {
+    queue: &'a mut Q,
+    recorder: &'a Recorder,
+    time: Duration,
+}
+
+impl<'a, Q: tx::Queue> tx::Queue for TxQueue<'a, Q> {
+    type Entry = Q::Entry;
+    type Handle = Q::Handle;
+
+    const SUPPORTS_ECN: bool = Q::SUPPORTS_ECN;
+    const SUPPORTS_PACING: bool = Q::SUPPORTS_PACING;
+    const SUPPORTS_FLOW_LABELS: bool = Q::SUPPORTS_FLOW_LABELS;
+
+    fn push<M: tx::Message<Handle = Self::Handle>>(
+        &mut self,
+        message: M,
+    ) -> Result<tx::Outcome, tx::Error> {
+        let handle = message.path_handle();
+        let mut datagram = Datagram {
+            time: self.time,
+            local_address: handle.local_address().0,
+            remote_address: handle.remote_address().0,
+            ecn: Default::default(),
+            payload: vec![],
+        };
+
+        let message = TxMessage {
+            message,
+            datagram: &mut datagram,
+        };
+        let outcome = self.queue.push(message)?;
+
+        self.recorder.write(&Record::Transmit(datagram));
+
+        Ok(outcome)
+    }
+
+    fn as_slice_mut(&mut self) -> &mut [Self::Entry] {
+        self.queue.as_slice_mut()
+    }
+
+    fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+/// Copies the ECN markings and payload of a message into the datagram record
+struct TxMessage<'a, M> {
+    message: M,
+    datagram: &'a mut Datagram,
+}
+
+impl<'a, M: tx::Message> tx::Message for TxMessage<'a, M> {
+    type Handle = M::Handle;
+
+    fn path_handle(&self) -> &Self::Handle {
+        self.message.path_handle()
+    }
+
+    fn ecn(&mut self) -> ExplicitCongestionNotification {
+        let ecn = self.message.ecn();
+        self.datagram.ecn = ecn;
+        ecn
+    }
+
+    fn delay(&mut self) -> Duration {
+        self.message.delay()
+    }
+
+    fn ipv6_flow_label(&mut self) -> u32 {
+        self.message.ipv6_flow_label()
+    }
+
+    fn can_gso(&self, segment_len: usize, segment_count: usize) -> bool {
+        self.message.can_gso(segment_len, segment_count)
+    }
+
+    fn write_payload(
+        &mut self,
+        buffer: tx::PayloadBuffer,
+        gso_offset: usize,
+    ) -> Result<usize, tx::Error> {
+        let buffer = unsafe {
+            // Safety: the buffer is only read after the message has written to it
+            buffer.into_mut_slice()
+        };
+        let len = self
+            .message
+            .write_payload(tx::PayloadBuffer::new(&mut *buffer), gso_offset)?;
+        self.datagram.payload = buffer[..len].to_vec();
+        Ok(len)
+    }
+}
+
+/// A random provider which records the values generated for the endpoint
+pub struct Random<P> {
+    random: P,
+    recorder: Recorder,
+}
+
+impl<P: random::Provider> random::Provider for Random<P> {
+    type Generator = Generator<P::Generator>;
+    type Error = P::Error;
+
+    fn start(self) -> Result<Self::Generator, Self::Error> {
+        let generator = self.random.start()?;
+        Ok(Generator {
+            generator,
+            recorder: self.recorder,
+        })
+    }
+}
+
+pub struct Generator<G> {
+    generator: G,
+    recorder: Recorder,
+}
+
+impl<G: random::Generator> random::Generator for Generator<G> {
+    fn public_random_fill(&mut self, dest: &mut [u8]) {
+        self.generator.public_random_fill(dest);
+        self.recorder.write(&Record::PublicRandom(dest.to_vec()));
+    }
+
+    fn private_random_fill(&mut self, dest: &mut [u8]) {
+        self.generator.private_random_fill(dest);
+        self.recorder.write(&Record::PrivateRandom(dest.to_vec()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_test() {
+        let records = vec![
+            Record::PublicRandom(vec![1, 2, 3]),
+            Record::Datagram(Datagram {
+                time: Duration::from_millis(25),
+                local_address: "127.0.0.1:443".parse::<SocketAddr>().unwrap().into(),
+                remote_address: "[::1]:49152".parse::<SocketAddr>().unwrap().into(),
+                ecn: ExplicitCongestionNotification::Ect0,
+                payload: vec![42; 1200],
+            }),
+            Record::PrivateRandom(vec![]),
+            Record::Transmit(Datagram {
+                time: Duration::from_millis(30),
+                local_address: "127.0.0.1:443".parse::<SocketAddr>().unwrap().into(),
+                remote_address: "[::1]:49152".parse::<SocketAddr>().unwrap().into(),
+                ecn: ExplicitCongestionNotification::NotEct,
+                payload: vec![7; 100],
+            }),
+            Record::KeyLog(b"SERVER_TRAFFIC_SECRET_0 0102 0304".to_vec()),
+            Record::Tls {
+                session: 1,
+                event: tls::Event::Send {
+                    space: tls::Space::Handshake,
+                    payload: vec![8; 10],
+                },
+            },
+            Record::Tls {
+                session: 1,
+                event: tls::Event::SessionInfo {
+                    cipher_suite: crate::provider::tls::CipherSuite::TLS_AES_128_GCM_SHA256,
+                    protocol_version: crate::provider::tls::ProtocolVersion::TLS_1_3,
+                    peer_certificate_chain: vec![vec![1, 2], vec![]],
+                    resumed: false,
+                },
+            },
+        ];
+
+        let mut out = MAGIC.to_vec();
+        for record in &records {
+            record.encode(&mut out).unwrap();
+        }
+
+        let decoded = Reader::new(&out[..])
+            .unwrap()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(decoded, records);
+
+        assert!(Reader::new(&b"invalid!"[..]).is_err());
+    }
+
+    #[test]
+    fn oversized_field_test() {
+        let mut out = MAGIC.to_vec();
+        out.push(PUBLIC_RANDOM);
+        out.extend_from_slice(&u32::MAX.to_le_bytes());
+
+        let error = Reader::new(&out[..]).unwrap().next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        // a field which is cut short by the end of the recording is also rejected
+        let mut out = MAGIC.to_vec();
+        out.push(PUBLIC_RANDOM);
+        out.extend_from_slice(&100u32.to_le_bytes());
+        out.extend_from_slice(&[1; 10]);
+
+        let error = Reader::new(&out[..]).unwrap().next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replays a recording through the testing IO provider
+//!
+//! ```rust,ignore
+//! let replay = Replay::open("connection.rec")?;
+//! let address = replay.local_address().unwrap();
+//! let random = replay.random();
+//! let tls = replay.tls();
+//!
+//! testing::test(replay, |handle| {
+//!     let server = Server::builder()
+//!         .with_io(handle.builder().with_address(address).build()?)?
+//!         .with_random(random)?
+//!         .with_tls(tls)?
+//!         .start()?;
+//!     Ok(())
+//! })?;
+//! ```
+//!
+//! The replayed endpoint is expected to transmit the same datagrams as the recorded endpoint.
+//! The replay panics at the first transmission which differs from the recording, since the
+//! endpoint has taken a different path from that point on. Connection IDs and stateless reset
+//! tokens aren't recorded, so both endpoints need providers which generate the same values on
+//! each run.
+
+use super::{Datagram, Reader, Record};
+use crate::provider::{
+    io::testing::{
+        network::{Buffers, Packet},
+        primary, time, Network,
+    },
+    random,
+};
+use core::{convert::Infallible, time::Duration};
+use s2n_quic_core::{inet::SocketAddress, path::Tuple, time::Timestamp};
+use std::{io, path::Path};
+
+pub mod tls;
+
+/// A testing network which delivers the recorded datagrams to the endpoint
+///
+/// Datagrams are delivered at the same offset from the start of the test as they were received
+/// in the recording. The datagrams transmitted by the endpoint are compared to the recorded
+/// transmissions and then dropped.
+#[derive(Debug, Default)]
+pub struct Replay {
+    local_address: Option<SocketAddress>,
+    datagrams: Option<Vec<Datagram>>,
+    transmissions: Vec<Datagram>,
+    /// The number of transmissions which matched the recording
+    transmitted: usize,
+    start: Option<Timestamp>,
+    public: Vec<u8>,
+    private: Vec<u8>,
+    tls: tls::Recording,
+}
+
+impl Replay {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(Reader::open(path)?)
+    }
+
+    pub fn new<R: io::Read>(reader: Reader<R>) -> io::Result<Self> {
+        let mut replay = Self::default();
+        let mut datagrams = vec![];
+
+        for record in reader {
+            match record? {
+                Record::Datagram(datagram) => {
+                    replay.local_address.get_or_insert(datagram.local_address);
+                    datagrams.push(datagram);
+                }
+                Record::PublicRandom(bytes) => replay.public.extend(bytes),
+                Record::PrivateRandom(bytes) => replay.private.extend(bytes),
+                Record::Transmit(datagram) => {
+                    replay.local_address.get_or_insert(datagram.local_address);
+                    replay.transmissions.push(datagram);
+                }
+                Record::KeyLog(line) => replay.tls.on_key_log(&line)?,
+                Record::Tls { session, event } => replay.tls.on_event(session, event),
+            }
+        }
+
+        replay.datagrams = Some(datagrams);
+
+        Ok(replay)
+    }
+
+    /// Returns the address of the recorded endpoint
+    ///
+    /// The replayed endpoint needs to be bound to this address in order to receive the datagrams.
+    /// Returns `None` if the recording doesn't contain any datagrams.
+    pub fn local_address(&self) -> Option<SocketAddress> {
+        self.local_address
+    }
+
+    /// Returns a random provider which generates the recorded random values
+    pub fn random(&self) -> Random {
+        Random {
+            public: self.public.clone(),
+            private: self.private.clone(),
+        }
+    }
+
+    /// Returns a TLS provider which replays the recorded sessions
+    pub fn tls(&self) -> tls::Provider {
+        tls::Provider::new(self.tls.clone())
+    }
+
+    /// Compares a datagram transmitted by the replayed endpoint to the recording
+    fn on_transmit(&mut self, packet: &Packet) {
+        let index = self.transmitted;
+
+        // the replay may outlive the recording
+        let expected = if let Some(expected) = self.transmissions.get(index) {
+            expected
+        } else {
+            return;
+        };
+
+        let time = self.start.map_or(Duration::ZERO, |start| {
+            time::now().saturating_duration_since(start)
+        });
+        let actual = Datagram {
+            time,
+            local_address: packet.path.local_address.0,
+            remote_address: packet.path.remote_address.0,
+            ecn: packet.ecn,
+            payload: packet.payload.clone(),
+        };
+
+        assert_eq!(
+            &actual, expected,
+            "transmission {} diverged from the recording",
+            index
+        );
+
+        self.transmitted += 1;
+    }
+}
+
+impl Network for Replay {
+    fn execute(&mut self, buffers: &Buffers) -> usize {
+        let start = *self.start.get_or_insert_with(time::now);
+
+        let mut transmissions = 0;
+        buffers.pending_transmissions(|packet| {
+            self.on_transmit(&packet);
+            transmissions += 1;
+            Ok(())
+        });
+
+        if let Some(datagrams) = self.datagrams.take() {
+            let buffers = buffers.clone();
+
+            // the replay is the primary task so the test runs until every datagram is delivered
+            primary::spawn(async move {
+                let mut datagrams = datagrams.into_iter().peekable();
+
+                while let Some(datagram) = datagrams.next() {
+                    let time = datagram.time;
+                    time::delay_until(start + time).await;
+
+                    // datagrams received at the same time are delivered together so the endpoint
+                    // processes them in a single batch, like the recorded endpoint did
+                    let mut batch = vec![datagram];
+                    while let Some(datagram) = datagrams.next_if(|datagram| datagram.time == time) {
+                        batch.push(datagram);
+                    }
+
+                    for datagram in batch {
+                        let packet = Packet {
+                            path: Tuple {
+                                local_address: datagram.local_address.into(),
+                                remote_address: datagram.remote_address.into(),
+                            },
+                            ecn: datagram.ecn,
+                            payload: datagram.payload,
+                        };
+
+                        buffers.rx(datagram.local_address, |queue| queue.receive(packet));
+                    }
+                }
+
+                // give the endpoint a chance to process the final datagram
+                time::delay(Duration::from_millis(1)).await;
+            });
+        }
+
+        transmissions
+    }
+}
+
+/// A random provider which generates the values from a recording
+///
+/// Once the recorded values are exhausted the generator fills with zeros.
+#[derive(Debug, Default)]
+pub struct Random {
+    public: Vec<u8>,
+    private: Vec<u8>,
+}
+
+impl random::Provider for Random {
+    type Generator = Generator;
+    type Error = Infallible;
+
+    fn start(self) -> Result<Self::Generator, Self::Error> {
+        Ok(Generator {
+            public: Stream::new(self.public),
+            private: Stream::new(self.private),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Generator {
+    public: Stream,
+    private: Stream,
+}
+
+impl random::Generator for Generator {
+    fn public_random_fill(&mut self, dest: &mut [u8]) {
+        self.public.fill(dest);
+    }
+
+    fn private_random_fill(&mut self, dest: &mut [u8]) {
+        self.private.fill(dest);
+    }
+}
+
+#[derive(Debug)]
+struct Stream {
+    bytes: Vec<u8>,
+    offset: usize,
+}
+
+impl Stream {
+    fn new(bytes: Vec<u8>) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    fn fill(&mut self, dest: &mut [u8]) {
+        let remaining = &self.bytes[self.offset..];
+        let len = remaining.len().min(dest.len());
+        let (recorded, exhausted) = dest.split_at_mut(len);
+        recorded.copy_from_slice(&remaining[..len]);
+        exhausted.fill(0);
+        self.offset += len;
+    }
+}
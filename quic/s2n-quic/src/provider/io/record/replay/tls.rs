@@ -0,0 +1,390 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replays the recorded TLS sessions
+//!
+//! The sessions don't perform a handshake. Instead, each session reads and sends the same
+//! messages as the recorded session and derives its keys from the secrets in the recorded key
+//! log. The secrets are looked up with the random value of the ClientHello.
+
+use super::super::{
+    invalid_data,
+    tls::{Event, Space},
+};
+use bytes::Bytes;
+use core::{convert::Infallible, fmt, task::Poll};
+use s2n_codec::EncoderValue;
+use s2n_quic_core::{
+    application::ServerName,
+    crypto::{tls, CryptoSuite},
+    endpoint, transport,
+};
+use s2n_quic_crypto::{
+    handshake::HandshakeKey,
+    one_rtt::OneRttKey,
+    ring::{aead, hkdf},
+    Prk, SecretPair, Suite, MAX_TAG_LEN,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    sync::Arc,
+};
+
+/// The offset of the random value in the ClientHello message
+///
+/// The message starts with its type (1 byte), length (3 bytes) and legacy version (2 bytes).
+const CLIENT_RANDOM_OFFSET: usize = 6;
+const CLIENT_RANDOM_LEN: usize = 32;
+
+/// The TLS sessions and secrets of a recording
+#[derive(Clone, Debug, Default)]
+pub(super) struct Recording {
+    sessions: HashMap<u32, VecDeque<Event>>,
+    /// The secrets of each session, by label, indexed by the random value of the ClientHello
+    secrets: HashMap<Vec<u8>, HashMap<String, Vec<u8>>>,
+}
+
+impl Recording {
+    pub fn on_event(&mut self, session: u32, event: Event) {
+        self.sessions.entry(session).or_default().push_back(event);
+    }
+
+    /// Parses a line of the key log in the NSS key log format
+    pub fn on_key_log(&mut self, line: &[u8]) -> io::Result<()> {
+        let line = core::str::from_utf8(line).map_err(|_| invalid_data("invalid key log"))?;
+        let mut parts = line.split_whitespace();
+
+        let mut next = || parts.next().ok_or_else(|| invalid_data("invalid key log"));
+        let label = next()?;
+        let client_random = decode_hex(next()?)?;
+        let secret = decode_hex(next()?)?;
+
+        self.secrets
+            .entry(client_random)
+            .or_default()
+            .insert(label.to_string(), secret);
+
+        Ok(())
+    }
+}
+
+fn decode_hex(hex: &str) -> io::Result<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return Err(invalid_data("invalid hex"));
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|offset| {
+            hex.get(offset..offset + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| invalid_data("invalid hex"))
+        })
+        .collect()
+}
+
+/// A TLS provider which replays the recorded sessions
+#[derive(Debug)]
+pub struct Provider {
+    recording: Arc<Recording>,
+}
+
+impl Provider {
+    pub(super) fn new(recording: Recording) -> Self {
+        Self {
+            recording: Arc::new(recording),
+        }
+    }
+}
+
+impl crate::provider::tls::Provider for Provider {
+    type Server = Endpoint;
+    type Client = Endpoint;
+    type Error = Infallible;
+
+    fn start_server(self) -> Result<Self::Server, Self::Error> {
+        Ok(Endpoint {
+            recording: self.recording,
+            next_session: 0,
+        })
+    }
+
+    fn start_client(self) -> Result<Self::Client, Self::Error> {
+        Ok(Endpoint {
+            recording: self.recording,
+            next_session: 0,
+        })
+    }
+}
+
+/// Creates the sessions in the same order as the recorded endpoint
+pub struct Endpoint {
+    recording: Arc<Recording>,
+    next_session: u32,
+}
+
+impl Endpoint {
+    fn session(&mut self, endpoint: endpoint::Type) -> Session {
+        let id = self.next_session;
+        self.next_session += 1;
+
+        Session {
+            endpoint,
+            events: self
+                .recording
+                .sessions
+                .get(&id)
+                .cloned()
+                .unwrap_or_default(),
+            recording: self.recording.clone(),
+            client_hello: vec![],
+            received: 0,
+            is_complete: false,
+        }
+    }
+}
+
+impl tls::Endpoint for Endpoint {
+    type Session = Session;
+
+    fn new_server_session<Params: EncoderValue>(
+        &mut self,
+        _transport_parameters: &Params,
+    ) -> Self::Session {
+        // the recorded messages already contain the transport parameters
+        self.session(endpoint::Type::Server)
+    }
+
+    fn new_client_session<Params: EncoderValue>(
+        &mut self,
+        _transport_parameters: &Params,
+        _server_name: ServerName,
+    ) -> Self::Session {
+        self.session(endpoint::Type::Client)
+    }
+
+    fn max_tag_length(&self) -> usize {
+        MAX_TAG_LEN
+    }
+}
+
+pub struct Session {
+    endpoint: endpoint::Type,
+    /// The remaining events of the recorded session
+    events: VecDeque<Event>,
+    recording: Arc<Recording>,
+    /// The start of the ClientHello, which is the first message in the Initial space
+    client_hello: Vec<u8>,
+    /// The number of bytes read for the current `Receive` event
+    received: usize,
+    is_complete: bool,
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("endpoint", &self.endpoint)
+            .field("events", &self.events.len())
+            .field("is_complete", &self.is_complete)
+            .finish()
+    }
+}
+
+impl CryptoSuite for Session {
+    type HandshakeKey = <Suite as CryptoSuite>::HandshakeKey;
+    type HandshakeHeaderKey = <Suite as CryptoSuite>::HandshakeHeaderKey;
+    type InitialKey = <Suite as CryptoSuite>::InitialKey;
+    type InitialHeaderKey = <Suite as CryptoSuite>::InitialHeaderKey;
+    type OneRttKey = <Suite as CryptoSuite>::OneRttKey;
+    type OneRttHeaderKey = <Suite as CryptoSuite>::OneRttHeaderKey;
+    type ZeroRttKey = <Suite as CryptoSuite>::ZeroRttKey;
+    type ZeroRttHeaderKey = <Suite as CryptoSuite>::ZeroRttHeaderKey;
+    type RetryKey = <Suite as CryptoSuite>::RetryKey;
+}
+
+impl Session {
+    fn on_initial(&mut self, bytes: &[u8]) {
+        let remaining =
+            (CLIENT_RANDOM_OFFSET + CLIENT_RANDOM_LEN).saturating_sub(self.client_hello.len());
+        let len = remaining.min(bytes.len());
+        self.client_hello.extend_from_slice(&bytes[..len]);
+    }
+
+    /// Looks up the secrets for the session in the key log
+    fn secrets(
+        &self,
+        cipher_suite: tls::CipherSuite,
+        client_label: &str,
+        server_label: &str,
+    ) -> Result<(&'static aead::Algorithm, SecretPair), transport::Error> {
+        let (prk_algo, aead_algo) = match cipher_suite {
+            tls::CipherSuite::TLS_AES_128_GCM_SHA256 => (hkdf::HKDF_SHA256, &aead::AES_128_GCM),
+            tls::CipherSuite::TLS_AES_256_GCM_SHA384 => (hkdf::HKDF_SHA384, &aead::AES_256_GCM),
+            tls::CipherSuite::TLS_CHACHA20_POLY1305_SHA256 => {
+                (hkdf::HKDF_SHA256, &aead::CHACHA20_POLY1305)
+            }
+            tls::CipherSuite::Unknown => {
+                return Err(transport::Error::INTERNAL_ERROR.with_reason("unknown cipher suite"))
+            }
+        };
+
+        let missing = || {
+            transport::Error::INTERNAL_ERROR
+                .with_reason("the key log doesn't contain the secrets of the session")
+        };
+
+        let secrets = self
+            .client_hello
+            .get(CLIENT_RANDOM_OFFSET..)
+            .and_then(|client_random| self.recording.secrets.get(client_random))
+            .ok_or_else(missing)?;
+        let secret = |label: &str| {
+            secrets
+                .get(label)
+                .map(|secret| Prk::new_less_safe(prk_algo, secret))
+                .ok_or_else(missing)
+        };
+
+        let secrets = SecretPair {
+            client: secret(client_label)?,
+            server: secret(server_label)?,
+        };
+
+        Ok((aead_algo, secrets))
+    }
+}
+
+impl tls::Session for Session {
+    fn poll<C: tls::Context<Self>>(
+        &mut self,
+        context: &mut C,
+    ) -> Poll<Result<(), transport::Error>> {
+        while let Some(event) = self.events.pop_front() {
+            match event {
+                Event::Receive { space, len } => {
+                    while self.received < len as usize {
+                        let max_len = Some(len as usize - self.received);
+                        let bytes = match space {
+                            Space::Initial => context.receive_initial(max_len),
+                            Space::Handshake => context.receive_handshake(max_len),
+                            Space::Application => context.receive_application(max_len),
+                        };
+
+                        if let Some(bytes) = bytes {
+                            if space == Space::Initial {
+                                self.on_initial(&bytes);
+                            }
+                            self.received += bytes.len();
+                        } else {
+                            // wait for the rest of the peer's messages
+                            self.events.push_front(Event::Receive { space, len });
+                            return Poll::Pending;
+                        }
+                    }
+
+                    self.received = 0;
+                }
+                Event::Send { space, payload } => {
+                    if space == Space::Initial {
+                        self.on_initial(&payload);
+                    }
+
+                    let payload = Bytes::from(payload);
+                    match space {
+                        Space::Initial => context.send_initial(payload),
+                        Space::Handshake => context.send_handshake(payload),
+                        Space::Application => context.send_application(payload),
+                    }
+                }
+                Event::HandshakeKeys { cipher_suite } => {
+                    let (algorithm, secrets) = self.secrets(
+                        cipher_suite,
+                        "CLIENT_HANDSHAKE_TRAFFIC_SECRET",
+                        "SERVER_HANDSHAKE_TRAFFIC_SECRET",
+                    )?;
+                    let (key, header_key) = HandshakeKey::new(self.endpoint, algorithm, secrets)
+                        .ok_or(transport::Error::INTERNAL_ERROR)?;
+                    context.on_handshake_keys(key, header_key)?;
+                }
+                Event::OneRttKeys {
+                    cipher_suite,
+                    transport_parameters,
+                } => {
+                    let (algorithm, secrets) = self.secrets(
+                        cipher_suite,
+                        "CLIENT_TRAFFIC_SECRET_0",
+                        "SERVER_TRAFFIC_SECRET_0",
+                    )?;
+                    let (key, header_key) = OneRttKey::new(self.endpoint, algorithm, secrets)
+                        .ok_or(transport::Error::INTERNAL_ERROR)?;
+                    let application_parameters = tls::ApplicationParameters {
+                        transport_parameters: &transport_parameters,
+                    };
+                    context.on_one_rtt_keys(key, header_key, application_parameters)?;
+                }
+                Event::ServerName(server_name) => {
+                    let server_name = String::from_utf8(server_name).map_err(|_| {
+                        transport::Error::INTERNAL_ERROR.with_reason("invalid server name")
+                    })?;
+                    context.on_server_name(server_name.into())?;
+                }
+                Event::ApplicationProtocol(protocol) => {
+                    context.on_application_protocol(protocol.into())?;
+                }
+                Event::SessionInfo {
+                    cipher_suite,
+                    protocol_version,
+                    peer_certificate_chain,
+                    resumed,
+                } => {
+                    context.on_session_info(tls::SessionInfo {
+                        cipher_suite,
+                        protocol_version,
+                        peer_certificate_chain: peer_certificate_chain
+                            .into_iter()
+                            .map(Bytes::from)
+                            .collect(),
+                        resumed,
+                    })?;
+                }
+                Event::HandshakeComplete => {
+                    context.on_handshake_complete()?;
+                    self.is_complete = true;
+                }
+            }
+        }
+
+        if self.is_complete {
+            Poll::Ready(Ok(()))
+        } else {
+            // the recording ended before the handshake completed
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_log_test() {
+        let mut recording = Recording::default();
+        recording
+            .on_key_log(b"SERVER_TRAFFIC_SECRET_0 00ff10 0a0b")
+            .unwrap();
+        assert_eq!(
+            recording.secrets[&vec![0x00, 0xff, 0x10]]["SERVER_TRAFFIC_SECRET_0"],
+            vec![0x0a, 0x0b]
+        );
+
+        for line in [
+            &b"SERVER_TRAFFIC_SECRET_0 00ff10"[..],
+            b"SERVER_TRAFFIC_SECRET_0 0ff10 0a0b",
+            b"SERVER_TRAFFIC_SECRET_0 zz 0a0b",
+        ] {
+            assert!(recording.on_key_log(line).is_err());
+        }
+    }
+}
@@ -0,0 +1,483 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Records the messages exchanged between each TLS session and its connection
+//!
+//! The keys can't be recorded, so only their cipher suite is noted. When the session is replayed,
+//! the keys are derived from the secrets in the key log of the TLS provider, which needs to be
+//! written to [`Recorder::key_log`].
+
+use super::{decode_bytes, encode_bytes, invalid_data, read_array, Recorder, MAX_TLS_MESSAGE_LEN};
+use bytes::Bytes;
+use core::{fmt, marker::PhantomData, task::Poll, time::Duration};
+use s2n_codec::EncoderValue;
+use s2n_quic_core::{
+    application::ServerName,
+    crypto::{
+        tls::{self, CipherSuite, ProtocolVersion},
+        CryptoSuite, Key as _,
+    },
+    transport,
+};
+use std::{
+    io::{self, Read, Write},
+    task::Waker,
+};
+
+const RECEIVE: u8 = 0;
+const SEND: u8 = 1;
+const HANDSHAKE_KEYS: u8 = 2;
+const ONE_RTT_KEYS: u8 = 3;
+const SERVER_NAME: u8 = 4;
+const APPLICATION_PROTOCOL: u8 = 5;
+const SESSION_INFO: u8 = 6;
+const HANDSHAKE_COMPLETE: u8 = 7;
+
+/// The packet space of the TLS messages
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Space {
+    Initial,
+    Handshake,
+    Application,
+}
+
+/// An interaction between a TLS session and its connection
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The session read `len` bytes of the peer's messages
+    Receive {
+        space: Space,
+        len: u32,
+    },
+    /// The session sent messages to the peer
+    Send {
+        space: Space,
+        payload: Vec<u8>,
+    },
+    /// The session derived the handshake keys
+    HandshakeKeys {
+        cipher_suite: CipherSuite,
+    },
+    /// The session derived the 1-RTT keys and received the peer's transport parameters
+    OneRttKeys {
+        cipher_suite: CipherSuite,
+        transport_parameters: Vec<u8>,
+    },
+    ServerName(Vec<u8>),
+    ApplicationProtocol(Vec<u8>),
+    SessionInfo {
+        cipher_suite: CipherSuite,
+        protocol_version: ProtocolVersion,
+        peer_certificate_chain: Vec<Vec<u8>>,
+        resumed: bool,
+    },
+    HandshakeComplete,
+}
+
+impl Event {
+    pub fn encode<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        match self {
+            Self::Receive { space, len } => {
+                w.write_all(&[RECEIVE, encode_space(*space)])?;
+                w.write_all(&len.to_le_bytes())
+            }
+            Self::Send { space, payload } => {
+                w.write_all(&[SEND, encode_space(*space)])?;
+                encode_bytes(w, payload)
+            }
+            Self::HandshakeKeys { cipher_suite } => {
+                w.write_all(&[HANDSHAKE_KEYS, encode_cipher_suite(*cipher_suite)])
+            }
+            Self::OneRttKeys {
+                cipher_suite,
+                transport_parameters,
+            } => {
+                w.write_all(&[ONE_RTT_KEYS, encode_cipher_suite(*cipher_suite)])?;
+                encode_bytes(w, transport_parameters)
+            }
+            Self::ServerName(server_name) => {
+                w.write_all(&[SERVER_NAME])?;
+                encode_bytes(w, server_name)
+            }
+            Self::ApplicationProtocol(protocol) => {
+                w.write_all(&[APPLICATION_PROTOCOL])?;
+                encode_bytes(w, protocol)
+            }
+            Self::SessionInfo {
+                cipher_suite,
+                protocol_version,
+                peer_certificate_chain,
+                resumed,
+            } => {
+                let protocol_version = match protocol_version {
+                    ProtocolVersion::TLS_1_3 => 1,
+                    ProtocolVersion::Unknown => 0,
+                };
+                w.write_all(&[
+                    SESSION_INFO,
+                    encode_cipher_suite(*cipher_suite),
+                    protocol_version,
+                    *resumed as u8,
+                ])?;
+                w.write_all(&(peer_certificate_chain.len() as u32).to_le_bytes())?;
+                for certificate in peer_certificate_chain {
+                    encode_bytes(w, certificate)?;
+                }
+                Ok(())
+            }
+            Self::HandshakeComplete => w.write_all(&[HANDSHAKE_COMPLETE]),
+        }
+    }
+
+    pub fn decode<R: Read>(r: &mut R) -> io::Result<Self> {
+        let [tag] = read_array::<_, 1>(r)?;
+
+        let event = match tag {
+            RECEIVE => {
+                let space = decode_space(r)?;
+                let len = u32::from_le_bytes(read_array(r)?);
+                Self::Receive { space, len }
+            }
+            SEND => {
+                let space = decode_space(r)?;
+                let payload = decode_bytes(r, MAX_TLS_MESSAGE_LEN)?;
+                Self::Send { space, payload }
+            }
+            HANDSHAKE_KEYS => Self::HandshakeKeys {
+                cipher_suite: decode_cipher_suite(r)?,
+            },
+            ONE_RTT_KEYS => Self::OneRttKeys {
+                cipher_suite: decode_cipher_suite(r)?,
+                transport_parameters: decode_bytes(r, MAX_TLS_MESSAGE_LEN)?,
+            },
+            SERVER_NAME => Self::ServerName(decode_bytes(r, MAX_TLS_MESSAGE_LEN)?),
+            APPLICATION_PROTOCOL => {
+                Self::ApplicationProtocol(decode_bytes(r, MAX_TLS_MESSAGE_LEN)?)
+            }
+            SESSION_INFO => {
+                let cipher_suite = decode_cipher_suite(r)?;
+                let [protocol_version, resumed] = read_array::<_, 2>(r)?;
+                let protocol_version = match protocol_version {
+                    1 => ProtocolVersion::TLS_1_3,
+                    _ => ProtocolVersion::Unknown,
+                };
+                let len = u32::from_le_bytes(read_array(r)?);
+                let peer_certificate_chain = (0..len)
+                    .map(|_| decode_bytes(r, MAX_TLS_MESSAGE_LEN))
+                    .collect::<io::Result<_>>()?;
+                Self::SessionInfo {
+                    cipher_suite,
+                    protocol_version,
+                    peer_certificate_chain,
+                    resumed: resumed != 0,
+                }
+            }
+            HANDSHAKE_COMPLETE => Self::HandshakeComplete,
+            _ => return Err(invalid_data("invalid tls event tag")),
+        };
+
+        Ok(event)
+    }
+}
+
+fn encode_space(space: Space) -> u8 {
+    match space {
+        Space::Initial => 0,
+        Space::Handshake => 1,
+        Space::Application => 2,
+    }
+}
+
+fn decode_space<R: Read>(r: &mut R) -> io::Result<Space> {
+    match read_array::<_, 1>(r)? {
+        [0] => Ok(Space::Initial),
+        [1] => Ok(Space::Handshake),
+        [2] => Ok(Space::Application),
+        _ => Err(invalid_data("invalid packet space")),
+    }
+}
+
+fn encode_cipher_suite(cipher_suite: CipherSuite) -> u8 {
+    match cipher_suite {
+        CipherSuite::TLS_AES_128_GCM_SHA256 => 1,
+        CipherSuite::TLS_AES_256_GCM_SHA384 => 2,
+        CipherSuite::TLS_CHACHA20_POLY1305_SHA256 => 3,
+        CipherSuite::Unknown => 0,
+    }
+}
+
+fn decode_cipher_suite<R: Read>(r: &mut R) -> io::Result<CipherSuite> {
+    match read_array::<_, 1>(r)? {
+        [1] => Ok(CipherSuite::TLS_AES_128_GCM_SHA256),
+        [2] => Ok(CipherSuite::TLS_AES_256_GCM_SHA384),
+        [3] => Ok(CipherSuite::TLS_CHACHA20_POLY1305_SHA256),
+        [0] => Ok(CipherSuite::Unknown),
+        _ => Err(invalid_data("invalid cipher suite")),
+    }
+}
+
+/// A TLS provider which records the sessions of the endpoint
+pub struct Provider<P> {
+    pub(super) provider: P,
+    pub(super) recorder: Recorder,
+}
+
+impl<P: crate::provider::tls::Provider> crate::provider::tls::Provider for Provider<P> {
+    type Server = Endpoint<P::Server>;
+    type Client = Endpoint<P::Client>;
+    type Error = P::Error;
+
+    fn start_server(self) -> Result<Self::Server, Self::Error> {
+        Ok(Endpoint {
+            endpoint: self.provider.start_server()?,
+            recorder: self.recorder,
+        })
+    }
+
+    fn start_client(self) -> Result<Self::Client, Self::Error> {
+        Ok(Endpoint {
+            endpoint: self.provider.start_client()?,
+            recorder: self.recorder,
+        })
+    }
+}
+
+pub struct Endpoint<E> {
+    endpoint: E,
+    recorder: Recorder,
+}
+
+impl<E: tls::Endpoint> tls::Endpoint for Endpoint<E> {
+    type Session = Session<E::Session>;
+
+    fn new_server_session<Params: EncoderValue>(
+        &mut self,
+        transport_parameters: &Params,
+    ) -> Self::Session {
+        Session {
+            session: self.endpoint.new_server_session(transport_parameters),
+            id: self.recorder.next_session(),
+            recorder: self.recorder.clone(),
+        }
+    }
+
+    fn new_client_session<Params: EncoderValue>(
+        &mut self,
+        transport_parameters: &Params,
+        server_name: ServerName,
+    ) -> Self::Session {
+        Session {
+            session: self
+                .endpoint
+                .new_client_session(transport_parameters, server_name),
+            id: self.recorder.next_session(),
+            recorder: self.recorder.clone(),
+        }
+    }
+
+    fn max_tag_length(&self) -> usize {
+        self.endpoint.max_tag_length()
+    }
+}
+
+pub struct Session<S> {
+    session: S,
+    /// Identifies the session in the recording
+    id: u32,
+    recorder: Recorder,
+}
+
+impl<S: fmt::Debug> fmt::Debug for Session<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("session", &self.session)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl<S: CryptoSuite> CryptoSuite for Session<S> {
+    type HandshakeKey = S::HandshakeKey;
+    type HandshakeHeaderKey = S::HandshakeHeaderKey;
+    type InitialKey = S::InitialKey;
+    type InitialHeaderKey = S::InitialHeaderKey;
+    type OneRttKey = S::OneRttKey;
+    type OneRttHeaderKey = S::OneRttHeaderKey;
+    type ZeroRttKey = S::ZeroRttKey;
+    type ZeroRttHeaderKey = S::ZeroRttHeaderKey;
+    type RetryKey = S::RetryKey;
+}
+
+impl<S: tls::Session> tls::Session for Session<S> {
+    fn poll<C: tls::Context<Self>>(
+        &mut self,
+        context: &mut C,
+    ) -> Poll<Result<(), transport::Error>> {
+        let mut context = Context {
+            context,
+            session: self.id,
+            recorder: &self.recorder,
+            suite: PhantomData,
+        };
+        self.session.poll(&mut context)
+    }
+}
+
+/// Records the calls the session makes to the connection
+struct Context<'a, C, S> {
+    context: &'a mut C,
+    session: u32,
+    recorder: &'a Recorder,
+    suite: PhantomData<S>,
+}
+
+impl<'a, C, S> Context<'a, C, S> {
+    fn record(&self, event: Event) {
+        self.recorder.on_tls(self.session, event);
+    }
+
+    fn on_receive(&self, space: Space, bytes: Option<Bytes>) -> Option<Bytes> {
+        if let Some(bytes) = bytes.as_ref() {
+            self.record(Event::Receive {
+                space,
+                len: bytes.len() as u32,
+            });
+        }
+        bytes
+    }
+
+    fn on_send(&self, space: Space, transmission: &Bytes) {
+        self.record(Event::Send {
+            space,
+            payload: transmission.to_vec(),
+        });
+    }
+}
+
+impl<'a, C, S> tls::Context<S> for Context<'a, C, S>
+where
+    C: tls::Context<Session<S>>,
+    S: tls::Session,
+{
+    fn on_handshake_keys(
+        &mut self,
+        key: S::HandshakeKey,
+        header_key: S::HandshakeHeaderKey,
+    ) -> Result<(), transport::Error> {
+        self.record(Event::HandshakeKeys {
+            cipher_suite: key.cipher_suite(),
+        });
+        self.context.on_handshake_keys(key, header_key)
+    }
+
+    fn on_zero_rtt_keys(
+        &mut self,
+        key: S::ZeroRttKey,
+        header_key: S::ZeroRttHeaderKey,
+        application_parameters: tls::ApplicationParameters,
+    ) -> Result<(), transport::Error> {
+        // 0-RTT isn't supported by the providers so it isn't recorded
+        self.context
+            .on_zero_rtt_keys(key, header_key, application_parameters)
+    }
+
+    fn on_one_rtt_keys(
+        &mut self,
+        key: S::OneRttKey,
+        header_key: S::OneRttHeaderKey,
+        application_parameters: tls::ApplicationParameters,
+    ) -> Result<(), transport::Error> {
+        self.record(Event::OneRttKeys {
+            cipher_suite: key.cipher_suite(),
+            transport_parameters: application_parameters.transport_parameters.to_vec(),
+        });
+        self.context
+            .on_one_rtt_keys(key, header_key, application_parameters)
+    }
+
+    fn on_server_name(&mut self, server_name: ServerName) -> Result<(), transport::Error> {
+        self.record(Event::ServerName(server_name.as_bytes().to_vec()));
+        self.context.on_server_name(server_name)
+    }
+
+    fn on_application_protocol(
+        &mut self,
+        application_protocol: Bytes,
+    ) -> Result<(), transport::Error> {
+        self.record(Event::ApplicationProtocol(application_protocol.to_vec()));
+        self.context.on_application_protocol(application_protocol)
+    }
+
+    fn on_session_info(&mut self, session_info: tls::SessionInfo) -> Result<(), transport::Error> {
+        self.record(Event::SessionInfo {
+            cipher_suite: session_info.cipher_suite,
+            protocol_version: session_info.protocol_version,
+            peer_certificate_chain: session_info
+                .peer_certificate_chain
+                .iter()
+                .map(|certificate| certificate.to_vec())
+                .collect(),
+            resumed: session_info.resumed,
+        });
+        self.context.on_session_info(session_info)
+    }
+
+    fn on_handshake_complete(&mut self) -> Result<(), transport::Error> {
+        self.record(Event::HandshakeComplete);
+        self.context.on_handshake_complete()
+    }
+
+    fn on_tls_callback_started(&mut self, callback: tls::TlsCallback) {
+        self.context.on_tls_callback_started(callback)
+    }
+
+    fn on_tls_callback_completed(&mut self, callback: tls::TlsCallback, duration: Duration) {
+        self.context.on_tls_callback_completed(callback, duration)
+    }
+
+    fn receive_initial(&mut self, max_len: Option<usize>) -> Option<Bytes> {
+        let bytes = self.context.receive_initial(max_len);
+        self.on_receive(Space::Initial, bytes)
+    }
+
+    fn receive_handshake(&mut self, max_len: Option<usize>) -> Option<Bytes> {
+        let bytes = self.context.receive_handshake(max_len);
+        self.on_receive(Space::Handshake, bytes)
+    }
+
+    fn receive_application(&mut self, max_len: Option<usize>) -> Option<Bytes> {
+        let bytes = self.context.receive_application(max_len);
+        self.on_receive(Space::Application, bytes)
+    }
+
+    fn can_send_initial(&self) -> bool {
+        self.context.can_send_initial()
+    }
+
+    fn send_initial(&mut self, transmission: Bytes) {
+        self.on_send(Space::Initial, &transmission);
+        self.context.send_initial(transmission)
+    }
+
+    fn can_send_handshake(&self) -> bool {
+        self.context.can_send_handshake()
+    }
+
+    fn send_handshake(&mut self, transmission: Bytes) {
+        self.on_send(Space::Handshake, &transmission);
+        self.context.send_handshake(transmission)
+    }
+
+    fn can_send_application(&self) -> bool {
+        self.context.can_send_application()
+    }
+
+    fn send_application(&mut self, transmission: Bytes) {
+        self.on_send(Space::Application, &transmission);
+        self.context.send_application(transmission)
+    }
+
+    fn waker(&self) -> &Waker {
+        self.context.waker()
+    }
+}
@@ -16,6 +16,16 @@ pub use random::Provider as Default;
 
 impl_provider_utils!();
 
+#[cfg(test)]
+impl Provider for s2n_quic_core::stateless_reset::token::testing::Generator {
+    type Generator = Self;
+    type Error = core::convert::Infallible;
+
+    fn start(self) -> Result<Self::Generator, Self::Error> {
+        Ok(self)
+    }
+}
+
 mod random {
     use core::convert::Infallible;
     use rand::prelude::*;
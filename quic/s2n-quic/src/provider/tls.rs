@@ -2,10 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! Provides TLS support for an endpoint
+//!
+//! Neither the s2n-tls nor the rustls provider supports certificate compression
+//! ([RFC 8879](https://www.rfc-editor.org/rfc/rfc8879)), so certificate chains are always sent
+//! uncompressed.
 
 use cfg_if::cfg_if;
 use s2n_quic_core::crypto;
 
+pub use s2n_quic_core::crypto::tls::{CipherSuite, ProtocolVersion};
+
 pub trait Provider {
     type Server: 'static + crypto::tls::Endpoint;
     type Client: 'static + crypto::tls::Endpoint;
@@ -20,6 +26,11 @@ pub trait Provider {
 
 impl_provider_utils!();
 
+pub mod reloadable;
+
+#[cfg(any(test, all(not(docdiff), feature = "unstable-provider-tls-null")))]
+pub mod null;
+
 cfg_if! {
     if #[cfg(feature = "provider-tls-default")] {
         pub mod default {
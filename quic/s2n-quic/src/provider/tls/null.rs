@@ -0,0 +1,682 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides a TLS implementation which performs NO authentication or encryption
+//!
+//! **THIS PROVIDER IS COMPLETELY INSECURE.** All packets are sent in plaintext and peers are
+//! not authenticated. It exists so the overhead of the transport can be measured independently
+//! of the cryptography in simulations and benchmarks and must never be used to communicate with
+//! an untrusted peer.
+//!
+//! The handshake completes in a single round trip:
+//!
+//! ```text
+//! Client                                     Server
+//!
+//! ClientHello (Initial)       -------->
+//!                                            ServerHello (Initial)
+//!                             <--------      Finished (Handshake)
+//! Finished (Handshake)        -------->
+//! ```
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use core::task::Poll;
+use s2n_codec::{DecoderBuffer, EncoderBuffer, EncoderValue};
+use s2n_quic_core::{
+    application::ServerName,
+    crypto::{self, retry::IntegrityTag, tls, CryptoError, CryptoSuite, HeaderProtectionMask},
+    transport,
+};
+
+/// The length of the tag which is appended to each packet
+///
+/// The tag holds a checksum of the packet so corrupted packets are still rejected. It's the
+/// same size as a real AEAD tag so packets are the same size as when encrypted.
+const TAG_LEN: usize = 16;
+
+/// The length of the header protection sample
+const SAMPLE_LEN: usize = 16;
+
+#[derive(Debug)]
+pub struct Endpoint {
+    application_protocols: Vec<Bytes>,
+}
+
+impl Default for Endpoint {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl Endpoint {
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+}
+
+#[derive(Debug)]
+pub struct Builder {
+    application_protocols: Vec<Bytes>,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            application_protocols: vec![Bytes::from_static(b"h3")],
+        }
+    }
+}
+
+impl Builder {
+    pub fn with_application_protocols<P: IntoIterator<Item = I>, I: AsRef<[u8]>>(
+        mut self,
+        protocols: P,
+    ) -> Self {
+        self.application_protocols = protocols
+            .into_iter()
+            .map(|protocol| Bytes::copy_from_slice(protocol.as_ref()))
+            .collect();
+        self
+    }
+
+    pub fn build(self) -> Endpoint {
+        Endpoint {
+            application_protocols: self.application_protocols,
+        }
+    }
+}
+
+impl tls::Endpoint for Endpoint {
+    type Session = Session;
+
+    fn new_server_session<Params: EncoderValue>(
+        &mut self,
+        transport_parameters: &Params,
+    ) -> Self::Session {
+        Session::new(
+            State::WaitingClientHello,
+            &self.application_protocols,
+            transport_parameters,
+            None,
+        )
+    }
+
+    fn new_client_session<Params: EncoderValue>(
+        &mut self,
+        transport_parameters: &Params,
+        server_name: ServerName,
+    ) -> Self::Session {
+        Session::new(
+            State::ClientStart,
+            &self.application_protocols,
+            transport_parameters,
+            Some(server_name),
+        )
+    }
+
+    fn max_tag_length(&self) -> usize {
+        TAG_LEN
+    }
+}
+
+impl super::Provider for Endpoint {
+    type Server = Self;
+    type Client = Self;
+    type Error = core::convert::Infallible;
+
+    fn start_server(self) -> Result<Self::Server, Self::Error> {
+        Ok(self)
+    }
+
+    fn start_client(self) -> Result<Self::Client, Self::Error> {
+        Ok(self)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    ClientStart,
+    WaitingServerHello,
+    WaitingServerFinished,
+    WaitingClientHello,
+    WaitingClientFinished,
+    Complete,
+}
+
+/// The message types use the TLS values since the transport inspects the hello messages
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+enum MessageType {
+    ClientHello = 1,
+    ServerHello = 2,
+    Finished = 20,
+}
+
+/// The length of the message header, which matches the TLS handshake header
+///
+/// The header contains a single byte for the type followed by a 24-bit length.
+const HEADER_LEN: usize = 4;
+
+#[derive(Debug)]
+pub struct Session {
+    state: State,
+    application_protocols: Vec<Bytes>,
+    transport_parameters: Bytes,
+    server_name: Option<ServerName>,
+    /// The peer's transport parameters, which are received in the hello message
+    peer_transport_parameters: Bytes,
+    /// Buffers partially received messages
+    rx_buffer: BytesMut,
+}
+
+impl Session {
+    fn new<Params: EncoderValue>(
+        state: State,
+        application_protocols: &[Bytes],
+        transport_parameters: &Params,
+        server_name: Option<ServerName>,
+    ) -> Self {
+        let mut buffer = vec![0; transport_parameters.encoding_size()];
+        transport_parameters.encode(&mut EncoderBuffer::new(&mut buffer));
+
+        Self {
+            state,
+            application_protocols: application_protocols.to_vec(),
+            transport_parameters: buffer.into(),
+            server_name,
+            peer_transport_parameters: Bytes::new(),
+            rx_buffer: BytesMut::new(),
+        }
+    }
+
+    fn poll_impl<C: tls::Context<Self>>(
+        &mut self,
+        context: &mut C,
+    ) -> Poll<Result<(), transport::Error>> {
+        loop {
+            match self.state {
+                State::ClientStart => {
+                    let mut message = BytesMut::new();
+                    put_slice(
+                        &mut message,
+                        self.server_name
+                            .as_ref()
+                            .map_or(&[][..], |name| name.as_bytes()),
+                    );
+                    put_list(&mut message, &self.application_protocols);
+                    put_slice(&mut message, &self.transport_parameters);
+                    context.send_initial(encode_message(MessageType::ClientHello, &message));
+
+                    self.state = State::WaitingServerHello;
+                }
+                State::WaitingServerHello => {
+                    let mut message = match self.receive(
+                        MessageType::ServerHello,
+                        |c, l| c.receive_initial(l),
+                        context,
+                    )? {
+                        Some(message) => message,
+                        None => return Poll::Pending,
+                    };
+
+                    let application_protocol = get_slice(&mut message)?;
+                    self.peer_transport_parameters = get_slice(&mut message)?;
+
+                    let (key, header_key) = Key::new_pair();
+                    context.on_handshake_keys(key, header_key)?;
+
+                    if let Some(server_name) = self.server_name.clone() {
+                        context.on_server_name(server_name)?;
+                    }
+                    context.on_application_protocol(application_protocol)?;
+
+                    self.state = State::WaitingServerFinished;
+                }
+                State::WaitingServerFinished => {
+                    if self
+                        .receive(
+                            MessageType::Finished,
+                            |c, l| c.receive_handshake(l),
+                            context,
+                        )?
+                        .is_none()
+                    {
+                        return Poll::Pending;
+                    }
+
+                    self.on_one_rtt_keys(context)?;
+                    context.send_handshake(encode_message(MessageType::Finished, &[]));
+                    self.on_complete(context)?;
+                }
+                State::WaitingClientHello => {
+                    let mut message = match self.receive(
+                        MessageType::ClientHello,
+                        |c, l| c.receive_initial(l),
+                        context,
+                    )? {
+                        Some(message) => message,
+                        None => return Poll::Pending,
+                    };
+
+                    let server_name = get_slice(&mut message)?;
+                    let client_protocols = get_list(&mut message)?;
+                    self.peer_transport_parameters = get_slice(&mut message)?;
+
+                    // pick the first protocol in the server's preferences which the client supports
+                    let application_protocol = self
+                        .application_protocols
+                        .iter()
+                        .find(|protocol| client_protocols.contains(protocol))
+                        .cloned()
+                        .ok_or_else(|| {
+                            CryptoError::NO_APPLICATION_PROTOCOL
+                                .with_reason("no common application protocol")
+                        })?;
+
+                    let mut reply = BytesMut::new();
+                    put_slice(&mut reply, &application_protocol);
+                    put_slice(&mut reply, &self.transport_parameters);
+                    context.send_initial(encode_message(MessageType::ServerHello, &reply));
+
+                    if !server_name.is_empty() {
+                        let server_name = core::str::from_utf8(&server_name).map_err(|_| {
+                            CryptoError::DECODE_ERROR.with_reason("invalid server name")
+                        })?;
+                        context.on_server_name(server_name.into())?;
+                    }
+
+                    let (key, header_key) = Key::new_pair();
+                    context.on_handshake_keys(key, header_key)?;
+                    context.on_application_protocol(application_protocol)?;
+                    context.send_handshake(encode_message(MessageType::Finished, &[]));
+                    self.on_one_rtt_keys(context)?;
+
+                    self.state = State::WaitingClientFinished;
+                }
+                State::WaitingClientFinished => {
+                    if self
+                        .receive(
+                            MessageType::Finished,
+                            |c, l| c.receive_handshake(l),
+                            context,
+                        )?
+                        .is_none()
+                    {
+                        return Poll::Pending;
+                    }
+
+                    self.on_complete(context)?;
+                }
+                State::Complete => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+
+    /// Reads the next message from the current packet space
+    ///
+    /// Returns `None` if the message hasn't been completely received yet.
+    fn receive<C: tls::Context<Self>>(
+        &mut self,
+        expected: MessageType,
+        receive: impl Fn(&mut C, Option<usize>) -> Option<Bytes>,
+        context: &mut C,
+    ) -> Result<Option<Bytes>, transport::Error> {
+        while let Some(chunk) = receive(context, None) {
+            self.rx_buffer.extend_from_slice(&chunk);
+        }
+
+        // each message is prefixed with the type and length of the body
+        if self.rx_buffer.len() < HEADER_LEN {
+            return Ok(None);
+        }
+
+        let message_type = self.rx_buffer[0];
+        let len = u32::from_be_bytes([0, self.rx_buffer[1], self.rx_buffer[2], self.rx_buffer[3]])
+            as usize;
+
+        if message_type != expected as u8 {
+            return Err(CryptoError::UNEXPECTED_MESSAGE.into());
+        }
+
+        if self.rx_buffer.len() < HEADER_LEN + len {
+            return Ok(None);
+        }
+
+        self.rx_buffer.advance(HEADER_LEN);
+        Ok(Some(self.rx_buffer.split_to(len).freeze()))
+    }
+
+    fn on_one_rtt_keys<C: tls::Context<Self>>(
+        &mut self,
+        context: &mut C,
+    ) -> Result<(), transport::Error> {
+        let (key, header_key) = Key::new_pair();
+        let params = tls::ApplicationParameters {
+            transport_parameters: &self.peer_transport_parameters,
+        };
+        context.on_one_rtt_keys(key, header_key, params)
+    }
+
+    fn on_complete<C: tls::Context<Self>>(
+        &mut self,
+        context: &mut C,
+    ) -> Result<(), transport::Error> {
+        context.on_session_info(tls::SessionInfo::default())?;
+        context.on_handshake_complete()?;
+        self.state = State::Complete;
+        Ok(())
+    }
+}
+
+impl tls::Session for Session {
+    fn poll<C: tls::Context<Self>>(
+        &mut self,
+        context: &mut C,
+    ) -> Poll<Result<(), transport::Error>> {
+        self.poll_impl(context)
+    }
+}
+
+impl CryptoSuite for Session {
+    type HandshakeKey = Key;
+    type HandshakeHeaderKey = HeaderKey;
+    type InitialKey = Key;
+    type InitialHeaderKey = HeaderKey;
+    type ZeroRttKey = Key;
+    type ZeroRttHeaderKey = HeaderKey;
+    type OneRttKey = Key;
+    type OneRttHeaderKey = HeaderKey;
+    type RetryKey = Key;
+}
+
+fn encode_message(message_type: MessageType, body: &[u8]) -> Bytes {
+    let mut message = BytesMut::with_capacity(HEADER_LEN + body.len());
+    message.put_u8(message_type as u8);
+    message.put_uint(body.len() as u64, HEADER_LEN - 1);
+    message.put_slice(body);
+    message.freeze()
+}
+
+fn put_slice(buffer: &mut BytesMut, value: &[u8]) {
+    buffer.put_u16(value.len() as u16);
+    buffer.put_slice(value);
+}
+
+fn put_list(buffer: &mut BytesMut, values: &[Bytes]) {
+    buffer.put_u16(values.len() as u16);
+    for value in values {
+        put_slice(buffer, value);
+    }
+}
+
+fn get_slice(buffer: &mut Bytes) -> Result<Bytes, transport::Error> {
+    let decoder = DecoderBuffer::new(buffer);
+    let (value, _) = decoder
+        .decode_slice_with_len_prefix::<u16>()
+        .map_err(|_| CryptoError::DECODE_ERROR)?;
+    let len = value.len();
+    let value = buffer.slice(2..2 + len);
+    buffer.advance(2 + len);
+    Ok(value)
+}
+
+fn get_list(buffer: &mut Bytes) -> Result<Vec<Bytes>, transport::Error> {
+    if buffer.len() < 2 {
+        return Err(CryptoError::DECODE_ERROR.into());
+    }
+    let count = buffer.get_u16();
+    (0..count).map(|_| get_slice(buffer)).collect()
+}
+
+/// A key which leaves the payload in plaintext
+#[derive(Debug, Default)]
+pub struct Key(());
+
+impl Key {
+    fn new_pair() -> (Self, HeaderKey) {
+        (Self::default(), HeaderKey::default())
+    }
+}
+
+impl crypto::Key for Key {
+    #[inline]
+    fn decrypt(
+        &self,
+        packet_number: u64,
+        header: &[u8],
+        payload: &mut [u8],
+    ) -> Result<(), CryptoError> {
+        let tag_start = payload
+            .len()
+            .checked_sub(TAG_LEN)
+            .ok_or(CryptoError::DECRYPT_ERROR)?;
+        let (payload, tag) = payload.split_at(tag_start);
+
+        if checksum(&[&packet_number.to_be_bytes(), header, payload])[..] != tag[..] {
+            return Err(CryptoError::DECRYPT_ERROR);
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn encrypt(
+        &self,
+        packet_number: u64,
+        header: &[u8],
+        payload: &mut [u8],
+    ) -> Result<(), CryptoError> {
+        let tag_start = payload
+            .len()
+            .checked_sub(TAG_LEN)
+            .ok_or(CryptoError::INTERNAL_ERROR)?;
+        let (payload, tag) = payload.split_at_mut(tag_start);
+        tag.copy_from_slice(&checksum(&[&packet_number.to_be_bytes(), header, payload]));
+        Ok(())
+    }
+
+    #[inline]
+    fn tag_len(&self) -> usize {
+        TAG_LEN
+    }
+
+    #[inline]
+    fn aead_confidentiality_limit(&self) -> u64 {
+        u64::MAX
+    }
+
+    #[inline]
+    fn aead_integrity_limit(&self) -> u64 {
+        u64::MAX
+    }
+
+    #[inline]
+    fn cipher_suite(&self) -> tls::CipherSuite {
+        tls::CipherSuite::Unknown
+    }
+}
+
+impl crypto::InitialKey for Key {
+    type HeaderKey = HeaderKey;
+
+    fn new_server(_connection_id: &[u8]) -> (Self, Self::HeaderKey) {
+        Self::new_pair()
+    }
+
+    fn new_client(_connection_id: &[u8]) -> (Self, Self::HeaderKey) {
+        Self::new_pair()
+    }
+}
+
+impl crypto::HandshakeKey for Key {}
+
+impl crypto::OneRttKey for Key {
+    fn derive_next_key(&self) -> Self {
+        Self::default()
+    }
+
+    fn update_sealer_pmtu(&mut self, _pmtu: u16) {}
+
+    fn update_opener_pmtu(&mut self, _pmtu: u16) {}
+}
+
+impl crypto::ZeroRttKey for Key {}
+
+impl crypto::RetryKey for Key {
+    fn generate_tag(payload: &[u8]) -> IntegrityTag {
+        checksum(&[payload])
+    }
+
+    fn validate(payload: &[u8], tag: IntegrityTag) -> Result<(), CryptoError> {
+        if Self::generate_tag(payload) != tag {
+            return Err(CryptoError::DECRYPT_ERROR);
+        }
+        Ok(())
+    }
+}
+
+/// Computes a cheap, non-cryptographic checksum of the inputs
+///
+/// This is a 64-bit FNV-1a hash of the inputs followed by the hash of the input length. It only
+/// detects accidental corruption and offers no protection against a malicious peer.
+#[inline]
+fn checksum(inputs: &[&[u8]]) -> [u8; TAG_LEN] {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut hash = OFFSET_BASIS;
+    let mut len = 0u64;
+    for input in inputs {
+        for byte in input.iter() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+        len += input.len() as u64;
+    }
+
+    let mut tag = [0; TAG_LEN];
+    tag[..8].copy_from_slice(&hash.to_be_bytes());
+    tag[8..].copy_from_slice(&(hash ^ len).wrapping_mul(PRIME).to_be_bytes());
+    tag
+}
+
+/// A header key which leaves the packet header unprotected
+#[derive(Debug, Default)]
+pub struct HeaderKey(());
+
+impl crypto::HeaderKey for HeaderKey {
+    #[inline]
+    fn opening_header_protection_mask(&self, _sample: &[u8]) -> HeaderProtectionMask {
+        [0; 5]
+    }
+
+    #[inline]
+    fn opening_sample_len(&self) -> usize {
+        SAMPLE_LEN
+    }
+
+    #[inline]
+    fn sealing_header_protection_mask(&self, _sample: &[u8]) -> HeaderProtectionMask {
+        [0; 5]
+    }
+
+    #[inline]
+    fn sealing_sample_len(&self) -> usize {
+        SAMPLE_LEN
+    }
+}
+
+impl crypto::InitialHeaderKey for HeaderKey {}
+impl crypto::HandshakeHeaderKey for HeaderKey {}
+impl crypto::OneRttHeaderKey for HeaderKey {}
+impl crypto::ZeroRttHeaderKey for HeaderKey {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s2n_quic_core::crypto::tls::testing::Pair;
+
+    #[test]
+    fn handshake_test() {
+        let mut server = Endpoint::default();
+        let mut client = Endpoint::default();
+
+        let mut pair = Pair::new(&mut server, &mut client, "localhost".into());
+
+        while pair.is_handshaking() {
+            pair.poll(None).unwrap();
+        }
+
+        // `Pair::finish` can't be used since it expects a negotiated cipher suite and
+        // certificate chain
+        for (context_tp, expected_tp) in [
+            (&pair.client.context.transport_parameters, &[1, 2, 3]),
+            (&pair.server.context.transport_parameters, &[3, 2, 1]),
+        ] {
+            assert_eq!(context_tp.as_deref(), Some(&expected_tp[..]));
+        }
+
+        for server_name in [
+            &pair.client.context.server_name,
+            &pair.server.context.server_name,
+        ] {
+            assert_eq!(server_name.as_deref(), Some(&b"localhost"[..]));
+        }
+
+        for application_protocol in [
+            &pair.client.context.application_protocol,
+            &pair.server.context.application_protocol,
+        ] {
+            assert_eq!(application_protocol.as_deref(), Some(&b"h3"[..]));
+        }
+
+        assert!(pair.client.context.session_info.is_some());
+        assert!(pair.server.context.session_info.is_some());
+    }
+
+    #[test]
+    fn no_application_protocol_test() {
+        let mut server = Endpoint::builder()
+            .with_application_protocols(["h3"])
+            .build();
+        let mut client = Endpoint::builder()
+            .with_application_protocols(["hq-interop"])
+            .build();
+
+        let mut pair = Pair::new(&mut server, &mut client, "localhost".into());
+
+        // the client sends its hello and the server rejects it
+        pair.poll(None).unwrap();
+        assert!(pair.poll(None).is_err());
+    }
+
+    #[test]
+    fn corrupted_payload_test() {
+        use crypto::{Key as _, RetryKey as _};
+
+        let key = Key::default();
+        let header = [1, 2, 3];
+        let mut payload = [0; 32];
+        payload[..4].copy_from_slice(&[4, 5, 6, 7]);
+        key.encrypt(1, &header, &mut payload).unwrap();
+
+        assert!(key.decrypt(1, &header, &mut payload.clone()).is_ok());
+        // the packet number, header, payload and tag are all covered by the checksum
+        assert!(key.decrypt(2, &header, &mut payload.clone()).is_err());
+        assert!(key.decrypt(1, &[1, 2, 4], &mut payload.clone()).is_err());
+        for index in 0..payload.len() {
+            let mut corrupted = payload;
+            corrupted[index] ^= 1;
+            assert!(key.decrypt(1, &header, &mut corrupted).is_err());
+        }
+        // payloads shorter than the tag are rejected
+        assert!(key.decrypt(1, &header, &mut [0; TAG_LEN - 1]).is_err());
+
+        let tag = Key::generate_tag(&payload);
+        assert!(Key::validate(&payload, tag).is_ok());
+        let mut corrupted = tag;
+        corrupted[0] ^= 1;
+        assert!(Key::validate(&payload, corrupted).is_err());
+        assert!(Key::validate(&payload[1..], tag).is_err());
+    }
+}
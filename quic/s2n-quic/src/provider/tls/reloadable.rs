@@ -0,0 +1,314 @@
+// Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Provides a TLS endpoint which can be replaced while the QUIC endpoint is running
+//!
+//! This can be used to rotate certificates, private keys and trust stores without restarting
+//! the server. Connections which were created before an update continue to use the previous
+//! configuration; only new connections use the replacement.
+//!
+//! ```rust,ignore
+//! let tls = s2n_quic::provider::tls::default::Server::builder()
+//!     .with_certificate(cert_path, key_path)?
+//!     .build()?;
+//! let (tls, handle) = Reloadable::new(tls);
+//!
+//! // rebuild the endpoint whenever the certificate or key changes
+//! handle.watch(
+//!     [cert_path, key_path],
+//!     Duration::from_secs(60),
+//!     move || {
+//!         s2n_quic::provider::tls::default::Server::builder()
+//!             .with_certificate(cert_path, key_path)?
+//!             .build()
+//!     },
+//!     |error| eprintln!("could not reload the certificate: {}", error),
+//! );
+//!
+//! let server = Server::builder().with_tls(tls)?.start()?;
+//! ```
+
+use s2n_codec::EncoderValue;
+use s2n_quic_core::{application::ServerName, crypto::tls};
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// A TLS endpoint which can be replaced through its [`Handle`]
+pub struct Reloadable<E> {
+    current: E,
+    shared: Arc<Shared<E>>,
+}
+
+struct Shared<E> {
+    /// Set when `next` contains an endpoint so sessions don't need to acquire the lock
+    has_update: AtomicBool,
+    next: Mutex<Option<E>>,
+}
+
+impl<E: tls::Endpoint> Reloadable<E> {
+    /// Wraps `endpoint` and returns a handle which can be used to replace it
+    pub fn new(endpoint: E) -> (Self, Handle<E>) {
+        let shared = Arc::new(Shared {
+            has_update: AtomicBool::new(false),
+            next: Mutex::new(None),
+        });
+
+        let handle = Handle {
+            shared: Arc::downgrade(&shared),
+        };
+
+        let endpoint = Self {
+            current: endpoint,
+            shared,
+        };
+
+        (endpoint, handle)
+    }
+
+    /// Switches to the most recent update, if any
+    #[inline]
+    fn poll_update(&mut self) {
+        // clear the flag before taking the endpoint so an update which races with this one
+        // is picked up by the next session
+        if !self.shared.has_update.swap(false, Ordering::AcqRel) {
+            return;
+        }
+
+        if let Some(endpoint) = self.shared.next.lock().unwrap().take() {
+            self.current = endpoint;
+        }
+    }
+}
+
+impl<E: tls::Endpoint> tls::Endpoint for Reloadable<E> {
+    type Session = E::Session;
+
+    fn new_server_session<Params: EncoderValue>(
+        &mut self,
+        transport_parameters: &Params,
+    ) -> Self::Session {
+        self.poll_update();
+        self.current.new_server_session(transport_parameters)
+    }
+
+    fn new_client_session<Params: EncoderValue>(
+        &mut self,
+        transport_parameters: &Params,
+        server_name: ServerName,
+    ) -> Self::Session {
+        self.poll_update();
+        self.current
+            .new_client_session(transport_parameters, server_name)
+    }
+
+    fn max_tag_length(&self) -> usize {
+        self.current.max_tag_length()
+    }
+}
+
+impl<E: tls::Endpoint> super::Provider for Reloadable<E> {
+    type Server = Self;
+    type Client = Self;
+    type Error = core::convert::Infallible;
+
+    fn start_server(self) -> Result<Self::Server, Self::Error> {
+        Ok(self)
+    }
+
+    fn start_client(self) -> Result<Self::Client, Self::Error> {
+        Ok(self)
+    }
+}
+
+/// Replaces the endpoint of a [`Reloadable`]
+///
+/// The handle doesn't keep the endpoint alive; updates are discarded once the QUIC endpoint
+/// is closed.
+pub struct Handle<E> {
+    shared: Weak<Shared<E>>,
+}
+
+impl<E> Clone for Handle<E> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<E: tls::Endpoint> Handle<E> {
+    /// Replaces the endpoint used for new connections
+    ///
+    /// Returns `false` if the QUIC endpoint has been closed.
+    pub fn update(&self, endpoint: E) -> bool {
+        let shared = if let Some(shared) = self.shared.upgrade() {
+            shared
+        } else {
+            return false;
+        };
+
+        *shared.next.lock().unwrap() = Some(endpoint);
+        shared.has_update.store(true, Ordering::Release);
+
+        true
+    }
+
+    /// Returns `true` if the QUIC endpoint has been closed
+    pub fn is_closed(&self) -> bool {
+        self.shared.strong_count() == 0
+    }
+
+    /// Spawns a thread which rebuilds the endpoint whenever any of `paths` is modified
+    ///
+    /// The files are checked for changes every `interval`. If `build` fails, for example because
+    /// only one of the certificate and key files has been written, the error is passed to
+    /// `on_error`, the current endpoint is kept and the build is retried at the next interval.
+    /// The thread exits once the QUIC endpoint is closed.
+    pub fn watch<P, F, Error, OnError>(
+        &self,
+        paths: P,
+        interval: Duration,
+        mut build: F,
+        mut on_error: OnError,
+    ) -> thread::JoinHandle<()>
+    where
+        P: IntoIterator,
+        P::Item: Into<PathBuf>,
+        F: 'static + FnMut() -> Result<E, Error> + Send,
+        OnError: 'static + FnMut(Error) + Send,
+    {
+        let handle = self.clone();
+        let paths: Vec<PathBuf> = paths.into_iter().map(Into::into).collect();
+
+        let modified = move || -> Vec<Option<SystemTime>> {
+            paths
+                .iter()
+                .map(|path| path.metadata().and_then(|meta| meta.modified()).ok())
+                .collect()
+        };
+
+        thread::spawn(move || {
+            let mut prev = modified();
+
+            loop {
+                thread::sleep(interval);
+
+                if handle.is_closed() {
+                    return;
+                }
+
+                let current = modified();
+                if current == prev {
+                    continue;
+                }
+
+                match build() {
+                    Ok(endpoint) => {
+                        if !handle.update(endpoint) {
+                            return;
+                        }
+                        // only record the modification times once the update succeeds so failed
+                        // builds are retried
+                        prev = current;
+                    }
+                    Err(error) => on_error(error),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "provider-tls-default"))]
+mod tests {
+    use super::*;
+    use crate::provider::tls::default;
+    use s2n_quic_core::crypto::tls::{testing::certificates::*, Endpoint};
+
+    fn server() -> default::Server {
+        default::Server::builder()
+            .with_certificate(CERT_PEM, KEY_PEM)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn update_test() {
+        let (mut endpoint, handle) = Reloadable::new(server());
+
+        assert!(!endpoint.shared.has_update.load(Ordering::Acquire));
+        assert!(handle.update(server()));
+        assert!(endpoint.shared.has_update.load(Ordering::Acquire));
+
+        // the update is applied on the next session
+        let params: &[u8] = &[1, 2, 3];
+        let _ = endpoint.new_server_session(&params);
+        assert!(!endpoint.shared.has_update.load(Ordering::Acquire));
+        assert!(endpoint.shared.next.lock().unwrap().is_none());
+
+        drop(endpoint);
+        assert!(handle.is_closed());
+        assert!(!handle.update(server()));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn watch_test() {
+        use std::sync::{atomic::AtomicUsize, mpsc};
+
+        let path = std::env::temp_dir().join(format!("s2n-quic-watch-{}.pem", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let (endpoint, handle) = Reloadable::new(server());
+        let (errors, error_rx) = mpsc::channel();
+
+        // fail the first build to make sure errors are reported and the build is retried
+        let builds = Arc::new(AtomicUsize::new(0));
+        let build = {
+            let builds = builds.clone();
+            move || {
+                if builds.fetch_add(1, Ordering::Relaxed) == 0 {
+                    Err("incomplete")
+                } else {
+                    Ok(server())
+                }
+            }
+        };
+        let on_error = move |error| errors.send(error).unwrap();
+
+        let thread = handle.watch([&path], Duration::from_millis(10), build, on_error);
+
+        // nothing is rebuilt until the file changes
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(builds.load(Ordering::Relaxed), 0);
+
+        std::fs::write(&path, CERT_PEM).unwrap();
+
+        let error = error_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(error, "incomplete");
+
+        let start = std::time::Instant::now();
+        while !endpoint.shared.has_update.load(Ordering::Acquire) {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "endpoint was not updated"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(builds.load(Ordering::Relaxed), 2);
+
+        // the thread exits once the endpoint is closed
+        drop(endpoint);
+        thread.join().unwrap();
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -3,14 +3,33 @@
 
 use crate::{
     client::Connect,
+    connection,
     provider::{
         self,
-        io::testing::{spawn, test, time::delay, Model},
+        endpoint_limits::source_address,
+        io::{
+            record,
+            testing::{
+                spawn, test, time::delay, Change, GilbertElliott, Handle, Model, Profile,
+                QueueDiscipline, Rebind, Result,
+            },
+        },
         packet_interceptor::Loss,
     },
-    Server,
+    stream, Client, Server,
+};
+use s2n_quic_core::{
+    connection::id::testing::Format as ConnectionIds, crypto::tls::testing::certificates,
+    stateless_reset::token::testing::Generator as ResetTokens,
+};
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU16, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
 };
-use std::time::Duration;
 
 mod setup;
 use bytes::Bytes;
@@ -66,6 +85,64 @@ fn blackhole_failure_test() {
     blackhole(model, blackhole_duration);
 }
 
+/// Ensures connections recover from transitions in the network conditions
+#[test]
+fn network_profile_test() {
+    let model = Model::default();
+    test(model.clone(), |handle| {
+        Profile::default()
+            .with(
+                Duration::ZERO,
+                Change::BurstLoss(GilbertElliott {
+                    p: 0.05,
+                    r: 0.5,
+                    good_loss: 0.0,
+                    bad_loss: 1.0,
+                }),
+            )
+            .with(Duration::ZERO, Change::CeThreshold(10))
+            .with(Duration::from_millis(500), Change::Bandwidth(100_000))
+            .with_outage(Duration::from_secs(1), Duration::from_millis(500))
+            // drop the MTU to the minimum so the larger packets are black-holed
+            .with(Duration::from_secs(2), Change::MaxUdpPayload(1200))
+            .with(Duration::from_secs(3), Change::Bandwidth(u64::MAX))
+            .spawn(model);
+        client_server(handle)
+    })
+    .unwrap();
+}
+
+/// Ensures connections survive the client being rebound by a NAT
+#[test]
+fn nat_rebinding_test() {
+    let model = Model::default();
+    model.set_rebind_rate(0.01).set_rebind_kind(Rebind::Port);
+    test(model.clone(), |handle| {
+        Profile::default()
+            .with(
+                Duration::from_millis(500),
+                Change::RebindAll(Rebind::Address),
+            )
+            .with(Duration::from_secs(1), Change::RebindAll(Rebind::Both))
+            .spawn(model);
+        client_server(handle)
+    })
+    .unwrap();
+}
+
+/// Ensures connections complete through a bandwidth-limited link with a finite queue
+#[test]
+fn bottleneck_test() {
+    for discipline in [QueueDiscipline::DropTail, QueueDiscipline::CODEL] {
+        let model = Model::default();
+        model
+            .set_bandwidth(100_000)
+            .set_queue_capacity(20_000)
+            .set_queue_discipline(discipline);
+        test(model, client_server).unwrap();
+    }
+}
+
 fn intercept_loss(loss: Loss<Random>) {
     let model = Model::default();
     test(model, |handle| {
@@ -104,6 +181,53 @@ fn interceptor_failure_test() {
     )
 }
 
+/// Ensures the null TLS provider rejects corrupted packets instead of delivering them
+#[test]
+fn null_tls_corruption_test() {
+    let model = Model::default();
+    model.set_corrupt_rate(0.05);
+    test(model, |handle| {
+        let server = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(provider::tls::null::Endpoint::default())?
+                .with_event(events())?
+                .start()?)
+        })?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build().unwrap())?
+            .with_tls(provider::tls::null::Endpoint::default())?
+            .with_event(events())?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let stream = connection.open_bidirectional_stream().await.unwrap();
+            let (mut recv, mut send) = stream.split();
+
+            let mut send_data = s2n_quic_core::stream::testing::Data::new(100_000);
+            let mut recv_data = send_data;
+            primary::spawn(async move {
+                while let Some(chunk) = send_data.send_one(usize::MAX) {
+                    send.send(chunk).await.unwrap();
+                }
+                send.finish().unwrap();
+            });
+
+            // any corrupted stream data which isn't rejected will fail the comparison
+            while let Some(chunk) = recv.receive().await.unwrap() {
+                recv_data.receive(&[chunk]);
+            }
+            assert!(recv_data.is_finished());
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
 /// Ensures streams with STOP_SENDING are properly cleaned up
 ///
 /// See https://github.com/aws/s2n-quic/pull/1361
@@ -172,3 +296,516 @@ fn stream_reset_test() {
     })
     .unwrap();
 }
+
+// The testing token generator derives tokens from the connection ID, which means a restarted
+// endpoint will generate the same tokens as the instance it replaced.
+fn reset_server(handle: &Handle, address: Option<SocketAddr>) -> Result<SocketAddr> {
+    let mut io = handle.builder();
+    if let Some(address) = address {
+        io = io.with_address(address.into());
+    }
+
+    let server = Server::builder()
+        .with_io(io.build()?)?
+        .with_tls(SERVER_CERTS)?
+        .with_event(events())?
+        .with_stateless_reset_token(ResetTokens::default())?
+        .start()?;
+
+    echo(server)
+}
+
+fn reset_client(handle: &Handle, address: Option<SocketAddr>) -> Result<Client> {
+    let mut io = handle.builder();
+    if let Some(address) = address {
+        io = io.with_address(address.into());
+    }
+
+    Ok(Client::builder()
+        .with_io(io.build()?)?
+        .with_tls(certificates::CERT_PEM)?
+        .with_event(events())?
+        .with_stateless_reset_token(ResetTokens::default())?
+        .start()?)
+}
+
+fn is_stateless_reset(error: &stream::Error) -> bool {
+    matches!(
+        error,
+        stream::Error::ConnectionError {
+            error: connection::Error::StatelessReset { .. },
+            ..
+        }
+    )
+}
+
+/// Restarts the server in the middle of a connection and ensures the client
+/// closes the connection after receiving a stateless reset
+#[test]
+fn server_restart_stateless_reset_test() {
+    let model = Model::default();
+    test(model, |handle| {
+        let server_addr = reset_server(handle, None)?;
+        let client = reset_client(handle, None)?;
+        let handle = handle.clone();
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+
+            stream.send(Bytes::from_static(&[42])).await.unwrap();
+            stream.receive().await.unwrap().unwrap();
+
+            // give the peers time to switch to connection IDs issued after the handshake
+            delay(Duration::from_secs(1)).await;
+
+            // the new server takes over the address without any of the connection state
+            reset_server(&handle, Some(server_addr)).unwrap();
+
+            let _ = stream.send(Bytes::from_static(&[42; 1024])).await;
+            let error = stream.receive().await.unwrap_err();
+            assert!(is_stateless_reset(&error), "{:?}", error);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Restarts the client in the middle of a connection and ensures the server
+/// closes the connection after receiving a stateless reset
+#[test]
+fn client_restart_stateless_reset_test() {
+    let model = Model::default();
+    test(model, |handle| {
+        let mut server = Server::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(SERVER_CERTS)?
+            .with_event(events())?
+            .with_stateless_reset_token(ResetTokens::default())?
+            .start()?;
+        let server_addr = server.local_addr()?;
+
+        let client = reset_client(handle, None)?;
+        let client_addr = client.local_addr()?;
+        let handle = handle.clone();
+
+        primary::spawn(async move {
+            let mut connection = server.accept().await.unwrap();
+            let mut stream = connection
+                .accept_bidirectional_stream()
+                .await
+                .unwrap()
+                .unwrap();
+            stream.receive().await.unwrap().unwrap();
+
+            // wait for the client to restart
+            delay(Duration::from_secs(2)).await;
+
+            let _ = stream.send(Bytes::from_static(&[42; 1024])).await;
+            let error = stream.receive().await.unwrap_err();
+            assert!(is_stateless_reset(&error), "{:?}", error);
+        });
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(&[42])).await.unwrap();
+
+            // give the peers time to switch to connection IDs issued after the handshake
+            delay(Duration::from_secs(1)).await;
+
+            // the new client takes over the address without any of the connection state
+            let client = reset_client(&handle, Some(client_addr)).unwrap();
+
+            // keep the new client around until the server has been reset
+            delay(Duration::from_secs(5)).await;
+            drop(client);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Records the latest 1-RTT key generation observed by an endpoint
+#[derive(Clone, Default)]
+struct KeyGeneration(Arc<AtomicU16>);
+
+impl KeyGeneration {
+    fn get(&self) -> u16 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl provider::event::Subscriber for KeyGeneration {
+    type ConnectionContext = ();
+
+    fn create_connection_context(
+        &mut self,
+        _meta: &provider::event::ConnectionMeta,
+        _info: &provider::event::ConnectionInfo,
+    ) -> Self::ConnectionContext {
+    }
+
+    fn on_key_update(
+        &mut self,
+        _context: &mut Self::ConnectionContext,
+        _meta: &provider::event::ConnectionMeta,
+        event: &provider::event::events::KeyUpdate,
+    ) {
+        if let provider::event::events::KeyType::OneRtt { generation, .. } = event.key_type {
+            self.0.store(generation, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Opens connections from a single address up to the source address limit and ensures a new
+/// connection is allowed once one of them closes
+#[test]
+fn source_address_limit_test() {
+    let model = Model::default();
+    test(model, |handle| {
+        let server_addr = server_with(handle, |io| {
+            let limits = source_address::Limits::builder()
+                .with_close_threshold(2)?
+                .build()?;
+
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event(events())?
+                .with_endpoint_limits(limits)?
+                .start()?)
+        })?;
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+
+            let first = client.connect(connect.clone()).await.unwrap();
+            let _second = client.connect(connect.clone()).await.unwrap();
+
+            // the address has reached the limit
+            assert!(client.connect(connect.clone()).await.is_err());
+
+            // wait for the server to finish draining the closed connection
+            first.close(0u8.into());
+            delay(Duration::from_secs(5)).await;
+
+            client.connect(connect).await.unwrap();
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Initiates several key updates from the client and ensures both endpoints rotate their keys
+#[test]
+fn key_update_test() {
+    let model = Model::default();
+    let server_generation = KeyGeneration::default();
+    let client_generation = KeyGeneration::default();
+
+    test(model, |handle| {
+        let subscriber = server_generation.clone();
+        let server_addr = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event((provider::event::Provider::start(events())?, subscriber))?
+                .start()?)
+        })?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event((
+                provider::event::Provider::start(events())?,
+                client_generation.clone(),
+            ))?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+
+            for _ in 0..3 {
+                connection.initiate_key_update().unwrap();
+
+                stream.send(Bytes::from_static(&[42])).await.unwrap();
+                stream.receive().await.unwrap().unwrap();
+
+                // the next update can only start after the previous keys have been discarded
+                delay(Duration::from_secs(1)).await;
+            }
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(server_generation.get(), 3);
+    assert_eq!(client_generation.get(), 3);
+}
+
+/// Replaces the server certificate and ensures only new connections use the replacement
+#[test]
+fn reloadable_tls_test() {
+    use provider::tls::{default, reloadable::Reloadable};
+
+    fn server_tls(cert: &'static str, key: &'static str) -> default::Server {
+        default::Server::builder()
+            .with_certificate(cert, key)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    let model = Model::default();
+    test(model, |handle| {
+        let (tls, tls_handle) =
+            Reloadable::new(server_tls(certificates::CERT_PEM, certificates::KEY_PEM));
+        let server_addr = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(tls)?
+                .with_event(events())?
+                .start()?)
+        })?;
+
+        let client = build_client(handle)?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+
+            let mut existing = client.connect(connect.clone()).await.unwrap();
+            let mut stream = existing.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(&[1])).await.unwrap();
+            stream.receive().await.unwrap().unwrap();
+
+            // the client doesn't trust the replacement so new connections should be rejected
+            assert!(tls_handle.update(server_tls(
+                certificates::UNTRUSTED_CERT_PEM,
+                certificates::UNTRUSTED_KEY_PEM,
+            )));
+            assert!(client.connect(connect.clone()).await.is_err());
+
+            // the existing connection continues to use the previous configuration
+            stream.send(Bytes::from_static(&[2])).await.unwrap();
+            stream.receive().await.unwrap().unwrap();
+
+            assert!(tls_handle.update(server_tls(certificates::CERT_PEM, certificates::KEY_PEM)));
+            let mut connection = client.connect(connect).await.unwrap();
+            let mut stream = connection.open_bidirectional_stream().await.unwrap();
+            stream.send(Bytes::from_static(&[3])).await.unwrap();
+            stream.receive().await.unwrap().unwrap();
+        });
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Records the duration of each handshake phase observed by an endpoint
+#[derive(Clone, Default)]
+struct HandshakePhases(Arc<Mutex<Vec<(String, Duration)>>>);
+
+impl HandshakePhases {
+    fn get(&self, phase: &str) -> Duration {
+        let phases = self.0.lock().unwrap();
+        phases
+            .iter()
+            .find(|(name, _)| name == phase)
+            .map(|(_, duration)| *duration)
+            .unwrap_or_else(|| panic!("{} phase was not completed in {:?}", phase, phases))
+    }
+}
+
+impl provider::event::Subscriber for HandshakePhases {
+    type ConnectionContext = ();
+
+    fn create_connection_context(
+        &mut self,
+        _meta: &provider::event::ConnectionMeta,
+        _info: &provider::event::ConnectionInfo,
+    ) -> Self::ConnectionContext {
+    }
+
+    fn on_handshake_phase_completed(
+        &mut self,
+        _context: &mut Self::ConnectionContext,
+        _meta: &provider::event::ConnectionMeta,
+        event: &provider::event::events::HandshakePhaseCompleted,
+    ) {
+        let phase = format!("{:?}", event.phase);
+        self.0.lock().unwrap().push((phase, event.duration));
+    }
+}
+
+/// Ensures the phases which wait on the peer include the round trip
+#[test]
+fn handshake_phase_timing_test() {
+    let model = Model::default();
+    let rtt = Duration::from_millis(100);
+    model.set_delay(rtt / 2);
+    let server_phases = HandshakePhases::default();
+    let client_phases = HandshakePhases::default();
+
+    test(model, |handle| {
+        let subscriber = server_phases.clone();
+        let server_addr = server_with(handle, |io| {
+            Ok(Server::builder()
+                .with_io(io)?
+                .with_tls(SERVER_CERTS)?
+                .with_event((provider::event::Provider::start(events())?, subscriber))?
+                .start()?)
+        })?;
+
+        let client = Client::builder()
+            .with_io(handle.builder().build()?)?
+            .with_tls(certificates::CERT_PEM)?
+            .with_event((
+                provider::event::Provider::start(events())?,
+                client_phases.clone(),
+            ))?
+            .start()?;
+
+        primary::spawn(async move {
+            let connect = Connect::new(server_addr).with_server_name("localhost");
+            let connection = client.connect(connect).await.unwrap();
+
+            // keep the connection open until the HANDSHAKE_DONE frame is acknowledged
+            delay(Duration::from_secs(1)).await;
+            drop(connection);
+        });
+
+        Ok(())
+    })
+    .unwrap();
+
+    // allow for the granularity of the simulated clock
+    let rtt = rtt - Duration::from_millis(1);
+
+    // the client waits a round trip for the server's first flight
+    assert!(client_phases.get("ServerHello") >= rtt);
+    // the server waits a round trip for the client's Finished message
+    assert!(server_phases.get("Complete") >= rtt);
+
+    // the HANDSHAKE_DONE frame is sent once the server completes the handshake
+    assert!(client_phases.get("HandshakeDoneAcked") >= rtt);
+    assert!(server_phases.get("HandshakeDoneAcked") >= rtt);
+}
+
+#[derive(Clone, Default)]
+struct Recording(Arc<Mutex<Vec<u8>>>);
+
+impl Recording {
+    fn records(&self) -> Vec<record::Record> {
+        let bytes = self.0.lock().unwrap();
+        record::Reader::new(&bytes[..])
+            .unwrap()
+            .map(|record| record.unwrap())
+            .collect()
+    }
+
+    fn datagrams(&self) -> Vec<record::Datagram> {
+        self.records()
+            .into_iter()
+            .filter_map(|record| match record {
+                record::Record::Datagram(datagram) => Some(datagram),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn transmissions(&self) -> Vec<record::Datagram> {
+        self.records()
+            .into_iter()
+            .filter_map(|record| match record {
+                record::Record::Transmit(datagram) => Some(datagram),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl std::io::Write for Recording {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Records a server and ensures the replayed server receives and transmits the same datagrams
+///
+/// The key log is only supported by s2n-tls, which is the default provider on unix platforms.
+#[test]
+#[cfg(unix)]
+fn record_replay_test() {
+    let recording = Recording::default();
+    let recorder = record::Recorder::new(recording.clone()).unwrap();
+
+    test(Model::default(), |handle| {
+        let tls = provider::tls::default::Server::builder()
+            .with_certificate(certificates::CERT_PEM, certificates::KEY_PEM)?
+            .with_key_log(recorder.key_log())?
+            .build()?;
+        // The event provider is left out since the type of the builder grows with each
+        // provider, which makes longer chains too expensive to type-check
+        let server = Server::builder()
+            .with_io(recorder.io(handle.builder().build()?))?
+            .with_tls(recorder.tls(tls))?
+            .with_connection_id(ConnectionIds::default())?
+            .with_stateless_reset_token(ResetTokens::default())?
+            .with_random(recorder.random(provider::random::Default::default()))?
+            .start()?;
+        let server_addr = echo(server)?;
+
+        client(handle, server_addr)
+    })
+    .unwrap();
+    recorder.flush().unwrap();
+
+    let received = recording.datagrams();
+    let transmitted = recording.transmissions();
+    assert!(!received.is_empty());
+    assert!(!transmitted.is_empty());
+
+    let replay = {
+        let bytes = recording.0.lock().unwrap();
+        record::replay::Replay::new(record::Reader::new(&bytes[..]).unwrap()).unwrap()
+    };
+    let address = replay.local_address().unwrap();
+    let random = replay.random();
+    let tls = replay.tls();
+
+    let replayed = Recording::default();
+    let recorder = record::Recorder::new(replayed.clone()).unwrap();
+
+    test(replay, |handle| {
+        let server = Server::builder()
+            .with_io(recorder.io(handle.builder().with_address(address).build()?))?
+            .with_tls(tls)?
+            .with_connection_id(ConnectionIds::default())?
+            .with_stateless_reset_token(ResetTokens::default())?
+            .with_random(recorder.random(random))?
+            .start()?;
+        echo(server)?;
+
+        Ok(())
+    })
+    .unwrap();
+    recorder.flush().unwrap();
+
+    assert_eq!(replayed.datagrams(), received);
+    assert_eq!(replayed.transmissions(), transmitted);
+}
@@ -42,7 +42,12 @@ pub fn server_with<F: FnOnce(Io) -> Result<Server>>(
     handle: &Handle,
     build: F,
 ) -> Result<SocketAddr> {
-    let mut server = build(handle.builder().build().unwrap())?;
+    let server = build(handle.builder().build().unwrap())?;
+    echo(server)
+}
+
+/// Accepts connections on the server and echoes back any received stream data
+pub fn echo(mut server: Server) -> Result<SocketAddr> {
     let server_addr = server.local_addr()?;
 
     // accept connections and echo back